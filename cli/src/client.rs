@@ -1,28 +1,69 @@
 use anchor_client::{
     anchor_lang::{
-        prelude::Pubkey, AnchorDeserialize, AnchorSerialize, Discriminator,
+        prelude::Pubkey, AccountDeserialize, AnchorDeserialize, AnchorSerialize, Discriminator,
     },
-    solana_sdk::{signature::Signature, signer::Signer, system_program},
-    Client, Program,
+    solana_client::{
+        client_error::{ClientError as RpcClientError, ClientErrorKind}, nonce_utils,
+        pubsub_client::{PubsubAccountClientSubscription, PubsubClient},
+        rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient}, rpc_config::RpcAccountInfoConfig,
+        rpc_request::{RpcError, RpcResponseErrorData},
+    },
+    solana_sdk::{
+        account::Account, compute_budget::{self, ComputeBudgetInstruction},
+        instruction::{AccountMeta, Instruction}, keccak::hashv, native_token::LAMPORTS_PER_SOL, signature::Keypair,
+        signature::Signature, signer::Signer, system_instruction, system_program,
+        transaction::{Transaction, TransactionError},
+    },
+    Client, ClientError, Program, RequestBuilder,
 };
 use anyhow::Result;
+use serde::Serialize;
+use solana_account_decoder::UiAccountEncoding;
 use std::rc::Rc;
+use tracing::instrument;
+
+use crate::compression::{self, BUBBLEGUM_PROGRAM_ID, COMPRESSION_PROGRAM_ID, NOOP_PROGRAM_ID};
+use crate::events::{fetch_cpi_events, fetch_program_instructions, history_commitment, ProgramInstruction};
+use crate::shielded::{self, DleqProof, ElGamalCiphertext, ElGamalKeypair, ShieldedChoice, ShieldedResult};
+use crate::squads;
+use crate::utils::{
+    get_ballot_address, get_candidate_address, get_compressed_voter_address, get_config_address, get_creator_pass_address, get_creator_stats_address, get_event_authority_address,
+    get_master_edition_address, get_metadata_address, get_nomination_address, get_poll_address, get_poll_archive_address, get_program_data_address,
+    get_poll_authority_address, get_poll_proposal_address, get_poll_result_address, get_shielded_tally_address, get_proposal_address, get_receipt_address, get_reward_vault_address, get_session_address, get_stake_marker_address,
+    get_attestation_address, get_conviction_lock_address, get_conviction_vault_address, get_token_owner_record_address,
+    get_governance_lock_address, get_realm_config_address,
+    get_finalize_thread_address, get_treasury_address, get_access_grant_address, get_weight_record_address, ACCESS_GRANT_BATCH_SIZE, CANDIDATE_BATCH_SIZE, THREAD_PROGRAM_ID,
+    TRANSFER_BATCH_SIZE, RECEIPT_GRACE_PERIOD, START_TIME_GRACE_PERIOD,
+    TOKEN_METADATA_PROGRAM_ID, VoteSelection,
+};
 
-use crate::utils::{get_candidate_address, get_poll_address, get_receipt_address};
+// Compute budget for shielded ballots and results, which verify several curve proofs on-chain
+const SHIELDED_COMPUTE_UNITS: u32 = 1_000_000;
+
+// How often `watch_tallies` checks a poll for new transactions
+const WATCH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+// Most accounts one getMultipleAccounts request may ask for
+const MULTIPLE_ACCOUNTS_BATCH_SIZE: usize = 100;
+
+// Airdrop requests made before giving up, and the wait before the first retry, doubled for each next one
+const AIRDROP_ATTEMPTS: u32 = 3;
+const AIRDROP_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
 
 // Define the account structures matching the on-chain program
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
-pub struct Poll {
-    pub poll_id: u64,
-    pub creator: Pubkey,
-    pub question: String,
-    pub description: String,
-    pub start_time: i64,
-    pub end_time: i64,
-    pub candidate_count: u64,
+pub struct Config {
+    pub admin: Pubkey,
+    pub poll_creation_fee: u64,
+    pub council: Vec<Pubkey>,
+    pub council_threshold: u8,
+    pub restrict_creators: bool,
+    pub max_poll_duration: i64,
+    pub max_polls_per_window: u16,
+    pub creation_window: i64,
 }
 
-impl anchor_client::anchor_lang::AccountDeserialize for Poll {
+impl anchor_client::anchor_lang::AccountDeserialize for Config {
     fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
         if buf.len() < 8 {
             return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
@@ -41,19 +82,19 @@ impl anchor_client::anchor_lang::AccountDeserialize for Poll {
     }
 }
 
-impl anchor_client::anchor_lang::Discriminator for Poll {
-    const DISCRIMINATOR: [u8; 8] = [110, 234, 189, 127, 197, 119, 248, 65];
+impl anchor_client::anchor_lang::Discriminator for Config {
+    const DISCRIMINATOR: [u8; 8] = [155, 12, 170, 224, 30, 250, 204, 130];
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
-pub struct Candidate {
-    pub poll: Pubkey,
-    pub name: String,
-    pub party: String,
-    pub votes: u64,
+pub struct CreatorStats {
+    pub creator: Pubkey,
+    pub total_polls: u64,
+    pub recent_polls: Vec<i64>,
+    pub bump: u8,
 }
 
-impl anchor_client::anchor_lang::AccountDeserialize for Candidate {
+impl anchor_client::anchor_lang::AccountDeserialize for CreatorStats {
     fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
         if buf.len() < 8 {
             return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
@@ -72,18 +113,16 @@ impl anchor_client::anchor_lang::AccountDeserialize for Candidate {
     }
 }
 
-impl anchor_client::anchor_lang::Discriminator for Candidate {
-    const DISCRIMINATOR: [u8; 8] = [176, 27, 202, 124, 178, 75, 76, 43];
+impl anchor_client::anchor_lang::Discriminator for CreatorStats {
+    const DISCRIMINATOR: [u8; 8] = [239, 158, 112, 237, 227, 82, 97, 129];
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
-pub struct VoterReceipt {
-    pub poll: Pubkey,
-    pub voter: Pubkey,
-    pub has_voted: bool,
+pub struct Treasury {
+    pub total_collected: u64,
 }
 
-impl anchor_client::anchor_lang::AccountDeserialize for VoterReceipt {
+impl anchor_client::anchor_lang::AccountDeserialize for Treasury {
     fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
         if buf.len() < 8 {
             return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
@@ -102,251 +141,6677 @@ impl anchor_client::anchor_lang::AccountDeserialize for VoterReceipt {
     }
 }
 
-impl anchor_client::anchor_lang::Discriminator for VoterReceipt {
-    const DISCRIMINATOR: [u8; 8] = [36, 100, 107, 120, 65, 243, 217, 180];
+impl anchor_client::anchor_lang::Discriminator for Treasury {
+    const DISCRIMINATOR: [u8; 8] = [238, 239, 123, 238, 89, 1, 168, 253];
 }
 
-pub struct VotingClient<C: Signer> {
-    program: Program<Rc<C>>,
-    program_id: Pubkey,
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Serialize)]
+pub struct Poll {
+    pub poll_id: u64,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub creator: Pubkey,
+    pub question: String,
+    pub description: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub candidate_count: u64,
+    pub candidate_deposit_lamports: u64,
+    pub deposit_refund_threshold: u64,
+    pub finalized: bool,
+    pub total_votes: u64,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub reward_mint: Pubkey,
+    pub reward_pool_amount: u64,
+    pub bounty_lamports: u64,
+    pub bounty_claimed: bool,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub winner: Pubkey,
+    pub category: String,
+    pub tags: Vec<String>,
+    pub metadata_uri: String,
+    #[serde(serialize_with = "crate::utils::serialize_hex")]
+    pub metadata_hash: [u8; 32],
+    pub version: u8,
+    pub paused: bool,
+    pub paused_at: i64,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub receipt_tree: Pubkey,
+    pub compressed_voter_count: u32,
+    pub mint_receipt_nft: bool,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub receipt_nft_tree: Pubkey,
+    pub winner_badge_minted: bool,
+    pub min_voter_lamports: u64,
+    pub min_stake_lamports: u64,
+    pub weight_decay: WeightDecay,
+    pub eligible_voters: u64,
+    pub turnout_bps: u16,
+    pub status: PollStatus,
+    pub max_votes_per_slot: u32,
+    pub last_vote_slot: u64,
+    pub votes_in_slot: u32,
+    pub allow_cpi: bool,
+    #[serde(serialize_with = "crate::utils::serialize_hex")]
+    pub shielded_key: [u8; 32],
+    pub shielded_published: bool,
+    pub stake_weighted: bool,
+    pub snapshot_epoch: u64,
+    pub gauge_budget: u16,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub conviction_mint: Pubkey,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub governance_program: Pubkey,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub governance_realm: Pubkey,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub governance_mint: Pubkey,
+    #[serde(serialize_with = "crate::utils::serialize_optional_pubkey")]
+    pub candidate_manager: Option<Pubkey>,
+    #[serde(serialize_with = "crate::utils::serialize_optional_pubkey")]
+    pub eligibility_program: Option<Pubkey>,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub attestation_program: Pubkey,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub attestation_issuer: Pubkey,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub attestation_schema: Pubkey,
+    pub bump: u8,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub finalize_thread: Pubkey,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub randomness_account: Pubkey,
+    pub randomness_seed_slot: u64,
+    pub randomness_pending: bool,
+    #[serde(serialize_with = "crate::utils::serialize_hex")]
+    pub ballot_order_seed: [u8; 32],
+    #[serde(serialize_with = "crate::utils::serialize_hex")]
+    pub tie_break_seed: [u8; 32],
+    pub visibility: PollVisibility,
+    pub access_restricted: bool,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub weight_oracle: Pubkey,
+    pub max_weight_age: i64,
+    pub vote_fee: u64,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub vote_fee_mint: Pubkey,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub vote_fee_recipient: Pubkey,
+    pub max_extension: i64,
+    pub extended_by: i64,
+    pub first_vote_at: i64,
+    pub last_vote_at: i64,
+    #[serde(serialize_with = "crate::utils::serialize_histogram")]
+    pub vote_histogram: [u32; 48],
+    pub open_candidacy: bool,
 }
 
-impl<C: Signer> VotingClient<C> {
-    pub fn new(client: Client<Rc<C>>, program_id: Pubkey) -> Self {
-        let program = client.program(program_id).unwrap();
-        Self { program, program_id }
+impl anchor_client::anchor_lang::AccountDeserialize for Poll {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+        }
+        let given_disc = &buf[0..8];
+        if Self::DISCRIMINATOR != given_disc {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
     }
 
-    pub fn payer_pubkey(&self) -> Pubkey {
-        self.program.payer()
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        Self::deserialize(buf)
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
     }
+}
 
-    /// Initialize a new poll
-    pub fn initialize_poll(
-        &self,
-        poll_id: u64,
-        question: String,
-        description: String,
-        start_time: i64,
-        end_time: i64,
-    ) -> Result<Signature> {
-        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
-
-        let signature = self
-            .program
-            .request()
-            .accounts(voting_dapp::accounts::InitializePoll {
-                poll: poll_address,
-                creator: self.program.payer(),
-                system_program: system_program::ID,
-            })
-            .args(voting_dapp::instruction::InitializePoll {
-                poll_id,
-                question,
-                description,
-                start_time,
-                end_time,
-            })
-            .send()?;
+impl anchor_client::anchor_lang::Discriminator for Poll {
+    const DISCRIMINATOR: [u8; 8] = [110, 234, 167, 188, 231, 136, 153, 111];
+}
 
-        Ok(signature)
-    }
+/// Optional settings chosen by the creator at poll creation
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Default)]
+pub struct PollOptions {
+    pub candidate_deposit_lamports: u64,
+    pub deposit_refund_threshold: u64,
+    pub bounty_lamports: u64,
+    pub category: String,
+    pub tags: Vec<String>,
+    pub metadata_uri: String,
+    pub metadata_hash: [u8; 32],
+    pub mint_receipt_nft: bool,
+    pub receipt_nft_tree: Pubkey,
+    pub min_voter_lamports: u64,
+    pub min_stake_lamports: u64,
+    pub weight_decay: WeightDecay,
+    pub max_votes_per_slot: u32,
+    pub allow_cpi: bool,
+    pub shielded_key: [u8; 32],
+    pub stake_weighted: bool,
+    pub gauge_budget: u16,
+    pub conviction_mint: Pubkey,
+    pub governance_program: Pubkey,
+    pub governance_realm: Pubkey,
+    pub governance_mint: Pubkey,
+    pub eligibility_program: Option<Pubkey>,
+    pub attestation_program: Pubkey,
+    pub attestation_issuer: Pubkey,
+    pub attestation_schema: Pubkey,
+    pub visibility: PollVisibility,
+    pub access_restricted: bool,
+    pub weight_oracle: Pubkey,
+    pub max_weight_age: i64,
+    pub vote_fee: u64,
+    pub vote_fee_mint: Pubkey,
+    pub vote_fee_recipient: Pubkey,
+    pub max_extension: i64,
+    pub open_candidacy: bool,
+}
 
-    /// Add a candidate to a poll
-    pub fn add_candidate(
-        &self,
-        poll_id: u64,
-        name: String,
-        party: String,
-    ) -> Result<Signature> {
-        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
-        let (candidate_address, _) = get_candidate_address(&self.program_id, poll_id, &name);
+/// Whether a poll is listed alongside the others or only found by its ID
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum PollVisibility {
+    #[default]
+    Public,
+    Unlisted,
+}
 
-        let signature = self
-            .program
-            .request()
-            .accounts(voting_dapp::accounts::InitializeCandidate {
-                poll: poll_address,
-                candidate: candidate_address,
-                creator: self.program.payer(),
-                system_program: system_program::ID,
-            })
-            .args(voting_dapp::instruction::InitializeCandidate { name, party })
-            .send()?;
+/// Lifecycle stage of a poll
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PollStatus {
+    Draft,
+    Active,
+    Closed,
+}
 
-        Ok(signature)
+impl Poll {
+    /// Lifecycle stage at `now`, matching the on-chain `status_at`
+    pub fn status_at(&self, now: i64) -> PollStatus {
+        match self.status {
+            PollStatus::Active if now > self.end_time => PollStatus::Closed,
+            status => status,
+        }
     }
 
-    /// Cast a vote for a candidate
-    pub fn vote(&self, poll_id: u64, candidate_name: String) -> Result<Signature> {
-        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
-        let (candidate_address, _) =
-            get_candidate_address(&self.program_id, poll_id, &candidate_name);
-        let (receipt_address, _) =
-            get_receipt_address(&self.program_id, poll_id, &self.program.payer());
-
-        let signature = self
-            .program
-            .request()
-            .accounts(voting_dapp::accounts::Vote {
-                poll: poll_address,
-                candidate: candidate_address,
-                voter_receipt: receipt_address,
-                voter: self.program.payer(),
-                system_program: system_program::ID,
-            })
-            .args(voting_dapp::instruction::Vote {})
-            .send()?;
-
-        Ok(signature)
+    /// Whether votes are cast as encrypted ballots
+    pub fn is_shielded(&self) -> bool {
+        self.shielded_key != [0u8; 32]
     }
 
-    /// Get poll details
-    pub fn get_poll(&self, poll_id: u64) -> Result<Poll> {
-        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
-        let account = self.program.account::<Poll>(poll_address)?;
-        Ok(account)
+    /// Whether votes are weighed by the poll's oracle
+    pub fn is_oracle_weighted(&self) -> bool {
+        self.weight_oracle != Pubkey::default()
     }
 
-    /// Get all candidates for a poll along with their vote counts
-    pub fn get_poll_results(&self, poll_id: u64) -> Result<(Poll, Vec<Candidate>)> {
-        let poll = self.get_poll(poll_id)?;
-        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
-
-        // Fetch all candidate accounts for this poll
-        let accounts = self
-            .program
-            .accounts::<Candidate>(vec![
-                // Filter by discriminator and poll pubkey
-                anchor_client::solana_client::rpc_filter::RpcFilterType::Memcmp(
-                    anchor_client::solana_client::rpc_filter::Memcmp::new_raw_bytes(
-                        8, // Skip discriminator
-                        poll_address.to_bytes().to_vec(),
-                    ),
-                ),
-            ])?;
-
-        let mut candidates = Vec::new();
-        for (_, candidate) in accounts {
-            candidates.push(candidate);
+    /// The fee charged per standard vote, in lamports or in base units of the fee mint, or `None`
+    /// when voting is free
+    pub fn describe_vote_fee(&self) -> Option<String> {
+        if self.vote_fee == 0 {
+            None
+        } else if self.vote_fee_mint == Pubkey::default() {
+            Some(format!("{} lamports", self.vote_fee))
+        } else {
+            Some(format!("{} base units of mint {}", self.vote_fee, self.vote_fee_mint))
         }
+    }
 
-        // Sort candidates by name for consistent display
-        candidates.sort_by(|a, b| a.name.cmp(&b.name));
-
-        Ok((poll, candidates))
+    /// Scale of one unit in the poll's `votes` tallies: a full vote, or one SOL of stake
+    pub fn vote_scale(&self) -> u64 {
+        if self.stake_weighted {
+            LAMPORTS_PER_SOL
+        } else {
+            self.weight_decay.vote_scale()
+        }
     }
+}
 
-    /// Check if a user has voted in a poll
-    pub fn has_voted(&self, poll_id: u64, voter: Pubkey) -> Result<bool> {
-        let (receipt_address, _) = get_receipt_address(&self.program_id, poll_id, &voter);
+/// How a vote's weight changes over the voting period
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum WeightDecay {
+    #[default]
+    None,
+    /// Weight falls linearly from 100% at the start to `end_bps` basis points at the end
+    Linear { end_bps: u16 },
+}
 
-        match self.program.account::<VoterReceipt>(receipt_address) {
-            Ok(receipt) => Ok(receipt.has_voted),
-            Err(_) => Ok(false), // Receipt doesn't exist, so user hasn't voted
+impl WeightDecay {
+    /// Scale of one full-weight vote in a poll's `votes` tally
+    pub fn vote_scale(&self) -> u64 {
+        match self {
+            WeightDecay::None => 1,
+            WeightDecay::Linear { .. } => 10_000,
         }
     }
 }
 
-// Define the instruction and account structs for the program
-mod voting_dapp {
-    use super::*;
+/// Event emitted by the program whenever a vote is counted
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct TallyUpdated {
+    pub poll: Pubkey,
+    pub candidate: Pubkey,
+    pub votes: u64,
+    pub raw_votes: u64,
+    pub total_votes: u64,
+}
 
-    pub mod instruction {
-        use super::*;
+impl anchor_client::anchor_lang::Discriminator for TallyUpdated {
+    const DISCRIMINATOR: [u8; 8] = [129, 78, 53, 66, 58, 54, 61, 112];
+}
 
-        #[derive(AnchorSerialize, AnchorDeserialize)]
-        pub struct InitializePoll {
-            pub poll_id: u64,
-            pub question: String,
-            pub description: String,
-            pub start_time: i64,
-            pub end_time: i64,
-        }
+/// Event emitted by the program whenever lamports are withdrawn from the treasury
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct TreasuryWithdrawal {
+    pub admin: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
 
-        impl anchor_client::anchor_lang::Discriminator for InitializePoll {
-            const DISCRIMINATOR: [u8; 8] = [155, 234, 66, 103, 52, 251, 109, 89];
-        }
+impl anchor_client::anchor_lang::Discriminator for TreasuryWithdrawal {
+    const DISCRIMINATOR: [u8; 8] = [244, 117, 175, 46, 187, 109, 20, 16];
+}
 
-        impl anchor_client::anchor_lang::InstructionData for InitializePoll {
-            fn data(&self) -> Vec<u8> {
-                let mut data = Self::DISCRIMINATOR.to_vec();
-                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
-                data
+/// Order in which a poll's candidates are listed
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CandidateOrder {
+    /// Registration order, as shown on the ballot
+    #[default]
+    Ballot,
+    /// Most votes first
+    Votes,
+    /// Alphabetical by name
+    Name,
+    /// Shuffled by the poll's verifiable ballot order seed (registration order until drawn)
+    Random,
+}
+
+/// Sort candidates in the given order, breaking ties by name
+pub fn sort_candidates(candidates: &mut [Candidate], order: CandidateOrder, ballot_order_seed: &[u8; 32]) {
+    candidates.sort_by(|a, b| {
+        let primary = match order {
+            CandidateOrder::Ballot => a.index.cmp(&b.index),
+            CandidateOrder::Votes => b.votes.cmp(&a.votes),
+            CandidateOrder::Name => std::cmp::Ordering::Equal,
+            CandidateOrder::Random if *ballot_order_seed == [0u8; 32] => a.index.cmp(&b.index),
+            CandidateOrder::Random => {
+                random_rank(ballot_order_seed, a.index).cmp(&random_rank(ballot_order_seed, b.index))
             }
-        }
+        };
+        primary.then_with(|| a.name.cmp(&b.name))
+    });
+}
 
-        #[derive(AnchorSerialize, AnchorDeserialize)]
-        pub struct InitializeCandidate {
-            pub name: String,
-            pub party: String,
-        }
+/// Rank of a candidate under a random seed, matching the on-chain `random_rank`: lower ranks
+/// come first on a shuffled ballot and win tie-breaks
+pub fn random_rank(seed: &[u8; 32], index: u16) -> [u8; 32] {
+    hashv(&[seed.as_ref(), index.to_le_bytes().as_ref()]).to_bytes()
+}
 
-        impl anchor_client::anchor_lang::Discriminator for InitializeCandidate {
-            const DISCRIMINATOR: [u8; 8] = [248, 73, 66, 106, 202, 55, 70, 196];
-        }
+/// Filters applied when listing polls
+#[derive(Debug, Clone, Default)]
+pub struct PollFilter {
+    /// Only polls created by this wallet (matched on-chain via memcmp)
+    pub creator: Option<Pubkey>,
+    /// Only polls in this category (case-insensitive)
+    pub category: Option<String>,
+    /// Only polls carrying this tag (case-insensitive)
+    pub tag: Option<String>,
+    /// Only polls accepting votes at this time
+    pub active_at: Option<i64>,
+}
 
-        impl anchor_client::anchor_lang::InstructionData for InitializeCandidate {
-            fn data(&self) -> Vec<u8> {
-                let mut data = Self::DISCRIMINATOR.to_vec();
-                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
-                data
-            }
-        }
+impl PollFilter {
+    fn matches(&self, poll: &Poll) -> bool {
+        let category_matches = self
+            .category
+            .as_ref()
+            .is_none_or(|category| poll.category.eq_ignore_ascii_case(category));
+        let tag_matches = self
+            .tag
+            .as_ref()
+            .is_none_or(|tag| poll.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+        let active_matches = self
+            .active_at
+            .is_none_or(|now| poll.status_at(now) == PollStatus::Active && now >= poll.start_time);
+        poll.visibility == PollVisibility::Public && category_matches && tag_matches && active_matches
+    }
+}
 
-        #[derive(AnchorSerialize, AnchorDeserialize)]
-        pub struct Vote {}
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Serialize)]
+pub struct Candidate {
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub poll: Pubkey,
+    pub name: String,
+    pub party: String,
+    pub votes: u64,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub payout_wallet: Pubkey,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub depositor: Pubkey,
+    pub deposit: u64,
+    pub metadata_uri: String,
+    pub version: u8,
+    pub raw_votes: u64,
+    pub index: u16,
+    pub is_winner: bool,
+    pub bump: u8,
+}
 
-        impl anchor_client::anchor_lang::Discriminator for Vote {
-            const DISCRIMINATOR: [u8; 8] = [227, 110, 155, 23, 136, 126, 172, 25];
+impl anchor_client::anchor_lang::AccountDeserialize for Candidate {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
         }
-
-        impl anchor_client::anchor_lang::InstructionData for Vote {
-            fn data(&self) -> Vec<u8> {
-                Self::DISCRIMINATOR.to_vec()
-            }
+        let given_disc = &buf[0..8];
+        if Self::DISCRIMINATOR != given_disc {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
         }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
     }
 
-    pub mod accounts {
-        use super::*;
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        Self::deserialize(buf)
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
 
-        pub struct InitializePoll {
-            pub poll: Pubkey,
-            pub creator: Pubkey,
-            pub system_program: Pubkey,
-        }
+impl anchor_client::anchor_lang::Discriminator for Candidate {
+    const DISCRIMINATOR: [u8; 8] = [86, 69, 250, 96, 193, 10, 222, 123];
+}
 
-        impl anchor_client::anchor_lang::ToAccountMetas for InitializePoll {
-            fn to_account_metas(
-                &self,
-                _is_signer: Option<bool>,
-            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
-                vec![
-                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
-                        self.poll,
-                        false,
-                    ),
-                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
-                        self.creator,
-                        true,
-                    ),
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Serialize)]
+pub struct VoterReceipt {
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub poll: Pubkey,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub voter: Pubkey,
+    pub has_voted: bool,
+    pub reward_claimed: bool,
+    pub version: u8,
+    pub weight: u64,
+    pub allocations: Vec<GaugeAllocation>,
+    pub bump: u8,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub payer: Pubkey,
+}
+
+/// One candidate of a batch registration
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct CandidateEntry {
+    pub name: String,
+    pub party: String,
+}
+
+/// A candidate to add in bulk. The batch instruction takes no metadata, so a URI is set
+/// once the candidate exists.
+#[derive(Debug, Clone)]
+pub struct NewCandidate {
+    pub name: String,
+    pub party: String,
+    pub metadata_uri: String,
+}
+
+/// Points a gauge vote gave to one candidate
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Serialize)]
+pub struct GaugeAllocation {
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub candidate: Pubkey,
+    pub points: u16,
+}
+
+impl anchor_client::anchor_lang::AccountDeserialize for VoterReceipt {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+        }
+        let given_disc = &buf[0..8];
+        if Self::DISCRIMINATOR != given_disc {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        Self::deserialize(buf)
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl anchor_client::anchor_lang::Discriminator for VoterReceipt {
+    const DISCRIMINATOR: [u8; 8] = [94, 230, 87, 51, 169, 216, 144, 98];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ShieldedTally {
+    pub poll: Pubkey,
+    pub totals: Vec<ElGamalCiphertext>,
+}
+
+impl anchor_client::anchor_lang::AccountDeserialize for ShieldedTally {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+        }
+        let given_disc = &buf[0..8];
+        if Self::DISCRIMINATOR != given_disc {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        Self::deserialize(buf)
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl anchor_client::anchor_lang::Discriminator for ShieldedTally {
+    const DISCRIMINATOR: [u8; 8] = [20, 195, 235, 48, 8, 160, 189, 14];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct Ballot {
+    pub ballot_id: u64,
+    pub creator: Pubkey,
+    pub title: String,
+    pub polls: Vec<Pubkey>,
+}
+
+impl anchor_client::anchor_lang::AccountDeserialize for Ballot {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+        }
+        let given_disc = &buf[0..8];
+        if Self::DISCRIMINATOR != given_disc {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        Self::deserialize(buf)
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl anchor_client::anchor_lang::Discriminator for Ballot {
+    const DISCRIMINATOR: [u8; 8] = [3, 232, 121, 204, 232, 137, 138, 164];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct Session {
+    pub voter: Pubkey,
+    pub ballot: Pubkey,
+    pub session_key: Pubkey,
+    pub expiry_slot: u64,
+    pub bump: u8,
+}
+
+impl anchor_client::anchor_lang::AccountDeserialize for Session {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+        }
+        let given_disc = &buf[0..8];
+        if Self::DISCRIMINATOR != given_disc {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        Self::deserialize(buf)
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl anchor_client::anchor_lang::Discriminator for Session {
+    const DISCRIMINATOR: [u8; 8] = [243, 81, 72, 115, 214, 188, 72, 144];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct PollResult {
+    pub poll: Pubkey,
+    pub auditors: Vec<Pubkey>,
+    pub attestations: u16,
+    pub attestation_count: u8,
+    pub winner: Pubkey,
+    pub total_votes: u64,
+    pub bump: u8,
+}
+
+impl PollResult {
+    /// Whether the auditor at `index` has attested the result
+    pub fn has_attested(&self, index: usize) -> bool {
+        self.attestations & (1 << index) != 0
+    }
+}
+
+impl anchor_client::anchor_lang::AccountDeserialize for PollResult {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+        }
+        let given_disc = &buf[0..8];
+        if Self::DISCRIMINATOR != given_disc {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        Self::deserialize(buf)
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl anchor_client::anchor_lang::Discriminator for PollResult {
+    const DISCRIMINATOR: [u8; 8] = [139, 201, 153, 117, 71, 38, 98, 60];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct PollArchive {
+    pub poll_id: u64,
+    pub creator: Pubkey,
+    pub question: String,
+    pub winner: Pubkey,
+    pub total_votes: u64,
+    pub end_time: i64,
+    pub archived_at: i64,
+    pub candidates: Vec<ArchivedCandidate>,
+}
+
+/// Tokens locked behind a conviction vote
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ConvictionLock {
+    pub poll: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+    pub multiplier: u8,
+    pub expiry: i64,
+    pub unlocked: bool,
+}
+
+impl anchor_client::anchor_lang::AccountDeserialize for ConvictionLock {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+        }
+        let given_disc = &buf[0..8];
+        if Self::DISCRIMINATOR != given_disc {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        Self::deserialize(buf)
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl anchor_client::anchor_lang::Discriminator for ConvictionLock {
+    const DISCRIMINATOR: [u8; 8] = [183, 136, 186, 16, 167, 40, 71, 234];
+}
+
+/// How long a conviction vote keeps its tokens locked past the poll's end
+#[derive(clap::ValueEnum, AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockPeriod {
+    /// Until the poll ends (1x weight)
+    #[default]
+    PollEnd,
+    /// 30 days past the end (2x weight)
+    Month,
+    /// 90 days past the end (4x weight)
+    Quarter,
+}
+
+impl LockPeriod {
+    /// Weight multiplier earned by the lock, matching the program
+    pub fn multiplier(&self) -> u64 {
+        match self {
+            LockPeriod::PollEnd => 1,
+            LockPeriod::Month => 2,
+            LockPeriod::Quarter => 4,
+        }
+    }
+}
+
+/// Final totals of one candidate, as recorded in a poll archive
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ArchivedCandidate {
+    pub name: String,
+    pub votes: u64,
+    pub raw_votes: u64,
+    pub is_winner: bool,
+}
+
+impl anchor_client::anchor_lang::AccountDeserialize for PollArchive {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+        }
+        let given_disc = &buf[0..8];
+        if Self::DISCRIMINATOR != given_disc {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        Self::deserialize(buf)
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl anchor_client::anchor_lang::Discriminator for PollArchive {
+    const DISCRIMINATOR: [u8; 8] = [120, 183, 204, 54, 2, 240, 212, 70];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct PollProposal {
+    pub poll_id: u64,
+    pub creator: Pubkey,
+    pub question: String,
+    pub approvals: Vec<Pubkey>,
+}
+
+impl anchor_client::anchor_lang::AccountDeserialize for PollProposal {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+        }
+        let given_disc = &buf[0..8];
+        if Self::DISCRIMINATOR != given_disc {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        Self::deserialize(buf)
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl anchor_client::anchor_lang::Discriminator for PollProposal {
+    const DISCRIMINATOR: [u8; 8] = [208, 212, 116, 225, 247, 179, 112, 204];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct Proposal {
+    pub poll: Pubkey,
+    pub yes_candidate: Pubkey,
+    pub threshold_bps: u16,
+    pub program_id: Pubkey,
+    pub accounts: Vec<ProposalAccountMeta>,
+    pub data: Vec<u8>,
+    pub executed: bool,
+}
+
+/// Serializable form of an `AccountMeta`, as stored in a proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ProposalAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl anchor_client::anchor_lang::AccountDeserialize for Proposal {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        if buf.len() < 8 {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+        }
+        let given_disc = &buf[0..8];
+        if Self::DISCRIMINATOR != given_disc {
+            return Err(anchor_client::anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_client::anchor_lang::Result<Self> {
+        Self::deserialize(buf)
+            .map_err(|_| anchor_client::anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl anchor_client::anchor_lang::Discriminator for Proposal {
+    const DISCRIMINATOR: [u8; 8] = [26, 94, 189, 187, 116, 136, 53, 33];
+}
+
+/// What one instruction in a poll's history did
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PollAction {
+    PollCreated {
+        #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+        creator: Pubkey,
+    },
+    CandidatesAdded {
+        names: Vec<String>,
+    },
+    /// A ballot, naming its candidate unless the vote is shielded or the candidate is gone
+    Vote {
+        #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+        voter: Pubkey,
+        candidate: Option<String>,
+    },
+    Other {
+        description: &'static str,
+    },
+}
+
+impl std::fmt::Display for PollAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PollAction::PollCreated { creator } => write!(f, "poll created by {}", creator),
+            PollAction::CandidatesAdded { names } => write!(f, "candidate added: {}", names.join(", ")),
+            PollAction::Vote { voter, candidate: Some(candidate) } => write!(f, "vote by {} for {}", voter, candidate),
+            PollAction::Vote { voter, candidate: None } => write!(f, "vote by {}", voter),
+            PollAction::Other { description } => f.write_str(description),
+        }
+    }
+}
+
+/// A transaction that touched a poll, with what it did to the poll
+#[derive(Debug, Clone, Serialize)]
+pub struct PollHistoryEntry {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// Why the transaction failed; a failed transaction changed nothing
+    pub error: Option<String>,
+    pub actions: Vec<PollAction>,
+}
+
+/// An account given to a decoded instruction
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedAccount {
+    /// Name in the program's account struct, or its position past the named accounts
+    pub name: String,
+    #[serde(serialize_with = "crate::utils::serialize_pubkey")]
+    pub address: Pubkey,
+    pub is_signer: bool,
+}
+
+/// An instruction of the program decoded from a transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedInstruction {
+    pub name: &'static str,
+    /// The args as Rust would print them; `None` when the data doesn't decode
+    pub args: Option<String>,
+    /// Raw instruction data in hex
+    pub data: String,
+    pub accounts: Vec<DecodedAccount>,
+}
+
+/// A websocket subscription to an account, delivering updates on a thread of its own until it is
+/// dropped or unsubscribed
+pub struct Subscription {
+    subscription: PubsubAccountClientSubscription,
+    listener: std::thread::JoinHandle<()>,
+}
+
+impl Subscription {
+    /// End the subscription, waiting for the callback to return from an update in progress
+    pub fn unsubscribe(self) -> Result<()> {
+        // Closing the socket ends the stream of updates, and with it the listener
+        drop(self.subscription);
+        self.listener
+            .join()
+            .map_err(|_| anyhow::anyhow!("The subscription callback panicked"))
+    }
+}
+
+/// A simulated transaction, returned as the error of a sending method in dry-run mode so that
+/// nothing after it is submitted
+#[derive(Debug, Serialize)]
+pub struct DryRun {
+    pub error: Option<String>,
+    pub units_consumed: Option<u64>,
+    pub fee: u64,
+    pub logs: Vec<String>,
+}
+
+impl std::fmt::Display for DryRun {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Dry run, nothing was submitted")
+    }
+}
+
+impl std::error::Error for DryRun {}
+
+/// A transaction built in offline mode, returned as the error of a sending method in place of
+/// sending it. Nothing is signed yet.
+#[derive(Debug)]
+pub struct Unsent {
+    pub transaction: Transaction,
+    /// Durable nonce the transaction advances; without one it expires with its recent blockhash
+    pub nonce_account: Option<Pubkey>,
+}
+
+impl std::fmt::Display for Unsent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transaction built for offline signing, nothing was submitted")
+    }
+}
+
+impl std::error::Error for Unsent {}
+
+/// Compute budget instructions added to every transaction the client sends
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ComputeBudget {
+    /// Price per compute unit, in micro-lamports
+    pub priority_fee: Option<u64>,
+    /// Price each transaction from recent prioritization fees when no fixed price is set
+    pub auto_priority_fee: bool,
+    /// Compute unit limit
+    pub compute_limit: Option<u32>,
+}
+
+/// How sending retries after failures that may pass: the RPC node not answering, or a blockhash
+/// expiring before the transaction landed. Transactions the program rejects are never retried.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RetryPolicy {
+    /// Attempts after the first
+    pub retries: u32,
+    /// Wait before the first retry, doubled for each next one
+    pub backoff: std::time::Duration,
+}
+
+/// Whether a send failed in a way a retry may get past
+fn is_transient(error: &ClientError) -> bool {
+    let ClientError::SolanaClientError(error) = error else {
+        return false;
+    };
+    let blockhash_not_found = |e: &TransactionError| *e == TransactionError::BlockhashNotFound;
+    match error.kind() {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        ClientErrorKind::TransactionError(e) => blockhash_not_found(e),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(simulation),
+            ..
+        }) => simulation.err.as_ref().is_some_and(blockhash_not_found),
+        // Confirmation gives up once the blockhash expires
+        ClientErrorKind::RpcError(RpcError::ForUser(message)) => message.starts_with("unable to confirm transaction"),
+        _ => false,
+    }
+}
+
+pub struct VotingClient<C: Signer> {
+    program: Program<Rc<C>>,
+    program_id: Pubkey,
+    dry_run: bool,
+    compute_budget: ComputeBudget,
+    retry_policy: RetryPolicy,
+    offline: bool,
+    nonce_account: Option<Pubkey>,
+    multisig: Option<Pubkey>,
+    ws_url: Option<String>,
+}
+
+impl<C: Signer> VotingClient<C> {
+    pub fn new(client: Client<Rc<C>>, program_id: Pubkey) -> Self {
+        let program = client.program(program_id).unwrap();
+        Self {
+            program,
+            program_id,
+            dry_run: false,
+            compute_budget: ComputeBudget::default(),
+            retry_policy: RetryPolicy::default(),
+            offline: false,
+            nonce_account: None,
+            multisig: None,
+            ws_url: None,
+        }
+    }
+
+    /// Simulate transactions instead of sending them; the first one ends the command with a `DryRun`
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Build transactions instead of sending them, against a durable nonce if given and otherwise a
+    /// recent blockhash; the first one ends the command with an `Unsent` holding it, to be signed
+    /// and submitted later
+    pub fn offline(mut self, nonce_account: Option<Pubkey>) -> Self {
+        self.offline = true;
+        self.nonce_account = nonce_account;
+        self
+    }
+
+    /// Act from a Squads multisig's vault: transactions are proposed to the multisig, created and
+    /// paid for by the client's keypair, and take effect once members approve and execute them
+    pub fn with_multisig(mut self, multisig: Pubkey) -> Self {
+        self.multisig = Some(multisig);
+        self
+    }
+
+    /// Websocket URL of the cluster, needed for subscriptions
+    pub fn with_ws_url(mut self, ws_url: &str) -> Self {
+        self.ws_url = Some(ws_url.to_string());
+        self
+    }
+
+    /// Set the compute unit limit and priority fee of every transaction sent from now on
+    pub fn with_compute_budget(mut self, compute_budget: ComputeBudget) -> Self {
+        self.compute_budget = compute_budget;
+        self
+    }
+
+    /// Whether transactions are sent, rather than simulated or built for offline signing
+    pub fn sends_transactions(&self) -> bool {
+        !self.dry_run && !self.offline
+    }
+
+    /// Simulate the first transaction `send` would send, to preview its fee and outcome before
+    /// sending it for real. Errors `send` raises before that are returned as they are.
+    pub fn preview<T>(&mut self, send: impl FnOnce(&Self) -> Result<T>) -> Result<DryRun> {
+        let dry_run = std::mem::replace(&mut self.dry_run, true);
+        let outcome = send(self);
+        self.dry_run = dry_run;
+        match outcome {
+            Ok(_) => Err(anyhow::anyhow!("Nothing was sent to preview")),
+            Err(e) => e.downcast::<DryRun>(),
+        }
+    }
+
+    /// Retry sending under a policy; without one, a failed send fails the command at once
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Add the configured compute budget instructions to a request. A limit or price the request
+    /// already sets is kept, since a transaction may only carry one of each.
+    #[instrument(level = "debug", skip_all)]
+    fn apply_compute_budget<'a>(
+        &self,
+        mut request: RequestBuilder<'a, Rc<C>, Box<dyn Signer + 'a>>,
+    ) -> Result<RequestBuilder<'a, Rc<C>, Box<dyn Signer + 'a>>> {
+        let budget = self.compute_budget;
+        if budget.compute_limit.is_none() && budget.priority_fee.is_none() && !budget.auto_priority_fee {
+            return Ok(request);
+        }
+        let instructions = request.instructions()?;
+        // Borsh tags of SetComputeUnitLimit and SetComputeUnitPrice
+        let sets = |tag: u8| {
+            instructions.iter().any(|instruction| {
+                instruction.program_id == compute_budget::ID && instruction.data.first() == Some(&tag)
+            })
+        };
+
+        if let Some(units) = budget.compute_limit.filter(|_| !sets(2)) {
+            request = request.instruction(ComputeBudgetInstruction::set_compute_unit_limit(units));
+        }
+        let price = match budget.priority_fee {
+            Some(price) => Some(price),
+            None if budget.auto_priority_fee => Some(self.recent_priority_fee(&instructions)?),
+            None => None,
+        };
+        if let Some(price) = price.filter(|_| !sets(3)) {
+            request = request.instruction(ComputeBudgetInstruction::set_compute_unit_price(price));
+        }
+        Ok(request)
+    }
+
+    /// The 75th percentile of the prioritization fees recently paid to write the accounts the
+    /// instructions write, in micro-lamports per compute unit
+    #[instrument(level = "debug", skip_all)]
+    fn recent_priority_fee(&self, instructions: &[Instruction]) -> Result<u64> {
+        let mut writable: Vec<Pubkey> = instructions
+            .iter()
+            .flat_map(|instruction| instruction.accounts.iter())
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .collect();
+        writable.sort();
+        writable.dedup();
+        // RPC nodes take at most 128 addresses
+        writable.truncate(128);
+
+        let mut fees: Vec<u64> = self
+            .program
+            .rpc()
+            .get_recent_prioritization_fees(&writable)?
+            .into_iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+        if fees.is_empty() {
+            return Ok(0);
+        }
+        fees.sort_unstable();
+        Ok(fees[(fees.len() - 1) * 3 / 4])
+    }
+
+    /// Send a request, or with a multisig propose it. In dry-run mode the transaction is simulated
+    /// and the outcome returned as a `DryRun` error, and in offline mode it is built unsigned and
+    /// returned as an `Unsent` error.
+    #[instrument(level = "debug", skip_all)]
+    fn submit(&self, request: RequestBuilder<'_, Rc<C>, Box<dyn Signer + '_>>) -> Result<Signature> {
+        match self.multisig {
+            Some(multisig) => self.send_request(self.multisig_proposal(multisig, request.instructions()?)?),
+            None => self.send_request(request),
+        }
+    }
+
+    /// Wrap instructions in a vault transaction of the multisig and open its proposal
+    #[instrument(level = "debug", skip(self, instructions))]
+    fn multisig_proposal(
+        &self,
+        multisig: Pubkey,
+        instructions: Vec<Instruction>,
+    ) -> Result<RequestBuilder<'_, Rc<C>, Box<dyn Signer + '_>>> {
+        let account = self
+            .program
+            .rpc()
+            .get_account_data(&multisig)
+            .map_err(|e| anyhow::anyhow!("Failed to read multisig {}: {}", multisig, e))?;
+        let transaction_index = squads::transaction_index(&account)? + 1;
+        let message = squads::compile_vault_message(&self.payer_pubkey(), &instructions)?;
+        let member = self.program.payer();
+
+        Ok(self
+            .program
+            .request()
+            .instruction(squads::vault_transaction_create_instruction(
+                &multisig,
+                transaction_index,
+                &member,
+                message,
+                Some("Proposed with voting-cli".to_string()),
+            )?)
+            .instruction(squads::proposal_create_instruction(&multisig, transaction_index, &member)?))
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    fn send_request(&self, request: RequestBuilder<'_, Rc<C>, Box<dyn Signer + '_>>) -> Result<Signature> {
+        let request = self.apply_compute_budget(request)?;
+        if self.offline {
+            return Err(self.unsigned_transaction(request.instructions()?)?.into());
+        }
+        if !self.dry_run {
+            return self.send_with_retries(|| request.signed_transaction());
+        }
+        Err(self.simulate(&request.signed_transaction()?)?.into())
+    }
+
+    /// Send a transaction from `sign` and wait for it to be confirmed, retrying under the retry
+    /// policy. A retry resends the same transaction while its blockhash is valid, so it can't land
+    /// twice, and asks `sign` for a new one once the blockhash has expired. Retries are logged as
+    /// warnings, and the attempt that succeeded after them as info.
+    #[instrument(level = "debug", skip_all)]
+    fn send_with_retries(&self, mut sign: impl FnMut() -> Result<Transaction, ClientError>) -> Result<Signature> {
+        let rpc = self.program.rpc();
+        let attempts = self.retry_policy.retries + 1;
+        let mut delay = self.retry_policy.backoff;
+        let mut transaction = None;
+        for attempt in 1..=attempts {
+            match Self::send_attempt(&rpc, &mut sign, &mut transaction) {
+                Ok(signature) => {
+                    if attempt > 1 {
+                        tracing::info!(%signature, "Sent on attempt {} of {}", attempt, attempts);
+                    } else {
+                        tracing::debug!(%signature, "Confirmed");
+                    }
+                    return Ok(signature);
+                }
+                Err(e) if attempt < attempts && is_transient(&e) => {
+                    tracing::warn!(
+                        "Attempt {} of {} failed: {}; retrying in {} ms",
+                        attempt,
+                        attempts,
+                        e,
+                        delay.as_millis()
+                    );
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                }
+                Err(e) if attempt > 1 => return Err(anyhow::anyhow!("Failed after {} attempts: {}", attempt, e)),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        unreachable!("the last attempt returns")
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    fn send_attempt(
+        rpc: &RpcClient,
+        sign: &mut impl FnMut() -> Result<Transaction, ClientError>,
+        transaction: &mut Option<Transaction>,
+    ) -> Result<Signature, ClientError> {
+        if let Some(previous) = transaction.as_ref() {
+            // The previous attempt may have landed even though confirming it failed
+            let signature = previous.signatures[0];
+            if let Some(status) = rpc.get_signature_status(&signature)? {
+                return status.map(|_| signature).map_err(|e| RpcClientError::from(e).into());
+            }
+            if !rpc.is_blockhash_valid(&previous.message.recent_blockhash, rpc.commitment())? {
+                *transaction = None;
+            }
+        }
+        if transaction.is_none() {
+            *transaction = Some(sign()?);
+        }
+        Ok(rpc.send_and_confirm_transaction(transaction.as_ref().expect("signed above"))?)
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    fn simulate(&self, transaction: &Transaction) -> Result<DryRun> {
+        let rpc = self.program.rpc();
+        let simulation = rpc.simulate_transaction(transaction)?.value;
+        Ok(DryRun {
+            error: simulation.err.map(|err| err.to_string()),
+            units_consumed: simulation.units_consumed,
+            fee: rpc.get_fee_for_message(&transaction.message)?,
+            logs: simulation.logs.unwrap_or_default(),
+        })
+    }
+
+    /// Build an unsigned transaction. With a nonce account it first advances the nonce and uses
+    /// its stored blockhash, so it stays valid until the nonce is advanced again.
+    #[instrument(level = "debug", skip_all)]
+    fn unsigned_transaction(&self, instructions: Vec<Instruction>) -> Result<Unsent> {
+        let Some(nonce_account) = self.nonce_account else {
+            let mut transaction = Transaction::new_with_payer(&instructions, Some(&self.program.payer()));
+            transaction.message.recent_blockhash = self.program.rpc().get_latest_blockhash()?;
+            return Ok(Unsent {
+                transaction,
+                nonce_account: None,
+            });
+        };
+        let account = nonce_utils::get_account(&self.program.rpc(), &nonce_account)
+            .map_err(|e| anyhow::anyhow!("Failed to read nonce account {}: {}", nonce_account, e))?;
+        let nonce = nonce_utils::data_from_account(&account)
+            .map_err(|e| anyhow::anyhow!("{} is not an initialized nonce account: {}", nonce_account, e))?;
+
+        let instructions: Vec<Instruction> =
+            std::iter::once(system_instruction::advance_nonce_account(&nonce_account, &nonce.authority))
+                .chain(instructions)
+                .collect();
+        let mut transaction = Transaction::new_with_payer(&instructions, Some(&self.program.payer()));
+        transaction.message.recent_blockhash = nonce.blockhash();
+        Ok(Unsent {
+            transaction,
+            nonce_account: Some(nonce_account),
+        })
+    }
+
+    /// Send a transaction signed elsewhere, or simulate it in dry-run mode
+    #[instrument(level = "debug", skip_all)]
+    pub fn send_signed(&self, transaction: &Transaction) -> Result<Signature> {
+        if self.dry_run {
+            return Err(self.simulate(transaction)?.into());
+        }
+        // Signed elsewhere, so an expired transaction can only be resent as it is
+        self.send_with_retries(|| Ok(transaction.clone()))
+    }
+
+    /// The account the client acts as: its keypair, or a multisig's vault
+    pub fn payer_pubkey(&self) -> Pubkey {
+        match self.multisig {
+            Some(multisig) => squads::get_vault_address(&multisig, squads::VAULT_INDEX).0,
+            None => self.program.payer(),
+        }
+    }
+
+    /// The payer's balance, in lamports
+    pub fn payer_balance(&self) -> Result<u64> {
+        self.balance(&self.payer_pubkey())
+    }
+
+    /// An account's balance, in lamports
+    #[instrument(level = "debug", skip(self))]
+    pub fn balance(&self, address: &Pubkey) -> Result<u64> {
+        Ok(self.program.rpc().get_balance(address)?)
+    }
+
+    /// Airdrop lamports to an account and wait until they land (localnet and devnet only). Faucets
+    /// often rate-limit or drop requests, so a failed request is retried with a growing delay.
+    pub fn request_airdrop(&self, recipient: &Pubkey, lamports: u64) -> Result<Signature> {
+        let rpc = self.program.rpc();
+        let mut delay = AIRDROP_RETRY_DELAY;
+        for attempt in 1..=AIRDROP_ATTEMPTS {
+            let outcome = rpc
+                .request_airdrop(recipient, lamports)
+                .and_then(|signature| rpc.poll_for_signature(&signature).map(|_| signature));
+            match outcome {
+                Ok(signature) => return Ok(signature),
+                Err(e) if attempt == AIRDROP_ATTEMPTS => {
+                    return Err(anyhow::anyhow!("Airdrop failed after {} attempts: {}", AIRDROP_ATTEMPTS, e));
+                }
+                Err(_) => {
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                }
+            }
+        }
+        unreachable!("the last attempt returns")
+    }
+
+    /// Send `lamports` from the payer to each recipient, several per transaction. Returns the
+    /// outcome of each transaction, covering `TRANSFER_BATCH_SIZE` recipients in order; a failed
+    /// transaction doesn't stop the ones after it.
+    pub fn fund_accounts(&self, recipients: &[Pubkey], lamports: u64) -> Result<Vec<Result<Signature>>> {
+        let mut outcomes = Vec::new();
+        for batch in recipients.chunks(TRANSFER_BATCH_SIZE) {
+            let mut request = self.program.request();
+            for recipient in batch {
+                request = request.instruction(system_instruction::transfer(&self.payer_pubkey(), recipient, lamports));
+            }
+            match self.submit(request) {
+                // A dry run stops at the first batch rather than reporting it against its recipients
+                Err(e) if e.is::<DryRun>() => return Err(e),
+                outcome => outcomes.push(outcome),
+            }
+        }
+        Ok(outcomes)
+    }
+
+    /// Lamports an account of `space` bytes must hold to be rent-exempt
+    pub fn rent_exempt_minimum(&self, space: usize) -> Result<u64> {
+        Ok(self.program.rpc().get_minimum_balance_for_rent_exemption(space)?)
+    }
+
+    /// The cluster's current Unix time, read from the clock sysvar the program checks against
+    #[instrument(level = "debug", skip_all)]
+    pub fn cluster_time(&self) -> Result<i64> {
+        let clock = self.program.rpc().get_account_data(&anchor_client::solana_sdk::sysvar::clock::ID)?;
+        // `unix_timestamp` follows the slot, epoch start timestamp, epoch and leader schedule epoch
+        let unix_timestamp = clock
+            .get(32..40)
+            .ok_or_else(|| anyhow::anyhow!("The clock sysvar is too short"))?;
+        Ok(i64::from_le_bytes(unix_timestamp.try_into()?))
+    }
+
+    /// Initialize the global config and treasury
+    pub fn initialize_config(&self, poll_creation_fee: u64) -> Result<Signature> {
+        let (config_address, _) = get_config_address(&self.program_id);
+        let (treasury_address, _) = get_treasury_address(&self.program_id);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::InitializeConfig {
+                config: config_address,
+                treasury: treasury_address,
+                program_data: get_program_data_address(&self.program_id).0,
+                admin: self.payer_pubkey(),
+                system_program: system_program::ID,
+            })
+            .args(voting_dapp::instruction::InitializeConfig { poll_creation_fee });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Withdraw lamports from the treasury to a recipient
+    pub fn withdraw_treasury(&self, amount: u64, recipient: Pubkey) -> Result<Signature> {
+        let (config_address, _) = get_config_address(&self.program_id);
+        let (treasury_address, _) = get_treasury_address(&self.program_id);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::WithdrawTreasury {
+                config: config_address,
+                treasury: treasury_address,
+                admin: self.payer_pubkey(),
+                recipient,
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .args(voting_dapp::instruction::WithdrawTreasury { amount });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Change the poll creation fee charged into the treasury (admin only)
+    pub fn set_fee_schedule(&self, poll_creation_fee: u64) -> Result<Signature> {
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::SetFeeSchedule {
+                config: get_config_address(&self.program_id).0,
+                admin: self.payer_pubkey(),
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .args(voting_dapp::instruction::SetFeeSchedule { poll_creation_fee });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// The most recent treasury withdrawals, newest first, each with the transaction it was
+    /// made in and that transaction's block time. Scans the latest `limit` treasury transactions.
+    pub fn get_treasury_withdrawals(&self, limit: usize) -> Result<Vec<(TreasuryWithdrawal, Signature, Option<i64>)>> {
+        let (treasury_address, _) = get_treasury_address(&self.program_id);
+        let rpc = self.program.rpc();
+        let statuses = rpc.get_signatures_for_address_with_config(
+            &treasury_address,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(limit),
+                commitment: Some(history_commitment(&rpc)),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )?;
+
+        let mut withdrawals = Vec::new();
+        for status in statuses.iter().filter(|status| status.err.is_none()) {
+            let signature: Signature = status.signature.parse()?;
+            let events: Vec<TreasuryWithdrawal> = fetch_cpi_events(&rpc, &self.program_id, &signature)?;
+            withdrawals.extend(events.into_iter().map(|event| (event, signature, status.block_time)));
+        }
+        Ok(withdrawals)
+    }
+
+    /// Get the global config
+    #[instrument(level = "debug", skip_all)]
+    pub fn get_config(&self) -> Result<Config> {
+        let (config_address, _) = get_config_address(&self.program_id);
+        let account = self.program.account::<Config>(config_address)?;
+        Ok(account)
+    }
+
+    /// Fetch a wallet's poll creation history
+    pub fn get_creator_stats(&self, creator: &Pubkey) -> Result<CreatorStats> {
+        let (stats_address, _) = get_creator_stats_address(&self.program_id, creator);
+        let account = self.program.account::<CreatorStats>(stats_address)?;
+        Ok(account)
+    }
+
+    /// Set the council that must approve new polls (admin only); a threshold of 0 disables it
+    pub fn set_council(&self, members: Vec<Pubkey>, threshold: u8) -> Result<Signature> {
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::SetCouncil {
+                config: get_config_address(&self.program_id).0,
+                admin: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::SetCouncil { members, threshold });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Turn the creator allowlist on or off (admin only)
+    pub fn set_creator_allowlist(&self, enabled: bool) -> Result<Signature> {
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::SetCreatorAllowlist {
+                config: get_config_address(&self.program_id).0,
+                admin: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::SetCreatorAllowlist { enabled });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Issue a creator pass to a wallet (admin only)
+    pub fn grant_creator_pass(&self, creator: Pubkey) -> Result<Signature> {
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::GrantCreatorPass {
+                config: get_config_address(&self.program_id).0,
+                creator_pass: get_creator_pass_address(&self.program_id, &creator).0,
+                admin: self.payer_pubkey(),
+                system_program: system_program::ID,
+            })
+            .args(voting_dapp::instruction::GrantCreatorPass { creator });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Revoke a wallet's creator pass (admin only)
+    pub fn revoke_creator_pass(&self, creator: Pubkey) -> Result<Signature> {
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::RevokeCreatorPass {
+                config: get_config_address(&self.program_id).0,
+                creator_pass: get_creator_pass_address(&self.program_id, &creator).0,
+                admin: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::RevokeCreatorPass {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Stage a poll for council approval
+    pub fn propose_poll(&self, poll_id: u64, question: String) -> Result<Signature> {
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::ProposePoll {
+                poll_proposal: get_poll_proposal_address(&self.program_id, poll_id).0,
+                creator: self.payer_pubkey(),
+                system_program: system_program::ID,
+            })
+            .args(voting_dapp::instruction::ProposePoll { poll_id, question });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Withdraw a staged poll, as its proposer or the admin, returning its rent to the proposer
+    pub fn cancel_poll_proposal(&self, poll_id: u64) -> Result<Signature> {
+        let (proposal_address, _) = get_poll_proposal_address(&self.program_id, poll_id);
+        let proposal = self.program.account::<PollProposal>(proposal_address)?;
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::CancelPollProposal {
+                config: get_config_address(&self.program_id).0,
+                poll_proposal: proposal_address,
+                creator: proposal.creator,
+                authority: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::CancelPollProposal {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Approve a staged poll as a council member, returning the approval count
+    pub fn approve_poll_creation(&self, poll_id: u64) -> Result<(Signature, usize)> {
+        let (proposal_address, _) = get_poll_proposal_address(&self.program_id, poll_id);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::ApprovePollCreation {
+                config: get_config_address(&self.program_id).0,
+                poll_proposal: proposal_address,
+                member: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::ApprovePollCreation {});
+        let signature = self.submit(request)?;
+
+        let proposal = self.program.account::<PollProposal>(proposal_address)?;
+        Ok((signature, proposal.approvals.len()))
+    }
+
+    /// Get the treasury account and its current lamport balance
+    #[instrument(level = "debug", skip_all)]
+    pub fn get_treasury(&self) -> Result<(Treasury, u64)> {
+        let (treasury_address, _) = get_treasury_address(&self.program_id);
+        let account = self.program.account::<Treasury>(treasury_address)?;
+        let balance = self.program.rpc().get_balance(&treasury_address)?;
+        Ok((account, balance))
+    }
+
+    /// Cap how far from creation a poll's end time may be, in seconds (admin only)
+    pub fn set_max_poll_duration(&self, max_poll_duration: i64) -> Result<Signature> {
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::SetMaxPollDuration {
+                config: get_config_address(&self.program_id).0,
+                admin: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::SetMaxPollDuration { max_poll_duration });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Cap how many polls each creator may create per rolling window of `creation_window`
+    /// seconds (admin only)
+    pub fn set_creation_limit(&self, max_polls_per_window: u16, creation_window: i64) -> Result<Signature> {
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::SetCreationLimit {
+                config: get_config_address(&self.program_id).0,
+                admin: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::SetCreationLimit {
+                max_polls_per_window,
+                creation_window,
+            });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Initialize a new poll
+    pub fn initialize_poll(
+        &self,
+        poll_id: u64,
+        question: String,
+        description: String,
+        start_time: i64,
+        end_time: i64,
+        options: PollOptions,
+    ) -> Result<Signature> {
+        // Mirror the program's checks so mistakes are explained before a transaction is sent,
+        // against the cluster's clock so a skewed local clock doesn't misjudge them
+        let now = self.cluster_time()?;
+        if question.trim().is_empty() {
+            return Err(anyhow::anyhow!("The poll question cannot be empty"));
+        }
+        if start_time >= end_time {
+            return Err(anyhow::anyhow!("The start time must be before the end time"));
+        }
+        if start_time < now - START_TIME_GRACE_PERIOD {
+            return Err(anyhow::anyhow!(
+                "The start time is more than {} seconds in the past; use the current time or later",
+                START_TIME_GRACE_PERIOD
+            ));
+        }
+        let config = self.get_config()?;
+        if config.max_poll_duration > 0 && end_time > now + config.max_poll_duration {
+            return Err(anyhow::anyhow!(
+                "The end time must be within {} seconds of now; choose an earlier end time",
+                config.max_poll_duration
+            ));
+        }
+        let (creator_stats, _) = get_creator_stats_address(&self.program_id, &self.payer_pubkey());
+        if config.max_polls_per_window > 0 {
+            if let Ok(stats) = self.get_creator_stats(&self.payer_pubkey()) {
+                let recent = stats
+                    .recent_polls
+                    .iter()
+                    .filter(|created_at| now - **created_at < config.creation_window)
+                    .count();
+                if recent >= config.max_polls_per_window as usize {
+                    return Err(anyhow::anyhow!(
+                        "You have created {} polls in the last {} seconds, the most allowed; try again later",
+                        recent,
+                        config.creation_window
+                    ));
+                }
+            }
+        }
+
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let (config_address, _) = get_config_address(&self.program_id);
+        let (treasury_address, _) = get_treasury_address(&self.program_id);
+
+        // Pass the staged proposal if there is one so it gets closed;
+        // Anchor treats the program ID as a missing optional account
+        let (proposal_address, _) = get_poll_proposal_address(&self.program_id, poll_id);
+        let poll_proposal = match self.program.account::<PollProposal>(proposal_address) {
+            Ok(_) => proposal_address,
+            Err(_) => self.program_id,
+        };
+        let (pass_address, _) = get_creator_pass_address(&self.program_id, &self.payer_pubkey());
+        let creator_pass = match self.program.rpc().get_account(&pass_address) {
+            Ok(_) => pass_address,
+            Err(_) => self.program_id,
+        };
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::InitializePoll {
+                poll: poll_address,
+                config: config_address,
+                treasury: treasury_address,
+                poll_proposal,
+                creator_pass,
+                creator: self.payer_pubkey(),
+                system_program: system_program::ID,
+                creator_stats,
+                poll_archive: get_poll_archive_address(&self.program_id, poll_id).0,
+            })
+            .args(voting_dapp::instruction::InitializePoll {
+                poll_id,
+                question,
+                description,
+                start_time,
+                end_time,
+                options,
+            });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Edit a draft poll's question or description, or move a poll's end time
+    pub fn update_poll(
+        &self,
+        poll_id: u64,
+        question: Option<String>,
+        description: Option<String>,
+        end_time: Option<i64>,
+    ) -> Result<Signature> {
+        // Explain the end time rules before sending; the program enforces them regardless
+        if let Some(end_time) = end_time {
+            let poll = self.get_poll(poll_id)?;
+            if poll.total_votes > 0 && end_time < poll.end_time {
+                return Err(anyhow::anyhow!(
+                    "Poll {} already has votes, so its end time can only be extended",
+                    poll_id
+                ));
+            }
+            let remaining = poll.max_extension - poll.extended_by;
+            if poll.total_votes > 0 && end_time - poll.end_time > remaining {
+                return Err(anyhow::anyhow!(
+                    "Poll {} can only be extended by {} more seconds",
+                    poll_id,
+                    remaining
+                ));
+            }
+        }
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::UpdatePoll {
+                poll: poll_address,
+                config: get_config_address(&self.program_id).0,
+                creator: self.payer_pubkey(),
+                system_program: system_program::ID,
+            })
+            .args(voting_dapp::instruction::UpdatePoll {
+                question,
+                description,
+                end_time,
+            });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Freeze a draft poll's candidate list and open it for voting. With `auto_finalize_fee`,
+    /// a Clockwork thread funded with that many lamports finalizes the poll once it ends.
+    pub fn activate_poll(&self, poll_id: u64, auto_finalize_fee: Option<u64>) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let mut instructions = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::ActivatePoll {
+                poll: poll_address,
+                creator: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::ActivatePoll {})
+            .instructions()?;
+
+        // Shielded polls open their encrypted tally in the same transaction
+        if poll.is_shielded() {
+            if auto_finalize_fee.is_some() {
+                return Err(anyhow::anyhow!(
+                    "Shielded polls finalize after their results are published, not automatically"
+                ));
+            }
+            let (tally_address, _) = get_shielded_tally_address(&self.program_id, &poll_address);
+            instructions.extend(
+                self.program
+                    .request()
+                    .accounts(voting_dapp::accounts::InitShieldedTally {
+                        poll: poll_address,
+                        tally: tally_address,
+                        creator: self.payer_pubkey(),
+                        system_program: system_program::ID,
+                    })
+                    .args(voting_dapp::instruction::InitShieldedTally {})
+                    .instructions()?,
+            );
+        }
+
+        // The thread needs the frozen candidate list, so it is registered right after activation
+        if let Some(fee_lamports) = auto_finalize_fee {
+            let candidate_metas: Vec<AccountMeta> = (0..poll.candidate_count)
+                .map(|index| get_candidate_address(&self.program_id, &poll_address, index as u16).0)
+                .map(|candidate| AccountMeta::new_readonly(candidate, false))
+                .collect();
+            instructions.extend(
+                self.program
+                    .request()
+                    .accounts(voting_dapp::accounts::ScheduleFinalization {
+                        poll: poll_address,
+                        creator: self.payer_pubkey(),
+                        thread: get_finalize_thread_address(&poll_address).0,
+                        thread_program: THREAD_PROGRAM_ID,
+                        system_program: system_program::ID,
+                    })
+                    .accounts(candidate_metas)
+                    .args(voting_dapp::instruction::ScheduleFinalization { fee_lamports })
+                    .instructions()?,
+            );
+        }
+
+        let request = instructions
+            .into_iter()
+            .fold(self.program.request(), |request, instruction| request.instruction(instruction));
+        self.submit(request)
+    }
+
+    /// Bind a committed Switchboard randomness account to the poll, for its ballot order while
+    /// a draft or its tie-break once ended
+    pub fn request_randomness(&self, poll_id: u64, randomness: Pubkey) -> Result<Signature> {
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::RequestRandomness {
+                poll: get_poll_address(&self.program_id, poll_id).0,
+                requester: self.payer_pubkey(),
+                randomness,
+            })
+            .args(voting_dapp::instruction::RequestRandomness {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Apply the revealed value of a poll's pending randomness request
+    pub fn apply_randomness(&self, poll_id: u64) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        if !poll.randomness_pending {
+            return Err(anyhow::anyhow!("Poll {} has no pending randomness request", poll_id));
+        }
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::ApplyRandomness {
+                poll: get_poll_address(&self.program_id, poll_id).0,
+                randomness: poll.randomness_account,
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .args(voting_dapp::instruction::ApplyRandomness {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Cancel a poll's randomness request that was never revealed, so a new one can be made
+    pub fn cancel_randomness(&self, poll_id: u64) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        if !poll.randomness_pending {
+            return Err(anyhow::anyhow!("Poll {} has no pending randomness request", poll_id));
+        }
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::CancelRandomness {
+                poll: get_poll_address(&self.program_id, poll_id).0,
+                randomness: poll.randomness_account,
+            })
+            .args(voting_dapp::instruction::CancelRandomness {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Delete a poll's finalization thread, cancelling automatic finalization if it has not run
+    pub fn cancel_finalization(&self, poll_id: u64) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        if poll.finalize_thread == Pubkey::default() {
+            return Err(anyhow::anyhow!("Poll {} has no finalization thread", poll_id));
+        }
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::CancelFinalization {
+                poll: get_poll_address(&self.program_id, poll_id).0,
+                creator: self.payer_pubkey(),
+                thread: poll.finalize_thread,
+                thread_program: THREAD_PROGRAM_ID,
+            })
+            .args(voting_dapp::instruction::CancelFinalization {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Stop a live poll from accepting votes
+    pub fn pause_poll(&self, poll_id: u64) -> Result<Signature> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::SetPollPaused {
+                poll: poll_address,
+                creator: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::PausePoll {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Appoint or remove the registrar allowed to add candidates to a poll
+    pub fn set_candidate_manager(&self, poll_id: u64, manager: Option<Pubkey>) -> Result<Signature> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::SetCandidateManager {
+                poll: poll_address,
+                creator: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::SetCandidateManager { manager });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Let each of `voters` vote in an access-restricted poll, several grants per transaction
+    pub fn grant_access(&self, poll_id: u64, voters: &[Pubkey]) -> Result<Vec<Signature>> {
+        if voters.is_empty() {
+            return Err(anyhow::anyhow!("The voter list is empty"));
+        }
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let mut signatures = Vec::new();
+        for batch in voters.chunks(ACCESS_GRANT_BATCH_SIZE) {
+            let mut request = self.program.request();
+            for voter in batch {
+                let instruction = self
+                    .program
+                    .request()
+                    .accounts(voting_dapp::accounts::GrantAccess {
+                        poll: poll_address,
+                        access_grant: get_access_grant_address(&self.program_id, &poll_address, voter).0,
+                        creator: self.payer_pubkey(),
+                        system_program: system_program::ID,
+                    })
+                    .args(voting_dapp::instruction::GrantAccess { voter: *voter })
+                    .instructions()?
+                    .remove(0);
+                request = request.instruction(instruction);
+            }
+            signatures.push(self.submit(request)?);
+        }
+
+        Ok(signatures)
+    }
+
+    /// Withdraw a voter's access to a restricted poll, returning the grant's rent
+    pub fn revoke_access(&self, poll_id: u64, voter: Pubkey) -> Result<Signature> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::RevokeAccess {
+                poll: poll_address,
+                access_grant: get_access_grant_address(&self.program_id, &poll_address, &voter).0,
+                creator: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::RevokeAccess {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Publish a voter's weight, signing as the oracle that oracle-weighted polls name
+    pub fn publish_weight(&self, voter: Pubkey, weight: u64) -> Result<Signature> {
+        let oracle = self.payer_pubkey();
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::PublishWeight {
+                weight_record: get_weight_record_address(&self.program_id, &oracle, &voter).0,
+                oracle,
+                system_program: system_program::ID,
+            })
+            .args(voting_dapp::instruction::PublishWeight { voter, weight });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Resume a paused poll, optionally extending its end time by the pause duration
+    pub fn resume_poll(&self, poll_id: u64, extend_end_time: bool) -> Result<Signature> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::SetPollPaused {
+                poll: poll_address,
+                creator: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::ResumePoll { extend_end_time });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Add a candidate to a poll
+    pub fn add_candidate(
+        &self,
+        poll_id: u64,
+        name: String,
+        party: String,
+        payout_wallet: Pubkey,
+        metadata_uri: String,
+    ) -> Result<Signature> {
+        if name.trim().is_empty() {
+            return Err(anyhow::anyhow!("The candidate name cannot be empty"));
+        }
+
+        // Names are not part of the candidate address, so duplicates are refused here
+        if self.get_candidate_index(poll_id, &name).is_ok() {
+            return Err(anyhow::anyhow!("Poll {} already has a candidate named {}", poll_id, name));
+        }
+        let poll = self.get_poll(poll_id)?;
+        let index = u16::try_from(poll.candidate_count)
+            .map_err(|_| anyhow::anyhow!("Poll {} cannot take more candidates", poll_id))?;
+        // Anyone else may only nominate themselves, once, and only with open candidacy
+        let payer = self.payer_pubkey();
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let self_nomination = payer != poll.creator && poll.candidate_manager != Some(payer);
+        let (nomination_address, _) = get_nomination_address(&self.program_id, &poll_address, &payer);
+        if self_nomination {
+            if !poll.open_candidacy {
+                return Err(anyhow::anyhow!("Only the creator of poll {} can add candidates", poll_id));
+            }
+            if payout_wallet != payer {
+                return Err(anyhow::anyhow!(
+                    "Self-nominated candidates must pay out to your own wallet; drop --payout-wallet"
+                ));
+            }
+            let nominated = self.program.rpc().get_account(&nomination_address).map(|account| account.owner);
+            if nominated.is_ok_and(|owner| owner == self.program_id) {
+                return Err(anyhow::anyhow!("You already stand in poll {}", poll_id));
+            }
+        }
+
+        let (candidate_address, _) = get_candidate_address(&self.program_id, &poll_address, index);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::InitializeCandidate {
+                poll: poll_address,
+                candidate: candidate_address,
+                creator: self.payer_pubkey(),
+                system_program: system_program::ID,
+                // Anchor treats the program ID as a missing optional account
+                nomination: if self_nomination { nomination_address } else { self.program_id },
+            })
+            .args(voting_dapp::instruction::InitializeCandidate {
+                name,
+                party,
+                payout_wallet,
+                metadata_uri,
+            });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Add many candidates, several per transaction. Each candidate pays out to the payer.
+    /// Returns the outcome of each transaction, covering `CANDIDATE_BATCH_SIZE` entries in order;
+    /// a failed transaction doesn't stop the ones after it. Metadata URIs are set with one more
+    /// transaction per candidate that has one, after its batch lands.
+    pub fn add_candidates(&self, poll_id: u64, entries: &[NewCandidate]) -> Result<Vec<Result<Signature>>> {
+        // Names are not part of the candidate address, so duplicates are refused here
+        let mut names: Vec<String> = self
+            .get_candidates(poll_id)?
+            .into_iter()
+            .map(|(_, candidate)| candidate.name)
+            .collect();
+        for entry in entries {
+            if entry.name.trim().is_empty() {
+                return Err(anyhow::anyhow!("The candidate name cannot be empty"));
+            }
+            if names.contains(&entry.name) {
+                return Err(anyhow::anyhow!("Poll {} already has a candidate named {}", poll_id, entry.name));
+            }
+            names.push(entry.name.clone());
+        }
+
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let mut next_index = self.get_poll(poll_id)?.candidate_count;
+        let mut outcomes = Vec::new();
+        for batch in entries.chunks(CANDIDATE_BATCH_SIZE) {
+            let candidate_metas = (next_index..next_index + batch.len() as u64)
+                .map(|index| {
+                    let index = u16::try_from(index)
+                        .map_err(|_| anyhow::anyhow!("Poll {} cannot take more candidates", poll_id))?;
+                    Ok(AccountMeta::new(get_candidate_address(&self.program_id, &poll_address, index).0, false))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let request = self
+                .program
+                .request()
+                .accounts(voting_dapp::accounts::InitializeCandidatesBatch {
+                    poll: poll_address,
+                    creator: self.payer_pubkey(),
+                    system_program: system_program::ID,
+                })
+                .accounts(candidate_metas)
+                .args(voting_dapp::instruction::InitializeCandidatesBatch {
+                    candidates: batch
+                        .iter()
+                        .map(|entry| CandidateEntry {
+                            name: entry.name.clone(),
+                            party: entry.party.clone(),
+                        })
+                        .collect(),
+                });
+            let outcome = match self.submit(request) {
+                // A dry run stops at the first batch rather than reporting it against its rows
+                Err(e) if e.is::<DryRun>() => return Err(e),
+                outcome => outcome,
+            };
+            // Only a landed batch takes up ballot indices
+            let outcome = outcome.and_then(|signature| {
+                let first_index = next_index;
+                next_index += batch.len() as u64;
+                self.set_batch_metadata(&poll_address, first_index, batch).map(|()| signature).map_err(|e| {
+                    anyhow::anyhow!("Added in {}, but setting the metadata URIs failed: {}", signature, e)
+                })
+            });
+            outcomes.push(outcome);
+        }
+
+        Ok(outcomes)
+    }
+
+    // Set the metadata URIs of a landed batch, whose candidates start at `first_index`
+    fn set_batch_metadata(&self, poll_address: &Pubkey, first_index: u64, batch: &[NewCandidate]) -> Result<()> {
+        for (index, entry) in (first_index..).zip(batch) {
+            if entry.metadata_uri.is_empty() {
+                continue;
+            }
+            let request = self
+                .program
+                .request()
+                .accounts(voting_dapp::accounts::UpdateCandidateMetadata {
+                    poll: *poll_address,
+                    candidate: get_candidate_address(&self.program_id, poll_address, index as u16).0,
+                    creator: self.payer_pubkey(),
+                })
+                .args(voting_dapp::instruction::UpdateCandidateMetadata {
+                    metadata_uri: entry.metadata_uri.clone(),
+                });
+            self.submit(request)?;
+        }
+        Ok(())
+    }
+
+    /// Update a candidate's metadata URI before voting opens
+    pub fn update_candidate_metadata(
+        &self,
+        poll_id: u64,
+        candidate_name: &str,
+        metadata_uri: String,
+    ) -> Result<Signature> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let candidate_address = self.get_candidate_address(poll_id, candidate_name)?;
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::UpdateCandidateMetadata {
+                poll: poll_address,
+                candidate: candidate_address,
+                creator: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::UpdateCandidateMetadata { metadata_uri });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Cast a vote for a candidate, attaching a native stake account for stake-gated and
+    /// stake-weighted polls. A `sponsor` pays the receipt rent in place of the voter.
+    pub fn vote(
+        &self,
+        poll_id: u64,
+        candidate_name: String,
+        stake_account: Option<Pubkey>,
+        verifier_accounts: Vec<AccountMeta>,
+        sponsor: Option<&Keypair>,
+    ) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        if (poll.is_shielded() || poll.governance_realm != Pubkey::default()) && sponsor.is_some() {
+            return Err(anyhow::anyhow!("Poll {} does not accept sponsored votes", poll_id));
+        }
+        if poll.is_shielded() {
+            return self.vote_shielded(poll_id, &candidate_name);
+        }
+        if poll.governance_realm != Pubkey::default() {
+            return self.vote_governance(&poll, &candidate_name);
+        }
+
+        let payer = sponsor.map_or(self.payer_pubkey(), |sponsor| sponsor.pubkey());
+        let instruction = self.vote_instruction(&poll, &candidate_name, stake_account, verifier_accounts, payer)?;
+        let mut request = self.program.request().instruction(instruction);
+        if let Some(sponsor) = sponsor {
+            request = request.signer(sponsor);
+        }
+
+        self.submit(request)
+    }
+
+    /// Vote in several standard polls with a single transaction, one `vote` instruction per poll
+    pub fn vote_all(&self, votes: &[VoteSelection]) -> Result<Signature> {
+        if votes.is_empty() {
+            return Err(anyhow::anyhow!("The ballot file lists no votes"));
+        }
+
+        // The program rejects a transaction voting twice in one poll
+        let mut poll_ids: Vec<u64> = votes.iter().map(|selection| selection.poll_id).collect();
+        poll_ids.sort_unstable();
+        if let Some(pair) = poll_ids.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(anyhow::anyhow!("The ballot file votes in poll {} more than once", pair[0]));
+        }
+
+        let mut request = self.program.request();
+        for selection in votes {
+            let poll = self.get_poll(selection.poll_id)?;
+            // Polls that need extra accounts or another instruction are voted on separately
+            if poll.is_shielded()
+                || poll.governance_realm != Pubkey::default()
+                || poll.gauge_budget > 0
+                || poll.conviction_mint != Pubkey::default()
+                || poll.stake_weighted
+                || poll.min_stake_lamports > 0
+                || poll.eligibility_program.is_some()
+            {
+                return Err(anyhow::anyhow!(
+                    "Poll {} needs its own vote transaction; use `vote` for it",
+                    selection.poll_id
+                ));
+            }
+            let instruction =
+                self.vote_instruction(&poll, &selection.candidate, None, Vec::new(), self.payer_pubkey())?;
+            request = request.instruction(instruction);
+        }
+
+        self.submit(request)
+    }
+
+    // Build the `vote` instruction for a standard poll, with `payer` covering the rent
+    fn vote_instruction(
+        &self,
+        poll: &Poll,
+        candidate_name: &str,
+        stake_account: Option<Pubkey>,
+        verifier_accounts: Vec<AccountMeta>,
+        payer: Pubkey,
+    ) -> Result<Instruction> {
+        let poll_id = poll.poll_id;
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let candidate_address = self.get_candidate_address(poll_id, candidate_name)?;
+        let (receipt_address, _) =
+            get_receipt_address(&self.program_id, poll_id, &self.payer_pubkey());
+
+        // Anchor treats the program ID as a missing optional account
+        let (tree_authority, merkle_tree, bubblegum_program, log_wrapper, compression_program) =
+            if poll.mint_receipt_nft {
+                (
+                    compression::get_tree_config_address(&poll.receipt_nft_tree).0,
+                    poll.receipt_nft_tree,
+                    BUBBLEGUM_PROGRAM_ID,
+                    NOOP_PROGRAM_ID,
+                    COMPRESSION_PROGRAM_ID,
+                )
+            } else {
+                (self.program_id, self.program_id, self.program_id, self.program_id, self.program_id)
+            };
+
+        // Stake-weighted polls read the stake history and mark the stake account as used
+        let (stake_history, stake_marker) = if poll.stake_weighted {
+            let stake_account = stake_account.ok_or_else(|| {
+                anyhow::anyhow!("Poll {} weighs votes by stake; pass the stake account to vote with", poll_id)
+            })?;
+            (
+                anchor_client::solana_sdk::sysvar::stake_history::ID,
+                get_stake_marker_address(&self.program_id, &poll_address, &stake_account).0,
+            )
+        } else {
+            (self.program_id, self.program_id)
+        };
+
+        // Attestation-gated polls read the issuer's attestation about the voter
+        let attestation = if poll.attestation_issuer != Pubkey::default() {
+            get_attestation_address(
+                &poll.attestation_program,
+                &poll.attestation_issuer,
+                &poll.attestation_schema,
+                &self.payer_pubkey(),
+            )
+            .0
+        } else {
+            self.program_id
+        };
+
+        // Access-restricted polls check the creator's grant to the voter
+        let access_grant = if poll.access_restricted {
+            get_access_grant_address(&self.program_id, &poll_address, &self.payer_pubkey()).0
+        } else {
+            self.program_id
+        };
+
+        // Oracle-weighted polls read the weight the oracle published for the voter
+        let weight_record = if poll.is_oracle_weighted() {
+            get_weight_record_address(&self.program_id, &poll.weight_oracle, &self.payer_pubkey()).0
+        } else {
+            self.program_id
+        };
+
+        // Fee polls charge the payer: lamports go straight to the recipient wallet, tokens from
+        // the payer's associated token account
+        let (fee_recipient, fee_source, token_program) = if poll.vote_fee == 0 {
+            (self.program_id, self.program_id, self.program_id)
+        } else if poll.vote_fee_mint == Pubkey::default() {
+            (poll.vote_fee_recipient, self.program_id, self.program_id)
+        } else {
+            (
+                poll.vote_fee_recipient,
+                spl_associated_token_account::get_associated_token_address(&payer, &poll.vote_fee_mint),
+                spl_token::ID,
+            )
+        };
+
+        let instruction = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::Vote {
+                poll: poll_address,
+                candidate: candidate_address,
+                voter_receipt: receipt_address,
+                voter: self.payer_pubkey(),
+                payer,
+                system_program: system_program::ID,
+                tree_authority,
+                merkle_tree,
+                bubblegum_program,
+                log_wrapper,
+                compression_program,
+                stake_account: stake_account.unwrap_or(self.program_id),
+                instructions: anchor_client::solana_sdk::sysvar::instructions::ID,
+                stake_history,
+                stake_marker,
+                eligibility_program: poll.eligibility_program.unwrap_or(self.program_id),
+                attestation,
+                access_grant,
+                weight_record,
+                fee_recipient,
+                fee_source,
+                token_program,
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .accounts(verifier_accounts)
+            .args(voting_dapp::instruction::Vote {})
+            .instructions()?
+            .remove(0);
+
+        Ok(instruction)
+    }
+
+    /// Vote in a realm-backed poll with the payer's deposited governance tokens
+    pub fn vote_governance(&self, poll: &Poll, candidate_name: &str) -> Result<Signature> {
+        let voter = self.payer_pubkey();
+        let (token_owner_record, _) = get_token_owner_record_address(
+            &poll.governance_program,
+            &poll.governance_realm,
+            &poll.governance_mint,
+            &voter,
+        );
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::VoteGovernance {
+                poll: get_poll_address(&self.program_id, poll.poll_id).0,
+                candidate: self.get_candidate_address(poll.poll_id, candidate_name)?,
+                voter_receipt: get_receipt_address(&self.program_id, poll.poll_id, &voter).0,
+                token_owner_record,
+                voter,
+                system_program: system_program::ID,
+                instructions: anchor_client::solana_sdk::sysvar::instructions::ID,
+                realm: poll.governance_realm,
+                realm_config: get_realm_config_address(&poll.governance_program, &poll.governance_realm).0,
+                lock_authority: get_governance_lock_address(&self.program_id).0,
+                governance_program: poll.governance_program,
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .args(voting_dapp::instruction::VoteGovernance {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Cast an encrypted ballot in a shielded poll
+    pub fn vote_shielded(&self, poll_id: u64, candidate_name: &str) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let (tally_address, _) = get_shielded_tally_address(&self.program_id, &poll_address);
+        let (receipt_address, _) =
+            get_receipt_address(&self.program_id, poll_id, &self.payer_pubkey());
+
+        // Ballot entries follow the candidates' ballot order
+        let mut candidates: Vec<Candidate> =
+            self.get_candidates(poll_id)?.into_iter().map(|(_, candidate)| candidate).collect();
+        sort_candidates(&mut candidates, CandidateOrder::Ballot, &poll.ballot_order_seed);
+        let choice = candidates
+            .iter()
+            .position(|candidate| candidate.name == candidate_name)
+            .ok_or_else(|| anyhow::anyhow!("Poll {} has no candidate named {}", poll_id, candidate_name))?;
+        let (choices, sum_proof) = shielded::encrypt_ballot(
+            &poll.shielded_key,
+            &poll_address,
+            &self.payer_pubkey(),
+            choice,
+            candidates.len(),
+        )?;
+
+        let request = self
+            .program
+            .request()
+            .instruction(ComputeBudgetInstruction::set_compute_unit_limit(SHIELDED_COMPUTE_UNITS))
+            .accounts(voting_dapp::accounts::VoteShielded {
+                poll: poll_address,
+                tally: tally_address,
+                voter_receipt: receipt_address,
+                voter: self.payer_pubkey(),
+                system_program: system_program::ID,
+                instructions: anchor_client::solana_sdk::sysvar::instructions::ID,
+            })
+            .args(voting_dapp::instruction::VoteShielded { choices, sum_proof });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Decrypt a shielded poll's totals with its key and publish them with their proofs
+    pub fn publish_shielded_results(&self, poll_id: u64, keypair: &ElGamalKeypair) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        if keypair.public_key() != poll.shielded_key {
+            return Err(anyhow::anyhow!("This key does not match poll {}'s shielded key", poll_id));
+        }
+
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let (tally_address, _) = get_shielded_tally_address(&self.program_id, &poll_address);
+        let tally = self.program.account::<ShieldedTally>(tally_address)?;
+        let results = shielded::decrypt_totals(keypair, &poll_address, &tally.totals, poll.total_votes)?;
+
+        // Totals are stored in ballot order, so the candidates are passed the same way
+        let mut candidates = self.get_candidates(poll_id)?;
+        candidates.sort_by_key(|(_, candidate)| candidate.index);
+        let candidate_metas: Vec<AccountMeta> = candidates
+            .into_iter()
+            .map(|(address, _)| AccountMeta::new(address, false))
+            .collect();
+
+        let request = self
+            .program
+            .request()
+            .instruction(ComputeBudgetInstruction::set_compute_unit_limit(SHIELDED_COMPUTE_UNITS))
+            .accounts(voting_dapp::accounts::PublishShieldedResults {
+                poll: poll_address,
+                tally: tally_address,
+            })
+            .accounts(candidate_metas)
+            .args(voting_dapp::instruction::PublishShieldedResults { results });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Allocate a receipt tree for the compression program and attach it to a poll
+    pub fn init_receipt_tree(
+        &self,
+        poll_id: u64,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<(Signature, Pubkey)> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let tree = Keypair::new();
+        let size = compression::tree_account_size(max_depth, max_buffer_size);
+        let lamports = self.program.rpc().get_minimum_balance_for_rent_exemption(size)?;
+
+        let request = self
+            .program
+            .request()
+            .instruction(system_instruction::create_account(
+                &self.payer_pubkey(),
+                &tree.pubkey(),
+                lamports,
+                size as u64,
+                &COMPRESSION_PROGRAM_ID,
+            ))
+            .accounts(voting_dapp::accounts::ManageReceiptTree {
+                poll: poll_address,
+                creator: self.payer_pubkey(),
+                merkle_tree: tree.pubkey(),
+                compression_program: COMPRESSION_PROGRAM_ID,
+                log_wrapper: NOOP_PROGRAM_ID,
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .args(voting_dapp::instruction::InitReceiptTree {
+                max_depth,
+                max_buffer_size,
+            })
+            .signer(&tree);
+        let signature = self.submit(request)?;
+
+        Ok((signature, tree.pubkey()))
+    }
+
+    /// Register a voter in a poll's receipt tree, refusing voters that are already registered
+    pub fn register_compressed_voter(&self, poll_id: u64, voter: Pubkey) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        // Every registered voter has a registration PDA, which the program refuses to create twice
+        let (registration_address, _) = get_compressed_voter_address(&self.program_id, &poll_address, &voter);
+        if self.get_account_data_at_slot(&registration_address)?.1.is_some() {
+            return Err(anyhow::anyhow!("{} is already registered in poll {}", voter, poll_id));
+        }
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::RegisterCompressedVoter {
+                poll: poll_address,
+                registration: registration_address,
+                creator: self.payer_pubkey(),
+                merkle_tree: poll.receipt_tree,
+                compression_program: COMPRESSION_PROGRAM_ID,
+                log_wrapper: NOOP_PROGRAM_ID,
+                system_program: system_program::ID,
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .args(voting_dapp::instruction::RegisterCompressedVoter { voter });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Cast a vote recorded as a compressed receipt leaf instead of a receipt account
+    pub fn vote_compressed(&self, poll_id: u64, candidate_name: String) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let candidate_address = self.get_candidate_address(poll_id, &candidate_name)?;
+        let tree = compression::fetch_receipt_tree(&self.program.rpc(), &self.program_id, &poll_address, &poll.receipt_tree)?;
+
+        let unvoted = compression::receipt_leaf(&poll_address, &self.payer_pubkey(), &Pubkey::default());
+        let index = tree.position(&unvoted).ok_or_else(|| {
+            anyhow::anyhow!("You are not registered in poll {} or have already voted", poll_id)
+        })?;
+        let proof_metas: Vec<AccountMeta> = tree
+            .proof(index)
+            .into_iter()
+            .map(|node| AccountMeta::new_readonly(Pubkey::new_from_array(node), false))
+            .collect();
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::VoteCompressed {
+                poll: poll_address,
+                candidate: candidate_address,
+                voter: self.payer_pubkey(),
+                merkle_tree: poll.receipt_tree,
+                compression_program: COMPRESSION_PROGRAM_ID,
+                log_wrapper: NOOP_PROGRAM_ID,
+                instructions: anchor_client::solana_sdk::sysvar::instructions::ID,
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .accounts(proof_metas)
+            .args(voting_dapp::instruction::VoteCompressed {
+                root: tree.root(),
+                index,
+            });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Make a poll's PDA the delegate of a Bubblegum tree the payer created, so votes can mint from it
+    pub fn delegate_receipt_nft_tree(&self, poll_id: u64, merkle_tree: Pubkey) -> Result<Signature> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let request = self
+            .program
+            .request()
+            .instruction(compression::set_tree_delegate_instruction(
+                &merkle_tree,
+                &self.payer_pubkey(),
+                &poll_address,
+            ));
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Upgrade a poll, candidate or receipt account to the current layout
+    pub fn migrate_account(&self, account: Pubkey) -> Result<Signature> {
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::MigrateAccount {
+                account,
+                payer: self.payer_pubkey(),
+                system_program: system_program::ID,
+            })
+            .args(voting_dapp::instruction::MigrateAccount {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Create a ballot linking several existing polls
+    pub fn initialize_ballot(&self, ballot_id: u64, title: String, poll_ids: &[u64]) -> Result<Signature> {
+        let (ballot_address, _) = get_ballot_address(&self.program_id, ballot_id);
+        let race_metas: Vec<AccountMeta> = poll_ids
+            .iter()
+            .map(|poll_id| AccountMeta::new_readonly(get_poll_address(&self.program_id, *poll_id).0, false))
+            .collect();
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::InitializeBallot {
+                ballot: ballot_address,
+                creator: self.payer_pubkey(),
+                system_program: system_program::ID,
+            })
+            .accounts(race_metas)
+            .args(voting_dapp::instruction::InitializeBallot { ballot_id, title });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Vote in every race of a ballot, one candidate name per race in ballot order
+    pub fn vote_ballot(&self, ballot_id: u64, choices: &[String]) -> Result<Signature> {
+        let ballot = self.get_ballot(ballot_id)?;
+        if choices.len() != ballot.polls.len() {
+            return Err(anyhow::anyhow!(
+                "Ballot {} has {} races but {} choices were given",
+                ballot_id,
+                ballot.polls.len(),
+                choices.len()
+            ));
+        }
+
+        let mut race_metas = Vec::with_capacity(ballot.polls.len() * 3);
+        for (poll_address, choice) in ballot.polls.iter().zip(choices) {
+            let poll = self.program.account::<Poll>(*poll_address)?;
+            let candidate_address = self.get_candidate_address(poll.poll_id, choice)?;
+            let (receipt_address, _) =
+                get_receipt_address(&self.program_id, poll.poll_id, &self.payer_pubkey());
+            race_metas.push(AccountMeta::new(*poll_address, false));
+            race_metas.push(AccountMeta::new(candidate_address, false));
+            race_metas.push(AccountMeta::new(receipt_address, false));
+        }
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::VoteBallot {
+                ballot: get_ballot_address(&self.program_id, ballot_id).0,
+                voter: self.payer_pubkey(),
+                system_program: system_program::ID,
+                instructions: anchor_client::solana_sdk::sysvar::instructions::ID,
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .accounts(race_metas)
+            .args(voting_dapp::instruction::VoteBallot {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Authorize `session_key` to vote for the payer in a ballot's races for the next `slots`
+    /// slots, funding it with `fund_lamports` for receipt rent and fees. Returns the expiry slot.
+    pub fn create_session(
+        &self,
+        ballot_id: u64,
+        session_key: Pubkey,
+        slots: u64,
+        fund_lamports: u64,
+    ) -> Result<(Signature, u64)> {
+        let (ballot_address, _) = get_ballot_address(&self.program_id, ballot_id);
+        let voter = self.payer_pubkey();
+        let expiry_slot = self.program.rpc().get_slot()? + slots;
+
+        let mut request = self.program.request();
+        if fund_lamports > 0 {
+            request = request.instruction(system_instruction::transfer(&voter, &session_key, fund_lamports));
+        }
+        let request = request
+            .accounts(voting_dapp::accounts::CreateSession {
+                ballot: ballot_address,
+                session: get_session_address(&self.program_id, &ballot_address, &voter).0,
+                voter,
+                system_program: system_program::ID,
+            })
+            .args(voting_dapp::instruction::CreateSession { session_key, expiry_slot });
+        let signature = self.submit(request)?;
+
+        Ok((signature, expiry_slot))
+    }
+
+    /// Revoke the payer's session key for a ballot
+    pub fn revoke_session(&self, ballot_id: u64) -> Result<Signature> {
+        let (ballot_address, _) = get_ballot_address(&self.program_id, ballot_id);
+        let voter = self.payer_pubkey();
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::RevokeSession {
+                session: get_session_address(&self.program_id, &ballot_address, &voter).0,
+                voter,
+            })
+            .args(voting_dapp::instruction::RevokeSession {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Vote for `voter` in one race of a ballot, signing with the payer as their session key
+    pub fn vote_with_session(
+        &self,
+        ballot_id: u64,
+        voter: Pubkey,
+        poll_id: u64,
+        candidate_name: &str,
+    ) -> Result<Signature> {
+        let (ballot_address, _) = get_ballot_address(&self.program_id, ballot_id);
+        let (session_address, _) = get_session_address(&self.program_id, &ballot_address, &voter);
+        let session = self.program.account::<Session>(session_address)?;
+        if session.session_key != self.payer_pubkey() {
+            return Err(anyhow::anyhow!(
+                "{} is not the session key of {} for ballot {}",
+                self.payer_pubkey(),
+                voter,
+                ballot_id
+            ));
+        }
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::VoteWithSession {
+                poll: get_poll_address(&self.program_id, poll_id).0,
+                candidate: self.get_candidate_address(poll_id, candidate_name)?,
+                ballot: ballot_address,
+                session: session_address,
+                voter_receipt: get_receipt_address(&self.program_id, poll_id, &voter).0,
+                voter,
+                session_key: self.payer_pubkey(),
+                system_program: system_program::ID,
+                instructions: anchor_client::solana_sdk::sysvar::instructions::ID,
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .args(voting_dapp::instruction::VoteWithSession {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Split a gauge poll's point budget across candidates, given as (name, points) pairs
+    pub fn vote_gauge(&self, poll_id: u64, allocations: &[(String, u16)]) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        if poll.gauge_budget == 0 {
+            return Err(anyhow::anyhow!("Poll {} is not a gauge poll", poll_id));
+        }
+        let spent: u64 = allocations.iter().map(|(_, points)| *points as u64).sum();
+        if spent != poll.gauge_budget as u64 {
+            return Err(anyhow::anyhow!(
+                "Allocations spend {} points but poll {} has a budget of {}",
+                spent,
+                poll_id,
+                poll.gauge_budget
+            ));
+        }
+
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let (receipt_address, _) =
+            get_receipt_address(&self.program_id, poll_id, &self.payer_pubkey());
+        let candidates = self.get_candidates(poll_id)?;
+        let candidate_metas = allocations
+            .iter()
+            .map(|(name, _)| {
+                let (_, candidate) = candidates
+                    .iter()
+                    .find(|(_, candidate)| &candidate.name == name)
+                    .ok_or_else(|| anyhow::anyhow!("Poll {} has no candidate named {}", poll_id, name))?;
+                let (address, _) = get_candidate_address(&self.program_id, &poll_address, candidate.index);
+                Ok(AccountMeta::new(address, false))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::VoteGauge {
+                poll: poll_address,
+                voter_receipt: receipt_address,
+                voter: self.payer_pubkey(),
+                system_program: system_program::ID,
+                instructions: anchor_client::solana_sdk::sysvar::instructions::ID,
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .accounts(candidate_metas)
+            .args(voting_dapp::instruction::VoteGauge {
+                points: allocations.iter().map(|(_, points)| *points).collect(),
+            });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Vote in a conviction poll, locking `amount` tokens from the payer's associated token account
+    pub fn vote_conviction(
+        &self,
+        poll_id: u64,
+        candidate_name: &str,
+        amount: u64,
+        lock: LockPeriod,
+    ) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        if poll.conviction_mint == Pubkey::default() {
+            return Err(anyhow::anyhow!("Poll {} does not take conviction votes", poll_id));
+        }
+        let voter = self.payer_pubkey();
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::VoteConviction {
+                poll: poll_address,
+                candidate: self.get_candidate_address(poll_id, candidate_name)?,
+                voter_receipt: get_receipt_address(&self.program_id, poll_id, &voter).0,
+                conviction_lock: get_conviction_lock_address(&self.program_id, &poll_address, &voter).0,
+                lock_vault: get_conviction_vault_address(&self.program_id, &poll_address, &voter).0,
+                mint: poll.conviction_mint,
+                voter_token_account: spl_associated_token_account::get_associated_token_address(
+                    &voter,
+                    &poll.conviction_mint,
+                ),
+                voter,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                instructions: anchor_client::solana_sdk::sysvar::instructions::ID,
+                event_authority: get_event_authority_address(&self.program_id).0,
+                program: self.program_id,
+            })
+            .args(voting_dapp::instruction::VoteConviction { amount, lock });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Return the payer's conviction tokens once the lock has expired
+    pub fn unlock(&self, poll_id: u64) -> Result<(Signature, ConvictionLock)> {
+        let voter = self.payer_pubkey();
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let (lock_address, _) = get_conviction_lock_address(&self.program_id, &poll_address, &voter);
+        let (vault_address, _) = get_conviction_vault_address(&self.program_id, &poll_address, &voter);
+        let conviction_lock = self.program.account::<ConvictionLock>(lock_address)?;
+        if conviction_lock.unlocked {
+            return Err(anyhow::anyhow!("Tokens for poll {} were already unlocked", poll_id));
+        }
+        if chrono::Utc::now().timestamp() < conviction_lock.expiry {
+            return Err(anyhow::anyhow!(
+                "Tokens for poll {} stay locked until {}",
+                poll_id,
+                chrono::DateTime::from_timestamp(conviction_lock.expiry, 0).unwrap_or_default()
+            ));
+        }
+        // The vault outlives an archived poll, so read the mint from the token account itself
+        let vault_data = self.program.rpc().get_account_data(&vault_address)?;
+        let mint = Pubkey::try_from(&vault_data[..32])?;
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::Unlock {
+                conviction_lock: lock_address,
+                lock_vault: vault_address,
+                voter_token_account: spl_associated_token_account::get_associated_token_address(&voter, &mint),
+                voter,
+                token_program: spl_token::ID,
+            })
+            .args(voting_dapp::instruction::Unlock {});
+        let signature = self.submit(request)?;
+
+        Ok((signature, conviction_lock))
+    }
+
+    /// Get a voter's conviction lock in a poll
+    pub fn get_conviction_lock(&self, poll_id: u64, voter: &Pubkey) -> Result<ConvictionLock> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let (lock_address, _) = get_conviction_lock_address(&self.program_id, &poll_address, voter);
+        let account = self.program.account::<ConvictionLock>(lock_address)?;
+        Ok(account)
+    }
+
+    /// Get ballot details
+    pub fn get_ballot(&self, ballot_id: u64) -> Result<Ballot> {
+        let (ballot_address, _) = get_ballot_address(&self.program_id, ballot_id);
+        let account = self.program.account::<Ballot>(ballot_address)?;
+        Ok(account)
+    }
+
+    /// Attach an instruction to a draft poll, executed if `yes_candidate` passes the threshold
+    pub fn create_proposal(
+        &self,
+        poll_id: u64,
+        yes_candidate: &str,
+        threshold_bps: u16,
+        instruction: Instruction,
+    ) -> Result<(Signature, Pubkey)> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let candidate_address = self.get_candidate_address(poll_id, yes_candidate)?;
+        let (proposal_address, _) = get_proposal_address(&self.program_id, &poll_address);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::CreateProposal {
+                poll: poll_address,
+                yes_candidate: candidate_address,
+                proposal: proposal_address,
+                creator: self.payer_pubkey(),
+                system_program: system_program::ID,
+            })
+            .args(voting_dapp::instruction::CreateProposal {
+                threshold_bps,
+                program_id: instruction.program_id,
+                accounts: instruction
+                    .accounts
+                    .iter()
+                    .map(|meta| ProposalAccountMeta {
+                        pubkey: meta.pubkey,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    })
+                    .collect(),
+                data: instruction.data,
+            });
+        let signature = self.submit(request)?;
+
+        Ok((signature, get_poll_authority_address(&self.program_id, &poll_address).0))
+    }
+
+    /// Execute a finalized poll's proposal, passing its stored accounts along
+    pub fn execute_proposal(&self, poll_id: u64) -> Result<Signature> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let (proposal_address, _) = get_proposal_address(&self.program_id, &poll_address);
+        let proposal = self.program.account::<Proposal>(proposal_address)?;
+
+        // The authority PDA signs inside the program, so no account is a signer here
+        let remaining: Vec<AccountMeta> = proposal
+            .accounts
+            .iter()
+            .map(|meta| AccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: false,
+                is_writable: meta.is_writable,
+            })
+            .collect();
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::ExecuteProposal {
+                poll: poll_address,
+                proposal: proposal_address,
+                yes_candidate: proposal.yes_candidate,
+                poll_authority: get_poll_authority_address(&self.program_id, &poll_address).0,
+                target_program: proposal.program_id,
+            })
+            .accounts(remaining)
+            .args(voting_dapp::instruction::ExecuteProposal {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Finalize a poll after its voting period has ended
+    pub fn finalize_poll(&self, poll_id: u64) -> Result<Signature> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        // The program checks the winner against the full candidate list and flags the winners
+        let candidate_metas: Vec<AccountMeta> = self
+            .get_candidates(poll_id)?
+            .into_iter()
+            .map(|(address, _)| AccountMeta::new(address, false))
+            .collect();
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::FinalizePoll { poll: poll_address })
+            .accounts(candidate_metas)
+            .args(voting_dapp::instruction::FinalizePoll {});
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Register the auditors who may attest a draft poll's result
+    pub fn register_auditors(&self, poll_id: u64, auditors: Vec<Pubkey>) -> Result<Signature> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::RegisterAuditors {
+                poll: poll_address,
+                poll_result: get_poll_result_address(&self.program_id, &poll_address).0,
+                creator: self.payer_pubkey(),
+                system_program: system_program::ID,
+            })
+            .args(voting_dapp::instruction::RegisterAuditors { auditors });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Attest a finalized poll's winner and vote total as one of its auditors
+    pub fn attest_result(&self, poll_id: u64) -> Result<Signature> {
+        let poll = self.get_poll(poll_id)?;
+        if !poll.finalized {
+            return Err(anyhow::anyhow!("Poll {} has not been finalized yet", poll_id));
+        }
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::AttestResult {
+                poll: poll_address,
+                poll_result: get_poll_result_address(&self.program_id, &poll_address).0,
+                auditor: self.payer_pubkey(),
+            })
+            .args(voting_dapp::instruction::AttestResult {
+                winner: poll.winner,
+                total_votes: poll.total_votes,
+            });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Fetch a poll's auditors and their attestations
+    pub fn get_poll_result(&self, poll_id: u64) -> Result<PollResult> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let (result_address, _) = get_poll_result_address(&self.program_id, &poll_address);
+        let account = self.program.account::<PollResult>(result_address)?;
+        Ok(account)
+    }
+
+    /// Pay a finalized poll's bounty to the winner (or back to the creator if there is none)
+    pub fn claim_bounty(&self, poll_id: u64) -> Result<(Signature, Pubkey)> {
+        let poll = self.get_poll(poll_id)?;
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let (candidate, recipient) = if poll.winner == Pubkey::default() {
+            (None, poll.creator)
+        } else {
+            let winner = self.program.account::<Candidate>(poll.winner)?;
+            (Some(poll.winner), winner.payout_wallet)
+        };
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::ClaimBounty {
+                poll: poll_address,
+                // Anchor treats the program ID as a missing optional account
+                candidate: candidate.unwrap_or(self.program_id),
+                recipient,
+            })
+            .args(voting_dapp::instruction::ClaimBounty {});
+        let signature = self.submit(request)?;
+
+        Ok((signature, recipient))
+    }
+
+    /// Mint the winner badge NFT to the winning candidate's payout wallet, returning the new mint
+    pub fn mint_winner_badge(&self, poll_id: u64, uri: String) -> Result<(Signature, Pubkey)> {
+        let poll = self.get_poll(poll_id)?;
+        if poll.winner == Pubkey::default() {
+            return Err(anyhow::anyhow!("Poll {} has no winner", poll_id));
+        }
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let winner = self.program.account::<Candidate>(poll.winner)?;
+        let badge_mint = Keypair::new();
+        let badge_token_account = spl_associated_token_account::get_associated_token_address(
+            &winner.payout_wallet,
+            &badge_mint.pubkey(),
+        );
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::MintWinnerBadge {
+                poll: poll_address,
+                winner: poll.winner,
+                payout_wallet: winner.payout_wallet,
+                badge_mint: badge_mint.pubkey(),
+                badge_token_account,
+                metadata: get_metadata_address(&badge_mint.pubkey()).0,
+                master_edition: get_master_edition_address(&badge_mint.pubkey()).0,
+                payer: self.payer_pubkey(),
+                token_program: spl_token::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                token_metadata_program: TOKEN_METADATA_PROGRAM_ID,
+                system_program: system_program::ID,
+            })
+            .args(voting_dapp::instruction::MintWinnerBadge { uri })
+            .signer(&badge_mint);
+        let signature = self.submit(request)?;
+
+        Ok((signature, badge_mint.pubkey()))
+    }
+
+    /// Fund a poll's reward pool from the payer's associated token account
+    pub fn fund_reward_pool(&self, poll_id: u64, mint: Pubkey, amount: u64) -> Result<Signature> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let (reward_vault, _) = get_reward_vault_address(&self.program_id, &poll_address);
+        let creator_token_account =
+            spl_associated_token_account::get_associated_token_address(&self.payer_pubkey(), &mint);
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::FundRewardPool {
+                poll: poll_address,
+                mint,
+                reward_vault,
+                creator_token_account,
+                creator: self.payer_pubkey(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            })
+            .args(voting_dapp::instruction::FundRewardPool { amount });
+        let signature = self.submit(request)?;
+
+        Ok(signature)
+    }
+
+    /// Claim the payer's share of a poll's reward pool into their associated token account
+    pub fn claim_reward(&self, poll_id: u64) -> Result<(Signature, u64)> {
+        let poll = self.get_poll(poll_id)?;
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let (receipt_address, _) =
+            get_receipt_address(&self.program_id, poll_id, &self.payer_pubkey());
+        let (reward_vault, _) = get_reward_vault_address(&self.program_id, &poll_address);
+        let voter_token_account = spl_associated_token_account::get_associated_token_address(
+            &self.payer_pubkey(),
+            &poll.reward_mint,
+        );
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::ClaimReward {
+                poll: poll_address,
+                voter_receipt: receipt_address,
+                reward_vault,
+                voter_token_account,
+                voter: self.payer_pubkey(),
+                token_program: spl_token::ID,
+            })
+            .args(voting_dapp::instruction::ClaimReward {});
+        let signature = self.submit(request)?;
+
+        let share = poll.reward_pool_amount.checked_div(poll.total_votes).unwrap_or(0);
+        Ok((signature, share))
+    }
+
+    /// Settle a candidate's registration deposit, returning whether it was refunded
+    pub fn claim_deposit(&self, poll_id: u64, candidate_name: &str) -> Result<(Signature, bool)> {
+        let poll = self.get_poll(poll_id)?;
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let candidate_address = self.get_candidate_address(poll_id, candidate_name)?;
+        let (treasury_address, _) = get_treasury_address(&self.program_id);
+        let candidate = self.program.account::<Candidate>(candidate_address)?;
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::ClaimDeposit {
+                poll: poll_address,
+                candidate: candidate_address,
+                depositor: candidate.depositor,
+                treasury: treasury_address,
+            })
+            .args(voting_dapp::instruction::ClaimDeposit {});
+        let signature = self.submit(request)?;
+
+        Ok((signature, candidate.votes >= poll.deposit_refund_threshold))
+    }
+
+    /// Close every receipt of a poll whose grace period has passed, collecting the cranker's cut.
+    /// Receipts still owed a reward share are left for their voter to claim first.
+    pub fn crank_close_receipts(&self, poll_id: u64) -> Result<Vec<(Pubkey, Signature)>> {
+        let poll = self.get_poll(poll_id)?;
+        let now = chrono::Utc::now().timestamp();
+        if now <= poll.end_time.saturating_add(RECEIPT_GRACE_PERIOD) {
+            return Err(anyhow::anyhow!(
+                "Receipts of poll {} cannot be closed until {}",
+                poll_id,
+                chrono::DateTime::from_timestamp(poll.end_time + RECEIPT_GRACE_PERIOD, 0).unwrap()
+            ));
+        }
+
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let share = poll.reward_pool_amount.checked_div(poll.total_votes).unwrap_or(0);
+        let receipts: Vec<_> = self
+            .get_receipts_for_poll(poll_id)?
+            .into_iter()
+            .filter(|(_, receipt)| receipt.reward_claimed || share == 0)
+            .collect();
+
+        let mut closed = Vec::with_capacity(receipts.len());
+        for (receipt_address, receipt) in receipts {
+            let request = self
+                .program
+                .request()
+                .accounts(voting_dapp::accounts::CrankCloseReceipt {
+                    poll: poll_address,
+                    voter_receipt: receipt_address,
+                    payer: receipt.payer,
+                    cranker: self.payer_pubkey(),
+                })
+                .args(voting_dapp::instruction::CrankCloseReceipt {});
+            let signature = self.submit(request)?;
+            closed.push((receipt_address, signature));
+        }
+
+        Ok(closed)
+    }
+
+    /// Archive a settled poll, closing its poll and candidate accounts into a compact summary
+    pub fn archive_poll(&self, poll_id: u64) -> Result<(Signature, Pubkey)> {
+        let poll = self.get_poll(poll_id)?;
+        let now = chrono::Utc::now().timestamp();
+        if !poll.finalized {
+            return Err(anyhow::anyhow!("Poll {} must be finalized before it can be archived", poll_id));
+        }
+        if now <= poll.end_time.saturating_add(RECEIPT_GRACE_PERIOD) {
+            return Err(anyhow::anyhow!(
+                "Poll {} cannot be archived until {}",
+                poll_id,
+                chrono::DateTime::from_timestamp(poll.end_time + RECEIPT_GRACE_PERIOD, 0).unwrap()
+            ));
+        }
+
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let (reward_vault, _) = get_reward_vault_address(&self.program_id, &poll_address);
+        // A funded poll's vault is closed too, with any remainder going back to the creator
+        let funded = poll.reward_mint != Pubkey::default();
+        let vault_amount = if funded {
+            self.program.rpc().get_token_account_balance(&reward_vault)?.amount.parse::<u64>()?
+        } else {
+            0
+        };
+        if poll.reward_pool_amount > 0 {
+            // Equal shares leave the remainder they can't split in the vault
+            let unclaimable = poll.reward_pool_amount.checked_rem(poll.total_votes).unwrap_or(poll.reward_pool_amount);
+            if vault_amount > unclaimable {
+                return Err(anyhow::anyhow!(
+                    "Voters of poll {} still have rewards to claim before it can be archived",
+                    poll_id
+                ));
+            }
+        }
+        if poll.bounty_lamports > 0 && !poll.bounty_claimed {
+            return Err(anyhow::anyhow!("Claim the bounty of poll {} before archiving it", poll_id));
+        }
+
+        let candidates = self.get_candidates(poll_id)?;
+        if let Some((_, candidate)) = candidates.iter().find(|(_, c)| c.deposit > 0) {
+            return Err(anyhow::anyhow!(
+                "Claim the deposit of candidate {} before archiving the poll",
+                candidate.name
+            ));
+        }
+
+        let (archive_address, _) = get_poll_archive_address(&self.program_id, poll_id);
+        let creator_token_account = if vault_amount > 0 {
+            spl_associated_token_account::get_associated_token_address(&self.payer_pubkey(), &poll.reward_mint)
+        } else {
+            self.program_id
+        };
+
+        // Every candidate is closed along with the poll
+        let candidate_metas: Vec<AccountMeta> = candidates
+            .into_iter()
+            .map(|(address, _)| AccountMeta::new(address, false))
+            .collect();
+
+        let request = self
+            .program
+            .request()
+            .accounts(voting_dapp::accounts::ArchivePoll {
+                poll: poll_address,
+                archive: archive_address,
+                // Anchor treats the program ID as a missing optional account
+                reward_vault: if funded { reward_vault } else { self.program_id },
+                creator_token_account,
+                creator: self.payer_pubkey(),
+                system_program: system_program::ID,
+                token_program: if funded { spl_token::ID } else { self.program_id },
+            })
+            .accounts(candidate_metas)
+            .args(voting_dapp::instruction::ArchivePoll {});
+        let signature = self.submit(request)?;
+
+        Ok((signature, archive_address))
+    }
+
+    /// Get the archived summary of a poll
+    pub fn get_poll_archive(&self, poll_id: u64) -> Result<PollArchive> {
+        let (archive_address, _) = get_poll_archive_address(&self.program_id, poll_id);
+        let account = self.program.account::<PollArchive>(archive_address)?;
+        Ok(account)
+    }
+
+    /// Get poll details
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_poll(&self, poll_id: u64) -> Result<Poll> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let account = self.program.account::<Poll>(poll_address)?;
+        Ok(account)
+    }
+
+    /// Get all polls matching a filter, sorted by poll ID
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_all_polls(&self, filter: &PollFilter) -> Result<Vec<(Pubkey, Poll)>> {
+        let mut filters = Vec::new();
+        if let Some(creator) = filter.creator {
+            filters.push(anchor_client::solana_client::rpc_filter::RpcFilterType::Memcmp(
+                anchor_client::solana_client::rpc_filter::Memcmp::new_raw_bytes(
+                    8 + 8, // Skip discriminator and poll_id
+                    creator.to_bytes().to_vec(),
+                ),
+            ));
+        }
+
+        // Variable-length fields precede the category, tags and visibility, so those are matched
+        // locally; unlisted polls are always left out
+        let mut polls: Vec<(Pubkey, Poll)> = self
+            .program
+            .accounts::<Poll>(filters)?
+            .into_iter()
+            .filter(|(_, poll)| filter.matches(poll))
+            .collect();
+        polls.sort_by_key(|(_, poll)| poll.poll_id);
+
+        Ok(polls)
+    }
+
+    /// Every poll a wallet created, unlisted ones included, in poll ID order
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_polls_by_creator(&self, creator: &Pubkey) -> Result<Vec<(Pubkey, Poll)>> {
+        let mut polls = self.program.accounts::<Poll>(vec![
+            anchor_client::solana_client::rpc_filter::RpcFilterType::Memcmp(
+                anchor_client::solana_client::rpc_filter::Memcmp::new_raw_bytes(
+                    8 + 8, // Skip discriminator and poll_id
+                    creator.to_bytes().to_vec(),
+                ),
+            ),
+        ])?;
+        polls.sort_by_key(|(_, poll)| poll.poll_id);
+        Ok(polls)
+    }
+
+    /// Get all candidates for a poll along with their vote counts
+    pub fn get_poll_results(&self, poll_id: u64, order: CandidateOrder) -> Result<(Poll, Vec<Candidate>)> {
+        let poll = self.get_poll(poll_id)?;
+
+        let mut candidates: Vec<Candidate> = self
+            .get_candidates(poll_id)?
+            .into_iter()
+            .map(|(_, candidate)| candidate)
+            .collect();
+        sort_candidates(&mut candidates, order, &poll.ballot_order_seed);
+
+        Ok((poll, candidates))
+    }
+
+    /// Get all candidate accounts for a poll along with their addresses
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_candidates(&self, poll_id: u64) -> Result<Vec<(Pubkey, Candidate)>> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        // Fetch all candidate accounts for this poll
+        let accounts = self
+            .program
+            .accounts::<Candidate>(vec![
+                // Filter by discriminator and poll pubkey
+                anchor_client::solana_client::rpc_filter::RpcFilterType::Memcmp(
+                    anchor_client::solana_client::rpc_filter::Memcmp::new_raw_bytes(
+                        8, // Skip discriminator
+                        poll_address.to_bytes().to_vec(),
+                    ),
+                ),
+            ])?;
+
+        Ok(accounts)
+    }
+
+    /// Find a candidate's ballot index by name
+    pub fn get_candidate_index(&self, poll_id: u64, name: &str) -> Result<u16> {
+        self.get_candidates(poll_id)?
+            .into_iter()
+            .find(|(_, candidate)| candidate.name == name)
+            .map(|(_, candidate)| candidate.index)
+            .ok_or_else(|| anyhow::anyhow!("Poll {} has no candidate named {}", poll_id, name))
+    }
+
+    /// Resolve a candidate's name to its account address. Candidates registered before index
+    /// addressing live at their name-derived PDA, so the address is looked up, not derived.
+    pub fn get_candidate_address(&self, poll_id: u64, name: &str) -> Result<Pubkey> {
+        self.get_candidates(poll_id)?
+            .into_iter()
+            .find(|(_, candidate)| candidate.name == name)
+            .map(|(address, _)| address)
+            .ok_or_else(|| anyhow::anyhow!("Poll {} has no candidate named {}", poll_id, name))
+    }
+
+    /// Follow a poll's running tallies from the events recorded in its transactions, calling
+    /// `on_update` with each update and the slot it landed in. Blocks until the process is stopped.
+    pub fn watch_tallies(&self, poll_id: u64, on_update: impl Fn(TallyUpdated, u64)) -> Result<()> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let rpc = self.program.rpc();
+        let signatures_since = |until: Option<Signature>| -> Result<Vec<_>> {
+            Ok(rpc.get_signatures_for_address_with_config(
+                &poll_address,
+                GetConfirmedSignaturesForAddress2Config {
+                    until,
+                    commitment: Some(history_commitment(&rpc)),
+                    ..GetConfirmedSignaturesForAddress2Config::default()
+                },
+            )?)
+        };
+
+        // Only report votes landing from now on
+        let mut last = signatures_since(None)?
+            .first()
+            .map(|status| status.signature.parse::<Signature>())
+            .transpose()?;
+
+        loop {
+            std::thread::sleep(WATCH_POLL_INTERVAL);
+
+            // Signatures come newest first; replay them in the order they landed
+            let statuses = signatures_since(last)?;
+            if let Some(newest) = statuses.first() {
+                last = Some(newest.signature.parse()?);
+            }
+            for status in statuses.iter().rev().filter(|status| status.err.is_none()) {
+                let events: Vec<TallyUpdated> =
+                    fetch_cpi_events(&rpc, &self.program_id, &status.signature.parse()?)?;
+                for event in events.into_iter().filter(|event| event.poll == poll_address) {
+                    on_update(event, status.slot);
+                }
+            }
+        }
+    }
+
+    /// Subscribe to a poll's account, calling `on_update` with the poll and the slot of each change,
+    /// or with `None` once the account is closed
+    pub fn subscribe_poll(
+        &self,
+        poll_id: u64,
+        on_update: impl FnMut(Option<Poll>, u64) + Send + 'static,
+    ) -> Result<Subscription> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        self.subscribe_account(&poll_address, on_update)
+    }
+
+    /// Subscribe to a candidate's account, calling `on_update` with the candidate and the slot of
+    /// each change, such as a vote, or with `None` once the account is closed
+    pub fn subscribe_candidate(
+        &self,
+        poll_id: u64,
+        name: &str,
+        on_update: impl FnMut(Option<Candidate>, u64) + Send + 'static,
+    ) -> Result<Subscription> {
+        let candidate_address = self.get_candidate_address(poll_id, name)?;
+        self.subscribe_account(&candidate_address, on_update)
+    }
+
+    fn subscribe_account<T: AccountDeserialize + Send + 'static>(
+        &self,
+        address: &Pubkey,
+        mut on_update: impl FnMut(Option<T>, u64) + Send + 'static,
+    ) -> Result<Subscription> {
+        let ws_url = self
+            .ws_url
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Subscriptions need the cluster's websocket URL"))?;
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.program.rpc().commitment()),
+            ..RpcAccountInfoConfig::default()
+        };
+        let (subscription, updates) = PubsubClient::account_subscribe(ws_url, address, Some(config))
+            .map_err(|e| anyhow::anyhow!("Failed to subscribe to {} at {}: {}", address, ws_url, e))?;
+
+        let listener = std::thread::spawn(move || {
+            for update in updates {
+                let Some(account) = update.value.decode::<Account>() else {
+                    continue;
+                };
+                // A closed account is left without lamports or data
+                if account.lamports == 0 {
+                    on_update(None, update.context.slot);
+                } else if let Ok(decoded) = T::try_deserialize(&mut &account.data[..]) {
+                    on_update(Some(decoded), update.context.slot);
+                }
+            }
+        });
+        Ok(Subscription { subscription, listener })
+    }
+
+    /// A page of the transactions that touched a poll, newest first, each decoded into what it did
+    /// to the poll. Pages like `getSignaturesForAddress`: up to `limit` transactions older than
+    /// `before`, or the latest ones.
+    pub fn get_poll_history(
+        &self,
+        poll_id: u64,
+        limit: usize,
+        before: Option<Signature>,
+    ) -> Result<Vec<PollHistoryEntry>> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+        let rpc = self.program.rpc();
+        let statuses = rpc.get_signatures_for_address_with_config(
+            &poll_address,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                limit: Some(limit),
+                commitment: Some(history_commitment(&rpc)),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )?;
+
+        // Votes name their candidate by account; candidates closed since can't be named
+        let candidate_names: Vec<(Pubkey, String)> = self
+            .get_candidates(poll_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(address, candidate)| (address, candidate.name))
+            .collect();
+
+        statuses
+            .into_iter()
+            .map(|status| {
+                let instructions = fetch_program_instructions(&rpc, &self.program_id, &status.signature.parse()?)?;
+                let actions = instructions
+                    .iter()
+                    .filter_map(|instruction| describe_poll_instruction(instruction, &poll_address, &candidate_names))
+                    .collect();
+                Ok(PollHistoryEntry {
+                    signature: status.signature,
+                    slot: status.slot,
+                    block_time: status.block_time,
+                    error: status.err.map(|e| e.to_string()),
+                    actions,
+                })
+            })
+            .collect()
+    }
+
+    /// Decode the program's instructions in any transaction, whichever client sent it: the name
+    /// and args of each, and the accounts it was given
+    pub fn decode_transaction(&self, signature: &Signature) -> Result<Vec<DecodedInstruction>> {
+        let instructions = fetch_program_instructions(&self.program.rpc(), &self.program_id, signature)?;
+        Ok(instructions.iter().map(decode_instruction).collect())
+    }
+
+    /// An account's raw data with the slot it was read at; `None` when the account doesn't exist
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_account_data_at_slot(&self, address: &Pubkey) -> Result<(u64, Option<Vec<u8>>)> {
+        let rpc = self.program.rpc();
+        let response = rpc.get_account_with_commitment(address, rpc.commitment())?;
+        Ok((response.context.slot, response.value.map(|account| account.data)))
+    }
+
+    /// Check if a user has voted in a poll
+    #[instrument(level = "debug", skip(self))]
+    pub fn has_voted(&self, poll_id: u64, voter: Pubkey) -> Result<bool> {
+        let (receipt_address, _) = get_receipt_address(&self.program_id, poll_id, &voter);
+
+        match self.program.account::<VoterReceipt>(receipt_address) {
+            Ok(receipt) => Ok(receipt.has_voted),
+            Err(_) => Ok(false), // Receipt doesn't exist, so user hasn't voted
+        }
+    }
+
+    /// Check many voters at once, fetching their receipts `MULTIPLE_ACCOUNTS_BATCH_SIZE` at a
+    /// time. Returns whether each voter has voted, in the order given.
+    #[instrument(level = "debug", skip(self, voters), fields(voters = voters.len()))]
+    pub fn has_voted_many(&self, poll_id: u64, voters: &[Pubkey]) -> Result<Vec<bool>> {
+        let rpc = self.program.rpc();
+        let mut voted = Vec::with_capacity(voters.len());
+        for batch in voters.chunks(MULTIPLE_ACCOUNTS_BATCH_SIZE) {
+            let receipt_addresses: Vec<Pubkey> = batch
+                .iter()
+                .map(|voter| get_receipt_address(&self.program_id, poll_id, voter).0)
+                .collect();
+            for account in rpc.get_multiple_accounts(&receipt_addresses)? {
+                // A missing receipt means no vote, as in `has_voted`
+                let has_voted = account
+                    .and_then(|account| VoterReceipt::try_deserialize(&mut &account.data[..]).ok())
+                    .is_some_and(|receipt| receipt.has_voted);
+                voted.push(has_voted);
+            }
+        }
+        Ok(voted)
+    }
+
+    /// Get the voter receipts of a poll. Receipts closed after the poll are no longer listed.
+    pub fn get_receipts_for_poll(&self, poll_id: u64) -> Result<Vec<(Pubkey, VoterReceipt)>> {
+        let (poll_address, _) = get_poll_address(&self.program_id, poll_id);
+
+        let receipts = self
+            .program
+            .accounts::<VoterReceipt>(vec![
+                // Filter by discriminator and poll pubkey
+                anchor_client::solana_client::rpc_filter::RpcFilterType::Memcmp(
+                    anchor_client::solana_client::rpc_filter::Memcmp::new_raw_bytes(
+                        8, // Skip discriminator
+                        poll_address.to_bytes().to_vec(),
+                    ),
+                ),
+            ])?;
+
+        Ok(receipts)
+    }
+
+    /// Sum the vote weights recorded in a poll's receipts, checking it against the candidate totals
+    pub fn sum_receipt_weights(&self, poll_id: u64) -> Result<u128> {
+        let receipts = self.get_receipts_for_poll(poll_id)?;
+        // Widened, since weighted totals can each approach u64::MAX
+        let receipt_total: u128 = receipts.iter().map(|(_, receipt)| receipt.weight as u128).sum();
+
+        let candidate_total: u128 = self
+            .get_candidates(poll_id)?
+            .iter()
+            .map(|(_, candidate)| candidate.votes as u128)
+            .sum();
+
+        if receipt_total != candidate_total {
+            return Err(anyhow::anyhow!(
+                "Poll {} receipts carry {} votes of weight but candidates hold {}",
+                poll_id,
+                receipt_total,
+                candidate_total
+            ));
+        }
+
+        Ok(receipt_total)
+    }
+}
+
+// Define the instruction and account structs for the program
+// Ways of casting a ballot, told apart by instruction discriminator
+const VOTE_INSTRUCTIONS: [[u8; 8]; 7] = [
+    voting_dapp::instruction::Vote::DISCRIMINATOR,
+    voting_dapp::instruction::VoteShielded::DISCRIMINATOR,
+    voting_dapp::instruction::VoteBallot::DISCRIMINATOR,
+    voting_dapp::instruction::VoteGauge::DISCRIMINATOR,
+    voting_dapp::instruction::VoteConviction::DISCRIMINATOR,
+    voting_dapp::instruction::VoteGovernance::DISCRIMINATOR,
+    voting_dapp::instruction::VoteCompressed::DISCRIMINATOR,
+];
+
+// Other instructions on a poll, as the history describes them
+const POLL_INSTRUCTIONS: [([u8; 8], &str); 18] = [
+    (voting_dapp::instruction::ProposePoll::DISCRIMINATOR, "poll proposed to the council"),
+    (voting_dapp::instruction::CancelPollProposal::DISCRIMINATOR, "poll proposal cancelled"),
+    (voting_dapp::instruction::ApprovePollCreation::DISCRIMINATOR, "poll creation approved"),
+    (voting_dapp::instruction::UpdatePoll::DISCRIMINATOR, "poll updated"),
+    (voting_dapp::instruction::UpdateCandidateMetadata::DISCRIMINATOR, "candidate metadata updated"),
+    (voting_dapp::instruction::SetCandidateManager::DISCRIMINATOR, "candidate manager changed"),
+    (voting_dapp::instruction::ActivatePoll::DISCRIMINATOR, "poll activated"),
+    (voting_dapp::instruction::PausePoll::DISCRIMINATOR, "voting paused"),
+    (voting_dapp::instruction::ResumePoll::DISCRIMINATOR, "voting resumed"),
+    (voting_dapp::instruction::GrantAccess::DISCRIMINATOR, "voter access granted"),
+    (voting_dapp::instruction::RevokeAccess::DISCRIMINATOR, "voter access revoked"),
+    (voting_dapp::instruction::ScheduleFinalization::DISCRIMINATOR, "finalization scheduled"),
+    (voting_dapp::instruction::CancelFinalization::DISCRIMINATOR, "scheduled finalization cancelled"),
+    (voting_dapp::instruction::FinalizePoll::DISCRIMINATOR, "poll finalized"),
+    (voting_dapp::instruction::PublishShieldedResults::DISCRIMINATOR, "shielded results published"),
+    (voting_dapp::instruction::ClaimDeposit::DISCRIMINATOR, "candidate deposit claimed"),
+    (voting_dapp::instruction::ClaimReward::DISCRIMINATOR, "voter reward claimed"),
+    (voting_dapp::instruction::ArchivePoll::DISCRIMINATOR, "poll archived"),
+];
+
+/// Describe what an instruction did to a poll, or `None` when it didn't involve the poll
+fn describe_poll_instruction(
+    instruction: &ProgramInstruction,
+    poll: &Pubkey,
+    candidate_names: &[(Pubkey, String)],
+) -> Option<PollAction> {
+    if !instruction.accounts.iter().any(|(key, _)| key == poll) {
+        return None;
+    }
+    let discriminator: [u8; 8] = instruction.data.get(..8)?.try_into().ok()?;
+    let mut args = &instruction.data[8..];
+    // Anchor lists the acting signer before the payer, so the first signer is the creator or voter
+    let first_signer = instruction.accounts.iter().find(|(_, is_signer)| *is_signer).map(|(key, _)| *key);
+
+    let action = if discriminator == voting_dapp::instruction::InitializePoll::DISCRIMINATOR {
+        PollAction::PollCreated {
+            creator: first_signer?,
+        }
+    } else if discriminator == voting_dapp::instruction::InitializeCandidate::DISCRIMINATOR {
+        let candidate = voting_dapp::instruction::InitializeCandidate::deserialize(&mut args).ok()?;
+        PollAction::CandidatesAdded {
+            names: vec![candidate.name],
+        }
+    } else if discriminator == voting_dapp::instruction::InitializeCandidatesBatch::DISCRIMINATOR {
+        let batch = voting_dapp::instruction::InitializeCandidatesBatch::deserialize(&mut args).ok()?;
+        PollAction::CandidatesAdded {
+            names: batch.candidates.into_iter().map(|candidate| candidate.name).collect(),
+        }
+    } else if VOTE_INSTRUCTIONS.contains(&discriminator) {
+        let candidate = instruction.accounts.iter().find_map(|(key, _)| {
+            candidate_names
+                .iter()
+                .find(|(address, _)| address == key)
+                .map(|(_, name)| name.clone())
+        });
+        PollAction::Vote {
+            voter: first_signer?,
+            candidate,
+        }
+    } else {
+        let description = POLL_INSTRUCTIONS
+            .iter()
+            .find(|(known, _)| *known == discriminator)
+            .map_or("other instruction", |(_, description)| *description);
+        PollAction::Other { description }
+    };
+    Some(action)
+}
+
+/// How `decode-tx` reads an instruction: its name, its accounts in order and a decoder for its args
+struct InstructionLayout {
+    name: &'static str,
+    discriminator: [u8; 8],
+    accounts: &'static [&'static str],
+    decode_args: fn(&[u8]) -> Option<String>,
+}
+
+macro_rules! instruction_layouts {
+    ($($name:ident: [$($account:ident),* $(,)?]),* $(,)?) => {
+        &[$(InstructionLayout {
+            name: stringify!($name),
+            discriminator: voting_dapp::instruction::$name::DISCRIMINATOR,
+            accounts: &[$(stringify!($account)),*],
+            decode_args: |mut data| {
+                voting_dapp::instruction::$name::deserialize(&mut data).ok().map(|args| format!("{:#?}", args))
+            },
+        }),*]
+    };
+}
+
+// Every instruction of the program, with its accounts named as in the program's account structs
+const INSTRUCTION_LAYOUTS: &[InstructionLayout] = instruction_layouts! {
+    InitializeConfig: [config, treasury, program_data, admin, system_program],
+    WithdrawTreasury: [config, treasury, admin, recipient, event_authority, program],
+    SetCouncil: [config, admin],
+    SetCreatorAllowlist: [config, admin],
+    SetMaxPollDuration: [config, admin],
+    GrantCreatorPass: [config, creator_pass, admin, system_program],
+    RevokeCreatorPass: [config, creator_pass, admin],
+    ProposePoll: [poll_proposal, creator, system_program],
+    CancelPollProposal: [config, poll_proposal, creator, authority],
+    ApprovePollCreation: [config, poll_proposal, member],
+    InitializePoll: [
+        poll, config, treasury, poll_proposal, creator_pass, creator, system_program, creator_stats, poll_archive
+    ],
+    InitializeCandidate: [poll, candidate, creator, system_program, nomination],
+    Vote: [
+        poll, candidate, voter_receipt, voter, payer, system_program, tree_authority, merkle_tree, bubblegum_program,
+        log_wrapper, compression_program, stake_account, instructions, stake_history, stake_marker, eligibility_program,
+        attestation, access_grant, weight_record, fee_recipient, fee_source, token_program, event_authority, program
+    ],
+    FinalizePoll: [poll],
+    ClaimDeposit: [poll, candidate, depositor, treasury],
+    ArchivePoll: [
+        poll, archive, reward_vault, creator_token_account, creator, system_program, token_program
+    ],
+    InitShieldedTally: [poll, tally, creator, system_program],
+    VoteShielded: [poll, tally, voter_receipt, voter, system_program, instructions],
+    PublishShieldedResults: [poll, tally],
+    CrankCloseReceipt: [poll, voter_receipt, payer, cranker],
+    FundRewardPool: [poll, mint, reward_vault, creator_token_account, creator, token_program, system_program],
+    ClaimReward: [poll, voter_receipt, reward_vault, voter_token_account, voter, token_program],
+    ClaimBounty: [poll, candidate, recipient],
+    MintWinnerBadge: [
+        poll, winner, payout_wallet, badge_mint, badge_token_account, metadata, master_edition, payer, token_program,
+        associated_token_program, token_metadata_program, system_program
+    ],
+    InitializeBallot: [ballot, creator, system_program],
+    CreateProposal: [poll, yes_candidate, proposal, creator, system_program],
+    ExecuteProposal: [poll, proposal, yes_candidate, poll_authority, target_program],
+    VoteBallot: [ballot, voter, system_program, instructions, event_authority, program],
+    VoteGauge: [poll, voter_receipt, voter, system_program, instructions, event_authority, program],
+    VoteConviction: [
+        poll, candidate, voter_receipt, conviction_lock, lock_vault, mint, voter_token_account, voter, token_program,
+        system_program, instructions, event_authority, program
+    ],
+    Unlock: [conviction_lock, lock_vault, voter_token_account, voter, token_program],
+    VoteGovernance: [
+        poll, candidate, voter_receipt, token_owner_record, voter, system_program, instructions, realm, realm_config,
+        lock_authority, governance_program, event_authority, program
+    ],
+    InitializeCandidatesBatch: [poll, creator, system_program],
+    ScheduleFinalization: [poll, creator, thread, thread_program, system_program],
+    CancelFinalization: [poll, creator, thread, thread_program],
+    RequestRandomness: [poll, requester, randomness],
+    ApplyRandomness: [poll, randomness, event_authority, program],
+    CancelRandomness: [poll, randomness],
+    GrantAccess: [poll, access_grant, creator, system_program],
+    RevokeAccess: [poll, access_grant, creator],
+    PublishWeight: [weight_record, oracle, system_program],
+    SetCreationLimit: [config, admin],
+    RegisterAuditors: [poll, poll_result, creator, system_program],
+    AttestResult: [poll, poll_result, auditor],
+    SetFeeSchedule: [config, admin, event_authority, program],
+    UpdateCandidateMetadata: [poll, candidate, creator],
+    UpdatePoll: [poll, config, creator, system_program],
+    MigrateAccount: [account, payer, system_program],
+    InitReceiptTree: [poll, creator, merkle_tree, compression_program, log_wrapper, event_authority, program],
+    RegisterCompressedVoter: [
+        poll, registration, creator, merkle_tree, compression_program, log_wrapper, system_program, event_authority,
+        program
+    ],
+    VoteCompressed: [
+        poll, candidate, voter, merkle_tree, compression_program, log_wrapper, instructions, event_authority, program
+    ],
+    ActivatePoll: [poll, creator],
+    SetCandidateManager: [poll, creator],
+    PausePoll: [poll, creator],
+    ResumePoll: [poll, creator],
+    CreateSession: [ballot, session, voter, system_program],
+    RevokeSession: [session, voter],
+    VoteWithSession: [
+        poll, candidate, ballot, session, voter_receipt, voter, session_key, system_program, instructions,
+        event_authority, program
+    ],
+};
+
+/// Name an instruction and its accounts, and decode its args, from the program's layouts
+fn decode_instruction(instruction: &ProgramInstruction) -> DecodedInstruction {
+    let layout = instruction
+        .data
+        .get(..8)
+        .and_then(|discriminator| INSTRUCTION_LAYOUTS.iter().find(|layout| layout.discriminator[..] == *discriminator));
+    let accounts = instruction
+        .accounts
+        .iter()
+        .enumerate()
+        .map(|(index, (address, is_signer))| DecodedAccount {
+            name: match layout.and_then(|layout| layout.accounts.get(index)) {
+                Some(name) => name.to_string(),
+                None => format!("account {}", index),
+            },
+            address: *address,
+            is_signer: *is_signer,
+        })
+        .collect();
+    DecodedInstruction {
+        name: layout.map_or("unknown instruction", |layout| layout.name),
+        args: layout.and_then(|layout| (layout.decode_args)(&instruction.data[8..])),
+        data: crate::utils::to_hex(&instruction.data),
+        accounts,
+    }
+}
+
+mod voting_dapp {
+    use super::*;
+
+    pub mod instruction {
+        use super::*;
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct InitializeConfig {
+            pub poll_creation_fee: u64,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for InitializeConfig {
+            const DISCRIMINATOR: [u8; 8] = [208, 127, 21, 1, 194, 190, 196, 70];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for InitializeConfig {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct WithdrawTreasury {
+            pub amount: u64,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for WithdrawTreasury {
+            const DISCRIMINATOR: [u8; 8] = [40, 63, 122, 158, 144, 216, 83, 96];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for WithdrawTreasury {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct SetCouncil {
+            pub members: Vec<Pubkey>,
+            pub threshold: u8,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for SetCouncil {
+            const DISCRIMINATOR: [u8; 8] = [34, 228, 120, 49, 39, 179, 251, 154];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for SetCouncil {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct SetCreatorAllowlist {
+            pub enabled: bool,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for SetCreatorAllowlist {
+            const DISCRIMINATOR: [u8; 8] = [2, 19, 205, 224, 240, 8, 82, 63];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for SetCreatorAllowlist {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct SetMaxPollDuration {
+            pub max_poll_duration: i64,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for SetMaxPollDuration {
+            const DISCRIMINATOR: [u8; 8] = [19, 226, 7, 146, 175, 235, 18, 44];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for SetMaxPollDuration {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct GrantCreatorPass {
+            pub creator: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for GrantCreatorPass {
+            const DISCRIMINATOR: [u8; 8] = [239, 132, 131, 28, 246, 49, 233, 177];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for GrantCreatorPass {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct RevokeCreatorPass {}
+
+        impl anchor_client::anchor_lang::Discriminator for RevokeCreatorPass {
+            const DISCRIMINATOR: [u8; 8] = [15, 144, 0, 21, 126, 130, 244, 232];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for RevokeCreatorPass {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct ProposePoll {
+            pub poll_id: u64,
+            pub question: String,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for ProposePoll {
+            const DISCRIMINATOR: [u8; 8] = [125, 113, 112, 30, 102, 166, 14, 95];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for ProposePoll {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct CancelPollProposal {}
+
+        impl anchor_client::anchor_lang::Discriminator for CancelPollProposal {
+            const DISCRIMINATOR: [u8; 8] = [19, 145, 199, 148, 251, 223, 130, 214];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for CancelPollProposal {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct ApprovePollCreation {}
+
+        impl anchor_client::anchor_lang::Discriminator for ApprovePollCreation {
+            const DISCRIMINATOR: [u8; 8] = [166, 8, 205, 93, 210, 157, 45, 18];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for ApprovePollCreation {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct InitializePoll {
+            pub poll_id: u64,
+            pub question: String,
+            pub description: String,
+            pub start_time: i64,
+            pub end_time: i64,
+            pub options: PollOptions,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for InitializePoll {
+            const DISCRIMINATOR: [u8; 8] = [193, 22, 99, 197, 18, 33, 115, 117];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for InitializePoll {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct InitializeCandidate {
+            pub name: String,
+            pub party: String,
+            pub payout_wallet: Pubkey,
+            pub metadata_uri: String,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for InitializeCandidate {
+            const DISCRIMINATOR: [u8; 8] = [210, 107, 118, 204, 255, 97, 112, 26];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for InitializeCandidate {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct Vote {}
+
+        impl anchor_client::anchor_lang::Discriminator for Vote {
+            const DISCRIMINATOR: [u8; 8] = [227, 110, 155, 23, 136, 126, 172, 25];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for Vote {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct FinalizePoll {}
+
+        impl anchor_client::anchor_lang::Discriminator for FinalizePoll {
+            const DISCRIMINATOR: [u8; 8] = [90, 57, 229, 211, 20, 47, 151, 93];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for FinalizePoll {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct ClaimDeposit {}
+
+        impl anchor_client::anchor_lang::Discriminator for ClaimDeposit {
+            const DISCRIMINATOR: [u8; 8] = [201, 106, 1, 224, 122, 144, 210, 155];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for ClaimDeposit {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct ArchivePoll {}
+
+        impl anchor_client::anchor_lang::Discriminator for ArchivePoll {
+            const DISCRIMINATOR: [u8; 8] = [65, 87, 109, 72, 238, 221, 190, 242];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for ArchivePoll {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct InitShieldedTally {}
+
+        impl anchor_client::anchor_lang::Discriminator for InitShieldedTally {
+            const DISCRIMINATOR: [u8; 8] = [146, 212, 187, 37, 172, 172, 197, 169];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for InitShieldedTally {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct VoteShielded {
+            pub choices: Vec<ShieldedChoice>,
+            pub sum_proof: DleqProof,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for VoteShielded {
+            const DISCRIMINATOR: [u8; 8] = [4, 220, 9, 224, 109, 91, 45, 124];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for VoteShielded {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct PublishShieldedResults {
+            pub results: Vec<ShieldedResult>,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for PublishShieldedResults {
+            const DISCRIMINATOR: [u8; 8] = [109, 93, 56, 135, 51, 97, 136, 76];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for PublishShieldedResults {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct CrankCloseReceipt {}
+
+        impl anchor_client::anchor_lang::Discriminator for CrankCloseReceipt {
+            const DISCRIMINATOR: [u8; 8] = [74, 230, 68, 138, 249, 203, 158, 180];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for CrankCloseReceipt {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct FundRewardPool {
+            pub amount: u64,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for FundRewardPool {
+            const DISCRIMINATOR: [u8; 8] = [85, 49, 108, 245, 204, 70, 243, 3];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for FundRewardPool {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct ClaimReward {}
+
+        impl anchor_client::anchor_lang::Discriminator for ClaimReward {
+            const DISCRIMINATOR: [u8; 8] = [149, 95, 181, 242, 94, 90, 158, 162];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for ClaimReward {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct ClaimBounty {}
+
+        impl anchor_client::anchor_lang::Discriminator for ClaimBounty {
+            const DISCRIMINATOR: [u8; 8] = [225, 157, 163, 238, 239, 169, 75, 226];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for ClaimBounty {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct MintWinnerBadge {
+            pub uri: String,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for MintWinnerBadge {
+            const DISCRIMINATOR: [u8; 8] = [181, 60, 45, 164, 157, 254, 89, 89];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for MintWinnerBadge {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct InitializeBallot {
+            pub ballot_id: u64,
+            pub title: String,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for InitializeBallot {
+            const DISCRIMINATOR: [u8; 8] = [47, 140, 132, 69, 184, 201, 73, 0];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for InitializeBallot {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct CreateProposal {
+            pub threshold_bps: u16,
+            pub program_id: Pubkey,
+            pub accounts: Vec<ProposalAccountMeta>,
+            pub data: Vec<u8>,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for CreateProposal {
+            const DISCRIMINATOR: [u8; 8] = [132, 116, 68, 174, 216, 160, 198, 22];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for CreateProposal {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct ExecuteProposal {}
+
+        impl anchor_client::anchor_lang::Discriminator for ExecuteProposal {
+            const DISCRIMINATOR: [u8; 8] = [186, 60, 116, 133, 108, 128, 111, 28];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for ExecuteProposal {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct VoteBallot {}
+
+        impl anchor_client::anchor_lang::Discriminator for VoteBallot {
+            const DISCRIMINATOR: [u8; 8] = [87, 195, 116, 62, 158, 142, 191, 98];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for VoteBallot {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct VoteGauge {
+            pub points: Vec<u16>,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for VoteGauge {
+            const DISCRIMINATOR: [u8; 8] = [181, 240, 7, 119, 123, 217, 116, 48];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for VoteGauge {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct VoteConviction {
+            pub amount: u64,
+            pub lock: LockPeriod,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for VoteConviction {
+            const DISCRIMINATOR: [u8; 8] = [11, 46, 15, 185, 9, 122, 30, 26];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for VoteConviction {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct Unlock {}
+
+        impl anchor_client::anchor_lang::Discriminator for Unlock {
+            const DISCRIMINATOR: [u8; 8] = [101, 155, 40, 21, 158, 189, 56, 203];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for Unlock {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct VoteGovernance {}
+
+        impl anchor_client::anchor_lang::Discriminator for VoteGovernance {
+            const DISCRIMINATOR: [u8; 8] = [150, 39, 71, 82, 72, 251, 170, 60];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for VoteGovernance {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct InitializeCandidatesBatch {
+            pub candidates: Vec<CandidateEntry>,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for InitializeCandidatesBatch {
+            const DISCRIMINATOR: [u8; 8] = [110, 12, 31, 124, 94, 27, 89, 42];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for InitializeCandidatesBatch {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct ScheduleFinalization {
+            pub fee_lamports: u64,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for ScheduleFinalization {
+            const DISCRIMINATOR: [u8; 8] = [222, 195, 40, 189, 25, 91, 102, 37];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for ScheduleFinalization {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct CancelFinalization {}
+
+        impl anchor_client::anchor_lang::Discriminator for CancelFinalization {
+            const DISCRIMINATOR: [u8; 8] = [220, 54, 231, 128, 99, 135, 205, 169];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for CancelFinalization {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct RequestRandomness {}
+
+        impl anchor_client::anchor_lang::Discriminator for RequestRandomness {
+            const DISCRIMINATOR: [u8; 8] = [213, 5, 173, 166, 37, 236, 31, 18];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for RequestRandomness {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct ApplyRandomness {}
+
+        impl anchor_client::anchor_lang::Discriminator for ApplyRandomness {
+            const DISCRIMINATOR: [u8; 8] = [38, 37, 208, 134, 100, 5, 201, 118];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for ApplyRandomness {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct CancelRandomness {}
+
+        impl anchor_client::anchor_lang::Discriminator for CancelRandomness {
+            const DISCRIMINATOR: [u8; 8] = [21, 192, 215, 3, 117, 52, 21, 68];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for CancelRandomness {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct GrantAccess {
+            pub voter: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for GrantAccess {
+            const DISCRIMINATOR: [u8; 8] = [66, 88, 87, 113, 39, 22, 27, 165];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for GrantAccess {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct RevokeAccess {}
+
+        impl anchor_client::anchor_lang::Discriminator for RevokeAccess {
+            const DISCRIMINATOR: [u8; 8] = [106, 128, 38, 169, 103, 238, 102, 147];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for RevokeAccess {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct PublishWeight {
+            pub voter: Pubkey,
+            pub weight: u64,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for PublishWeight {
+            const DISCRIMINATOR: [u8; 8] = [237, 123, 20, 103, 116, 6, 230, 214];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for PublishWeight {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct SetCreationLimit {
+            pub max_polls_per_window: u16,
+            pub creation_window: i64,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for SetCreationLimit {
+            const DISCRIMINATOR: [u8; 8] = [115, 183, 51, 16, 148, 177, 0, 191];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for SetCreationLimit {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct RegisterAuditors {
+            pub auditors: Vec<Pubkey>,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for RegisterAuditors {
+            const DISCRIMINATOR: [u8; 8] = [61, 232, 189, 15, 39, 180, 5, 77];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for RegisterAuditors {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct AttestResult {
+            pub winner: Pubkey,
+            pub total_votes: u64,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for AttestResult {
+            const DISCRIMINATOR: [u8; 8] = [69, 124, 3, 11, 254, 100, 69, 181];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for AttestResult {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct SetFeeSchedule {
+            pub poll_creation_fee: u64,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for SetFeeSchedule {
+            const DISCRIMINATOR: [u8; 8] = [239, 37, 205, 178, 164, 47, 23, 13];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for SetFeeSchedule {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct UpdateCandidateMetadata {
+            pub metadata_uri: String,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for UpdateCandidateMetadata {
+            const DISCRIMINATOR: [u8; 8] = [171, 241, 240, 158, 200, 133, 44, 29];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for UpdateCandidateMetadata {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct UpdatePoll {
+            pub question: Option<String>,
+            pub description: Option<String>,
+            pub end_time: Option<i64>,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for UpdatePoll {
+            const DISCRIMINATOR: [u8; 8] = [188, 131, 217, 106, 140, 114, 130, 5];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for UpdatePoll {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct MigrateAccount {}
+
+        impl anchor_client::anchor_lang::Discriminator for MigrateAccount {
+            const DISCRIMINATOR: [u8; 8] = [177, 228, 60, 125, 13, 116, 44, 84];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for MigrateAccount {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct InitReceiptTree {
+            pub max_depth: u32,
+            pub max_buffer_size: u32,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for InitReceiptTree {
+            const DISCRIMINATOR: [u8; 8] = [163, 226, 30, 137, 146, 72, 33, 201];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for InitReceiptTree {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct RegisterCompressedVoter {
+            pub voter: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for RegisterCompressedVoter {
+            const DISCRIMINATOR: [u8; 8] = [246, 211, 206, 38, 6, 35, 146, 124];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for RegisterCompressedVoter {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct VoteCompressed {
+            pub root: [u8; 32],
+            pub index: u32,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for VoteCompressed {
+            const DISCRIMINATOR: [u8; 8] = [97, 173, 165, 48, 112, 117, 49, 36];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for VoteCompressed {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct ActivatePoll {}
+
+        impl anchor_client::anchor_lang::Discriminator for ActivatePoll {
+            const DISCRIMINATOR: [u8; 8] = [93, 248, 5, 3, 106, 145, 72, 39];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for ActivatePoll {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct SetCandidateManager {
+            pub manager: Option<Pubkey>,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for SetCandidateManager {
+            const DISCRIMINATOR: [u8; 8] = [242, 94, 231, 108, 64, 107, 105, 17];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for SetCandidateManager {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct PausePoll {}
+
+        impl anchor_client::anchor_lang::Discriminator for PausePoll {
+            const DISCRIMINATOR: [u8; 8] = [41, 68, 96, 33, 228, 53, 145, 162];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for PausePoll {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct ResumePoll {
+            pub extend_end_time: bool,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for ResumePoll {
+            const DISCRIMINATOR: [u8; 8] = [210, 200, 15, 73, 62, 226, 83, 136];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for ResumePoll {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct CreateSession {
+            pub session_key: Pubkey,
+            pub expiry_slot: u64,
+        }
+
+        impl anchor_client::anchor_lang::Discriminator for CreateSession {
+            const DISCRIMINATOR: [u8; 8] = [242, 193, 143, 179, 150, 25, 122, 227];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for CreateSession {
+            fn data(&self) -> Vec<u8> {
+                let mut data = Self::DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&anchor_client::anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                data
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct RevokeSession {}
+
+        impl anchor_client::anchor_lang::Discriminator for RevokeSession {
+            const DISCRIMINATOR: [u8; 8] = [86, 92, 198, 120, 144, 2, 7, 194];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for RevokeSession {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+        pub struct VoteWithSession {}
+
+        impl anchor_client::anchor_lang::Discriminator for VoteWithSession {
+            const DISCRIMINATOR: [u8; 8] = [76, 108, 20, 128, 27, 84, 160, 77];
+        }
+
+        impl anchor_client::anchor_lang::InstructionData for VoteWithSession {
+            fn data(&self) -> Vec<u8> {
+                Self::DISCRIMINATOR.to_vec()
+            }
+        }
+    }
+
+    pub mod accounts {
+        use super::*;
+
+        pub struct InitializeConfig {
+            pub config: Pubkey,
+            pub treasury: Pubkey,
+            pub program_data: Pubkey,
+            pub admin: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for InitializeConfig {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.treasury,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program_data,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.admin,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct WithdrawTreasury {
+            pub config: Pubkey,
+            pub treasury: Pubkey,
+            pub admin: Pubkey,
+            pub recipient: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for WithdrawTreasury {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.treasury,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.admin,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.recipient,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct SetCouncil {
+            pub config: Pubkey,
+            pub admin: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for SetCouncil {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.admin,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct SetCreatorAllowlist {
+            pub config: Pubkey,
+            pub admin: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for SetCreatorAllowlist {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.admin,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct SetMaxPollDuration {
+            pub config: Pubkey,
+            pub admin: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for SetMaxPollDuration {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.admin,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct GrantCreatorPass {
+            pub config: Pubkey,
+            pub creator_pass: Pubkey,
+            pub admin: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for GrantCreatorPass {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator_pass,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.admin,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct RevokeCreatorPass {
+            pub config: Pubkey,
+            pub creator_pass: Pubkey,
+            pub admin: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for RevokeCreatorPass {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator_pass,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.admin,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct ProposePoll {
+            pub poll_proposal: Pubkey,
+            pub creator: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for ProposePoll {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll_proposal,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct CancelPollProposal {
+            pub config: Pubkey,
+            pub poll_proposal: Pubkey,
+            pub creator: Pubkey,
+            pub authority: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for CancelPollProposal {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll_proposal,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.authority,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct ApprovePollCreation {
+            pub config: Pubkey,
+            pub poll_proposal: Pubkey,
+            pub member: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for ApprovePollCreation {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll_proposal,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.member,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct InitializePoll {
+            pub poll: Pubkey,
+            pub config: Pubkey,
+            pub treasury: Pubkey,
+            pub poll_proposal: Pubkey,
+            pub creator_pass: Pubkey,
+            pub creator: Pubkey,
+            pub system_program: Pubkey,
+            pub creator_stats: Pubkey,
+            pub poll_archive: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for InitializePoll {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.treasury,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll_proposal,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.creator_pass,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator_stats,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.poll_archive,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct InitializeCandidate {
+            pub poll: Pubkey,
+            pub candidate: Pubkey,
+            pub creator: Pubkey,
+            pub system_program: Pubkey,
+            pub nomination: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for InitializeCandidate {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.candidate,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.nomination,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct Vote {
+            pub poll: Pubkey,
+            pub candidate: Pubkey,
+            pub voter_receipt: Pubkey,
+            pub voter: Pubkey,
+            pub payer: Pubkey,
+            pub system_program: Pubkey,
+            pub tree_authority: Pubkey,
+            pub merkle_tree: Pubkey,
+            pub bubblegum_program: Pubkey,
+            pub log_wrapper: Pubkey,
+            pub compression_program: Pubkey,
+            pub stake_account: Pubkey,
+            pub instructions: Pubkey,
+            pub stake_history: Pubkey,
+            pub stake_marker: Pubkey,
+            pub eligibility_program: Pubkey,
+            pub attestation: Pubkey,
+            pub access_grant: Pubkey,
+            pub weight_record: Pubkey,
+            pub fee_recipient: Pubkey,
+            pub fee_source: Pubkey,
+            pub token_program: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for Vote {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.candidate,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter_receipt,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.voter,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.payer,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.tree_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.merkle_tree,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.bubblegum_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.log_wrapper,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.compression_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.stake_account,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.instructions,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.stake_history,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.stake_marker,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.eligibility_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.attestation,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.access_grant,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.weight_record,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.fee_recipient,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.fee_source,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.token_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct FinalizePoll {
+            pub poll: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for FinalizePoll {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct ClaimDeposit {
+            pub poll: Pubkey,
+            pub candidate: Pubkey,
+            pub depositor: Pubkey,
+            pub treasury: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for ClaimDeposit {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.candidate,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.depositor,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.treasury,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct InitShieldedTally {
+            pub poll: Pubkey,
+            pub tally: Pubkey,
+            pub creator: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for InitShieldedTally {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.tally,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct VoteShielded {
+            pub poll: Pubkey,
+            pub tally: Pubkey,
+            pub voter_receipt: Pubkey,
+            pub voter: Pubkey,
+            pub system_program: Pubkey,
+            pub instructions: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for VoteShielded {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.tally,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter_receipt,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.instructions,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct PublishShieldedResults {
+            pub poll: Pubkey,
+            pub tally: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for PublishShieldedResults {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.tally,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct ArchivePoll {
+            pub poll: Pubkey,
+            pub archive: Pubkey,
+            pub reward_vault: Pubkey,
+            pub creator_token_account: Pubkey,
+            pub creator: Pubkey,
+            pub system_program: Pubkey,
+            pub token_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for ArchivePoll {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.archive,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.reward_vault,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator_token_account,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.token_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct CrankCloseReceipt {
+            pub poll: Pubkey,
+            pub voter_receipt: Pubkey,
+            pub payer: Pubkey,
+            pub cranker: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for CrankCloseReceipt {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter_receipt,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.payer,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.cranker,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct FundRewardPool {
+            pub poll: Pubkey,
+            pub mint: Pubkey,
+            pub reward_vault: Pubkey,
+            pub creator_token_account: Pubkey,
+            pub creator: Pubkey,
+            pub token_program: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for FundRewardPool {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.mint,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.reward_vault,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator_token_account,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.token_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct ClaimReward {
+            pub poll: Pubkey,
+            pub voter_receipt: Pubkey,
+            pub reward_vault: Pubkey,
+            pub voter_token_account: Pubkey,
+            pub voter: Pubkey,
+            pub token_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for ClaimReward {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter_receipt,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.reward_vault,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter_token_account,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.voter,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.token_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct ClaimBounty {
+            pub poll: Pubkey,
+            pub candidate: Pubkey,
+            pub recipient: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for ClaimBounty {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.candidate,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.recipient,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct MintWinnerBadge {
+            pub poll: Pubkey,
+            pub winner: Pubkey,
+            pub payout_wallet: Pubkey,
+            pub badge_mint: Pubkey,
+            pub badge_token_account: Pubkey,
+            pub metadata: Pubkey,
+            pub master_edition: Pubkey,
+            pub payer: Pubkey,
+            pub token_program: Pubkey,
+            pub associated_token_program: Pubkey,
+            pub token_metadata_program: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for MintWinnerBadge {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.winner,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.payout_wallet,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.badge_mint,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.badge_token_account,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.metadata,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.master_edition,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.payer,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.token_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.associated_token_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.token_metadata_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct CreateProposal {
+            pub poll: Pubkey,
+            pub yes_candidate: Pubkey,
+            pub proposal: Pubkey,
+            pub creator: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for CreateProposal {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.yes_candidate,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.proposal,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct ExecuteProposal {
+            pub poll: Pubkey,
+            pub proposal: Pubkey,
+            pub yes_candidate: Pubkey,
+            pub poll_authority: Pubkey,
+            pub target_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for ExecuteProposal {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.proposal,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.yes_candidate,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.poll_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.target_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct InitializeBallot {
+            pub ballot: Pubkey,
+            pub creator: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for InitializeBallot {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.ballot,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct VoteBallot {
+            pub ballot: Pubkey,
+            pub voter: Pubkey,
+            pub system_program: Pubkey,
+            pub instructions: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for VoteBallot {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.ballot,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.instructions,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct VoteGauge {
+            pub poll: Pubkey,
+            pub voter_receipt: Pubkey,
+            pub voter: Pubkey,
+            pub system_program: Pubkey,
+            pub instructions: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for VoteGauge {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter_receipt,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.instructions,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct VoteConviction {
+            pub poll: Pubkey,
+            pub candidate: Pubkey,
+            pub voter_receipt: Pubkey,
+            pub conviction_lock: Pubkey,
+            pub lock_vault: Pubkey,
+            pub mint: Pubkey,
+            pub voter_token_account: Pubkey,
+            pub voter: Pubkey,
+            pub token_program: Pubkey,
+            pub system_program: Pubkey,
+            pub instructions: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for VoteConviction {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.candidate,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter_receipt,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.conviction_lock,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.lock_vault,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.mint,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter_token_account,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.token_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.instructions,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct Unlock {
+            pub conviction_lock: Pubkey,
+            pub lock_vault: Pubkey,
+            pub voter_token_account: Pubkey,
+            pub voter: Pubkey,
+            pub token_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for Unlock {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.conviction_lock,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.lock_vault,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter_token_account,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.token_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct VoteGovernance {
+            pub poll: Pubkey,
+            pub candidate: Pubkey,
+            pub voter_receipt: Pubkey,
+            pub token_owner_record: Pubkey,
+            pub voter: Pubkey,
+            pub system_program: Pubkey,
+            pub instructions: Pubkey,
+            pub realm: Pubkey,
+            pub realm_config: Pubkey,
+            pub lock_authority: Pubkey,
+            pub governance_program: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for VoteGovernance {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.candidate,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter_receipt,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.token_owner_record,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.instructions,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.realm,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.realm_config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.lock_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.governance_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct InitializeCandidatesBatch {
+            pub poll: Pubkey,
+            pub creator: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for InitializeCandidatesBatch {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct CreateSession {
+            pub ballot: Pubkey,
+            pub session: Pubkey,
+            pub voter: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for CreateSession {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.ballot,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.session,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct RevokeSession {
+            pub session: Pubkey,
+            pub voter: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for RevokeSession {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.session,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct VoteWithSession {
+            pub poll: Pubkey,
+            pub candidate: Pubkey,
+            pub ballot: Pubkey,
+            pub session: Pubkey,
+            pub voter_receipt: Pubkey,
+            pub voter: Pubkey,
+            pub session_key: Pubkey,
+            pub system_program: Pubkey,
+            pub instructions: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for VoteWithSession {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.candidate,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.ballot,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.session,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.voter_receipt,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.voter,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.session_key,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.instructions,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct ScheduleFinalization {
+            pub poll: Pubkey,
+            pub creator: Pubkey,
+            pub thread: Pubkey,
+            pub thread_program: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for ScheduleFinalization {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.thread,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.thread_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct CancelFinalization {
+            pub poll: Pubkey,
+            pub creator: Pubkey,
+            pub thread: Pubkey,
+            pub thread_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for CancelFinalization {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.thread,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.thread_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct RequestRandomness {
+            pub poll: Pubkey,
+            pub requester: Pubkey,
+            pub randomness: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for RequestRandomness {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.requester,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.randomness,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct ApplyRandomness {
+            pub poll: Pubkey,
+            pub randomness: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for ApplyRandomness {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.randomness,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct CancelRandomness {
+            pub poll: Pubkey,
+            pub randomness: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for CancelRandomness {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.randomness,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct GrantAccess {
+            pub poll: Pubkey,
+            pub access_grant: Pubkey,
+            pub creator: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for GrantAccess {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.access_grant,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct RevokeAccess {
+            pub poll: Pubkey,
+            pub access_grant: Pubkey,
+            pub creator: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for RevokeAccess {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.access_grant,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct PublishWeight {
+            pub weight_record: Pubkey,
+            pub oracle: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for PublishWeight {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.weight_record,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.oracle,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct SetCreationLimit {
+            pub config: Pubkey,
+            pub admin: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for SetCreationLimit {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.admin,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct RegisterAuditors {
+            pub poll: Pubkey,
+            pub poll_result: Pubkey,
+            pub creator: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for RegisterAuditors {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll_result,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct AttestResult {
+            pub poll: Pubkey,
+            pub poll_result: Pubkey,
+            pub auditor: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for AttestResult {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
                     anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
-                        self.system_program,
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll_result,
                         false,
                     ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.auditor,
+                        true,
+                    ),
                 ]
             }
         }
 
-        pub struct InitializeCandidate {
+        pub struct SetFeeSchedule {
+            pub config: Pubkey,
+            pub admin: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for SetFeeSchedule {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.config,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.admin,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct UpdateCandidateMetadata {
             pub poll: Pubkey,
             pub candidate: Pubkey,
             pub creator: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for UpdateCandidateMetadata {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.candidate,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.creator,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct UpdatePoll {
+            pub poll: Pubkey,
+            pub config: Pubkey,
+            pub creator: Pubkey,
             pub system_program: Pubkey,
         }
 
-        impl anchor_client::anchor_lang::ToAccountMetas for InitializeCandidate {
+        impl anchor_client::anchor_lang::ToAccountMetas for UpdatePoll {
             fn to_account_metas(
                 &self,
                 _is_signer: Option<bool>,
@@ -356,31 +6821,169 @@ mod voting_dapp {
                         self.poll,
                         false,
                     ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.config,
+                        false,
+                    ),
                     anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
-                        self.candidate,
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct MigrateAccount {
+            pub account: Pubkey,
+            pub payer: Pubkey,
+            pub system_program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for MigrateAccount {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.account,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.payer,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.system_program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct ManageReceiptTree {
+            pub poll: Pubkey,
+            pub creator: Pubkey,
+            pub merkle_tree: Pubkey,
+            pub compression_program: Pubkey,
+            pub log_wrapper: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for ManageReceiptTree {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.creator,
+                        true,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.merkle_tree,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.compression_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.log_wrapper,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
+                        false,
+                    ),
+                ]
+            }
+        }
+
+        pub struct RegisterCompressedVoter {
+            pub poll: Pubkey,
+            pub registration: Pubkey,
+            pub creator: Pubkey,
+            pub merkle_tree: Pubkey,
+            pub compression_program: Pubkey,
+            pub log_wrapper: Pubkey,
+            pub system_program: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for RegisterCompressedVoter {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.registration,
                         false,
                     ),
                     anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
                         self.creator,
                         true,
                     ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.merkle_tree,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.compression_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.log_wrapper,
+                        false,
+                    ),
                     anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
                         self.system_program,
                         false,
                     ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
+                        false,
+                    ),
                 ]
             }
         }
 
-        pub struct Vote {
+        pub struct VoteCompressed {
             pub poll: Pubkey,
             pub candidate: Pubkey,
-            pub voter_receipt: Pubkey,
             pub voter: Pubkey,
-            pub system_program: Pubkey,
+            pub merkle_tree: Pubkey,
+            pub compression_program: Pubkey,
+            pub log_wrapper: Pubkey,
+            pub instructions: Pubkey,
+            pub event_authority: Pubkey,
+            pub program: Pubkey,
         }
 
-        impl anchor_client::anchor_lang::ToAccountMetas for Vote {
+        impl anchor_client::anchor_lang::ToAccountMetas for VoteCompressed {
             fn to_account_metas(
                 &self,
                 _is_signer: Option<bool>,
@@ -394,18 +6997,103 @@ mod voting_dapp {
                         self.candidate,
                         false,
                     ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.voter,
+                        true,
+                    ),
                     anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
-                        self.voter_receipt,
+                        self.merkle_tree,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.compression_program,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.log_wrapper,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.instructions,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.event_authority,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.program,
                         false,
                     ),
+                ]
+            }
+        }
+
+        pub struct ActivatePoll {
+            pub poll: Pubkey,
+            pub creator: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for ActivatePoll {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
                     anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
-                        self.voter,
+                        self.poll,
+                        false,
+                    ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.creator,
                         true,
                     ),
+                ]
+            }
+        }
+
+        pub struct SetCandidateManager {
+            pub poll: Pubkey,
+            pub creator: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for SetCandidateManager {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
+                        false,
+                    ),
                     anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
-                        self.system_program,
+                        self.creator,
+                        true,
+                    ),
+                ]
+            }
+        }
+
+        pub struct SetPollPaused {
+            pub poll: Pubkey,
+            pub creator: Pubkey,
+        }
+
+        impl anchor_client::anchor_lang::ToAccountMetas for SetPollPaused {
+            fn to_account_metas(
+                &self,
+                _is_signer: Option<bool>,
+            ) -> Vec<anchor_client::anchor_lang::solana_program::instruction::AccountMeta> {
+                vec![
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new(
+                        self.poll,
                         false,
                     ),
+                    anchor_client::anchor_lang::solana_program::instruction::AccountMeta::new_readonly(
+                        self.creator,
+                        true,
+                    ),
                 ]
             }
         }
@@ -0,0 +1,174 @@
+use std::io::Write;
+use std::rc::Rc;
+
+use anchor_client::{
+    anchor_lang::prelude::Pubkey,
+    solana_sdk::signer::{null_signer::NullSigner, Signer},
+    Client,
+};
+use anyhow::Result;
+use clap::{Command, CommandFactory};
+use clap_complete::Shell;
+
+use crate::client::{PollFilter, VotingClient};
+use crate::settings::{self, Settings};
+
+const BIN_NAME: &str = "voting-cli";
+
+// Hooks run before the generated completions: when `complete-values` offers poll IDs or candidate
+// names for the words typed so far, those are completed instead
+const BASH_HOOK: &str = r#"
+_voting_cli_values() {
+    local IFS=$'\n' values
+    values=$(voting-cli complete-values -- "${COMP_WORDS[@]:1:COMP_CWORD-1}" 2>/dev/null)
+    if [[ -n $values ]]; then
+        COMPREPLY=($(compgen -W "$values" -- "${COMP_WORDS[COMP_CWORD]}"))
+        return
+    fi
+    _voting-cli "$@"
+}
+complete -F _voting_cli_values -o nosort -o bashdefault -o default voting-cli
+"#;
+
+const ZSH_HOOK: &str = r#"
+_voting_cli_values() {
+    local -a values
+    values=("${(@f)$(voting-cli complete-values -- "${(@)words[2,CURRENT-1]}" 2>/dev/null)}")
+    if [[ -n ${values[1]} ]]; then
+        compadd -a values
+        return
+    fi
+    _voting-cli "$@"
+}
+compdef _voting_cli_values voting-cli
+"#;
+
+const FISH_HOOK: &str = r#"
+function __voting_cli_values
+    set -l words (commandline -opc)
+    set -e words[1]
+    voting-cli complete-values -- $words 2>/dev/null
+end
+complete -c voting-cli -a '(__voting_cli_values)'
+"#;
+
+/// Cluster data that completes the next positional argument
+#[derive(Debug, PartialEq)]
+enum Query {
+    PollIds,
+    CandidateNames(u64),
+}
+
+/// Write the completion script for a shell. Bash, zsh and fish also complete poll IDs and
+/// candidate names from the cluster; PowerShell and elvish complete commands and flags only.
+pub fn write_script(shell: Shell, out: &mut dyn Write) -> Result<()> {
+    clap_complete::generate(shell, &mut crate::Cli::command(), BIN_NAME, out);
+    let hook = match shell {
+        Shell::Bash => BASH_HOOK,
+        Shell::Zsh => ZSH_HOOK,
+        Shell::Fish => FISH_HOOK,
+        _ => return Ok(()),
+    };
+    out.write_all(hook.as_bytes())?;
+    Ok(())
+}
+
+/// Print the poll IDs or candidate names that complete the argument after `words`, one per
+/// line. Nothing is printed, and the cluster isn't queried, unless a profile is configured:
+/// the named `--profile` or, without one, the default profile.
+pub fn print_values(settings: &Settings, words: &[String]) -> Result<()> {
+    let (profile_name, Some(query)) = parse(words) else {
+        return Ok(());
+    };
+    if profile_name.is_none() && !settings.profiles.contains_key(settings::DEFAULT_PROFILE) {
+        return Ok(());
+    }
+    let profile = settings.profile(profile_name.as_deref())?;
+    let cluster = settings::parse_cluster(
+        profile.rpc_url.as_deref().unwrap_or(settings::DEFAULT_CLUSTER),
+        profile.ws_url.as_deref(),
+    )?;
+    let program_id = profile
+        .program_id
+        .as_deref()
+        .unwrap_or(settings::DEFAULT_PROGRAM_ID)
+        .parse::<Pubkey>()
+        .map_err(|e| anyhow::anyhow!("Invalid program ID: {}", e))?;
+    let commitment =
+        settings::parse_commitment(profile.commitment.as_deref().unwrap_or(settings::DEFAULT_COMMITMENT))?;
+
+    // Reading accounts needs no signature
+    let signer: Box<dyn Signer> = Box::new(NullSigner::new(&Pubkey::default()));
+    let voting_client = VotingClient::new(Client::new_with_options(cluster, Rc::new(signer), commitment), program_id);
+    match query {
+        Query::PollIds => {
+            for (_, poll) in voting_client.get_all_polls(&PollFilter::default())? {
+                println!("{}", poll.poll_id);
+            }
+        }
+        Query::CandidateNames(poll_id) => {
+            let mut candidates = voting_client.get_candidates(poll_id)?;
+            candidates.sort_by_key(|(_, candidate)| candidate.index);
+            for (_, candidate) in candidates {
+                println!("{}", candidate.name);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether `word` is an option of `command` that is followed by its value
+pub fn takes_value(command: &Command, word: &str) -> bool {
+    let is_flag = |arg: &clap::Arg| {
+        arg.get_long().is_some_and(|long| word.strip_prefix("--") == Some(long))
+            || arg.get_short().is_some_and(|short| word.strip_prefix('-').is_some_and(|rest| rest.chars().eq([short])))
+    };
+    command.get_arguments().any(|arg| arg.get_action().takes_values() && is_flag(arg))
+}
+
+/// Find the `--profile` and what the next positional argument needs from the cluster, using the
+/// argument definitions of the CLI itself
+fn parse(words: &[String]) -> (Option<String>, Option<Query>) {
+    let root = crate::Cli::command();
+    let mut profile = None;
+    let mut subcommand: Option<&Command> = None;
+    let mut positionals: Vec<&str> = Vec::new();
+
+    let mut words = words.iter();
+    while let Some(word) = words.next() {
+        if let Some(name) = word.strip_prefix("--profile=") {
+            profile = Some(name.to_string());
+        } else if word.starts_with('-') {
+            // Global options may follow the subcommand too
+            if takes_value(&root, word) || subcommand.is_some_and(|command| takes_value(command, word)) {
+                let value = words.next();
+                if word == "--profile" {
+                    profile = value.cloned();
+                }
+            }
+        } else if subcommand.is_some() {
+            positionals.push(word);
+        } else {
+            subcommand = root.find_subcommand(word);
+            if subcommand.is_none() {
+                return (profile, None);
+            }
+        }
+    }
+
+    let Some(subcommand) = subcommand else {
+        return (profile, None);
+    };
+    let ids: Vec<&str> = subcommand.get_positionals().map(|arg| arg.get_id().as_str()).collect();
+    let poll_id = ids
+        .iter()
+        .position(|id| *id == "poll_id")
+        .and_then(|index| positionals.get(index))
+        .and_then(|poll_id| poll_id.parse().ok());
+    let query = match ids.get(positionals.len()) {
+        Some(&"poll_id") => Some(Query::PollIds),
+        Some(&"candidate_name") => poll_id.map(Query::CandidateNames),
+        _ => None,
+    };
+    (profile, query)
+}
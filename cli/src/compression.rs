@@ -0,0 +1,174 @@
+use anchor_client::{
+    anchor_lang::{prelude::Pubkey, AnchorDeserialize, Discriminator},
+    solana_client::{rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_client::RpcClient},
+    solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        keccak::hashv,
+        pubkey,
+        signature::Signature,
+        system_program,
+    },
+};
+use anyhow::Result;
+use std::str::FromStr;
+
+use crate::events::fetch_cpi_events;
+
+/// spl-account-compression program that owns receipt trees
+pub const COMPRESSION_PROGRAM_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// Noop program the compression program logs tree changes through
+pub const NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// Bubblegum program that mints compressed NFTs
+pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+
+// Bubblegum `set_tree_delegate` instruction discriminator
+const SET_TREE_DELEGATE_DISCRIMINATOR: [u8; 8] = [253, 118, 66, 37, 190, 49, 154, 102];
+
+// Account type tag + header version + V1 header (buffer size, depth, authority, slot, padding)
+const TREE_HEADER_SIZE: usize = 2 + 54;
+
+/// Leaf recorded for a compressed receipt, matching the on-chain `receipt_leaf`.
+/// `candidate` is the default key until the voter has voted.
+pub fn receipt_leaf(poll: &Pubkey, voter: &Pubkey, candidate: &Pubkey) -> [u8; 32] {
+    hashv(&[poll.as_ref(), voter.as_ref(), candidate.as_ref()]).to_bytes()
+}
+
+/// Size of a concurrent merkle tree account without a canopy
+pub fn tree_account_size(max_depth: u32, max_buffer_size: u32) -> usize {
+    let (depth, buffer) = (max_depth as usize, max_buffer_size as usize);
+    // Change log entry and rightmost path: 32 bytes per level, a 32 byte root/leaf, index and padding
+    let path_size = 32 * depth + 40;
+    // Sequence number, active index and buffer size, then the change log buffer and rightmost path
+    TREE_HEADER_SIZE + 24 + buffer * path_size + path_size
+}
+
+/// Derive Bubblegum's tree config PDA for a merkle tree
+pub fn get_tree_config_address(merkle_tree: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[merkle_tree.as_ref()], &BUBBLEGUM_PROGRAM_ID)
+}
+
+/// Bubblegum instruction handing minting rights on a tree to a new delegate
+pub fn set_tree_delegate_instruction(
+    merkle_tree: &Pubkey,
+    tree_creator: &Pubkey,
+    new_delegate: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(get_tree_config_address(merkle_tree).0, false),
+            AccountMeta::new_readonly(*tree_creator, true),
+            AccountMeta::new_readonly(*new_delegate, false),
+            AccountMeta::new_readonly(*merkle_tree, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: SET_TREE_DELEGATE_DISCRIMINATOR.to_vec(),
+    }
+}
+
+/// Read the max depth from a tree account's header
+pub fn tree_depth(data: &[u8]) -> Result<u32> {
+    let bytes = data
+        .get(6..10)
+        .ok_or_else(|| anyhow::anyhow!("Account is not a concurrent merkle tree"))?;
+    Ok(u32::from_le_bytes(bytes.try_into()?))
+}
+
+/// In-memory copy of a receipt tree, rebuilt from `ReceiptLeafSet` events
+pub struct ReceiptTree {
+    depth: u32,
+    leaves: Vec<[u8; 32]>,
+}
+
+impl ReceiptTree {
+    pub fn new(depth: u32) -> Self {
+        Self { depth, leaves: Vec::new() }
+    }
+
+    pub fn set_leaf(&mut self, index: u32, leaf: [u8; 32]) {
+        let index = index as usize;
+        if index >= self.leaves.len() {
+            self.leaves.resize(index + 1, [0u8; 32]);
+        }
+        self.leaves[index] = leaf;
+    }
+
+    /// Index of the given leaf, if present
+    pub fn position(&self, leaf: &[u8; 32]) -> Option<u32> {
+        self.leaves.iter().position(|l| l == leaf).map(|i| i as u32)
+    }
+
+    /// Current root of the tree
+    pub fn root(&self) -> [u8; 32] {
+        self.walk(0).0
+    }
+
+    /// Sibling nodes from the leaf at `index` up to the root
+    pub fn proof(&self, index: u32) -> Vec<[u8; 32]> {
+        self.walk(index as usize).1
+    }
+
+    // Hash the tree level by level, collecting the siblings of `index` along the way
+    fn walk(&self, mut index: usize) -> ([u8; 32], Vec<[u8; 32]>) {
+        let mut empty = [0u8; 32];
+        let mut layer = self.leaves.clone();
+        let mut proof = Vec::with_capacity(self.depth as usize);
+
+        for _ in 0..self.depth {
+            proof.push(layer.get(index ^ 1).copied().unwrap_or(empty));
+            layer = layer
+                .chunks(2)
+                .map(|pair| hashv(&[pair[0].as_ref(), pair.get(1).unwrap_or(&empty).as_ref()]).to_bytes())
+                .collect();
+            empty = hashv(&[empty.as_ref(), empty.as_ref()]).to_bytes();
+            index /= 2;
+        }
+
+        (layer.first().copied().unwrap_or(empty), proof)
+    }
+}
+
+/// Event emitted by the program whenever a compressed receipt leaf changes
+#[derive(AnchorDeserialize, Debug, Clone)]
+pub struct ReceiptLeafSet {
+    pub poll: Pubkey,
+    pub index: u32,
+    pub leaf: [u8; 32],
+}
+
+impl Discriminator for ReceiptLeafSet {
+    const DISCRIMINATOR: [u8; 8] = [108, 164, 58, 88, 75, 140, 178, 173];
+}
+
+/// Rebuild a poll's receipt tree by replaying the program's events from the tree's history
+pub fn fetch_receipt_tree(rpc: &RpcClient, program_id: &Pubkey, poll: &Pubkey, tree: &Pubkey) -> Result<ReceiptTree> {
+    let depth = tree_depth(&rpc.get_account_data(tree)?)?;
+
+    // Page through the tree's transactions, newest first
+    let mut signatures = Vec::new();
+    let mut before = None;
+    loop {
+        let page = rpc.get_signatures_for_address_with_config(
+            tree,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )?;
+        let Some(last) = page.last() else { break };
+        before = Some(Signature::from_str(&last.signature)?);
+        signatures.extend(page.into_iter().filter(|s| s.err.is_none()).map(|s| s.signature));
+    }
+
+    let mut receipt_tree = ReceiptTree::new(depth);
+    for signature in signatures.iter().rev() {
+        let events: Vec<ReceiptLeafSet> = fetch_cpi_events(rpc, program_id, &Signature::from_str(signature)?)?;
+        for event in events.into_iter().filter(|event| event.poll == *poll) {
+            receipt_tree.set_leaf(event.index, event.leaf);
+        }
+    }
+
+    Ok(receipt_tree)
+}
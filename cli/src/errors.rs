@@ -0,0 +1,321 @@
+use std::fmt;
+
+/// First code of the program's `ErrorCode`, whose variants count up from it in order
+const PROGRAM_ERROR_OFFSET: u32 = 6000;
+
+// The program's `ErrorCode` variants in declaration order, with their messages
+const PROGRAM_ERRORS: [(&str, &str); 131] = [
+    ("InvalidTimeRange", "The poll start time must be before the end time"),
+    ("Unauthorized", "You are not authorized to perform this action"),
+    ("PollNotActive", "The poll is not currently active for voting"),
+    ("InsufficientTreasuryFunds", "The treasury does not hold enough withdrawable lamports"),
+    ("PollNotEnded", "The poll has not ended yet"),
+    ("PollAlreadyFinalized", "The poll has already been finalized"),
+    ("PollNotFinalized", "The poll has not been finalized yet"),
+    ("NoDepositToClaim", "There is no deposit left to claim"),
+    ("RewardMintMismatch", "The token mint does not match the poll's reward mint"),
+    ("RewardAlreadyClaimed", "The reward for this receipt has already been claimed"),
+    ("NoRewardToClaim", "There is no reward to claim for this poll"),
+    ("CandidateListMismatch", "The accounts passed do not match the poll's candidate list"),
+    ("NoBountyToClaim", "There is no bounty left to claim"),
+    ("InvalidBountyRecipient", "The bounty recipient does not match the poll's winner"),
+    ("CandidatePollMismatch", "The candidate does not belong to this poll"),
+    ("InvalidBallotRaces", "The accounts passed do not match the ballot's races"),
+    ("AlreadyVoted", "You have already voted in this poll"),
+    ("CategoryTooLong", "The poll category is too long"),
+    ("TooManyTags", "A poll can have at most 5 tags"),
+    ("TagTooLong", "A poll tag is too long"),
+    ("UriTooLong", "The metadata URI is too long"),
+    ("VotingAlreadyStarted", "Voting has already started for this poll"),
+    ("QuestionTooLong", "The poll question is too long"),
+    ("DescriptionTooLong", "The poll description is too long"),
+    ("UnsupportedAccount", "This account type cannot be migrated"),
+    ("PollPaused", "The poll is paused"),
+    ("PollNotPaused", "The poll is not paused"),
+    ("ReceiptTreeAlreadySet", "The poll already has a receipt tree"),
+    ("ReceiptTreeMismatch", "The merkle tree is not this poll's receipt tree"),
+    ("MissingReceiptNftAccounts", "The accounts needed to mint the receipt NFT are missing"),
+    ("WinnerBadgeAlreadyMinted", "The winner badge has already been minted"),
+    ("NotPollWinner", "The candidate is not the poll's winner"),
+    ("InsufficientVoterBalance", "The voter's balance is below the poll's minimum"),
+    ("InsufficientStake", "The voter's active stake is below the poll's minimum"),
+    ("InvalidStakeAccount", "The stake account is not an active delegation controlled by the voter"),
+    ("InvalidWeightDecay", "The weight decay must end between 0 and 10000 basis points"),
+    ("ReceiptGracePeriodActive", "Receipts can only be closed once the poll's grace period has passed"),
+    ("PollNotDraft", "This action is only allowed while the poll is a draft"),
+    ("NoCandidates", "A poll needs at least one candidate to be activated"),
+    ("InvalidThreshold", "The threshold must be between 0 and 10000 basis points"),
+    ("ProposalTooLarge", "The proposal instruction has too many accounts or too much data"),
+    ("ProposalAlreadyExecuted", "The proposal has already been executed"),
+    ("ProposalNotPassed", "The proposal did not reach its threshold"),
+    ("ProposalAccountMismatch", "The accounts passed do not match the proposal's instruction"),
+    ("TooManyCouncilMembers", "The council can have at most 10 members"),
+    ("InvalidCouncilThreshold", "The council threshold cannot exceed the number of members"),
+    ("DuplicateCouncilMember", "Council members must be unique"),
+    ("NotCouncilMember", "The signer is not a council member"),
+    ("AlreadyApproved", "This council member has already approved the poll"),
+    ("CouncilApprovalMissing", "The poll has not been approved by enough council members"),
+    ("CreatorNotAllowed", "This wallet does not hold a creator pass"),
+    ("VoteRateLimited", "Too many votes in this slot, try again shortly"),
+    ("EmptyQuestion", "The poll question cannot be empty"),
+    ("EmptyCandidateName", "The candidate name cannot be empty"),
+    ("StartTimeInPast", "The start time is too far in the past"),
+    ("PollTooLong", "The end time is further away than the maximum poll duration allows"),
+    ("TooManyCandidates", "The poll has reached the maximum number of candidates"),
+    ("ArchiveGracePeriodActive", "Polls can only be archived once the receipt grace period has passed"),
+    ("UnsettledPollFunds", "Settle the poll's bounty, deposits and reward pool before archiving it"),
+    ("TooManyCandidatesToArchive", "Polls with more than 20 candidates cannot be archived"),
+    ("VoteViaCpi", "This poll only accepts votes sent directly, not through another program"),
+    (
+        "InvalidShieldedKey",
+        "The shielded key must be a valid Ristretto point, and shielded polls cannot use weight decay",
+    ),
+    ("PollNotShielded", "This poll does not use shielded voting"),
+    ("PollShielded", "This poll only accepts shielded ballots"),
+    ("InvalidShieldedBallot", "A shielded ballot needs one valid ciphertext per candidate"),
+    ("InvalidShieldedProof", "A shielded voting proof failed to verify"),
+    ("ShieldedResultsPublished", "The shielded results have already been published"),
+    ("ShieldedResultsPending", "Publish the decrypted shielded results before finalizing"),
+    ("InvalidStakeWeighting", "Stake-weighted polls cannot use weight decay or shielded ballots"),
+    ("StakeAlreadyCounted", "This stake account has already voted in the poll"),
+    ("InvalidGaugeBudget", "Gauge polls cannot use weight decay, stake weighting or shielded ballots"),
+    ("PollNotGauge", "This poll is not a gauge poll"),
+    ("PollIsGauge", "This gauge poll only accepts votes that split its point budget"),
+    (
+        "InvalidGaugeAllocation",
+        "Gauge points must go to distinct candidates of the poll, each nonzero, summing to the budget",
+    ),
+    (
+        "InvalidConvictionMint",
+        "Conviction polls cannot use weight decay, stake weighting, gauge points or shielded ballots",
+    ),
+    ("PollNotConviction", "This poll does not take conviction votes in this token"),
+    ("PollIsConviction", "This conviction poll only accepts votes backed by locked tokens"),
+    ("InvalidConvictionAmount", "A conviction vote must lock a nonzero amount of tokens"),
+    ("TokensStillLocked", "The tokens are still locked"),
+    ("AlreadyUnlocked", "These tokens have already been unlocked"),
+    ("InvalidGovernanceConfig", "Realm-backed polls need a governance program, realm and mint, and no other weighting"),
+    ("PollNotGovernance", "This poll is not backed by a governance realm"),
+    ("PollIsGovernance", "This poll only accepts votes backed by a governance token owner record"),
+    ("InvalidTokenOwnerRecord", "The token owner record does not belong to the voter in the poll's realm and mint"),
+    ("InsufficientGovernancePower", "The voter has no governing tokens deposited in the realm"),
+    ("InvalidEligibilityProgram", "Eligibility programs only apply to standard votes and cannot be this program"),
+    ("EligibilityProgramMissing", "This poll requires its eligibility program to verify the voter"),
+    (
+        "InvalidAttestationConfig",
+        "Attestation-gated polls need an attestation program, issuer and schema, and only take standard votes",
+    ),
+    ("InvalidAttestation", "The voter must present an attestation about them from the poll's issuer and schema"),
+    ("AttestationExpired", "The voter's attestation has expired"),
+    ("CandidateNameTooLong", "The candidate name is too long"),
+    ("InvalidCandidateBatch", "A candidate batch needs one new candidate account per entry, in ballot order"),
+    ("InvalidSessionExpiry", "A session must expire in the future and within the maximum session length"),
+    ("SessionExpired", "The session key has expired"),
+    ("PollNotInSession", "The session does not cover this poll"),
+    ("FinalizationAlreadyScheduled", "The poll already has a finalization thread"),
+    ("FinalizationNotScheduled", "The poll has no finalization thread"),
+    ("RandomnessPending", "The poll is waiting for its randomness to be applied"),
+    ("RandomnessNotNeeded", "Randomness is only drawn for a draft poll's ballot order or an ended poll's tie-break"),
+    ("InvalidRandomness", "The account is not the poll's pending Switchboard randomness request"),
+    ("RandomnessNotRevealed", "The randomness has not been revealed yet"),
+    ("InvalidAccessConfig", "Access-restricted polls only take standard votes"),
+    ("AccessNotGranted", "The voter has not been granted access to this poll"),
+    (
+        "InvalidOracleConfig",
+        "Oracle-weighted polls need a non-negative maximum record age, use no other weighting and only take \
+         standard votes",
+    ),
+    ("InvalidWeightRecord", "The voter must present a nonzero weight record published by the poll's oracle"),
+    ("StaleWeightRecord", "The voter's weight record is older than the poll accepts"),
+    ("Overflow", "Arithmetic overflow"),
+    ("DuplicateVote", "The transaction votes more than once for the same poll and voter"),
+    ("InvalidVoteFeeConfig", "Vote fees need a recipient, and polls charging one only take standard votes"),
+    ("VoteFeeUnpaid", "This poll charges a vote fee, which only standard votes pay"),
+    ("InvalidFeeAccount", "The fee accounts do not match the poll's vote fee"),
+    ("InvalidCreationLimit", "Creation limits allow at most 20 polls per window, and need a positive window"),
+    ("CreationLimitReached", "The creator has reached the poll creation limit for now; try again later"),
+    ("InvalidAuditors", "Auditors must be 1 to 16 distinct keys"),
+    ("NotAuditor", "The signer is not a registered auditor of this poll"),
+    ("AlreadyAttested", "This auditor has already attested the result"),
+    ("ResultMismatch", "The attested winner or vote total does not match the poll's result"),
+    ("PollEnded", "The poll has ended, so its end time can no longer change"),
+    ("EndTimeLocked", "Votes have been cast, so the end time can only be extended"),
+    ("ExtensionTooLong", "The extension goes past the poll's maximum extension window"),
+    ("VoteOutsideWindow", "The poll recorded votes outside its voting window"),
+    (
+        "RaceNeedsCredentials",
+        "Ballot races must take plain votes, without eligibility gates, fees, stake or special voting modes",
+    ),
+    (
+        "ReceiptTreeNeedsPlainVotes",
+        "Receipt trees need a poll that takes plain votes, without eligibility gates, fees, stake or special voting \
+         modes",
+    ),
+    ("PollHasReceiptTree", "This poll records votes in its receipt tree and only takes compressed votes"),
+    ("TieBreakRequired", "The top candidates are tied; request a tie-break before finalizing"),
+    ("RandomnessNotExpired", "The randomness request has not timed out yet"),
+    ("RandomnessRevealed", "The randomness has been revealed; apply it instead"),
+    ("PollIdArchived", "This poll ID belongs to an archived poll and cannot be reused"),
+    ("RewardsNeedReceipts", "Reward pools are shared by vote receipts, which receipt-tree polls don't keep"),
+    ("InvalidNomination", "A self-nominee stands once per poll and must pass their unused nomination account"),
+    ("RewardUnclaimed", "The voter's reward share must be claimed before their receipt can be closed"),
+];
+
+// Anchor's own errors that wrong accounts or a wrong program ID commonly cause
+const ANCHOR_ERRORS: &[(u32, &str, &str)] = &[
+    (100, "InstructionMissing", "The instruction identifier is missing"),
+    (101, "InstructionFallbackNotFound", "The program has no such instruction"),
+    (102, "InstructionDidNotDeserialize", "The program could not deserialize the instruction"),
+    (2000, "ConstraintMut", "An account that must be writable was not"),
+    (2001, "ConstraintHasOne", "An account does not match the one stored on another account"),
+    (2002, "ConstraintSigner", "An account that must sign did not"),
+    (2003, "ConstraintRaw", "An account constraint was violated"),
+    (2004, "ConstraintOwner", "An account is owned by the wrong program"),
+    (2006, "ConstraintSeeds", "An account is not the address derived from its seeds"),
+    (2011, "ConstraintClose", "An account was closed to the wrong recipient"),
+    (2012, "ConstraintAddress", "An account is not at the expected address"),
+    (3001, "AccountDiscriminatorNotFound", "An account has no discriminator"),
+    (3002, "AccountDiscriminatorMismatch", "An account is of the wrong type"),
+    (3003, "AccountDidNotDeserialize", "An account could not be deserialized"),
+    (3005, "AccountNotEnoughKeys", "The instruction was given too few accounts"),
+    (3006, "AccountNotMutable", "An account that must be writable was not"),
+    (3007, "AccountOwnedByWrongProgram", "An account is owned by a different program than expected"),
+    (3008, "InvalidProgramId", "A program account is not the expected program"),
+    (3010, "AccountNotSigner", "An account that must sign did not"),
+    (3011, "AccountNotSystemOwned", "An account is not owned by the system program"),
+    (3012, "AccountNotInitialized", "An account the instruction needs does not exist yet"),
+    (4100, "DeclaredProgramIdMismatch", "The program was deployed under a different program ID"),
+];
+
+/// A program error a failed transaction reported, with what it means and what may help
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramError {
+    pub code: u32,
+    pub message: &'static str,
+    pub hint: Option<&'static str>,
+}
+
+impl fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)?;
+        if let Some(hint) = self.hint {
+            write!(f, "\n  Hint: {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+/// What to try next after some of the errors
+fn hint(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "Unauthorized" => "Sign with the keypair that owns the poll or config (--keypair)",
+        "PollNotActive" | "PollNotEnded" => "Check the voting window with `voting-cli status <POLL_ID>`",
+        "PollPaused" => "Wait for the creator to resume the poll",
+        "PollNotDraft" | "VotingAlreadyStarted" => "Only draft polls can be changed this way",
+        "AlreadyVoted" | "DuplicateVote" => "Check with `voting-cli has-voted <POLL_ID> <VOTER>`",
+        "CandidatePollMismatch" | "CandidateListMismatch" => {
+            "List the poll's candidates with `voting-cli list-candidates`"
+        }
+        "CouncilApprovalMissing" => "Council members approve with `voting-cli approve-poll`",
+        "CreationLimitReached" => "See when the window frees up with `voting-cli creator-stats`",
+        "VoteRateLimited" => "Send the vote again in a moment",
+        "InsufficientTreasuryFunds" => "Check the treasury balance with `voting-cli get-config`",
+        "RandomnessPending" | "RandomnessRevealed" => {
+            "Apply the revealed randomness with `voting-cli apply-randomness`"
+        }
+        "TieBreakRequired" => "Draw a tie-break with `voting-cli request-randomness`",
+        "RandomnessNotExpired" => "Wait for the oracle to reveal the value, then run `voting-cli apply-randomness`",
+        "ShieldedResultsPending" => "Publish the results first with `voting-cli publish-shielded-results`",
+        "AccessNotGranted" => "Ask the poll's creator for access",
+        "DescriptionTooLong" => {
+            "New polls take up to 280 bytes; lengthen the description with `voting-cli update-poll`"
+        }
+        "RaceNeedsCredentials" => "Leave the poll off the ballot; its voters use `voting-cli vote`",
+        "PollHasReceiptTree" => "Vote with `voting-cli vote-compressed`",
+        "PollIdArchived" => "Create the poll under a new ID; the old results stay in `voting-cli get-archive`",
+        "ConstraintSeeds" | "ConstraintAddress" => "Check the poll ID and --program-id",
+        "AccountNotInitialized" => "The poll or account may not exist on this cluster; check the poll ID and --cluster",
+        "AccountDiscriminatorMismatch" | "AccountOwnedByWrongProgram" | "DeclaredProgramIdMismatch" => {
+            "Check --program-id against the deployed program"
+        }
+        "InstructionFallbackNotFound" | "InstructionDidNotDeserialize" => {
+            "The deployed program may be a different version than this CLI expects"
+        }
+        _ => return None,
+    })
+}
+
+/// The program or Anchor error with this code
+pub fn lookup(code: u32) -> Option<ProgramError> {
+    let (name, message) = match code.checked_sub(PROGRAM_ERROR_OFFSET) {
+        Some(index) => *PROGRAM_ERRORS.get(index as usize)?,
+        None => ANCHOR_ERRORS
+            .iter()
+            .find(|(anchor_code, _, _)| *anchor_code == code)
+            .map(|(_, name, message)| (*name, *message))?,
+    };
+    Some(ProgramError {
+        code,
+        message,
+        hint: hint(name),
+    })
+}
+
+/// The program error named in an error's text, as `custom program error: 0x1772`. Errors of other
+/// programs the voting program calls carry codes too, so the explanation only fits when the
+/// voting program's instruction is the one that failed.
+pub fn explain(error: &str) -> Option<ProgramError> {
+    let (_, rest) = error.split_once("custom program error: 0x")?;
+    let digits: String = rest.chars().take_while(char::is_ascii_hexdigit).collect();
+    lookup(u32::from_str_radix(&digits, 16).ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_counts_program_errors_up_from_the_offset() {
+        let error = lookup(6001).unwrap();
+        assert_eq!(error.message, "You are not authorized to perform this action");
+        assert!(error.hint.is_some());
+        assert_eq!(
+            lookup(6130).unwrap().message,
+            "The voter's reward share must be claimed before their receipt can be closed"
+        );
+        assert_eq!(lookup(6131), None);
+    }
+
+    #[test]
+    fn lookup_knows_common_anchor_errors() {
+        assert_eq!(lookup(2006).unwrap().hint, Some("Check the poll ID and --program-id"));
+        assert_eq!(lookup(2005), None);
+        assert_eq!(lookup(5999), None);
+    }
+
+    #[test]
+    fn explain_reads_the_hex_code_from_the_error_text() {
+        let error = explain(
+            "RPC response error -32002: Transaction simulation failed: Error processing Instruction 0: \
+             custom program error: 0x1773",
+        )
+        .unwrap();
+        assert_eq!(error.code, 6003);
+        assert_eq!(
+            error.to_string(),
+            "The treasury does not hold enough withdrawable lamports (code 6003)\n  \
+             Hint: Check the treasury balance with `voting-cli get-config`"
+        );
+        assert_eq!(explain("custom program error: 0x7d6").unwrap().code, 2006);
+        assert_eq!(explain("Blockhash not found"), None);
+        assert_eq!(explain("custom program error: 0x"), None);
+    }
+
+    #[test]
+    fn program_error_names_are_unique() {
+        let mut names: Vec<_> = PROGRAM_ERRORS.iter().map(|(name, _)| *name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), PROGRAM_ERRORS.len());
+    }
+}
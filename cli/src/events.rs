@@ -0,0 +1,153 @@
+use anchor_client::{
+    anchor_lang::{prelude::Pubkey, AnchorDeserialize, Discriminator},
+    solana_client::{rpc_client::RpcClient, rpc_config::RpcTransactionConfig},
+    solana_sdk::{bs58, commitment_config::CommitmentConfig, signature::Signature},
+};
+use anyhow::Result;
+use solana_transaction_status::{
+    EncodedTransaction, UiCompiledInstruction, UiInnerInstructions, UiInstruction, UiLoadedAddresses, UiMessage,
+    UiTransactionEncoding,
+};
+use tracing::instrument;
+
+/// Tag opening the self-CPI instructions `emit_cpi!` records events in, before the event's
+/// own discriminator
+pub const EVENT_IX_TAG_LE: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
+
+/// The RPC client's commitment for reading transaction history, which is only served at
+/// `confirmed` or `finalized`; `processed` reads it at `confirmed`
+pub fn history_commitment(rpc: &RpcClient) -> CommitmentConfig {
+    let commitment = rpc.commitment();
+    if commitment.is_at_least_confirmed() {
+        commitment
+    } else {
+        CommitmentConfig::confirmed()
+    }
+}
+
+/// An instruction of the program as it ran, with its accounts resolved
+pub struct ProgramInstruction {
+    /// Accounts in instruction order, each with whether it signed the transaction
+    pub accounts: Vec<(Pubkey, bool)>,
+    pub data: Vec<u8>,
+}
+
+/// A transaction's instructions, top-level and inner, with the keys they index
+struct LoadedTransaction {
+    keys: Vec<String>,
+    signer_count: usize,
+    instructions: Vec<UiCompiledInstruction>,
+    inner_instructions: Vec<UiCompiledInstruction>,
+}
+
+impl LoadedTransaction {
+    /// The decoded data and accounts of an instruction, when `program_id` ran it
+    fn resolve(&self, instruction: &UiCompiledInstruction, program_id: &str) -> Option<ProgramInstruction> {
+        if *self.keys.get(instruction.program_id_index as usize)? != program_id {
+            return None;
+        }
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|&index| {
+                let key = self.keys.get(index as usize)?.parse().ok()?;
+                Some((key, (index as usize) < self.signer_count))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let data = bs58::decode(&instruction.data).into_vec().ok()?;
+        Some(ProgramInstruction { accounts, data })
+    }
+}
+
+#[instrument(level = "debug", skip(rpc))]
+fn load_transaction(rpc: &RpcClient, signature: &Signature) -> Result<Option<LoadedTransaction>> {
+    let transaction = rpc.get_transaction_with_config(
+        signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(history_commitment(rpc)),
+            max_supported_transaction_version: Some(0),
+        },
+    )?;
+    let (Some(meta), EncodedTransaction::Json(ui_transaction)) =
+        (transaction.transaction.meta, transaction.transaction.transaction)
+    else {
+        return Ok(None);
+    };
+    let UiMessage::Raw(message) = ui_transaction.message else {
+        return Ok(None);
+    };
+
+    // Instructions index the static keys first, then addresses loaded from lookup tables
+    let mut keys = message.account_keys;
+    let loaded: Option<UiLoadedAddresses> = meta.loaded_addresses.into();
+    if let Some(loaded) = loaded {
+        keys.extend(loaded.writable);
+        keys.extend(loaded.readonly);
+    }
+
+    let inner: Vec<UiInnerInstructions> = Option::from(meta.inner_instructions).unwrap_or_default();
+    let inner_instructions = inner
+        .into_iter()
+        .flat_map(|set| set.instructions)
+        .filter_map(|instruction| match instruction {
+            UiInstruction::Compiled(compiled) => Some(compiled),
+            _ => None,
+        })
+        .collect();
+
+    Ok(Some(LoadedTransaction {
+        keys,
+        signer_count: message.header.num_required_signatures as usize,
+        instructions: message.instructions,
+        inner_instructions,
+    }))
+}
+
+/// Decode the `E` events the program emitted in a transaction, in order. Events travel as
+/// inner instructions rather than logs, so they survive log truncation.
+pub fn fetch_cpi_events<E: AnchorDeserialize + Discriminator>(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    signature: &Signature,
+) -> Result<Vec<E>> {
+    let Some(transaction) = load_transaction(rpc, signature)? else {
+        return Ok(Vec::new());
+    };
+    let program_id = program_id.to_string();
+
+    let events = transaction
+        .inner_instructions
+        .iter()
+        .filter_map(|instruction| {
+            let data = transaction.resolve(instruction, &program_id)?.data;
+            let body = data.strip_prefix(&EVENT_IX_TAG_LE[..])?.strip_prefix(&E::DISCRIMINATOR[..])?;
+            E::deserialize(&mut &body[..]).ok()
+        })
+        .collect();
+
+    Ok(events)
+}
+
+/// The program's instructions in a transaction, whether called directly or through another
+/// program, top-level ones first. The self-CPIs carrying events are left out.
+pub fn fetch_program_instructions(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    signature: &Signature,
+) -> Result<Vec<ProgramInstruction>> {
+    let Some(transaction) = load_transaction(rpc, signature)? else {
+        return Ok(Vec::new());
+    };
+    let program_id = program_id.to_string();
+
+    let instructions = transaction
+        .instructions
+        .iter()
+        .chain(&transaction.inner_instructions)
+        .filter_map(|instruction| transaction.resolve(instruction, &program_id))
+        .filter(|instruction| !instruction.data.starts_with(&EVENT_IX_TAG_LE))
+        .collect();
+
+    Ok(instructions)
+}
@@ -0,0 +1,253 @@
+use anchor_client::anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::client::{Candidate, NewCandidate, Poll, PollOptions, PollVisibility, WeightDecay};
+use crate::utils::{self, get_treasury_address};
+
+/// Format of the exports written by this version
+const EXPORT_VERSION: u8 = 1;
+
+/// A poll's question, schedule, voting mode and candidates, to create the same poll on another
+/// cluster. Accounts the poll refers to, such as mints or an oracle, are copied as they are;
+/// edit the file where they differ between clusters. Omitted fields take their defaults.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct PollExport {
+    pub version: u8,
+    /// RPC URL and program the poll was exported from
+    pub cluster: String,
+    pub program_id: String,
+    pub poll_id: u64,
+    pub question: String,
+    pub description: String,
+    /// Original schedule; imports keep the duration and move the start
+    pub start_time: i64,
+    pub end_time: i64,
+    pub max_extension: i64,
+    pub category: String,
+    pub tags: Vec<String>,
+    pub metadata_uri: String,
+    /// sha256 of the metadata, in hex
+    pub metadata_hash: Option<String>,
+    pub unlisted: bool,
+    pub restricted: bool,
+    pub open_candidacy: bool,
+    pub allow_cpi: bool,
+    pub max_votes_per_slot: u32,
+    pub candidate_deposit_lamports: u64,
+    pub deposit_refund_threshold: u64,
+    pub bounty_lamports: u64,
+    pub min_voter_lamports: u64,
+    pub min_stake_lamports: u64,
+    pub stake_weighted: bool,
+    /// Share of full weight a vote cast at the end keeps, in basis points
+    pub weight_decay_bps: Option<u16>,
+    pub gauge_budget: u16,
+    pub conviction_mint: Option<String>,
+    pub governance_program: Option<String>,
+    pub governance_realm: Option<String>,
+    pub governance_mint: Option<String>,
+    pub eligibility_program: Option<String>,
+    pub attestation_program: Option<String>,
+    pub attestation_issuer: Option<String>,
+    pub attestation_schema: Option<String>,
+    pub weight_oracle: Option<String>,
+    pub max_weight_age: i64,
+    /// ElGamal public key ballots are encrypted to, in hex
+    pub shielded_key: Option<String>,
+    pub receipt_nft_tree: Option<String>,
+    pub vote_fee: u64,
+    pub vote_fee_mint: Option<String>,
+    pub vote_fee_recipient: Option<String>,
+    /// Candidates in ballot order
+    pub candidates: Vec<ExportedCandidate>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ExportedCandidate {
+    pub name: String,
+    pub party: String,
+    pub metadata_uri: String,
+}
+
+// Unset accounts are stored as the default key on-chain and left out of the export
+fn optional_key(key: &Pubkey) -> Option<String> {
+    (*key != Pubkey::default()).then(|| key.to_string())
+}
+
+fn parse_key(field: &str, value: &Option<String>) -> Result<Option<Pubkey>> {
+    value
+        .as_deref()
+        .map(|key| key.parse().map_err(|e| anyhow::anyhow!("Invalid {} {}: {}", field, key, e)))
+        .transpose()
+}
+
+fn parse_hash(field: &str, value: &Option<String>) -> Result<[u8; 32]> {
+    let Some(hex) = value else {
+        return Ok([0u8; 32]);
+    };
+    let invalid = || anyhow::anyhow!("Invalid {} {}: expected 64 hex digits", field, hex);
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let mut bytes = [0u8; 32];
+    for (byte, digits) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(digits)?, 16).map_err(|_| invalid())?;
+    }
+    Ok(bytes)
+}
+
+impl PollExport {
+    /// Capture a poll as read from `cluster`, with its candidates in ballot order
+    pub fn new(cluster: &str, program_id: &Pubkey, poll: &Poll, candidates: &[Candidate]) -> Self {
+        let mut candidates: Vec<&Candidate> = candidates.iter().collect();
+        candidates.sort_by_key(|candidate| candidate.index);
+        Self {
+            version: EXPORT_VERSION,
+            cluster: cluster.to_string(),
+            program_id: program_id.to_string(),
+            poll_id: poll.poll_id,
+            question: poll.question.clone(),
+            description: poll.description.clone(),
+            start_time: poll.start_time,
+            // Extensions are the creator's later edits, not part of the original schedule
+            end_time: poll.end_time - poll.extended_by,
+            max_extension: poll.max_extension,
+            category: poll.category.clone(),
+            tags: poll.tags.clone(),
+            metadata_uri: poll.metadata_uri.clone(),
+            metadata_hash: (poll.metadata_hash != [0u8; 32]).then(|| utils::to_hex(&poll.metadata_hash)),
+            unlisted: poll.visibility == PollVisibility::Unlisted,
+            restricted: poll.access_restricted,
+            open_candidacy: poll.open_candidacy,
+            allow_cpi: poll.allow_cpi,
+            max_votes_per_slot: poll.max_votes_per_slot,
+            candidate_deposit_lamports: poll.candidate_deposit_lamports,
+            deposit_refund_threshold: poll.deposit_refund_threshold,
+            bounty_lamports: poll.bounty_lamports,
+            min_voter_lamports: poll.min_voter_lamports,
+            min_stake_lamports: poll.min_stake_lamports,
+            stake_weighted: poll.stake_weighted,
+            weight_decay_bps: match poll.weight_decay {
+                WeightDecay::None => None,
+                WeightDecay::Linear { end_bps } => Some(end_bps),
+            },
+            gauge_budget: poll.gauge_budget,
+            conviction_mint: optional_key(&poll.conviction_mint),
+            governance_program: optional_key(&poll.governance_program),
+            governance_realm: optional_key(&poll.governance_realm),
+            governance_mint: optional_key(&poll.governance_mint),
+            eligibility_program: poll.eligibility_program.map(|program| program.to_string()),
+            attestation_program: optional_key(&poll.attestation_program),
+            attestation_issuer: optional_key(&poll.attestation_issuer),
+            attestation_schema: optional_key(&poll.attestation_schema),
+            weight_oracle: optional_key(&poll.weight_oracle),
+            max_weight_age: poll.max_weight_age,
+            shielded_key: poll.is_shielded().then(|| utils::to_hex(&poll.shielded_key)),
+            receipt_nft_tree: poll.mint_receipt_nft.then(|| poll.receipt_nft_tree.to_string()),
+            vote_fee: poll.vote_fee,
+            vote_fee_mint: optional_key(&poll.vote_fee_mint),
+            vote_fee_recipient: optional_key(&poll.vote_fee_recipient),
+            candidates: candidates
+                .into_iter()
+                .map(|candidate| ExportedCandidate {
+                    name: candidate.name.clone(),
+                    party: candidate.party.clone(),
+                    metadata_uri: candidate.metadata_uri.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// The poll's options for creating it under `program_id`. A vote fee paid to the source
+    /// program's treasury goes to this program's treasury instead.
+    pub fn options(&self, program_id: &Pubkey) -> Result<PollOptions> {
+        if self.version != EXPORT_VERSION {
+            return Err(anyhow::anyhow!("Unsupported export version {}", self.version));
+        }
+        let receipt_nft_tree = parse_key("receipt NFT tree", &self.receipt_nft_tree)?;
+        let mut vote_fee_recipient = parse_key("vote fee recipient", &self.vote_fee_recipient)?.unwrap_or_default();
+        if let Ok(source_program) = self.program_id.parse::<Pubkey>() {
+            if vote_fee_recipient == get_treasury_address(&source_program).0 {
+                vote_fee_recipient = get_treasury_address(program_id).0;
+            }
+        }
+
+        Ok(PollOptions {
+            candidate_deposit_lamports: self.candidate_deposit_lamports,
+            deposit_refund_threshold: self.deposit_refund_threshold,
+            bounty_lamports: self.bounty_lamports,
+            category: self.category.clone(),
+            tags: self.tags.clone(),
+            metadata_uri: self.metadata_uri.clone(),
+            metadata_hash: parse_hash("metadata hash", &self.metadata_hash)?,
+            mint_receipt_nft: receipt_nft_tree.is_some(),
+            receipt_nft_tree: receipt_nft_tree.unwrap_or_default(),
+            min_voter_lamports: self.min_voter_lamports,
+            min_stake_lamports: self.min_stake_lamports,
+            weight_decay: self
+                .weight_decay_bps
+                .map(|end_bps| WeightDecay::Linear { end_bps })
+                .unwrap_or_default(),
+            max_votes_per_slot: self.max_votes_per_slot,
+            allow_cpi: self.allow_cpi,
+            shielded_key: parse_hash("shielded key", &self.shielded_key)?,
+            stake_weighted: self.stake_weighted,
+            gauge_budget: self.gauge_budget,
+            conviction_mint: parse_key("conviction mint", &self.conviction_mint)?.unwrap_or_default(),
+            governance_program: parse_key("governance program", &self.governance_program)?.unwrap_or_default(),
+            governance_realm: parse_key("governance realm", &self.governance_realm)?.unwrap_or_default(),
+            governance_mint: parse_key("governance mint", &self.governance_mint)?.unwrap_or_default(),
+            eligibility_program: parse_key("eligibility program", &self.eligibility_program)?,
+            attestation_program: parse_key("attestation program", &self.attestation_program)?.unwrap_or_default(),
+            attestation_issuer: parse_key("attestation issuer", &self.attestation_issuer)?.unwrap_or_default(),
+            attestation_schema: parse_key("attestation schema", &self.attestation_schema)?.unwrap_or_default(),
+            visibility: if self.unlisted { PollVisibility::Unlisted } else { PollVisibility::Public },
+            access_restricted: self.restricted,
+            weight_oracle: parse_key("weight oracle", &self.weight_oracle)?.unwrap_or_default(),
+            max_weight_age: self.max_weight_age,
+            vote_fee: self.vote_fee,
+            vote_fee_mint: parse_key("vote fee mint", &self.vote_fee_mint)?.unwrap_or_default(),
+            vote_fee_recipient,
+            max_extension: self.max_extension,
+            open_candidacy: self.open_candidacy,
+        })
+    }
+
+    /// Accounts the poll can't work without on the cluster it's imported to: mints, programs,
+    /// the realm, the attestation schema and the receipt tree. Wallets such as the oracle or
+    /// the fee recipient need not exist beforehand and aren't listed.
+    pub fn required_accounts(&self) -> Result<Vec<(&'static str, Pubkey)>> {
+        let accounts = [
+            ("conviction mint", &self.conviction_mint),
+            ("governance program", &self.governance_program),
+            ("governance realm", &self.governance_realm),
+            ("governance mint", &self.governance_mint),
+            ("eligibility program", &self.eligibility_program),
+            ("attestation program", &self.attestation_program),
+            ("attestation schema", &self.attestation_schema),
+            ("receipt NFT tree", &self.receipt_nft_tree),
+            ("vote fee mint", &self.vote_fee_mint),
+        ];
+        let mut required = Vec::new();
+        for (field, value) in accounts {
+            if let Some(key) = parse_key(field, value)? {
+                required.push((field, key));
+            }
+        }
+        Ok(required)
+    }
+
+    pub fn candidate_entries(&self) -> Vec<NewCandidate> {
+        self.candidates
+            .iter()
+            .map(|candidate| NewCandidate {
+                name: candidate.name.clone(),
+                party: candidate.party.clone(),
+                metadata_uri: candidate.metadata_uri.clone(),
+            })
+            .collect()
+    }
+}
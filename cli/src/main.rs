@@ -1,35 +1,123 @@
 use anchor_client::{
     anchor_lang::prelude::Pubkey,
     solana_sdk::{
-        commitment_config::CommitmentConfig,
-        signature::read_keypair_file,
+        instruction::AccountMeta,
+        native_token::{lamports_to_sol, sol_to_lamports, LAMPORTS_PER_SOL},
+        signature::{read_keypair_file, write_keypair_file, Keypair, Signature},
+        signer::{null_signer::NullSigner, Signer},
     },
     Client, Cluster,
 };
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use std::rc::Rc;
 
 mod client;
+mod completion;
+mod compression;
+mod errors;
+mod events;
+mod export;
+mod proof;
+mod settings;
+mod shielded;
+mod squads;
+mod tui;
 mod utils;
+mod wizard;
 
-use client::VotingClient;
+use client::{
+    Candidate, CandidateOrder, ComputeBudget, DryRun, LockPeriod, Poll, PollFilter, PollOptions, PollStatus,
+    PollVisibility, RetryPolicy, Unsent, VotingClient, WeightDecay,
+};
+use export::PollExport;
+use proof::ReceiptProof;
+use settings::{ProfileKey, Settings};
+use utils::{LogFormat, PollTransition, ResultsFormat};
 
 #[derive(Parser)]
 #[command(name = "voting-cli")]
 #[command(about = "A Rust CLI for interacting with the Solana Voting Dapp", long_about = None)]
 struct Cli {
-    /// Path to the payer keypair file
-    #[arg(short, long, default_value = "~/.config/solana/id.json")]
-    keypair: String,
+    /// Path to the payer keypair file; with --export-unsigned, the signer's public key may be given instead
+    /// [default: ~/.config/solana/id.json]
+    #[arg(short, long, global = true)]
+    keypair: Option<String>,
+
+    /// Cluster to use (localnet, devnet, mainnet) or an RPC URL [default: localnet]
+    #[arg(short, long, global = true)]
+    cluster: Option<String>,
+
+    /// Websocket URL for subscriptions, when the RPC provider serves it apart from the RPC URL
+    /// [default: derived from the cluster]
+    #[arg(long)]
+    ws_url: Option<String>,
+
+    /// Program ID of the voting dapp [default: ErWpLzQeDSoB1nuTs2x1d2yHA2AsBvZHg4nNkAusyNK8]
+    #[arg(short, long)]
+    program_id: Option<String>,
+
+    /// Commitment level for reads and for confirming transactions: processed, confirmed or finalized
+    /// [default: confirmed]
+    #[arg(long, global = true, value_parser = ["processed", "confirmed", "finalized"])]
+    commitment: Option<String>,
+
+    /// Saved profile whose settings fill in flags that aren't given (default: the "default" profile)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Print read commands' output as JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Simulate the command's transaction and print its compute units, fee and logs instead of sending it
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Priority fee per compute unit, in micro-lamports
+    #[arg(long, global = true, conflicts_with = "auto_priority_fee")]
+    priority_fee: Option<u64>,
+
+    /// Set the priority fee from fees recently paid for the same accounts
+    #[arg(long, global = true)]
+    auto_priority_fee: bool,
+
+    /// Compute unit limit of each transaction
+    #[arg(long, global = true)]
+    compute_limit: Option<u32>,
+
+    /// Times a transaction is retried when the RPC node can't be reached or its blockhash expires
+    #[arg(long, global = true, default_value_t = 3)]
+    retries: u32,
+
+    /// Wait before the first retry in milliseconds, doubled for each next one
+    #[arg(long, global = true, default_value_t = 500)]
+    retry_backoff_ms: u64,
+
+    /// Write the command's transaction, unsigned, to this file instead of sending it
+    #[arg(long, global = true, value_name = "PATH", conflicts_with = "dry_run")]
+    export_unsigned: Option<String>,
+
+    /// Durable nonce account for --export-unsigned and `vote --offline`, so the transaction doesn't expire
+    #[arg(long, global = true)]
+    nonce_account: Option<String>,
+
+    /// Act from this Squads multisig's vault, proposing each transaction to its members
+    #[arg(long, global = true, value_name = "ADDRESS")]
+    multisig: Option<String>,
 
-    /// Cluster to use (localnet, devnet, mainnet)
-    #[arg(short, long, default_value = "localnet")]
-    cluster: String,
+    /// Level of the CLI's log lines on stderr: trace, debug, info, warn or error [default: info, or
+    /// RUST_LOG directives when set]
+    #[arg(long, global = true)]
+    log_level: Option<tracing::Level>,
 
-    /// Program ID of the voting dapp
-    #[arg(short, long, default_value = "ErWpLzQeDSoB1nuTs2x1d2yHA2AsBvZHg4nNkAusyNK8")]
-    program_id: String,
+    /// Write log lines as text or as JSON objects
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
+    /// Go ahead without asking: pay vote fees, finalize, archive, cancel finalization, withdraw
+    #[arg(short, long, global = true)]
+    yes: bool,
 
     #[command(subcommand)]
     command: Commands,
@@ -37,6 +125,95 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Initialize the global config and treasury (admin only, once per deployment)
+    InitializeConfig {
+        /// Fee charged on poll creation (lamports)
+        poll_creation_fee: u64,
+    },
+    /// Show the global config and treasury balance
+    GetConfig,
+    /// Withdraw lamports from the treasury (admin only)
+    WithdrawTreasury {
+        /// Amount to withdraw (lamports)
+        amount: u64,
+        /// Recipient public key (optional, defaults to payer)
+        #[arg(short, long)]
+        recipient: Option<String>,
+    },
+    /// Change the fee charged on poll creation (admin only)
+    SetFeeSchedule {
+        /// New poll creation fee (lamports)
+        poll_creation_fee: u64,
+    },
+    /// List recent treasury withdrawals, newest first
+    TreasuryHistory {
+        /// How many of the latest treasury transactions to scan
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+    },
+    /// Set the council that must approve new polls (admin only)
+    SetCouncil {
+        /// Council member public keys (comma-separated)
+        #[arg(value_delimiter = ',')]
+        members: Vec<String>,
+        /// Approvals required to create a poll (0 disables council mode)
+        #[arg(long)]
+        threshold: u8,
+    },
+    /// Require a creator pass to create polls, or lift the requirement (admin only)
+    SetCreatorAllowlist {
+        /// Whether the allowlist is enforced
+        #[arg(action = clap::ArgAction::Set)]
+        enabled: bool,
+    },
+    /// Limit how far ahead a new poll's end time may be (admin only)
+    SetMaxPollDuration {
+        /// Maximum duration in seconds (0 for no limit)
+        seconds: i64,
+    },
+    /// Limit how many polls each wallet may create within a rolling window (admin only)
+    SetCreationLimit {
+        /// Polls allowed per window (0 for no limit, at most 20)
+        max_polls: u16,
+        /// Window length in seconds
+        #[arg(long, default_value_t = 86_400)]
+        window: i64,
+    },
+    /// Summarize a wallet's polls: votes received, turnout, top candidates and the creation limit
+    CreatorStats {
+        /// Wallet to look up (defaults to the payer)
+        creator: Option<String>,
+        /// How many of the most-voted candidates to list
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+    },
+    /// Allow a wallet to create polls while the allowlist is on (admin only)
+    GrantCreator {
+        /// Wallet to grant a creator pass
+        creator: String,
+    },
+    /// Revoke a wallet's creator pass (admin only)
+    RevokeCreator {
+        /// Wallet whose creator pass is revoked
+        creator: String,
+    },
+    /// Stage a poll for council approval before creating it
+    ProposePoll {
+        /// Poll ID
+        poll_id: u64,
+        /// Poll question, which must match the one used at creation
+        question: String,
+    },
+    /// Withdraw a staged poll (proposer or admin)
+    CancelProposal {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Approve a staged poll as a council member
+    ApprovePoll {
+        /// Poll ID
+        poll_id: u64,
+    },
     /// Initialize a new poll
     InitializePoll {
         /// Unique poll ID
@@ -45,10 +222,228 @@ enum Commands {
         question: String,
         /// Poll description
         description: String,
-        /// Start time (Unix timestamp)
-        start_time: i64,
+        /// Start time (Unix timestamp, or `now` for the cluster's current time)
+        start_time: String,
         /// End time (Unix timestamp)
-        end_time: i64,
+        #[arg(required_unless_present = "duration")]
+        end_time: Option<i64>,
+        /// How long the poll runs instead of an end time, in seconds or with a unit (30m, 72h, 7d)
+        #[arg(long, value_parser = utils::parse_duration, conflicts_with = "end_time")]
+        duration: Option<i64>,
+        /// Deposit each candidate must escrow on registration (lamports)
+        #[arg(long, default_value_t = 0)]
+        candidate_deposit: u64,
+        /// Votes a candidate needs to get the deposit refunded
+        #[arg(long, default_value_t = 0)]
+        deposit_threshold: u64,
+        /// Bounty escrowed for the winner's payout wallet (lamports)
+        #[arg(long, default_value_t = 0)]
+        bounty: u64,
+        /// Poll category (e.g. governance, community)
+        #[arg(long, default_value = "")]
+        category: String,
+        /// Tag to attach to the poll (repeatable, up to 5)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// URI of long-form off-chain poll metadata
+        #[arg(long, default_value = "")]
+        metadata_uri: String,
+        /// Local copy of the metadata to hash (defaults to fetching the URI)
+        #[arg(long, requires = "metadata_uri")]
+        metadata_file: Option<String>,
+        /// Bubblegum tree to mint "I Voted" cNFTs from (enables receipt NFTs)
+        #[arg(long)]
+        receipt_nft_tree: Option<String>,
+        /// Minimum SOL balance a voter must hold (lamports)
+        #[arg(long, default_value_t = 0)]
+        min_voter_balance: u64,
+        /// Minimum native stake a voter must have actively delegated (lamports)
+        #[arg(long, default_value_t = 0)]
+        min_stake: u64,
+        /// Decay vote weight linearly to this share of full weight by the end (basis points)
+        #[arg(long)]
+        weight_decay_bps: Option<u16>,
+        /// Maximum votes accepted per slot (0 for no limit)
+        #[arg(long, default_value_t = 0)]
+        max_votes_per_slot: u32,
+        /// Let other programs cast votes through CPI
+        #[arg(long)]
+        allow_cpi: bool,
+        /// Encrypt ballots to the ElGamal key in this file (see generate-shielded-key)
+        #[arg(long)]
+        shielded_key: Option<String>,
+        /// Weigh each vote by the voter's delegated stake when voting opens
+        #[arg(long)]
+        stake_weighted: bool,
+        /// Make this a gauge poll where each voter splits this many points across candidates
+        #[arg(long, default_value_t = 0)]
+        gauge_budget: u16,
+        /// Make this a conviction poll where voters lock tokens of this mint
+        #[arg(long)]
+        conviction_mint: Option<String>,
+        /// Weigh votes by tokens deposited in this spl-governance realm
+        #[arg(long, requires = "governance_mint")]
+        governance_realm: Option<String>,
+        /// Governing mint (community or council) whose deposits count
+        #[arg(long, requires = "governance_realm")]
+        governance_mint: Option<String>,
+        /// spl-governance program the realm belongs to
+        #[arg(long, default_value = utils::GOVERNANCE_PROGRAM_ID)]
+        governance_program: String,
+        /// Program whose `verify(voter)` instruction must approve every voter
+        #[arg(long)]
+        eligibility_program: Option<String>,
+        /// Require voters to hold an attestation from this issuer (credential)
+        #[arg(long, requires = "attestation_schema")]
+        attestation_issuer: Option<String>,
+        /// Schema the required attestation must follow
+        #[arg(long, requires = "attestation_issuer")]
+        attestation_schema: Option<String>,
+        /// Attestation program that owns the issuer's attestations
+        #[arg(long, default_value = utils::ATTESTATION_PROGRAM_ID)]
+        attestation_program: String,
+        /// Leave the poll out of list-polls; voters need its ID to find it
+        #[arg(long)]
+        unlisted: bool,
+        /// Only let voters granted access with grant-access vote
+        #[arg(long)]
+        restricted: bool,
+        /// Weigh each vote by the weight this oracle published for the voter (see publish-weight)
+        #[arg(long)]
+        weight_oracle: Option<String>,
+        /// Oldest oracle weight accepted, in seconds (0 for no limit)
+        #[arg(long, default_value_t = 0, requires = "weight_oracle")]
+        max_weight_age: i64,
+        /// Let anyone register themselves as a candidate while the poll is a draft
+        #[arg(long)]
+        open_candidacy: bool,
+        /// How far the end time may be extended in total once votes are in, in seconds
+        #[arg(long, default_value_t = 0)]
+        max_extension: i64,
+        /// Fee charged to each voter, in lamports or base units of --vote-fee-mint
+        #[arg(long, default_value_t = 0, requires = "vote_fee_recipient")]
+        vote_fee: u64,
+        /// Charge the vote fee in this SPL token instead of SOL
+        #[arg(long, requires = "vote_fee")]
+        vote_fee_mint: Option<String>,
+        /// Where vote fees go: the poll's treasury or a charity wallet for SOL fees, a token
+        /// account of the fee mint for SPL fees
+        #[arg(long)]
+        vote_fee_recipient: Option<String>,
+    },
+    /// Create a poll step by step: prompts for the details, previews them, then submits
+    Create,
+    /// Save a poll's question, schedule, voting mode and candidates to a file for import-poll
+    ExportPoll {
+        /// Poll ID
+        poll_id: u64,
+        /// File to write (prints to stdout if omitted)
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+    /// Create a poll from an export-poll file, such as one rehearsed on devnet
+    ImportPoll {
+        /// File written by export-poll
+        path: String,
+        /// ID of the new poll [default: the exported poll's ID]
+        #[arg(long)]
+        poll_id: Option<u64>,
+        /// New start time (Unix timestamp, or `now` for the cluster's current time); the poll
+        /// keeps its duration
+        #[arg(long, default_value = "now")]
+        start: String,
+        /// Activate the poll once its candidates are added
+        #[arg(long)]
+        activate: bool,
+    },
+    /// Edit a draft poll's question or description, or move a poll's end time
+    UpdatePoll {
+        /// Poll ID
+        poll_id: u64,
+        /// New poll question
+        #[arg(long)]
+        question: Option<String>,
+        /// New poll description (up to 4000 characters)
+        #[arg(long)]
+        description: Option<String>,
+        /// New end time (Unix timestamp); once votes are in it can only be extended
+        #[arg(long)]
+        end_time: Option<i64>,
+    },
+    /// Let another wallet register candidates for your poll (omit the wallet to remove it)
+    SetCandidateManager {
+        /// Poll ID
+        poll_id: u64,
+        /// Wallet of the candidate manager
+        manager: Option<String>,
+    },
+    /// Let the wallets listed in a file vote in your access-restricted poll
+    GrantAccess {
+        /// Poll ID
+        poll_id: u64,
+        /// File with one voter wallet per line
+        #[arg(long)]
+        voters: String,
+    },
+    /// Withdraw a voter's access to your access-restricted poll
+    RevokeAccess {
+        /// Poll ID
+        poll_id: u64,
+        /// Voter wallet
+        voter: String,
+    },
+    /// Publish a voter's weight as an oracle, signing with your wallet
+    PublishWeight {
+        /// Voter wallet
+        voter: String,
+        /// Weight each of the voter's votes counts for in polls naming you as their oracle
+        weight: u64,
+    },
+    /// Stop a live poll from accepting votes
+    PausePoll {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Freeze a draft poll's candidates and open it for voting
+    ActivatePoll {
+        /// Poll ID
+        poll_id: u64,
+        /// Register a Clockwork thread that finalizes the poll shortly after it ends
+        #[arg(long)]
+        auto_finalize: bool,
+        /// Lamports funding the finalization thread's fees
+        #[arg(long, default_value_t = 10_000_000, requires = "auto_finalize")]
+        thread_fee: u64,
+    },
+    /// Delete a poll's finalization thread, cancelling automatic finalization
+    CancelFinalization {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Draw a draft poll's ballot order, or an ended poll's tie-break, from Switchboard randomness
+    RequestRandomness {
+        /// Poll ID
+        poll_id: u64,
+        /// Committed, not yet revealed Switchboard randomness account
+        randomness: String,
+    },
+    /// Apply the revealed value of a poll's randomness request
+    ApplyRandomness {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Cancel a poll's randomness request that was not revealed in time
+    CancelRandomness {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Resume a paused poll
+    ResumePoll {
+        /// Poll ID
+        poll_id: u64,
+        /// Push the end time back by however long the poll was paused
+        #[arg(long)]
+        extend: bool,
     },
     /// Add a candidate to a poll
     AddCandidate {
@@ -58,13 +453,327 @@ enum Commands {
         name: String,
         /// Candidate party affiliation
         party: String,
+        /// Wallet that receives payouts if the candidate wins (optional, defaults to payer)
+        #[arg(long)]
+        payout_wallet: Option<String>,
+        /// URI of off-chain candidate metadata (photo, bio, links)
+        #[arg(long, default_value = "")]
+        metadata_uri: String,
+    },
+    /// Add many candidates from a CSV file of `name,party` lines, several per transaction
+    AddCandidates {
+        /// Poll ID
+        poll_id: u64,
+        /// CSV (`name,party,metadata_uri`) or JSON file listing the candidates
+        #[arg(long)]
+        file: String,
+    },
+    /// Update a candidate's metadata URI before voting opens
+    UpdateCandidateMetadata {
+        /// Poll ID
+        poll_id: u64,
+        /// Candidate name
+        candidate_name: String,
+        /// New metadata URI
+        metadata_uri: String,
     },
     /// Vote for a candidate
     Vote {
         /// Poll ID
         poll_id: u64,
+        /// Candidate name; leave out to pick from the ballot
+        candidate_name: Option<String>,
+        /// Vote for the candidate at this ballot index, as shown by list-candidates
+        #[arg(long, conflicts_with = "candidate_name")]
+        index: Option<u16>,
+        /// Native stake account, required by stake-gated and stake-weighted polls
+        #[arg(long)]
+        stake_account: Option<String>,
+        /// Account the poll's eligibility program reads, as ADDRESS or ADDRESS:mut; repeat in order
+        #[arg(long = "verifier-account", value_parser = utils::parse_verifier_account)]
+        verifier_accounts: Vec<AccountMeta>,
+        /// Keypair file of a sponsor that pays the receipt rent instead of the voter
+        #[arg(long)]
+        sponsor: Option<String>,
+        /// Sign a transaction against --nonce-account into --out instead of sending it
+        #[arg(long, requires = "out")]
+        offline: bool,
+        /// File the offline transaction is written to
+        #[arg(long, requires = "offline")]
+        out: Option<String>,
+    },
+    /// Broadcast a transaction file signed offline
+    Submit {
+        /// Transaction file written by `vote --offline`
+        path: String,
+    },
+    /// Sign a transaction exported with --export-unsigned, using --keypair
+    Sign {
+        /// Transaction file to sign
+        path: String,
+        /// Where to write the signature file (default: <SIGNER>.sig)
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Merge the signature files of an exported transaction and send it
+    CombineAndSend {
+        /// Signature files written by `sign`, one per signer
+        #[arg(required = true)]
+        paths: Vec<String>,
+    },
+    /// Cast votes from every keypair in a directory, split across candidates by weight
+    SimulateVotes {
+        /// Poll ID
+        poll_id: u64,
+        /// Directory of voter keypair files (*.json)
+        #[arg(long)]
+        keypairs: String,
+        /// Share of the votes per candidate as NAME=WEIGHT, comma-separated (e.g. alice=60,bob=40)
+        #[arg(long, required = true, value_delimiter = ',', value_parser = utils::parse_allocation)]
+        distribution: Vec<(String, u16)>,
+    },
+    /// Split a gauge poll's points across candidates
+    VoteGauge {
+        /// Poll ID
+        poll_id: u64,
+        /// Points for one candidate as NAME=POINTS; repeat for each candidate
+        #[arg(long = "alloc", required = true, value_parser = utils::parse_allocation)]
+        allocations: Vec<(String, u16)>,
+    },
+    /// Vote in a conviction poll by locking tokens; longer locks multiply the vote
+    VoteConviction {
+        /// Poll ID
+        poll_id: u64,
+        /// Candidate name
+        candidate_name: String,
+        /// Tokens to lock, in base units
+        amount: u64,
+        /// How long the tokens stay locked past the poll's end
+        #[arg(long, value_enum, default_value_t = LockPeriod::PollEnd)]
+        lock: LockPeriod,
+    },
+    /// Return your conviction tokens once their lock has expired
+    Unlock {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Let a poll mint "I Voted" cNFTs from a Bubblegum tree you created
+    DelegateNftTree {
+        /// Poll ID
+        poll_id: u64,
+        /// Bubblegum merkle tree address
+        tree: String,
+    },
+    /// Attach a compressed receipt tree to a poll (creator only, before voting)
+    InitReceiptTree {
+        /// Poll ID
+        poll_id: u64,
+        /// Tree depth; the tree holds 2^depth voters
+        #[arg(long, default_value_t = 14)]
+        max_depth: u32,
+        /// Concurrent changes the tree tolerates between proof fetches
+        #[arg(long, default_value_t = 64)]
+        max_buffer_size: u32,
+    },
+    /// Register a voter in a poll's compressed receipt tree (creator only)
+    RegisterVoter {
+        /// Poll ID
+        poll_id: u64,
+        /// Voter public key
+        voter: String,
+    },
+    /// Vote without creating a receipt account, using the poll's receipt tree
+    VoteCompressed {
+        /// Poll ID
+        poll_id: u64,
+        /// Candidate name
+        candidate_name: String,
+    },
+    /// Finalize a poll after its voting period has ended
+    FinalizePoll {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Fund a poll's SPL token reward pool from your associated token account
+    FundRewardPool {
+        /// Poll ID
+        poll_id: u64,
+        /// Reward token mint
+        mint: String,
+        /// Amount to deposit (base units)
+        amount: u64,
+    },
+    /// Claim your share of a finalized poll's reward pool
+    ClaimReward {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Name the auditors who may attest your draft poll's result
+    RegisterAuditors {
+        /// Poll ID
+        poll_id: u64,
+        /// Auditor public keys (comma-separated, at most 16)
+        #[arg(value_delimiter = ',', required = true)]
+        auditors: Vec<String>,
+    },
+    /// Attest a finalized poll's winner and vote total as one of its auditors
+    AttestResult {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Show which auditors have attested a poll's result
+    VerifyResult {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Pay a finalized poll's bounty to the winner's payout wallet
+    ClaimBounty {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Write the off-chain metadata JSON for a finalized poll's winner badge
+    BadgeMetadata {
+        /// Poll ID
+        poll_id: u64,
+        /// File to write (prints to stdout if omitted)
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+    /// Mint a finalized poll's winner badge NFT to the winner's payout wallet
+    MintWinnerBadge {
+        /// Poll ID
+        poll_id: u64,
+        /// URI where the badge metadata JSON is hosted
+        uri: String,
+    },
+    /// Attach an instruction to a draft poll, executed if the yes candidate passes
+    CreateProposal {
+        /// Poll ID
+        poll_id: u64,
+        /// Candidate whose votes count as "yes"
+        yes_candidate: String,
+        /// JSON file describing the instruction (program_id, accounts, base64 data)
+        instruction: String,
+        /// Share of ballots the yes candidate must exceed (basis points)
+        #[arg(long, default_value_t = 5000)]
+        threshold_bps: u16,
+    },
+    /// Execute a finalized poll's proposal if it passed
+    ExecuteProposal {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Settle a candidate's registration deposit after finalization
+    ClaimDeposit {
+        /// Poll ID
+        poll_id: u64,
+        /// Candidate name
+        candidate_name: String,
+    },
+    /// Close a poll's receipts after its grace period, earning a share of their rent
+    CrankReceipts {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Condense a settled poll into an archive account and reclaim its rent
+    ArchivePoll {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Create an ElGamal key pair for a shielded poll's tally
+    GenerateShieldedKey {
+        /// Where to write the key pair
+        output: String,
+    },
+    /// Decrypt a shielded poll's totals after it ends and publish them with proofs
+    PublishShieldedResults {
+        /// Poll ID
+        poll_id: u64,
+        /// Path to the poll's ElGamal key pair
+        key: String,
+    },
+    /// Show the archived summary of a poll
+    GetArchive {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Upgrade a poll, candidate or receipt account created with an older layout
+    MigrateAccount {
+        /// Address of the account to migrate
+        address: String,
+    },
+    /// Create every poll, candidate and the ballot described in a manifest file
+    CreateBallot {
+        /// Path to the ballot manifest (JSON)
+        manifest: String,
+    },
+    /// Vote in every race of a ballot in one transaction
+    VoteBallot {
+        /// Ballot ID
+        ballot_id: u64,
+        /// Candidate names, one per race in ballot order
+        #[arg(required = true)]
+        choices: Vec<String>,
+    },
+    /// Vote in several polls at once, with a single transaction
+    VoteAll {
+        /// JSON file listing `{"poll_id": 1, "candidate": "Alice"}` entries
+        #[arg(long)]
+        ballot: String,
+    },
+    /// Create a short-lived session key that can vote for you in a ballot's races
+    CreateSession {
+        /// Ballot ID
+        ballot_id: u64,
+        /// File to write the new session keypair to
+        #[arg(long, default_value = "session.json")]
+        out: String,
+        /// How many slots the session stays valid (default about an hour)
+        #[arg(long, default_value_t = 9_000)]
+        slots: u64,
+        /// Lamports moved to the session key for receipt rent and fees
+        #[arg(long, default_value_t = 10_000_000)]
+        fund: u64,
+    },
+    /// Revoke your session key for a ballot
+    RevokeSession {
+        /// Ballot ID
+        ballot_id: u64,
+    },
+    /// Vote in one race of a ballot as a session key (pass the session keypair with -k)
+    VoteSession {
+        /// Ballot ID
+        ballot_id: u64,
+        /// Poll ID of the race
+        poll_id: u64,
         /// Candidate name
         candidate_name: String,
+        /// Voter who created the session
+        #[arg(long)]
+        voter: String,
+    },
+    /// List polls on the cluster
+    ListPolls {
+        /// Only show polls with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only show polls in this category
+        #[arg(long)]
+        category: Option<String>,
+        /// Only show polls created by this wallet
+        #[arg(long)]
+        creator: Option<String>,
+        /// Only show polls accepting votes right now
+        #[arg(long)]
+        active: bool,
+        /// Show at most this many polls, newest first
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Check a poll's off-chain metadata against its on-chain hash
+    VerifyMetadata {
+        /// Poll ID
+        poll_id: u64,
     },
     /// Get poll details
     GetPoll {
@@ -75,6 +784,52 @@ enum Commands {
     GetResults {
         /// Poll ID
         poll_id: u64,
+        /// Order candidates by ballot position, votes, name or the poll's random ballot order
+        #[arg(long, value_enum, default_value_t = CandidateOrder::Ballot)]
+        order: CandidateOrder,
+        /// Print a summary table, CSV rows or a Markdown report
+        #[arg(long, value_enum, default_value_t = ResultsFormat::Table)]
+        format: ResultsFormat,
+        /// Draw the results as bars sized to the terminal, starring the leader
+        #[arg(long, conflicts_with = "format")]
+        chart: bool,
+    },
+    /// Compare the results of two polls, such as recurring elections, matching candidates by name
+    Compare {
+        /// Poll ID of the earlier poll
+        poll_id_a: u64,
+        /// Poll ID of the later poll
+        poll_id_b: u64,
+    },
+    /// List the wallets that have voted in a poll
+    ListVoters {
+        /// Poll ID
+        poll_id: u64,
+        /// Only print how many voters there are
+        #[arg(long)]
+        count_only: bool,
+    },
+    /// List a poll's candidates in ballot order, with their account addresses
+    ListCandidates {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Open a terminal dashboard of polls with live results, voting and finalization
+    Tui,
+    /// Stream a poll's tallies as votes are counted
+    WatchResults {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Plot a poll's votes per hour since it started
+    TurnoutChart {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Check that a poll's receipt weights add up to its candidate totals
+    AuditWeights {
+        /// Poll ID
+        poll_id: u64,
     },
     /// Check if a user has voted in a poll
     HasVoted {
@@ -83,56 +838,828 @@ enum Commands {
         /// Voter public key (optional, defaults to payer)
         #[arg(short, long)]
         voter: Option<String>,
+        /// Check every wallet in a file with one address per line, printing a CSV of the results
+        #[arg(long, conflicts_with = "voter")]
+        voters_file: Option<String>,
+    },
+    /// Write a proof of a voter's receipt, signed by the keypair, that others can check with verify-proof
+    VerifyReceipt {
+        /// Poll ID
+        poll_id: u64,
+        /// Voter public key
+        voter: String,
+        /// File to write the proof to [default: print it]
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Check a receipt proof's signature and contents, and compare it with the receipt on the cluster
+    VerifyProof {
+        /// Proof file written by verify-receipt
+        path: String,
+    },
+    /// Show whether a poll is pending, active or ended, the time to its next change and the
+    /// cluster clock it is judged by
+    Status {
+        /// Poll ID
+        poll_id: u64,
+    },
+    /// Wait until a poll opens or closes, then run a command, e.g. `when 5 --on end -- finalize-poll 5`
+    When {
+        /// Poll ID
+        poll_id: u64,
+        /// Wait for voting to open (start) or to close (end)
+        #[arg(long, value_enum)]
+        on: PollTransition,
+        /// Command to run, after `--`; it gets the global options given to `when`
+        #[arg(last = true, required = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+    /// List the transactions that touched a poll, newest first: creation, candidates, votes and more
+    History {
+        /// Poll ID
+        poll_id: u64,
+        /// Number of transactions to list
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// List transactions older than this signature, to page back from the last one listed
+        #[arg(long)]
+        before: Option<String>,
+    },
+    /// Decode the voting program's instructions in a transaction, whichever client sent it
+    DecodeTx {
+        /// Transaction signature
+        signature: String,
+    },
+    /// Request SOL from the faucet on localnet or devnet and confirm the new balance
+    Airdrop {
+        /// Account to fund [default: the keypair's]
+        #[arg(long)]
+        to: Option<String>,
+        /// Amount in SOL
+        #[arg(long, default_value_t = 2.0)]
+        amount: f64,
+    },
+    /// Generate voter keypairs, optionally funded by the payer, and list them in manifest.csv
+    Keygen {
+        /// Number of keypairs
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// Directory the keypairs and manifest are written to
+        #[arg(long, default_value = "./voters")]
+        outdir: String,
+        /// SOL sent from the payer to each new keypair
+        #[arg(long)]
+        fund: Option<f64>,
+    },
+    /// Print a QR code linking to a poll, for posters and livestreams
+    Share {
+        /// Poll ID
+        poll_id: u64,
+        /// Candidate to prefill in the link
+        #[arg(long)]
+        candidate: Option<String>,
+        /// Also save the QR code as a PNG
+        #[arg(long)]
+        png: Option<String>,
+        /// Page the link opens, with the poll in its query string [default: a solana: URI of the poll]
+        #[arg(long)]
+        link: Option<String>,
+    },
+    /// Read and change the profiles saved in ~/.config/voting-cli/config.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Print a shell completion script; poll IDs and candidate names complete from the cluster of
+    /// a configured profile
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Print the values that complete the next argument after WORDS (used by completion scripts)
+    #[command(hide = true)]
+    CompleteValues {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Set a value of the --profile profile, creating the profile if needed
+    Set {
+        /// Setting to change
+        #[arg(value_enum)]
+        key: ProfileKey,
+        /// New value
+        value: String,
+    },
+    /// Print a value of the --profile profile
+    Get {
+        /// Setting to read
+        #[arg(value_enum)]
+        key: ProfileKey,
     },
+    /// List every profile and its settings
+    List,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-
-    // Expand tilde in keypair path
-    let keypair_path = shellexpand::tilde(&cli.keypair).to_string();
-    
-    // Read keypair
-    let payer = read_keypair_file(&keypair_path)
-        .map_err(|e| anyhow::anyhow!("Failed to read keypair from {}: {}", keypair_path, e))?;
-
-    // Parse cluster
-    let cluster = match cli.cluster.as_str() {
-        "localnet" => Cluster::Localnet,
-        "devnet" => Cluster::Devnet,
-        "mainnet" => Cluster::Mainnet,
-        _ => return Err(anyhow::anyhow!("Invalid cluster: {}", cli.cluster)),
+    utils::init_logging(cli.log_level, cli.log_format)?;
+    let json = cli.json;
+    let export_unsigned = cli.export_unsigned.clone();
+    let Err(e) = run(cli) else {
+        return Ok(());
     };
+    let e = match e.downcast::<DryRun>() {
+        Ok(dry_run) => return print_dry_run(&dry_run, json),
+        Err(e) => e,
+    };
+    match (e.downcast::<Unsent>(), export_unsigned) {
+        (Ok(unsent), Some(path)) => export_transaction(&unsent, &path),
+        (Ok(unsent), None) => Err(unsent.into()),
+        // The raw error stays below as its cause
+        (Err(e), _) => match errors::explain(&format!("{:#}", e)) {
+            Some(explained) => Err(e.context(explained.to_string())),
+            None => Err(e),
+        },
+    }
+}
 
-    // Parse program ID
-    let program_id = cli.program_id.parse::<Pubkey>()
-        .map_err(|e| anyhow::anyhow!("Invalid program ID: {}", e))?;
+/// Write an unsigned transaction for its signers, with how to sign and send it
+fn export_transaction(unsent: &Unsent, path: &str) -> Result<()> {
+    utils::write_transaction_file(path, &unsent.transaction, unsent.nonce_account.as_ref())?;
+    println!("✓ Unsigned transaction written to {}", path);
+    println!("  Signers:");
+    for signer in utils::missing_signers(&unsent.transaction) {
+        println!("    {}", signer);
+    }
+    match unsent.nonce_account {
+        Some(nonce_account) => println!("  Valid until nonce account {} is advanced", nonce_account),
+        None => println!("  Expires with its recent blockhash in about a minute; use --nonce-account for more time"),
+    }
+    println!("  Each signer runs: voting-cli sign {} --keypair <KEYPAIR>", path);
+    println!("  Then: voting-cli combine-and-send <SIGNATURE FILES>");
+    Ok(())
+}
 
-    // Create client
-    let client = Client::new_with_options(
-        cluster,
-        Rc::new(payer),
-        CommitmentConfig::confirmed(),
-    );
+/// Handle `config`, which works on the config file alone and needs no keypair or cluster
+fn run_config(mut settings: Settings, profile: Option<String>, action: ConfigAction) -> Result<()> {
+    let name = profile.unwrap_or_else(|| settings::DEFAULT_PROFILE.to_string());
+    match action {
+        ConfigAction::Set { key, value } => {
+            settings.profiles.entry(name.clone()).or_default().set(key, value)?;
+            settings.save()?;
+            println!("✓ Saved to profile {} in {}", name, settings::CONFIG_PATH);
+        }
+        ConfigAction::Get { key } => {
+            let profile = settings.profile(Some(&name))?;
+            match profile.get(key) {
+                Some(value) => println!("{}", value),
+                None => return Err(anyhow::anyhow!("Profile {} doesn't set {}", name, key.name())),
+            }
+        }
+        ConfigAction::List => {
+            if settings.profiles.is_empty() {
+                println!("No profiles yet; add one with `voting-cli config set`");
+            }
+            for (name, profile) in &settings.profiles {
+                println!("[{}]", name);
+                for (key, value) in profile.entries() {
+                    println!("  {} = {}", key.name(), value);
+                }
+            }
+        }
+    }
+    Ok(())
+}
 
-    let voting_client = VotingClient::new(client, program_id);
+/// Ask before sending a transaction that can't be undone, after listing what it changes and
+/// simulating it for its fee. Nothing is asked with `--yes`, or when the transaction is only
+/// simulated or exported anyway.
+fn confirm_irreversible<T>(
+    voting_client: &mut VotingClient<Box<dyn Signer>>,
+    yes: bool,
+    changes: &[String],
+    send: impl FnOnce(&VotingClient<Box<dyn Signer>>) -> Result<T>,
+) -> Result<bool> {
+    if yes || !voting_client.sends_transactions() {
+        return Ok(true);
+    }
+    let preview = voting_client.preview(send)?;
+    println!("This can't be undone:");
+    for change in changes {
+        println!("  • {}", change);
+    }
+    if let Some(error) = preview.error {
+        return Err(anyhow::anyhow!("The transaction would fail: {}", describe_failure(&error)));
+    }
+    println!("  Fee: {} SOL", lamports_to_sol(preview.fee));
+    utils::confirm("Continue?")
+}
 
-    // Execute command
-    match cli.command {
-        Commands::InitializePoll {
-            poll_id,
-            question,
+/// Check a candidate name against the poll's ballot before anything is signed. A name that isn't
+/// on it is matched against the candidates, and the closest one is offered in its place.
+fn confirm_candidate_name(voting_client: &VotingClient<Box<dyn Signer>>, poll_id: u64, name: String) -> Result<String> {
+    let names: Vec<String> = voting_client
+        .get_candidates(poll_id)?
+        .into_iter()
+        .map(|(_, candidate)| candidate.name)
+        .collect();
+    if names.contains(&name) {
+        return Ok(name);
+    }
+    let no_candidate = || anyhow::anyhow!("Poll {} has no candidate named {}; see list-candidates", poll_id, name);
+    let suggestion = utils::closest_name(&name, names.iter().map(String::as_str)).ok_or_else(no_candidate)?;
+    if !utils::confirm(&format!("Poll {} has no candidate named {}. Did you mean {}?", poll_id, name, suggestion))? {
+        return Err(no_candidate());
+    }
+    Ok(suggestion.to_string())
+}
+
+/// Name the candidate at a ballot index, or let the user pick one from the ballot. The list
+/// comes from the chain in ballot order, shuffled if the poll has a ballot order seed.
+fn choose_candidate(voting_client: &VotingClient<Box<dyn Signer>>, poll: &Poll, index: Option<u16>) -> Result<String> {
+    let mut candidates: Vec<Candidate> = voting_client
+        .get_candidates(poll.poll_id)?
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect();
+    if let Some(index) = index {
+        return candidates
+            .into_iter()
+            .find(|candidate| candidate.index == index)
+            .map(|candidate| candidate.name)
+            .ok_or_else(|| anyhow::anyhow!("Poll {} has no candidate at index {}", poll.poll_id, index));
+    }
+    if candidates.is_empty() {
+        return Err(anyhow::anyhow!("Poll {} has no candidates yet", poll.poll_id));
+    }
+    client::sort_candidates(&mut candidates, CandidateOrder::Random, &poll.ballot_order_seed);
+    let items: Vec<String> = candidates
+        .iter()
+        .map(|candidate| match candidate.party.as_str() {
+            "" => format!("[{}] {}", candidate.index, candidate.name),
+            party => format!("[{}] {} ({})", candidate.index, candidate.name, party),
+        })
+        .collect();
+    println!("Poll {}: {}", poll.poll_id, poll.question);
+    let choice = dialoguer::Select::new().with_prompt("Candidate").items(&items).default(0).interact()?;
+    Ok(candidates.swap_remove(choice).name)
+}
+
+/// The transaction an offline client built in place of sending it
+fn into_unsent(outcome: Result<Signature>) -> Result<Unsent> {
+    match outcome {
+        Ok(signature) => Err(anyhow::anyhow!("Transaction {} was sent instead of built offline", signature)),
+        Err(e) => e.downcast::<Unsent>(),
+    }
+}
+
+/// Report a simulated transaction; a failed simulation makes the command fail too
+fn print_dry_run(dry_run: &DryRun, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(dry_run)?);
+    } else {
+        println!("=== Dry Run (nothing was submitted) ===");
+        println!("Result: {}", dry_run.error.as_deref().unwrap_or("success"));
+        if let Some(explained) = dry_run.error.as_deref().and_then(errors::explain) {
+            println!("  {}", explained);
+        }
+        match dry_run.units_consumed {
+            Some(units) => println!("Compute units: {}", units),
+            None => println!("Compute units: not reported"),
+        }
+        println!("Fee: {} lamports", dry_run.fee);
+        println!("Logs:");
+        for log in &dry_run.logs {
+            println!("  {}", log);
+        }
+    }
+    match &dry_run.error {
+        Some(error) => Err(anyhow::anyhow!("The transaction would fail: {}", describe_failure(error))),
+        None => Ok(()),
+    }
+}
+
+/// A simulated transaction's error, in words when it is a known program error
+fn describe_failure(error: &str) -> String {
+    errors::explain(error).map_or_else(|| error.to_string(), |explained| explained.to_string())
+}
+
+/// The options of this invocation that `when` passes on to its command: everything before the
+/// `--` except `when` itself, its poll ID and `--on`
+fn forwarded_options(args: &[String]) -> Vec<String> {
+    let root = Cli::command();
+    let when = root.find_subcommand("when").expect("`when` is a subcommand");
+    let mut options = Vec::new();
+    let mut args = args.iter().take_while(|arg| arg.as_str() != "--");
+    while let Some(arg) = args.next() {
+        if completion::takes_value(when, arg) {
+            args.next();
+        } else if arg.starts_with('-') && !arg.starts_with("--on=") {
+            options.push(arg.clone());
+            if completion::takes_value(&root, arg) {
+                options.extend(args.next().cloned());
+            }
+        }
+        // Anything else is `when` or the poll ID
+    }
+    options
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let settings = Settings::load()?;
+    match cli.command {
+        Commands::Config { action } => return run_config(settings, cli.profile, action),
+        Commands::Completions { shell } => return completion::write_script(shell, &mut std::io::stdout()),
+        Commands::CompleteValues { words } => return completion::print_values(&settings, &words),
+        _ => {}
+    }
+
+    // Flags take precedence over the profile, which takes precedence over the defaults
+    let profile = settings.profile(cli.profile.as_deref())?;
+    let keypair = cli.keypair.or(profile.keypair).unwrap_or_else(|| settings::DEFAULT_KEYPAIR.to_string());
+    let cluster = cli.cluster.or(profile.rpc_url).unwrap_or_else(|| settings::DEFAULT_CLUSTER.to_string());
+    let program_id = cli
+        .program_id
+        .or(profile.program_id)
+        .unwrap_or_else(|| settings::DEFAULT_PROGRAM_ID.to_string());
+    let commitment = cli.commitment.or(profile.commitment).unwrap_or_else(|| settings::DEFAULT_COMMITMENT.to_string());
+    let commitment = settings::parse_commitment(&commitment)?;
+
+    // Expand tilde in keypair path
+    let keypair_path = shellexpand::tilde(&keypair).to_string();
+
+    // Read keypair or connect to the hardware wallet. An export for an offline signer may name it
+    // by public key alone, since nothing is signed.
+    let payer: Box<dyn Signer> = match keypair.parse::<Pubkey>() {
+        Ok(signer) if cli.export_unsigned.is_some() => Box::new(NullSigner::new(&signer)),
+        _ => utils::signer_from_path(&keypair_path)?,
+    };
+    let nonce_account = cli
+        .nonce_account
+        .as_deref()
+        .map(|address| address.parse::<Pubkey>())
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("Invalid nonce account: {}", e))?;
+
+    // Parse cluster
+    let cluster = settings::parse_cluster(&cluster, cli.ws_url.or(profile.ws_url).as_deref())?;
+
+    // Parse program ID
+    let program_id = program_id.parse::<Pubkey>()
+        .map_err(|e| anyhow::anyhow!("Invalid program ID: {}", e))?;
+
+    // Create client
+    let compute_budget = ComputeBudget {
+        priority_fee: cli.priority_fee,
+        auto_priority_fee: cli.auto_priority_fee,
+        compute_limit: cli.compute_limit,
+    };
+    let retry_policy = RetryPolicy {
+        retries: cli.retries,
+        backoff: std::time::Duration::from_millis(cli.retry_backoff_ms),
+    };
+    let dry_run = cli.dry_run;
+    let new_client = |signer: Rc<Box<dyn Signer>>| {
+        let client = Client::new_with_options(cluster.clone(), signer, commitment);
+        VotingClient::new(client, program_id)
+            .with_ws_url(cluster.ws_url())
+            .with_dry_run(dry_run)
+            .with_compute_budget(compute_budget)
+            .with_retry_policy(retry_policy)
+    };
+    let payer = Rc::new(payer);
+    let mut voting_client = new_client(payer.clone());
+    if cli.export_unsigned.is_some() {
+        voting_client = voting_client.offline(nonce_account);
+    }
+    if let Some(multisig) = &cli.multisig {
+        let multisig = multisig
+            .parse::<Pubkey>()
+            .map_err(|e| anyhow::anyhow!("Invalid multisig address: {}", e))?;
+        voting_client = voting_client.with_multisig(multisig);
+        if !cli.json {
+            println!("Proposing through Squads multisig {} as its vault {}", multisig, voting_client.payer_pubkey());
+            println!("  Transactions below take effect once members approve and execute them in Squads");
+        }
+    }
+    let json = cli.json;
+    let yes = cli.yes;
+
+    // Execute command
+    match cli.command {
+        Commands::InitializeConfig { poll_creation_fee } => {
+            println!("Initializing config...");
+            let signature = voting_client.initialize_config(poll_creation_fee)?;
+            println!("✓ Config initialized successfully!");
+            println!("  Admin: {}", voting_client.payer_pubkey());
+            println!("  Poll creation fee: {} lamports", poll_creation_fee);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::GetConfig => {
+            let config = voting_client.get_config()?;
+            let (treasury, balance) = voting_client.get_treasury()?;
+            println!("\n=== Config ===");
+            println!("Admin: {}", config.admin);
+            println!("Poll creation fee: {} lamports", config.poll_creation_fee);
+            if config.max_poll_duration > 0 {
+                println!("Maximum poll duration: {} seconds", config.max_poll_duration);
+            }
+            if config.restrict_creators {
+                println!("Poll creation: creator pass holders only");
+            }
+            if config.max_polls_per_window > 0 {
+                println!(
+                    "Creation limit: {} polls per {} seconds per creator",
+                    config.max_polls_per_window, config.creation_window
+                );
+            }
+            if config.council_threshold > 0 {
+                println!(
+                    "Council: {} of {} approvals required",
+                    config.council_threshold,
+                    config.council.len()
+                );
+                for member in &config.council {
+                    println!("  • {}", member);
+                }
+            }
+            println!("Treasury balance: {} lamports", balance);
+            println!("Total fees collected: {} lamports", treasury.total_collected);
+        }
+        Commands::WithdrawTreasury { amount, recipient } => {
+            let recipient_pubkey = if let Some(recipient_str) = recipient {
+                recipient_str.parse::<Pubkey>()?
+            } else {
+                voting_client.payer_pubkey()
+            };
+
+            let (_, balance) = voting_client.get_treasury()?;
+            let changes = [
+                format!("Move {} SOL out of the treasury to {}", lamports_to_sol(amount), recipient_pubkey),
+                format!("The treasury keeps {} SOL", lamports_to_sol(balance.saturating_sub(amount))),
+            ];
+            let send = |client: &VotingClient<_>| client.withdraw_treasury(amount, recipient_pubkey);
+            if !confirm_irreversible(&mut voting_client, yes, &changes, send)? {
+                println!("Withdrawal cancelled");
+                return Ok(());
+            }
+
+            println!("Withdrawing {} lamports from the treasury...", amount);
+            let signature = voting_client.withdraw_treasury(amount, recipient_pubkey)?;
+            println!("✓ Withdrawal successful!");
+            println!("  Recipient: {}", recipient_pubkey);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::SetFeeSchedule { poll_creation_fee } => {
+            let previous = voting_client.get_config()?.poll_creation_fee;
+            let signature = voting_client.set_fee_schedule(poll_creation_fee)?;
+            println!(
+                "✓ Poll creation fee changed from {} to {} lamports",
+                previous, poll_creation_fee
+            );
+            println!("  Transaction: {}", signature);
+        }
+        Commands::TreasuryHistory { limit } => {
+            let withdrawals = voting_client.get_treasury_withdrawals(limit)?;
+            if withdrawals.is_empty() {
+                println!("No withdrawals in the latest {} treasury transactions", limit);
+                return Ok(());
+            }
+
+            println!("\n=== Treasury Withdrawals ===");
+            for (withdrawal, signature, block_time) in withdrawals {
+                let when = block_time
+                    .and_then(|time| chrono::DateTime::from_timestamp(time, 0))
+                    .map(|time| time.to_string())
+                    .unwrap_or_else(|| "unknown time".to_string());
+                println!("\n{}: {} lamports to {}", when, withdrawal.amount, withdrawal.recipient);
+                println!("  Admin: {}", withdrawal.admin);
+                println!("  Left in treasury: {} lamports", withdrawal.remaining);
+                println!("  Transaction: {}", signature);
+            }
+        }
+        Commands::SetCouncil { members, threshold } => {
+            let members = members
+                .iter()
+                .map(|member| member.parse::<Pubkey>())
+                .collect::<Result<Vec<_>, _>>()?;
+            println!("Setting a council of {} members...", members.len());
+            let signature = voting_client.set_council(members, threshold)?;
+            println!("✓ Council updated, {} approvals required per poll", threshold);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::SetCreatorAllowlist { enabled } => {
+            let signature = voting_client.set_creator_allowlist(enabled)?;
+            if enabled {
+                println!("✓ Creator allowlist enabled, only pass holders can create polls");
+            } else {
+                println!("✓ Creator allowlist disabled, anyone can create polls");
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::SetMaxPollDuration { seconds } => {
+            let signature = voting_client.set_max_poll_duration(seconds)?;
+            if seconds > 0 {
+                println!("✓ Polls may now run for at most {} seconds", seconds);
+            } else {
+                println!("✓ Poll duration limit removed");
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::SetCreationLimit { max_polls, window } => {
+            let signature = voting_client.set_creation_limit(max_polls, window)?;
+            if max_polls > 0 {
+                println!("✓ Each creator may now create {} polls per {} seconds", max_polls, window);
+            } else {
+                println!("✓ Poll creation limit removed");
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::CreatorStats { creator, top } => {
+            let creator = match creator {
+                Some(creator) => creator.parse::<Pubkey>()?,
+                None => voting_client.payer_pubkey(),
+            };
+            let polls: Vec<Poll> = voting_client
+                .get_polls_by_creator(&creator)?
+                .into_iter()
+                .map(|(_, poll)| poll)
+                .collect();
+            let mut candidates: Vec<(u64, Candidate)> = Vec::new();
+            for poll in &polls {
+                candidates.extend(
+                    voting_client
+                        .get_candidates(poll.poll_id)?
+                        .into_iter()
+                        .map(|(_, candidate)| (poll.poll_id, candidate)),
+                );
+            }
+
+            let now = chrono::Utc::now().timestamp();
+            let total_votes: u64 = polls.iter().map(|poll| poll.total_votes).sum();
+            // Turnout is only known for polls with registered voters
+            let turnouts: Vec<f64> = polls
+                .iter()
+                .filter(|poll| poll.eligible_voters > 0)
+                .map(|poll| poll.total_votes as f64 * 100.0 / poll.eligible_voters as f64)
+                .collect();
+            let average_turnout = (!turnouts.is_empty()).then(|| turnouts.iter().sum::<f64>() / turnouts.len() as f64);
+            // Weighted tallies are scaled to whole votes so candidates of different polls compare
+            let scale = |poll_id: u64| {
+                polls.iter().find(|poll| poll.poll_id == poll_id).map_or(1, |poll| poll.vote_scale())
+            };
+            candidates.sort_by(|(a_poll, a), (b_poll, b)| {
+                let a_votes = a.votes as f64 / scale(*a_poll) as f64;
+                let b_votes = b.votes as f64 / scale(*b_poll) as f64;
+                b_votes.total_cmp(&a_votes).then_with(|| a.name.cmp(&b.name))
+            });
+            candidates.truncate(top);
+
+            if json {
+                let output = serde_json::json!({
+                    "creator": creator.to_string(),
+                    "total_polls": polls.len(),
+                    "total_votes": total_votes,
+                    "average_turnout_percent": average_turnout,
+                    "polls": polls
+                        .iter()
+                        .map(|poll| serde_json::json!({
+                            "poll_id": poll.poll_id,
+                            "question": poll.question,
+                            "status": poll.status_at(now),
+                            "total_votes": poll.total_votes,
+                            "eligible_voters": poll.eligible_voters,
+                        }))
+                        .collect::<Vec<_>>(),
+                    "top_candidates": candidates
+                        .iter()
+                        .map(|(poll_id, candidate)| serde_json::json!({
+                            "poll_id": poll_id,
+                            "name": candidate.name,
+                            "votes": candidate.votes,
+                        }))
+                        .collect::<Vec<_>>(),
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
+            println!("\n=== Creator {} ===", creator);
+            if polls.is_empty() {
+                println!("No polls created");
+                return Ok(());
+            }
+            println!("Polls created: {}", polls.len());
+            println!("Votes received: {}", total_votes);
+            println!("Average votes per poll: {:.1}", total_votes as f64 / polls.len() as f64);
+            match average_turnout {
+                Some(turnout) => println!("Average turnout: {:.2}% across {} polls", turnout, turnouts.len()),
+                None => println!("Average turnout: unknown, no poll has registered voters"),
+            }
+            let config = voting_client.get_config()?;
+            if config.max_polls_per_window > 0 {
+                let recent = voting_client.get_creator_stats(&creator).map_or(0, |stats| {
+                    stats
+                        .recent_polls
+                        .iter()
+                        .filter(|created_at| now - **created_at < config.creation_window)
+                        .count()
+                });
+                println!(
+                    "In the last {} seconds: {} of {} allowed",
+                    config.creation_window, recent, config.max_polls_per_window
+                );
+            }
+
+            println!("\nPolls:");
+            for poll in &polls {
+                println!(
+                    "  [{}] {} ({:?}): {} votes",
+                    poll.poll_id,
+                    poll.question,
+                    poll.status_at(now),
+                    poll.total_votes
+                );
+            }
+            if !candidates.is_empty() {
+                println!("\nMost-voted candidates:");
+                for (poll_id, candidate) in &candidates {
+                    println!(
+                        "  • {} (poll {}): {} votes",
+                        candidate.name,
+                        poll_id,
+                        utils::format_votes(candidate.votes, scale(*poll_id))
+                    );
+                }
+            }
+        }
+        Commands::GrantCreator { creator } => {
+            let creator = creator.parse::<Pubkey>()?;
+            let signature = voting_client.grant_creator_pass(creator)?;
+            println!("✓ Creator pass granted to {}", creator);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::RevokeCreator { creator } => {
+            let creator = creator.parse::<Pubkey>()?;
+            let signature = voting_client.revoke_creator_pass(creator)?;
+            println!("✓ Creator pass revoked from {}", creator);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::ProposePoll { poll_id, question } => {
+            println!("Proposing poll {} for council approval...", poll_id);
+            let signature = voting_client.propose_poll(poll_id, question)?;
+            println!("✓ Poll proposed, waiting for council approvals");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::CancelProposal { poll_id } => {
+            println!("Cancelling the proposal for poll {}...", poll_id);
+            let signature = voting_client.cancel_poll_proposal(poll_id)?;
+            println!("✓ Proposal cancelled, rent returned to the proposer");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::ApprovePoll { poll_id } => {
+            println!("Approving poll {}...", poll_id);
+            let (signature, approvals) = voting_client.approve_poll_creation(poll_id)?;
+            println!("✓ Poll approved ({} approvals so far)", approvals);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::InitializePoll {
+            poll_id,
+            question,
             description,
             start_time,
             end_time,
+            duration,
+            candidate_deposit,
+            deposit_threshold,
+            bounty,
+            category,
+            tags,
+            metadata_uri,
+            metadata_file,
+            receipt_nft_tree,
+            min_voter_balance,
+            min_stake,
+            weight_decay_bps,
+            max_votes_per_slot,
+            allow_cpi,
+            shielded_key,
+            stake_weighted,
+            gauge_budget,
+            conviction_mint,
+            governance_realm,
+            governance_mint,
+            governance_program,
+            eligibility_program,
+            attestation_issuer,
+            attestation_schema,
+            attestation_program,
+            unlisted,
+            restricted,
+            weight_oracle,
+            max_weight_age,
+            open_candidacy,
+            max_extension,
+            vote_fee,
+            vote_fee_mint,
+            vote_fee_recipient,
         } => {
+            let receipt_nft_tree = receipt_nft_tree.map(|tree| tree.parse::<Pubkey>()).transpose()?;
+            let conviction_mint = conviction_mint.map(|mint| mint.parse::<Pubkey>()).transpose()?;
+            let governance_realm = governance_realm.map(|realm| realm.parse::<Pubkey>()).transpose()?;
+            let governance_mint = governance_mint.map(|mint| mint.parse::<Pubkey>()).transpose()?;
+            let eligibility_program = eligibility_program.map(|program| program.parse::<Pubkey>()).transpose()?;
+            let attestation_issuer = attestation_issuer.map(|issuer| issuer.parse::<Pubkey>()).transpose()?;
+            let attestation_schema = attestation_schema.map(|schema| schema.parse::<Pubkey>()).transpose()?;
+            let weight_oracle = weight_oracle.map(|oracle| oracle.parse::<Pubkey>()).transpose()?;
+            let vote_fee_mint = vote_fee_mint.map(|mint| mint.parse::<Pubkey>()).transpose()?;
+            let vote_fee_recipient = vote_fee_recipient.map(|recipient| recipient.parse::<Pubkey>()).transpose()?;
+
+            // `now` comes from the cluster, which the program checks the start time against
+            let start_time = if start_time.eq_ignore_ascii_case("now") {
+                voting_client.cluster_time()?
+            } else {
+                start_time
+                    .parse::<i64>()
+                    .map_err(|_| anyhow::anyhow!("Invalid start time {}; use a Unix timestamp or now", start_time))?
+            };
+            let end_time = match (end_time, duration) {
+                (Some(end_time), _) => end_time,
+                (None, Some(duration)) => start_time
+                    .checked_add(duration)
+                    .ok_or_else(|| anyhow::anyhow!("The duration is too long"))?,
+                (None, None) => unreachable!("clap requires an end time or a duration"),
+            };
+
+            // Commit to the metadata contents so readers can detect later edits
+            let metadata_hash = match (&metadata_file, metadata_uri.is_empty()) {
+                (Some(path), _) => utils::sha256(&std::fs::read(path)?),
+                (None, false) => utils::sha256(&utils::fetch_uri(&metadata_uri)?),
+                (None, true) => [0u8; 32],
+            };
+
             println!("Initializing poll {}...", poll_id);
+            let options = PollOptions {
+                candidate_deposit_lamports: candidate_deposit,
+                deposit_refund_threshold: deposit_threshold,
+                bounty_lamports: bounty,
+                category,
+                tags,
+                metadata_uri: metadata_uri.clone(),
+                metadata_hash,
+                mint_receipt_nft: receipt_nft_tree.is_some(),
+                receipt_nft_tree: receipt_nft_tree.unwrap_or_default(),
+                min_voter_lamports: min_voter_balance,
+                min_stake_lamports: min_stake,
+                weight_decay: weight_decay_bps
+                    .map(|end_bps| WeightDecay::Linear { end_bps })
+                    .unwrap_or_default(),
+                max_votes_per_slot,
+                allow_cpi,
+                shielded_key: shielded_key
+                    .map(|path| shielded::ElGamalKeypair::read_from_file(&path))
+                    .transpose()?
+                    .map(|keypair| keypair.public_key())
+                    .unwrap_or_default(),
+                stake_weighted,
+                gauge_budget,
+                conviction_mint: conviction_mint.unwrap_or_default(),
+                governance_program: if governance_realm.is_some() {
+                    governance_program.parse::<Pubkey>()?
+                } else {
+                    Pubkey::default()
+                },
+                governance_realm: governance_realm.unwrap_or_default(),
+                governance_mint: governance_mint.unwrap_or_default(),
+                eligibility_program,
+                attestation_program: if attestation_issuer.is_some() {
+                    attestation_program.parse::<Pubkey>()?
+                } else {
+                    Pubkey::default()
+                },
+                attestation_issuer: attestation_issuer.unwrap_or_default(),
+                attestation_schema: attestation_schema.unwrap_or_default(),
+                visibility: if unlisted { PollVisibility::Unlisted } else { PollVisibility::Public },
+                access_restricted: restricted,
+                weight_oracle: weight_oracle.unwrap_or_default(),
+                max_weight_age,
+                vote_fee,
+                vote_fee_mint: vote_fee_mint.unwrap_or_default(),
+                vote_fee_recipient: vote_fee_recipient.unwrap_or_default(),
+                max_extension,
+                open_candidacy,
+            };
             let signature = voting_client.initialize_poll(
                 poll_id,
                 question.clone(),
                 description.clone(),
                 start_time,
                 end_time,
+                options,
             )?;
             println!("✓ Poll created successfully!");
             println!("  Poll ID: {}", poll_id);
@@ -140,64 +1667,1832 @@ fn main() -> Result<()> {
             println!("  Description: {}", description);
             println!("  Start: {}", chrono::DateTime::from_timestamp(start_time, 0).unwrap());
             println!("  End: {}", chrono::DateTime::from_timestamp(end_time, 0).unwrap());
+            if !metadata_uri.is_empty() {
+                println!("  Metadata: {} (sha256 {})", metadata_uri, utils::to_hex(&metadata_hash));
+            }
+            if let Some(tree) = receipt_nft_tree {
+                println!("  Receipt NFTs: minted from tree {}", tree);
+                println!("  Run `delegate-nft-tree {} {}` before voting opens", poll_id, tree);
+            }
             println!("  Transaction: {}", signature);
         }
+        Commands::UpdatePoll {
+            poll_id,
+            question,
+            description,
+            end_time,
+        } => {
+            if question.is_none() && description.is_none() && end_time.is_none() {
+                return Err(anyhow::anyhow!(
+                    "Nothing to update: pass --question, --description and/or --end-time"
+                ));
+            }
+
+            println!("Updating poll {}...", poll_id);
+            let signature =
+                voting_client.update_poll(poll_id, question.clone(), description.clone(), end_time)?;
+            println!("✓ Poll updated successfully!");
+            if let Some(question) = question {
+                println!("  Question: {}", question);
+            }
+            if let Some(description) = description {
+                println!("  Description: {}", description);
+            }
+            if let Some(end_time) = end_time {
+                println!("  End: {}", chrono::DateTime::from_timestamp(end_time, 0).unwrap());
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::ActivatePoll {
+            poll_id,
+            auto_finalize,
+            thread_fee,
+        } => {
+            println!("Activating poll {}...", poll_id);
+            let signature = voting_client.activate_poll(poll_id, auto_finalize.then_some(thread_fee))?;
+            println!("✓ Poll activated, the candidate list is now frozen");
+            if auto_finalize {
+                println!("  The poll will finalize itself shortly after it ends");
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::CancelFinalization { poll_id } => {
+            let changes = [
+                format!("Delete the finalization thread of poll {}", poll_id),
+                "The poll won't be finalized automatically; run `finalize-poll` once it ends".to_string(),
+            ];
+            let send = |client: &VotingClient<_>| client.cancel_finalization(poll_id);
+            if !confirm_irreversible(&mut voting_client, yes, &changes, send)? {
+                println!("Cancellation aborted");
+                return Ok(());
+            }
+            println!("Deleting the finalization thread of poll {}...", poll_id);
+            let signature = voting_client.cancel_finalization(poll_id)?;
+            println!("✓ Finalization thread deleted, its balance was returned to you");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::RequestRandomness { poll_id, randomness } => {
+            let randomness = randomness.parse::<Pubkey>()?;
+            let signature = voting_client.request_randomness(poll_id, randomness)?;
+            println!("✓ Poll {} awaits randomness from {}", poll_id, randomness);
+            println!("  Reveal it with Switchboard, then run `apply-randomness {}`", poll_id);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::CancelRandomness { poll_id } => {
+            let signature = voting_client.cancel_randomness(poll_id)?;
+            println!("✓ Randomness request of poll {} cancelled", poll_id);
+            println!("  Request a new one with `request-randomness {}`", poll_id);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::ApplyRandomness { poll_id } => {
+            let signature = voting_client.apply_randomness(poll_id)?;
+            let poll = voting_client.get_poll(poll_id)?;
+            println!("✓ Randomness applied to poll {}", poll_id);
+            if poll.tie_break_seed != [0u8; 32] {
+                println!("  Tie-break seed: {}", utils::to_hex(&poll.tie_break_seed));
+            } else {
+                println!("  Ballot order seed: {}", utils::to_hex(&poll.ballot_order_seed));
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::SetCandidateManager { poll_id, manager } => {
+            let manager = manager.map(|wallet| wallet.parse::<Pubkey>()).transpose()?;
+            let signature = voting_client.set_candidate_manager(poll_id, manager)?;
+            match manager {
+                Some(manager) => println!("✓ {} may now add candidates to poll {}", manager, poll_id),
+                None => println!("✓ Candidate manager of poll {} removed", poll_id),
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::GrantAccess { poll_id, voters } => {
+            let voters = utils::load_voter_list(&voters)?;
+            println!("Granting {} voters access to poll {}...", voters.len(), poll_id);
+            let signatures = voting_client.grant_access(poll_id, &voters)?;
+            println!("✓ {} voters granted access in {} transactions", voters.len(), signatures.len());
+            for signature in signatures {
+                println!("  Transaction: {}", signature);
+            }
+        }
+        Commands::RevokeAccess { poll_id, voter } => {
+            let voter = voter.parse::<Pubkey>()?;
+            let signature = voting_client.revoke_access(poll_id, voter)?;
+            println!("✓ {} may no longer vote in poll {}", voter, poll_id);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::PublishWeight { voter, weight } => {
+            let voter = voter.parse::<Pubkey>()?;
+            let signature = voting_client.publish_weight(voter, weight)?;
+            println!("✓ Weight of {} set to {}", voter, weight);
+            println!("  Oracle: {}", voting_client.payer_pubkey());
+            println!("  Transaction: {}", signature);
+        }
+        Commands::PausePoll { poll_id } => {
+            println!("Pausing poll {}...", poll_id);
+            let signature = voting_client.pause_poll(poll_id)?;
+            println!("✓ Poll paused, votes will be rejected until it is resumed");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::ResumePoll { poll_id, extend } => {
+            println!("Resuming poll {}...", poll_id);
+            let signature = voting_client.resume_poll(poll_id, extend)?;
+            let poll = voting_client.get_poll(poll_id)?;
+            println!("✓ Poll resumed!");
+            println!("  End: {}", chrono::DateTime::from_timestamp(poll.end_time, 0).unwrap());
+            println!("  Transaction: {}", signature);
+        }
+        Commands::Create => {
+            wizard::run(&voting_client)?;
+        }
+        Commands::ExportPoll { poll_id, out } => {
+            let poll = voting_client.get_poll(poll_id)?;
+            let candidates: Vec<Candidate> =
+                voting_client.get_candidates(poll_id)?.into_iter().map(|(_, candidate)| candidate).collect();
+            let export = PollExport::new(cluster.url(), &program_id, &poll, &candidates);
+            let contents = serde_json::to_string_pretty(&export)?;
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, contents).map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path, e))?;
+                    println!("✓ Poll {} and its {} candidates exported to {}", poll_id, candidates.len(), path);
+                    println!("  Re-create it with: voting-cli import-poll {} --cluster <CLUSTER>", path);
+                }
+                None => println!("{}", contents),
+            }
+        }
+        Commands::ImportPoll {
+            path,
+            poll_id,
+            start,
+            activate,
+        } => {
+            let contents =
+                std::fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path, e))?;
+            let export: PollExport =
+                serde_json::from_str(&contents).map_err(|e| anyhow::anyhow!("Invalid poll export {}: {}", path, e))?;
+            let options = export.options(&program_id)?;
+            let candidates = export.candidate_entries();
+            for entry in &candidates {
+                utils::check_candidate_entry(entry)
+                    .map_err(|reason| anyhow::anyhow!("Candidate {} can't be added: {}", entry.name, reason))?;
+            }
+
+            let poll_id = poll_id.unwrap_or(export.poll_id);
+            if voting_client.get_poll(poll_id).is_ok() {
+                return Err(anyhow::anyhow!(
+                    "Poll {} already exists on this cluster; choose another ID with --poll-id",
+                    poll_id
+                ));
+            }
+            // Mints, programs and the like are cluster-specific, so a devnet address rarely exists on mainnet
+            let mut missing = Vec::new();
+            for (field, address) in export.required_accounts()? {
+                if voting_client.get_account_data_at_slot(&address)?.1.is_none() {
+                    missing.push(format!("{} {}", field, address));
+                }
+            }
+            if !missing.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "The poll refers to accounts that don't exist on this cluster: {}; \
+                     replace them in {} with their counterparts here",
+                    missing.join(", "),
+                    path
+                ));
+            }
+
+            let start_time = if start.eq_ignore_ascii_case("now") {
+                voting_client.cluster_time()?
+            } else {
+                start
+                    .parse::<i64>()
+                    .map_err(|_| anyhow::anyhow!("Invalid start time {}; use a Unix timestamp or now", start))?
+            };
+            let end_time = export
+                .end_time
+                .checked_sub(export.start_time)
+                .and_then(|duration| start_time.checked_add(duration))
+                .ok_or_else(|| anyhow::anyhow!("The exported schedule is invalid"))?;
+
+            println!("Importing poll {} from {} as poll {}...", export.poll_id, export.cluster, poll_id);
+            let signature = voting_client.initialize_poll(
+                poll_id,
+                export.question.clone(),
+                export.description.clone(),
+                start_time,
+                end_time,
+                options,
+            )?;
+            println!("✓ Poll {} created", poll_id);
+            println!("  Question: {}", export.question);
+            println!("  Start: {}", chrono::DateTime::from_timestamp(start_time, 0).unwrap());
+            println!("  End: {}", chrono::DateTime::from_timestamp(end_time, 0).unwrap());
+            println!("  Transaction: {}", signature);
+
+            for outcome in voting_client.add_candidates(poll_id, &candidates)? {
+                let signature = outcome?;
+                println!("✓ Candidates added");
+                println!("  Transaction: {}", signature);
+            }
+
+            if activate {
+                let signature = voting_client.activate_poll(poll_id, None)?;
+                println!("✓ Poll activated, the candidate list is now frozen");
+                println!("  Transaction: {}", signature);
+            }
+        }
         Commands::AddCandidate {
             poll_id,
             name,
             party,
+            payout_wallet,
+            metadata_uri,
         } => {
+            let payout_wallet = if let Some(wallet_str) = payout_wallet {
+                wallet_str.parse::<Pubkey>()?
+            } else {
+                voting_client.payer_pubkey()
+            };
+
             println!("Adding candidate to poll {}...", poll_id);
-            let signature = voting_client.add_candidate(poll_id, name.clone(), party.clone())?;
+            let signature = voting_client.add_candidate(
+                poll_id,
+                name.clone(),
+                party.clone(),
+                payout_wallet,
+                metadata_uri.clone(),
+            )?;
             println!("✓ Candidate added successfully!");
             println!("  Name: {}", name);
             println!("  Party: {}", party);
+            println!("  Payout wallet: {}", payout_wallet);
+            if !metadata_uri.is_empty() {
+                println!("  Metadata: {}", metadata_uri);
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::AddCandidates { poll_id, file } => {
+            let rows = utils::load_candidate_rows(&file)?;
+            let mut names: Vec<String> = voting_client
+                .get_candidates(poll_id)?
+                .into_iter()
+                .map(|(_, candidate)| candidate.name)
+                .collect();
+
+            // Rows that would fail on-chain are reported rather than sent
+            let mut outcomes: Vec<(usize, String, std::result::Result<String, String>)> = Vec::new();
+            let mut accepted = Vec::new();
+            for (row, entry) in rows {
+                let checked = utils::check_candidate_entry(&entry).and_then(|()| {
+                    if names.contains(&entry.name) {
+                        Err("poll already has a candidate with this name".to_string())
+                    } else {
+                        Ok(())
+                    }
+                });
+                match checked {
+                    Ok(()) => {
+                        names.push(entry.name.clone());
+                        accepted.push((row, entry));
+                    }
+                    Err(reason) => outcomes.push((row, entry.name, Err(reason))),
+                }
+            }
+
+            println!("Adding {} candidates to poll {}...", accepted.len(), poll_id);
+            let entries: Vec<_> = accepted.iter().map(|(_, entry)| entry.clone()).collect();
+            let batches = voting_client.add_candidates(poll_id, &entries)?;
+            for (batch, outcome) in accepted.chunks(utils::CANDIDATE_BATCH_SIZE).zip(batches) {
+                for (row, entry) in batch {
+                    let outcome = match &outcome {
+                        Ok(signature) => Ok(signature.to_string()),
+                        Err(error) => Err(error.to_string()),
+                    };
+                    outcomes.push((*row, entry.name.clone(), outcome));
+                }
+            }
+
+            outcomes.sort_by_key(|(row, _, _)| *row);
+            for (row, name, outcome) in &outcomes {
+                match outcome {
+                    Ok(signature) => println!("  ✓ Row {}: {} ({})", row, name, signature),
+                    Err(reason) => println!("  ✗ Row {}: {}: {}", row, name, reason),
+                }
+            }
+            let added = outcomes.iter().filter(|(_, _, outcome)| outcome.is_ok()).count();
+            println!("{} candidates added, {} failed", added, outcomes.len() - added);
+        }
+        Commands::UpdateCandidateMetadata {
+            poll_id,
+            candidate_name,
+            metadata_uri,
+        } => {
+            println!("Updating metadata for {} in poll {}...", candidate_name, poll_id);
+            let signature =
+                voting_client.update_candidate_metadata(poll_id, &candidate_name, metadata_uri.clone())?;
+            println!("✓ Candidate metadata updated!");
+            println!("  Metadata: {}", metadata_uri);
             println!("  Transaction: {}", signature);
         }
         Commands::Vote {
             poll_id,
             candidate_name,
+            index,
+            stake_account,
+            verifier_accounts,
+            sponsor,
+            offline,
+            out,
         } => {
+            let stake_account = stake_account.map(|address| address.parse::<Pubkey>()).transpose()?;
+            let sponsor = sponsor
+                .map(|path| {
+                    let path = shellexpand::tilde(&path).to_string();
+                    read_keypair_file(&path)
+                        .map_err(|e| anyhow::anyhow!("Failed to read sponsor keypair from {}: {}", path, e))
+                })
+                .transpose()?;
+            let poll = voting_client.get_poll(poll_id)?;
+            let candidate_name = match candidate_name {
+                Some(name) => confirm_candidate_name(&voting_client, poll_id, name)?,
+                None => choose_candidate(&voting_client, &poll, index)?,
+            };
+            if let Some(fee) = poll.describe_vote_fee() {
+                let payer = sponsor.as_ref().map_or(voting_client.payer_pubkey(), |sponsor| sponsor.pubkey());
+                println!(
+                    "Poll {} charges {} per vote, paid by {} to {}",
+                    poll_id, fee, payer, poll.vote_fee_recipient
+                );
+                if !yes && !utils::confirm("Cast the vote and pay the fee?")? {
+                    println!("Vote cancelled");
+                    return Ok(());
+                }
+            }
+            if offline {
+                let nonce_account = nonce_account
+                    .ok_or_else(|| anyhow::anyhow!("--offline needs a durable nonce; pass --nonce-account"))?;
+                // Clap requires --out alongside --offline
+                let out = out.unwrap_or_default();
+                let outcome = new_client(payer.clone()).offline(Some(nonce_account)).vote(
+                    poll_id,
+                    candidate_name.clone(),
+                    stake_account,
+                    verifier_accounts,
+                    sponsor.as_ref(),
+                );
+                let mut unsent = into_unsent(outcome)?;
+                let signers: Vec<&dyn Signer> = std::iter::once(&**payer as &dyn Signer)
+                    .chain(sponsor.as_ref().map(|sponsor| sponsor as &dyn Signer))
+                    .collect();
+                utils::sign_transaction(&mut unsent.transaction, &signers)?;
+                let missing = utils::missing_signers(&unsent.transaction);
+                if !missing.is_empty() {
+                    let missing: Vec<String> = missing.iter().map(|pubkey| pubkey.to_string()).collect();
+                    return Err(anyhow::anyhow!(
+                        "The transaction also needs signatures from {}",
+                        missing.join(", ")
+                    ));
+                }
+                utils::write_transaction_file(&out, &unsent.transaction, unsent.nonce_account.as_ref())?;
+                println!("✓ Signed vote for {} written to {}", candidate_name, out);
+                println!("  Nonce account: {}", nonce_account);
+                println!("  Signature: {}", unsent.transaction.signatures[0]);
+                println!("  Broadcast it from a connected machine with: voting-cli submit {}", out);
+                return Ok(());
+            }
             println!("Voting for {} in poll {}...", candidate_name, poll_id);
-            let signature = voting_client.vote(poll_id, candidate_name.clone())?;
+            let signature = voting_client.vote(
+                poll_id,
+                candidate_name.clone(),
+                stake_account,
+                verifier_accounts,
+                sponsor.as_ref(),
+            )?;
+            println!("✓ Vote cast successfully!");
+            println!("  Candidate: {}", candidate_name);
+            if let Some(sponsor) = &sponsor {
+                println!("  Sponsored by: {}", sponsor.pubkey());
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::Submit { path } => {
+            let (transaction, _) = utils::read_transaction_file(&path)?;
+            let missing = utils::missing_signers(&transaction);
+            if !missing.is_empty() {
+                let missing: Vec<String> = missing.iter().map(|pubkey| pubkey.to_string()).collect();
+                return Err(anyhow::anyhow!("{} still needs signatures from {}", path, missing.join(", ")));
+            }
+            println!("Submitting {}...", path);
+            let signature = voting_client.send_signed(&transaction)?;
+            println!("✓ Transaction submitted successfully!");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::Sign { path, out } => {
+            let (mut transaction, nonce_account) = utils::read_transaction_file(&path)?;
+            let signer = payer.pubkey();
+            if !utils::missing_signers(&transaction).contains(&signer) {
+                return Err(anyhow::anyhow!("{} has no pending signature from {}", path, signer));
+            }
+            utils::sign_transaction(&mut transaction, &[&**payer])?;
+            let out = out.unwrap_or_else(|| format!("{}.sig", signer));
+            utils::write_transaction_file(&out, &transaction, nonce_account.as_ref())?;
+            println!("✓ Signed {} as {}", path, signer);
+            println!("  Signature file: {}", out);
+            let missing = utils::missing_signers(&transaction);
+            if !missing.is_empty() {
+                let missing: Vec<String> = missing.iter().map(|pubkey| pubkey.to_string()).collect();
+                println!("  Still needs: {}", missing.join(", "));
+            }
+        }
+        Commands::CombineAndSend { paths } => {
+            let copies = paths
+                .iter()
+                .map(|path| utils::read_transaction_file(path).map(|(transaction, _)| transaction))
+                .collect::<Result<Vec<_>>>()?;
+            let transaction = utils::combine_signatures(copies)?;
+            let missing = utils::missing_signers(&transaction);
+            if !missing.is_empty() {
+                let missing: Vec<String> = missing.iter().map(|pubkey| pubkey.to_string()).collect();
+                return Err(anyhow::anyhow!("Signatures are still missing from {}", missing.join(", ")));
+            }
+            println!("Sending the transaction signed in {} files...", paths.len());
+            let signature = voting_client.send_signed(&transaction)?;
+            println!("✓ Transaction submitted successfully!");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::SimulateVotes {
+            poll_id,
+            keypairs,
+            distribution,
+        } => {
+            let directory = shellexpand::tilde(&keypairs).to_string();
+            let mut paths: Vec<std::path::PathBuf> = std::fs::read_dir(&directory)
+                .map_err(|e| anyhow::anyhow!("Failed to read keypair directory {}: {}", directory, e))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+                .collect();
+            paths.sort();
+            if paths.is_empty() {
+                return Err(anyhow::anyhow!("No keypair files (*.json) in {}", directory));
+            }
+
+            let poll = voting_client.get_poll(poll_id)?;
+            let candidates = voting_client.get_candidates(poll_id)?;
+            for (name, _) in &distribution {
+                if !candidates.iter().any(|(_, candidate)| &candidate.name == name) {
+                    return Err(anyhow::anyhow!("{} is not a candidate in poll {}", name, poll_id));
+                }
+            }
+            if let Some(fee) = poll.describe_vote_fee() {
+                println!("Poll {} charges {} per vote, paid by each voter", poll_id, fee);
+            }
+
+            let weights: Vec<u16> = distribution.iter().map(|(_, weight)| *weight).collect();
+            // Each voter's ballot, as an index into the distribution
+            let ballots: Vec<usize> = utils::split_by_weight(&weights, paths.len())
+                .into_iter()
+                .enumerate()
+                .flat_map(|(index, count)| std::iter::repeat(index).take(count))
+                .collect();
+            // A dry run submits nothing, airdrops included
+            let fund = matches!(cluster, Cluster::Localnet) && !dry_run;
+
+            println!("Casting {} votes in poll {}...", paths.len(), poll_id);
+            let mut cast = vec![0usize; distribution.len()];
+            let mut failed = 0;
+            for (path, ballot) in paths.iter().zip(ballots) {
+                let candidate_name = &distribution[ballot].0;
+                let voter = match read_keypair_file(path) {
+                    Ok(voter) => voter,
+                    Err(e) => {
+                        println!("✗ {}: {}", path.display(), e);
+                        failed += 1;
+                        continue;
+                    }
+                };
+                let voter_pubkey = voter.pubkey();
+                let voter_client = new_client(Rc::new(Box::new(voter) as Box<dyn Signer>));
+                let outcome = voter_client.payer_balance().and_then(|balance| {
+                    // Airdrops only exist on test clusters, so other voters must already hold SOL
+                    if fund && balance < LAMPORTS_PER_SOL / 10 {
+                        voter_client.request_airdrop(&voter_pubkey, LAMPORTS_PER_SOL)?;
+                    }
+                    voter_client.vote(poll_id, candidate_name.clone(), None, Vec::new(), None)
+                });
+                match outcome {
+                    Ok(signature) => {
+                        println!("✓ {} → {} ({})", voter_pubkey, candidate_name, signature);
+                        cast[ballot] += 1;
+                    }
+                    Err(e) if e.is::<DryRun>() => return Err(e),
+                    Err(e) => {
+                        println!("✗ {} → {}: {}", voter_pubkey, candidate_name, e);
+                        failed += 1;
+                    }
+                }
+            }
+
+            println!("\n{} votes cast, {} failed", paths.len() - failed, failed);
+            for ((name, _), count) in distribution.iter().zip(cast) {
+                println!("  {}: {}", name, count);
+            }
+        }
+        Commands::VoteGauge { poll_id, allocations } => {
+            println!("Casting gauge vote in poll {}...", poll_id);
+            let signature = voting_client.vote_gauge(poll_id, &allocations)?;
+            println!("✓ Gauge vote cast successfully!");
+            for (name, points) in &allocations {
+                println!("  {}: {} points", name, points);
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::VoteConviction {
+            poll_id,
+            candidate_name,
+            amount,
+            lock,
+        } => {
+            let candidate_name = confirm_candidate_name(&voting_client, poll_id, candidate_name)?;
+            println!("Locking {} tokens behind {} in poll {}...", amount, candidate_name, poll_id);
+            let signature = voting_client.vote_conviction(poll_id, &candidate_name, amount, lock)?;
+            println!("✓ Conviction vote cast successfully!");
+            println!("  Weight: {} ({}x)", amount * lock.multiplier(), lock.multiplier());
+            println!("  Transaction: {}", signature);
+        }
+        Commands::Unlock { poll_id } => {
+            println!("Unlocking conviction tokens for poll {}...", poll_id);
+            let (signature, conviction_lock) = voting_client.unlock(poll_id)?;
+            println!("✓ Tokens unlocked successfully!");
+            println!("  Amount: {}", conviction_lock.amount);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::DelegateNftTree { poll_id, tree } => {
+            let tree_pubkey = tree.parse::<Pubkey>()?;
+            println!("Delegating tree {} to poll {}...", tree_pubkey, poll_id);
+            let signature = voting_client.delegate_receipt_nft_tree(poll_id, tree_pubkey)?;
+            println!("✓ Poll can now mint receipt NFTs from the tree");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::InitReceiptTree {
+            poll_id,
+            max_depth,
+            max_buffer_size,
+        } => {
+            println!("Creating receipt tree for poll {}...", poll_id);
+            let (signature, tree) =
+                voting_client.init_receipt_tree(poll_id, max_depth, max_buffer_size)?;
+            println!("✓ Receipt tree attached!");
+            println!("  Tree: {}", tree);
+            println!("  Capacity: {} voters", 1u64 << max_depth);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::RegisterVoter { poll_id, voter } => {
+            let voter_pubkey = voter.parse::<Pubkey>()?;
+            println!("Registering {} in poll {}...", voter_pubkey, poll_id);
+            let signature = voting_client.register_compressed_voter(poll_id, voter_pubkey)?;
+            println!("✓ Voter registered!");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::VoteCompressed {
+            poll_id,
+            candidate_name,
+        } => {
+            let candidate_name = confirm_candidate_name(&voting_client, poll_id, candidate_name)?;
+            println!("Voting for {} in poll {} (compressed receipt)...", candidate_name, poll_id);
+            let signature = voting_client.vote_compressed(poll_id, candidate_name.clone())?;
+            println!("✓ Vote cast successfully!");
+            println!("  Candidate: {}", candidate_name);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::FinalizePoll { poll_id } => {
+            let poll = voting_client.get_poll(poll_id)?;
+            let mut changes = vec![format!("Finalize poll {}: {}", poll_id, poll.question)];
+            let candidates = voting_client.get_candidates(poll_id)?;
+            if let Some(top) = candidates.iter().map(|(_, candidate)| candidate.votes).max() {
+                let leaders: Vec<&str> = candidates
+                    .iter()
+                    .filter(|(_, candidate)| candidate.votes == top)
+                    .map(|(_, candidate)| candidate.name.as_str())
+                    .collect();
+                changes.push(format!("Record {} as the winner with {} votes", leaders.join(" and "), top));
+            }
+            changes.push("The results can't change afterwards".to_string());
+            let send = |client: &VotingClient<_>| client.finalize_poll(poll_id);
+            if !confirm_irreversible(&mut voting_client, yes, &changes, send)? {
+                println!("Finalization cancelled");
+                return Ok(());
+            }
+            println!("Finalizing poll {}...", poll_id);
+            let signature = voting_client.finalize_poll(poll_id)?;
+            println!("✓ Poll finalized successfully!");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::FundRewardPool {
+            poll_id,
+            mint,
+            amount,
+        } => {
+            let mint_pubkey = mint.parse::<Pubkey>()?;
+            println!("Funding reward pool for poll {}...", poll_id);
+            let signature = voting_client.fund_reward_pool(poll_id, mint_pubkey, amount)?;
+            println!("✓ Reward pool funded successfully!");
+            println!("  Mint: {}", mint_pubkey);
+            println!("  Amount: {}", amount);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::ClaimReward { poll_id } => {
+            println!("Claiming reward for poll {}...", poll_id);
+            let (signature, share) = voting_client.claim_reward(poll_id)?;
+            println!("✓ Reward claimed successfully!");
+            println!("  Amount: {}", share);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::RegisterAuditors { poll_id, auditors } => {
+            let auditors = auditors
+                .iter()
+                .map(|auditor| auditor.parse::<Pubkey>())
+                .collect::<Result<Vec<_>, _>>()?;
+            println!("Registering {} auditors for poll {}...", auditors.len(), poll_id);
+            let signature = voting_client.register_auditors(poll_id, auditors)?;
+            println!("✓ Auditors registered!");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::AttestResult { poll_id } => {
+            println!("Attesting the result of poll {}...", poll_id);
+            let signature = voting_client.attest_result(poll_id)?;
+            println!("✓ Result attested!");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::VerifyResult { poll_id } => {
+            let result = voting_client.get_poll_result(poll_id)?;
+            println!("\n=== Poll {} Result Attestations ===", poll_id);
+            if result.attestation_count > 0 {
+                println!("Attested winner: {}", result.winner);
+                println!("Attested total votes: {}", result.total_votes);
+            }
+            println!(
+                "Attestations: {} of {} auditors",
+                result.attestation_count,
+                result.auditors.len()
+            );
+            for (index, auditor) in result.auditors.iter().enumerate() {
+                let status = if result.has_attested(index) { "✓ attested" } else { "pending" };
+                println!("  • {} {}", auditor, status);
+            }
+        }
+        Commands::ClaimBounty { poll_id } => {
+            println!("Claiming bounty for poll {}...", poll_id);
+            let (signature, recipient) = voting_client.claim_bounty(poll_id)?;
+            println!("✓ Bounty paid successfully!");
+            println!("  Recipient: {}", recipient);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::BadgeMetadata { poll_id, out } => {
+            let poll = voting_client.get_poll(poll_id)?;
+            if !poll.finalized || poll.winner == Pubkey::default() {
+                return Err(anyhow::anyhow!("Poll {} has not been decided yet", poll_id));
+            }
+            let winner = voting_client
+                .get_candidates(poll_id)?
+                .into_iter()
+                .find(|(address, _)| *address == poll.winner)
+                .map(|(_, candidate)| candidate)
+                .ok_or_else(|| anyhow::anyhow!("Winner account {} not found", poll.winner))?;
+
+            let metadata = serde_json::to_string_pretty(&utils::winner_badge_metadata(&poll, &winner))?;
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, metadata)?;
+                    println!("✓ Badge metadata written to {}", path);
+                    println!("  Host it and pass its URI to mint-winner-badge");
+                }
+                None => println!("{}", metadata),
+            }
+        }
+        Commands::MintWinnerBadge { poll_id, uri } => {
+            println!("Minting winner badge for poll {}...", poll_id);
+            let (signature, mint) = voting_client.mint_winner_badge(poll_id, uri)?;
+            println!("✓ Winner badge minted!");
+            println!("  Mint: {}", mint);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::CreateProposal {
+            poll_id,
+            yes_candidate,
+            instruction,
+            threshold_bps,
+        } => {
+            let instruction = utils::load_proposal_instruction(&instruction)?;
+            println!("Attaching proposal to poll {}...", poll_id);
+            let (signature, authority) =
+                voting_client.create_proposal(poll_id, &yes_candidate, threshold_bps, instruction)?;
+            println!("✓ Proposal attached!");
+            println!("  Passes if {} gets over {:.2}% of ballots", yes_candidate, threshold_bps as f64 / 100.0);
+            println!("  Poll authority (signs the instruction): {}", authority);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::ExecuteProposal { poll_id } => {
+            println!("Executing proposal of poll {}...", poll_id);
+            let signature = voting_client.execute_proposal(poll_id)?;
+            println!("✓ Proposal executed!");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::ClaimDeposit {
+            poll_id,
+            candidate_name,
+        } => {
+            println!("Claiming deposit for {} in poll {}...", candidate_name, poll_id);
+            let (signature, refunded) = voting_client.claim_deposit(poll_id, &candidate_name)?;
+            if refunded {
+                println!("✓ Deposit refunded to the candidate's depositor");
+            } else {
+                println!("✓ Deposit forfeited to the treasury");
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::CrankReceipts { poll_id } => {
+            println!("Closing expired receipts for poll {}...", poll_id);
+            let closed = voting_client.crank_close_receipts(poll_id)?;
+            for (receipt, signature) in &closed {
+                println!("  • {} ({})", receipt, signature);
+            }
+            println!("✓ Closed {} receipts", closed.len());
+        }
+        Commands::ArchivePoll { poll_id } => {
+            let candidates = voting_client.get_candidates(poll_id)?;
+            let (archive, _) = utils::get_poll_archive_address(&program_id, poll_id);
+            let changes = [
+                format!(
+                    "Close poll {} and its {} candidate accounts, returning their rent to you",
+                    poll_id,
+                    candidates.len()
+                ),
+                format!("Only the summary in archive {} remains", archive),
+            ];
+            let send = |client: &VotingClient<_>| client.archive_poll(poll_id);
+            if !confirm_irreversible(&mut voting_client, yes, &changes, send)? {
+                println!("Archiving cancelled");
+                return Ok(());
+            }
+            println!("Archiving poll {}...", poll_id);
+            let (signature, archive) = voting_client.archive_poll(poll_id)?;
+            println!("✓ Poll archived!");
+            println!("  Archive: {}", archive);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::GenerateShieldedKey { output } => {
+            let keypair = shielded::ElGamalKeypair::generate();
+            keypair.write_to_file(&output)?;
+            println!("✓ Shielded key written to {}", output);
+            println!("  Keep it secret: it decrypts every total of polls created with it");
+        }
+        Commands::PublishShieldedResults { poll_id, key } => {
+            let keypair = shielded::ElGamalKeypair::read_from_file(&key)?;
+            println!("Decrypting and publishing results of poll {}...", poll_id);
+            let signature = voting_client.publish_shielded_results(poll_id, &keypair)?;
+            println!("✓ Results published! The poll can now be finalized.");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::GetArchive { poll_id } => {
+            let archive = voting_client.get_poll_archive(poll_id)?;
+            println!("\n=== Archived Poll {} ===", archive.poll_id);
+            println!("Question: {}", archive.question);
+            println!("Creator: {}", archive.creator);
+            println!("Ended: {}", chrono::DateTime::from_timestamp(archive.end_time, 0).unwrap());
+            println!("Archived: {}", chrono::DateTime::from_timestamp(archive.archived_at, 0).unwrap());
+            println!("\nCandidates:");
+            for candidate in &archive.candidates {
+                println!(
+                    "  • {}: {} ballots{}",
+                    candidate.name,
+                    candidate.raw_votes,
+                    if candidate.is_winner { " (winner)" } else { "" }
+                );
+            }
+            println!("\nTotal votes cast: {}", archive.total_votes);
+        }
+        Commands::MigrateAccount { address } => {
+            let account = address.parse::<Pubkey>()?;
+            println!("Migrating account {}...", account);
+            let signature = voting_client.migrate_account(account)?;
+            println!("✓ Account migrated to the current layout!");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::CreateBallot { manifest } => {
+            let manifest = utils::load_ballot_manifest(&manifest)?;
+            println!("Creating ballot {} with {} races...", manifest.ballot_id, manifest.races.len());
+
+            for race in &manifest.races {
+                voting_client.initialize_poll(
+                    race.poll_id,
+                    race.question.clone(),
+                    race.description.clone(),
+                    manifest.start_time,
+                    manifest.end_time,
+                    PollOptions::default(),
+                )?;
+                println!("  ✓ Poll {} created: {}", race.poll_id, race.question);
+
+                for candidate in &race.candidates {
+                    voting_client.add_candidate(
+                        race.poll_id,
+                        candidate.name.clone(),
+                        candidate.party.clone(),
+                        voting_client.payer_pubkey(),
+                        String::new(),
+                    )?;
+                    println!("    ✓ Candidate added: {}", candidate.name);
+                }
+
+                voting_client.activate_poll(race.poll_id, None)?;
+                println!("    ✓ Poll {} activated", race.poll_id);
+            }
+
+            let poll_ids: Vec<u64> = manifest.races.iter().map(|race| race.poll_id).collect();
+            let signature =
+                voting_client.initialize_ballot(manifest.ballot_id, manifest.title.clone(), &poll_ids)?;
+            println!("✓ Ballot created successfully!");
+            println!("  Ballot ID: {}", manifest.ballot_id);
+            println!("  Title: {}", manifest.title);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::VoteBallot { ballot_id, choices } => {
+            println!("Casting ballot {}...", ballot_id);
+            let signature = voting_client.vote_ballot(ballot_id, &choices)?;
+            println!("✓ Ballot cast successfully!");
+            for (race, choice) in choices.iter().enumerate() {
+                println!("  Race {}: {}", race + 1, choice);
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::VoteAll { ballot } => {
+            let votes = utils::load_vote_selections(&ballot)?;
+            let mut charges_fees = false;
+            for selection in &votes {
+                let poll = voting_client.get_poll(selection.poll_id)?;
+                if let Some(fee) = poll.describe_vote_fee() {
+                    println!(
+                        "Poll {} charges {} per vote, paid to {}",
+                        selection.poll_id, fee, poll.vote_fee_recipient
+                    );
+                    charges_fees = true;
+                }
+            }
+            if charges_fees && !yes && !utils::confirm("Cast the votes and pay the fees?")? {
+                println!("Votes cancelled");
+                return Ok(());
+            }
+            println!("Casting {} votes...", votes.len());
+            let signature = voting_client.vote_all(&votes)?;
+            println!("✓ Votes cast successfully!");
+            for selection in &votes {
+                println!("  Poll {}: {}", selection.poll_id, selection.candidate);
+            }
+            println!("  Transaction: {}", signature);
+        }
+        Commands::CreateSession {
+            ballot_id,
+            out,
+            slots,
+            fund,
+        } => {
+            let session_key = Keypair::new();
+            let out = shellexpand::tilde(&out).to_string();
+            write_keypair_file(&session_key, &out)
+                .map_err(|e| anyhow::anyhow!("Failed to write session keypair to {}: {}", out, e))?;
+            println!("Creating session for ballot {}...", ballot_id);
+            let (signature, expiry_slot) =
+                voting_client.create_session(ballot_id, session_key.pubkey(), slots, fund)?;
+            println!("✓ Session created!");
+            println!("  Session key: {} (saved to {})", session_key.pubkey(), out);
+            println!("  Expires at slot: {}", expiry_slot);
+            println!("  Transaction: {}", signature);
+        }
+        Commands::RevokeSession { ballot_id } => {
+            println!("Revoking session for ballot {}...", ballot_id);
+            let signature = voting_client.revoke_session(ballot_id)?;
+            println!("✓ Session revoked!");
+            println!("  Transaction: {}", signature);
+        }
+        Commands::VoteSession {
+            ballot_id,
+            poll_id,
+            candidate_name,
+            voter,
+        } => {
+            let voter = voter.parse::<Pubkey>()?;
+            let candidate_name = confirm_candidate_name(&voting_client, poll_id, candidate_name)?;
+            println!("Voting for {} in poll {} on behalf of {}...", candidate_name, poll_id, voter);
+            let signature = voting_client.vote_with_session(ballot_id, voter, poll_id, &candidate_name)?;
             println!("✓ Vote cast successfully!");
             println!("  Candidate: {}", candidate_name);
             println!("  Transaction: {}", signature);
         }
+        Commands::ListPolls {
+            tag,
+            category,
+            creator,
+            active,
+            limit,
+        } => {
+            let now = chrono::Utc::now().timestamp();
+            let filter = PollFilter {
+                creator: creator.map(|creator| creator.parse::<Pubkey>()).transpose()?,
+                category,
+                tag,
+                active_at: active.then_some(now),
+            };
+            let mut polls = voting_client.get_all_polls(&filter)?;
+            // Polls come sorted by ID, so the highest IDs are the most recent
+            if let Some(limit) = limit {
+                polls.drain(..polls.len().saturating_sub(limit));
+            }
+
+            if json {
+                let output = polls
+                    .iter()
+                    .map(|(_, poll)| utils::poll_to_json(poll, now))
+                    .collect::<Result<Vec<_>>>()?;
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
+            println!("\n=== Polls ({}) ===", polls.len());
+            for (_, poll) in &polls {
+                println!("  #{} {} [{:?}]", poll.poll_id, poll.question, poll.status_at(now));
+                println!(
+                    "      {} → {}  Candidates: {}",
+                    chrono::DateTime::from_timestamp(poll.start_time, 0).unwrap(),
+                    chrono::DateTime::from_timestamp(poll.end_time, 0).unwrap(),
+                    poll.candidate_count
+                );
+                if !poll.category.is_empty() || !poll.tags.is_empty() {
+                    println!("      Category: {}  Tags: {}", poll.category, poll.tags.join(", "));
+                }
+            }
+        }
+        Commands::VerifyMetadata { poll_id } => {
+            let poll = voting_client.get_poll(poll_id)?;
+            if poll.metadata_uri.is_empty() {
+                return Err(anyhow::anyhow!("Poll {} has no off-chain metadata", poll_id));
+            }
+
+            println!("Fetching {}...", poll.metadata_uri);
+            let actual = utils::sha256(&utils::fetch_uri(&poll.metadata_uri)?);
+            println!("  On-chain hash: {}", utils::to_hex(&poll.metadata_hash));
+            println!("  Fetched hash:  {}", utils::to_hex(&actual));
+
+            if actual == poll.metadata_hash {
+                println!("✓ Metadata matches the on-chain commitment");
+            } else {
+                return Err(anyhow::anyhow!(
+                    "Metadata for poll {} does not match the on-chain commitment",
+                    poll_id
+                ));
+            }
+        }
         Commands::GetPoll { poll_id } => {
+            if json {
+                let poll = voting_client.get_poll(poll_id)?;
+                let output = utils::poll_to_json(&poll, chrono::Utc::now().timestamp())?;
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
             println!("Fetching poll {}...", poll_id);
             let poll = voting_client.get_poll(poll_id)?;
             println!("\n=== Poll {} ===", poll_id);
             println!("Creator: {}", poll.creator);
+            if let Some(manager) = poll.candidate_manager {
+                println!("Candidate manager: {}", manager);
+            }
+            if poll.open_candidacy {
+                println!("Candidacy: open to self-nomination");
+            }
             println!("Question: {}", poll.question);
             println!("Description: {}", poll.description);
             println!("Start: {}", chrono::DateTime::from_timestamp(poll.start_time, 0).unwrap());
             println!("End: {}", chrono::DateTime::from_timestamp(poll.end_time, 0).unwrap());
             println!("Candidates: {}", poll.candidate_count);
+            println!("Status: {:?}", poll.status_at(chrono::Utc::now().timestamp()));
+            if !poll.category.is_empty() {
+                println!("Category: {}", poll.category);
+            }
+            if !poll.tags.is_empty() {
+                println!("Tags: {}", poll.tags.join(", "));
+            }
+            if !poll.metadata_uri.is_empty() {
+                println!("Metadata: {} (sha256 {})", poll.metadata_uri, utils::to_hex(&poll.metadata_hash));
+            }
+            if poll.candidate_deposit_lamports > 0 {
+                println!(
+                    "Candidate deposit: {} lamports (refunded at {} votes)",
+                    poll.candidate_deposit_lamports, poll.deposit_refund_threshold
+                );
+            }
+            println!("Total votes: {}", poll.total_votes);
+            if poll.first_vote_at > 0 {
+                println!("First vote: {}", chrono::DateTime::from_timestamp(poll.first_vote_at, 0).unwrap());
+                println!("Last vote: {}", chrono::DateTime::from_timestamp(poll.last_vote_at, 0).unwrap());
+                let hours = (poll.last_vote_at - poll.first_vote_at) as f64 / 3600.0;
+                if hours > 0.0 {
+                    println!("Pace: {:.1} votes per hour", poll.total_votes as f64 / hours);
+                }
+            }
+            if poll.eligible_voters > 0 {
+                println!("Registered voters: {}", poll.eligible_voters);
+                if poll.finalized {
+                    println!("Turnout: {:.2}%", poll.turnout_bps as f64 / 100.0);
+                }
+            }
+            if poll.min_voter_lamports > 0 {
+                println!("Minimum voter balance: {} lamports", poll.min_voter_lamports);
+            }
+            if poll.min_stake_lamports > 0 {
+                println!("Minimum voter stake: {} lamports", poll.min_stake_lamports);
+            }
+            if poll.max_votes_per_slot > 0 {
+                println!("Rate limit: {} votes per slot", poll.max_votes_per_slot);
+            }
+            if poll.allow_cpi {
+                println!("Votes through other programs: allowed");
+            }
+            if poll.visibility == PollVisibility::Unlisted {
+                println!("Visibility: unlisted");
+            }
+            if poll.access_restricted {
+                println!("Access: restricted to granted voters");
+            }
+            if let Some(fee) = poll.describe_vote_fee() {
+                println!("Vote fee: {} to {}", fee, poll.vote_fee_recipient);
+            }
+            if poll.max_extension > 0 {
+                println!(
+                    "End time extensions: up to {} seconds once voting starts ({} used)",
+                    poll.max_extension, poll.extended_by
+                );
+            }
+            if poll.is_oracle_weighted() {
+                print!("Vote weight: published by oracle {}", poll.weight_oracle);
+                if poll.max_weight_age > 0 {
+                    print!(" (at most {} seconds old)", poll.max_weight_age);
+                }
+                println!();
+            }
+            if poll.is_shielded() {
+                println!(
+                    "Ballots: shielded (results {})",
+                    if poll.shielded_published { "published" } else { "pending" }
+                );
+            }
+            if poll.gauge_budget > 0 {
+                println!("Ballots: gauge, {} points split per voter", poll.gauge_budget);
+            }
+            if poll.governance_realm != Pubkey::default() {
+                println!(
+                    "Vote weight: {} tokens deposited in realm {}",
+                    poll.governance_mint, poll.governance_realm
+                );
+            }
+            if let Some(program) = poll.eligibility_program {
+                println!("Eligibility: verified by program {}", program);
+            }
+            if poll.attestation_issuer != Pubkey::default() {
+                println!(
+                    "Eligibility: attestation from issuer {} with schema {}",
+                    poll.attestation_issuer, poll.attestation_schema
+                );
+            }
+            if poll.finalize_thread != Pubkey::default() && !poll.finalized {
+                println!("Finalization: automatic, by thread {}", poll.finalize_thread);
+            }
+            if poll.randomness_pending {
+                println!("Randomness: awaiting reveal of {}", poll.randomness_account);
+            }
+            if poll.ballot_order_seed != [0u8; 32] {
+                println!("Ballot order: shuffled by seed {}", utils::to_hex(&poll.ballot_order_seed));
+            }
+            if poll.tie_break_seed != [0u8; 32] {
+                println!("Tie-break: decided by seed {}", utils::to_hex(&poll.tie_break_seed));
+            }
+            if poll.conviction_mint != Pubkey::default() {
+                println!("Ballots: conviction, locking tokens of {} (1x/2x/4x)", poll.conviction_mint);
+            }
+            if poll.stake_weighted {
+                if poll.status == PollStatus::Draft {
+                    println!("Vote weight: delegated stake, snapshotted when voting opens");
+                } else {
+                    println!("Vote weight: delegated stake effective at epoch {}", poll.snapshot_epoch);
+                }
+            }
+            if let WeightDecay::Linear { end_bps } = poll.weight_decay {
+                println!("Vote weight: decays linearly from 100% to {:.2}%", end_bps as f64 / 100.0);
+            }
+            if poll.paused {
+                println!(
+                    "Paused since: {}",
+                    chrono::DateTime::from_timestamp(poll.paused_at, 0).unwrap()
+                );
+            }
+            if poll.reward_pool_amount > 0 {
+                println!(
+                    "Reward pool: {} tokens of mint {}",
+                    poll.reward_pool_amount, poll.reward_mint
+                );
+            }
+            if poll.bounty_lamports > 0 {
+                println!(
+                    "Winner bounty: {} lamports{}",
+                    poll.bounty_lamports,
+                    if poll.bounty_claimed { " (claimed)" } else { "" }
+                );
+            }
+            println!("Finalized: {}", poll.finalized);
+            if poll.finalized && poll.winner != Pubkey::default() {
+                println!("Winner: {}", poll.winner);
+            }
         }
-        Commands::GetResults { poll_id } => {
-            println!("Fetching results for poll {}...", poll_id);
-            let (poll, candidates) = voting_client.get_poll_results(poll_id)?;
-            
-            println!("\n=== Poll {} Results ===", poll_id);
+        Commands::ListVoters { poll_id, count_only } => {
+            let mut receipts = voting_client.get_receipts_for_poll(poll_id)?;
+            receipts.retain(|(_, receipt)| receipt.has_voted);
+            if count_only {
+                if json {
+                    println!("{}", serde_json::json!({ "poll_id": poll_id, "count": receipts.len() }));
+                } else {
+                    println!("{}", receipts.len());
+                }
+                return Ok(());
+            }
+
+            receipts.sort_by_key(|(_, receipt)| receipt.voter);
+            if json {
+                let output: Vec<_> = receipts.iter().map(|(_, receipt)| receipt).collect();
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+            println!("\n=== Poll {} Voters ({}) ===", poll_id, receipts.len());
+            for (_, receipt) in &receipts {
+                if receipt.weight == 1 {
+                    println!("  • {}", receipt.voter);
+                } else {
+                    println!("  • {} (weight {})", receipt.voter, receipt.weight);
+                }
+            }
+        }
+        Commands::ListCandidates { poll_id } => {
+            let poll = voting_client.get_poll(poll_id)?;
+            let mut candidates = voting_client.get_candidates(poll_id)?;
+            candidates.sort_by_key(|(_, candidate)| candidate.index);
+
+            if json {
+                let output = candidates
+                    .iter()
+                    .map(|(address, candidate)| -> Result<serde_json::Value> {
+                        let mut value = serde_json::to_value(candidate)?;
+                        value["address"] = serde_json::json!(address.to_string());
+                        Ok(value)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
+            println!("\n=== Poll {} Candidates ({}) ===", poll_id, candidates.len());
+            println!("Status: {:?}", poll.status_at(chrono::Utc::now().timestamp()));
+            for (address, candidate) in &candidates {
+                println!("  [{}] {} ({})", candidate.index, candidate.name, candidate.party);
+                println!("      Address: {}", address);
+                println!("      Payout wallet: {}", candidate.payout_wallet);
+            }
+
+            // Every ballot slot up to the poll's count should be filled
+            if candidates.len() as u64 != poll.candidate_count {
+                println!(
+                    "\n✗ The poll counts {} candidates but {} were found",
+                    poll.candidate_count,
+                    candidates.len()
+                );
+            }
+        }
+        Commands::GetResults { poll_id, order, format, chart } => {
+            let quiet = json || chart || format != ResultsFormat::Table;
+            if !quiet {
+                println!("Fetching results for poll {}...", poll_id);
+            }
+            let (poll, candidates) = voting_client.get_poll_results(poll_id, order)?;
+
+            if json {
+                let output = serde_json::json!({
+                    "poll_id": poll.poll_id,
+                    "question": poll.question,
+                    "description": poll.description,
+                    "total_votes": poll.total_votes,
+                    "first_vote_at": poll.first_vote_at,
+                    "last_vote_at": poll.last_vote_at,
+                    "vote_scale": poll.vote_scale(),
+                    "candidates": candidates
+                        .iter()
+                        .map(|c| serde_json::json!({
+                            "name": c.name,
+                            "party": c.party,
+                            "votes": c.votes,
+                            "raw_votes": c.raw_votes,
+                            "index": c.index,
+                            "is_winner": c.is_winner,
+                            "metadata_uri": c.metadata_uri,
+                        }))
+                        .collect::<Vec<_>>(),
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else if chart {
+                let width = crossterm::terminal::size().map_or(80, |(columns, _)| columns as usize);
+                print!("{}", utils::results_chart(&poll, &candidates, width));
+            } else if format == ResultsFormat::Csv {
+                print!("{}", utils::results_csv(&poll, &candidates));
+            } else if format == ResultsFormat::Md {
+                print!("{}", utils::results_markdown(&poll, &candidates));
+            } else {
+                println!("\n=== Poll {} Results ===", poll_id);
+                println!("Question: {}", poll.question);
+                println!("Description: {}", poll.description);
+                println!("\nCandidates:");
+
+                // Decaying polls tally weighted votes in basis points of a full vote,
+                // stake-weighted polls tally lamports
+                let scale = poll.vote_scale() as f64;
+                let unit = if poll.stake_weighted { "SOL of stake" } else { "weighted votes" };
+                let mut total_votes = 0u64;
+                for candidate in &candidates {
+                    if poll.gauge_budget > 0 {
+                        println!(
+                            "  • {} ({}): {} points from {} voters",
+                            candidate.name, candidate.party, candidate.votes, candidate.raw_votes
+                        );
+                    } else if poll.is_oracle_weighted() {
+                        println!(
+                            "  • {} ({}): {} weight from {} voters",
+                            candidate.name, candidate.party, candidate.votes, candidate.raw_votes
+                        );
+                    } else if poll.vote_scale() == 1 {
+                        println!("  • {} ({}): {} votes", candidate.name, candidate.party, candidate.votes);
+                    } else {
+                        println!(
+                            "  • {} ({}): {:.2} {} ({} cast)",
+                            candidate.name,
+                            candidate.party,
+                            candidate.votes as f64 / scale,
+                            unit,
+                            candidate.raw_votes
+                        );
+                    }
+                    total_votes += candidate.raw_votes;
+                }
+
+                println!("\nTotal votes cast: {}", total_votes);
+
+                // Finalized polls flag every candidate tied for first place
+                let winners: Vec<&str> = candidates.iter().filter(|c| c.is_winner).map(|c| c.name.as_str()).collect();
+                if !winners.is_empty() {
+                    println!("Winner(s): {}", winners.join(", "));
+                } else if !candidates.is_empty() {
+                    let winner = candidates.iter().max_by_key(|c| c.votes).unwrap();
+                    println!(
+                        "Leading candidate: {} with {} votes",
+                        winner.name,
+                        winner.votes as f64 / scale
+                    );
+                }
+            }
+        }
+        Commands::Tui => {
+            tui::run(&voting_client)?;
+        }
+        Commands::WatchResults { poll_id } => {
+            let names: std::collections::HashMap<Pubkey, String> = voting_client
+                .get_candidates(poll_id)?
+                .into_iter()
+                .map(|(address, candidate)| (address, candidate.name))
+                .collect();
+
+            println!("Watching poll {} for votes (Ctrl+C to stop)...", poll_id);
+            voting_client.watch_tallies(poll_id, move |update, slot| {
+                let name = names.get(&update.candidate).cloned().unwrap_or_else(|| update.candidate.to_string());
+                println!(
+                    "[slot {}] {}: {} votes ({} ballots total)",
+                    slot, name, update.votes, update.total_votes
+                );
+            })?;
+        }
+        Commands::Compare { poll_id_a, poll_id_b } => {
+            let (poll_a, candidates_a) = voting_client.get_poll_results(poll_id_a, CandidateOrder::Votes)?;
+            let (poll_b, candidates_b) = voting_client.get_poll_results(poll_id_b, CandidateOrder::Votes)?;
+            let rows = utils::compare_candidates(&poll_a, &candidates_a, &poll_b, &candidates_b);
+            // Turnout is only known for polls with registered voters
+            let turnout = |poll: &Poll| {
+                (poll.eligible_voters > 0).then(|| poll.total_votes as f64 * 100.0 / poll.eligible_voters as f64)
+            };
+
+            if json {
+                let summary = |poll: &Poll| {
+                    serde_json::json!({
+                        "poll_id": poll.poll_id,
+                        "question": poll.question,
+                        "total_votes": poll.total_votes,
+                        "eligible_voters": poll.eligible_voters,
+                        "turnout_percent": turnout(poll),
+                    })
+                };
+                let output = serde_json::json!({
+                    "poll_a": summary(&poll_a),
+                    "poll_b": summary(&poll_b),
+                    "candidates": rows,
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
+            println!("\n=== Poll {} vs Poll {} ===", poll_id_a, poll_id_b);
+            println!("Poll {}: {}", poll_id_a, poll_a.question);
+            println!("Poll {}: {}", poll_id_b, poll_b.question);
+            if poll_a.vote_scale() != poll_b.vote_scale() || poll_a.stake_weighted != poll_b.stake_weighted {
+                println!("  The polls weigh votes differently, so compare shares rather than vote counts");
+            }
+
+            // Whole votes print as such, weighted tallies to two decimals
+            let decimals = |votes: f64| if votes.fract() == 0.0 { 0 } else { 2 };
+            let result = |votes: Option<f64>, share: Option<f64>| match (votes, share) {
+                (Some(votes), Some(share)) => format!("{:.*} ({:.1}%)", decimals(votes), votes, share),
+                _ => "—".to_string(),
+            };
+            let width = rows.iter().map(|row| row.name.chars().count()).max().unwrap_or(0).max(9);
+            let label_a = format!("Poll {}", poll_id_a);
+            let label_b = format!("Poll {}", poll_id_b);
+            println!("\n{:<width$}  {:>18}  {:>18}  Change", "Candidate", label_a, label_b);
+            for row in &rows {
+                let change = match (row.votes_delta(), row.share_delta()) {
+                    (Some(votes), Some(share)) => format!("{:+.*} votes, {:+.1} pts", decimals(votes), votes, share),
+                    _ if row.votes_a.is_none() => "new".to_string(),
+                    _ => "not on the ballot".to_string(),
+                };
+                println!(
+                    "{:<width$}  {:>18}  {:>18}  {}",
+                    row.name,
+                    result(row.votes_a, row.share_a),
+                    result(row.votes_b, row.share_b),
+                    change
+                );
+            }
+
+            let votes_change = poll_b.total_votes as i128 - poll_a.total_votes as i128;
+            print!("\nVotes cast: {} → {} ({:+})", poll_a.total_votes, poll_b.total_votes, votes_change);
+            if poll_a.total_votes > 0 {
+                print!(", {:+.1}%", votes_change as f64 * 100.0 / poll_a.total_votes as f64);
+            }
+            println!();
+            match (turnout(&poll_a), turnout(&poll_b)) {
+                (Some(a), Some(b)) => println!("Turnout: {:.2}% → {:.2}% ({:+.2} pts)", a, b, b - a),
+                (None, None) => {}
+                _ => println!("Turnout: only one of the polls has registered voters"),
+            }
+        }
+        Commands::TurnoutChart { poll_id } => {
+            let poll = voting_client.get_poll(poll_id)?;
+            let histogram = &poll.vote_histogram;
+            // Hours after the last vote are left off; the final bucket also holds later hours
+            let hours = histogram.iter().rposition(|&count| count > 0).map_or(0, |last| last + 1);
+            if hours == 0 {
+                println!("No votes in poll {} yet", poll_id);
+            } else {
+                let peak = histogram.iter().copied().max().unwrap_or(1) as usize;
+                let width = 50;
+                println!("\n=== Poll {} Votes per Hour ===", poll_id);
+                for (hour, &count) in histogram[..hours].iter().enumerate() {
+                    let label = if hour + 1 == histogram.len() { format!("{}h+", hour) } else { format!("{}h", hour) };
+                    let bar = "█".repeat(count as usize * width / peak);
+                    println!("{:>5} | {} {}", label, bar, count);
+                }
+            }
+        }
+        Commands::AuditWeights { poll_id } => {
+            println!("Auditing receipt weights for poll {}...", poll_id);
+            let total = voting_client.sum_receipt_weights(poll_id)?;
+            println!("✓ Receipt weights match candidate totals: {} votes", total);
+        }
+        Commands::When { poll_id, on, command } => {
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            let mut command_args = forwarded_options(&args);
+            command_args.extend(command);
+            // Check the command now, so a mistake shows before the wait rather than after it
+            Cli::try_parse_from(std::iter::once("voting-cli").chain(command_args.iter().map(String::as_str)))
+                .map_err(|e| anyhow::anyhow!("Invalid command to run: {}", e))?;
+
+            let (verb, past) = match on {
+                PollTransition::Start => ("open", "opened"),
+                PollTransition::End => ("close", "closed"),
+            };
+            // The program judges the voting period by the cluster clock, so the wait does too. The
+            // poll is read again each time, since its end moves when it's extended or resumed.
+            let mut announced = None;
+            let reached_at = loop {
+                let poll = voting_client.get_poll(poll_id)?;
+                let now = voting_client.cluster_time()?;
+                let status = poll.status_at(now);
+                if status == PollStatus::Draft && now > poll.end_time {
+                    return Err(anyhow::anyhow!(
+                        "Poll {} is still a draft past its end time, so it won't open",
+                        poll_id
+                    ));
+                }
+                let (reached, at) = match on {
+                    PollTransition::Start => (status != PollStatus::Draft && now >= poll.start_time, poll.start_time),
+                    PollTransition::End => (status == PollStatus::Closed, poll.end_time),
+                };
+                if reached {
+                    break now;
+                }
+
+                if !json && announced != Some((status, at)) {
+                    if status == PollStatus::Draft {
+                        println!("Poll {} is a draft; waiting for its creator to activate it...", poll_id);
+                    } else {
+                        println!(
+                            "Waiting for poll {} to {} at {} (in {})...",
+                            poll_id,
+                            verb,
+                            chrono::DateTime::from_timestamp(at, 0).unwrap(),
+                            utils::format_countdown(at - now)
+                        );
+                    }
+                    announced = Some((status, at));
+                }
+                // Check at least once a minute, and again right after the expected moment
+                let wait = if status == PollStatus::Draft { 60 } else { (at - now).clamp(1, 60) };
+                std::thread::sleep(std::time::Duration::from_secs(wait as u64));
+            };
+
+            if !json {
+                println!(
+                    "✓ Poll {} {} (cluster time {})",
+                    poll_id,
+                    past,
+                    chrono::DateTime::from_timestamp(reached_at, 0).unwrap()
+                );
+                println!("Running: voting-cli {}", command_args.join(" "));
+            }
+            let status = std::process::Command::new(std::env::current_exe()?).args(&command_args).status()?;
+            if !status.success() {
+                return Err(anyhow::anyhow!("The command failed ({})", status));
+            }
+        }
+        Commands::Status { poll_id } => {
+            let poll = voting_client.get_poll(poll_id)?;
+            // The program judges the voting period by the cluster clock, not this machine's
+            let cluster_now = voting_client.cluster_time()?;
+            let local_now = chrono::Utc::now().timestamp();
+            let drift = local_now - cluster_now;
+            let (phase, next) = match poll.status_at(cluster_now) {
+                PollStatus::Draft => ("draft", None),
+                PollStatus::Closed => ("ended", None),
+                PollStatus::Active if cluster_now < poll.start_time => ("pending", Some(("Opens", poll.start_time))),
+                PollStatus::Active if poll.paused => ("paused", Some(("Closes", poll.end_time))),
+                PollStatus::Active => ("active", Some(("Closes", poll.end_time))),
+            };
+
+            if json {
+                let output = serde_json::json!({
+                    "poll_id": poll_id,
+                    "status": phase,
+                    "start_time": poll.start_time,
+                    "end_time": poll.end_time,
+                    "seconds_until_change": next.map(|(_, at)| at - cluster_now),
+                    "total_votes": poll.total_votes,
+                    "eligible_voters": poll.eligible_voters,
+                    "cluster_time": cluster_now,
+                    "local_time": local_now,
+                    "clock_drift_seconds": drift,
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
+            println!("=== Poll {} ===", poll_id);
             println!("Question: {}", poll.question);
-            println!("Description: {}", poll.description);
-            println!("\nCandidates:");
-            
-            let mut total_votes = 0u64;
-            for candidate in &candidates {
-                println!("  • {} ({}): {} votes", candidate.name, candidate.party, candidate.votes);
-                total_votes += candidate.votes;
+            println!("Status: {}", phase);
+            match (phase, next) {
+                ("draft", _) => println!("  Voting opens once the creator activates the poll"),
+                ("paused", _) => println!("  Voting resumes when the creator unpauses the poll"),
+                ("ended", _) if cluster_now > poll.end_time => println!(
+                    "  Ended {} ago, at {}",
+                    utils::format_countdown(cluster_now - poll.end_time),
+                    chrono::DateTime::from_timestamp(poll.end_time, 0).unwrap()
+                ),
+                _ => {}
             }
-            
-            println!("\nTotal votes cast: {}", total_votes);
-            
-            if !candidates.is_empty() {
-                let winner = candidates.iter().max_by_key(|c| c.votes).unwrap();
-                println!("Leading candidate: {} with {} votes", winner.name, winner.votes);
+            if let Some((label, at)) = next {
+                println!(
+                    "{} in {}, at {}",
+                    label,
+                    utils::format_countdown(at - cluster_now),
+                    chrono::DateTime::from_timestamp(at, 0).unwrap()
+                );
+            }
+            println!("Votes cast: {}", utils::format_votes(poll.total_votes, poll.vote_scale()));
+            if poll.eligible_voters > 0 {
+                println!("Registered voters: {}", poll.eligible_voters);
+            }
+            println!("Cluster clock: {}", chrono::DateTime::from_timestamp(cluster_now, 0).unwrap());
+            println!("Local clock: {}", chrono::DateTime::from_timestamp(local_now, 0).unwrap());
+            match drift {
+                0 => println!("  In step with the cluster"),
+                drift if drift > 0 => println!("  {} ahead of the cluster", utils::format_countdown(drift)),
+                drift => println!("  {} behind the cluster", utils::format_countdown(-drift)),
+            }
+            if drift.abs() >= 60 {
+                println!("  Voting opens and closes by the cluster clock, so near those times a vote may fail with");
+                println!("  PollNotActive even though this machine's clock says the poll is open");
+            }
+        }
+        Commands::History { poll_id, limit, before } => {
+            let before = before
+                .map(|signature| signature.parse::<Signature>())
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid signature for --before: {}", e))?;
+            let entries = voting_client.get_poll_history(poll_id, limit, before)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+                return Ok(());
+            }
+
+            println!("=== Poll {} History (newest first) ===", poll_id);
+            if entries.is_empty() {
+                println!("No transactions found");
+            }
+            for entry in &entries {
+                let when = match entry.block_time.and_then(|time| chrono::DateTime::from_timestamp(time, 0)) {
+                    Some(time) => time.to_string(),
+                    None => format!("slot {}", entry.slot),
+                };
+                println!("\n{}", when);
+                if let Some(error) = &entry.error {
+                    println!("  ✗ failed, nothing changed: {}", error);
+                }
+                for action in &entry.actions {
+                    println!("  {}", action);
+                }
+                if entry.actions.is_empty() {
+                    println!("  no poll instructions");
+                }
+                println!("  {}", utils::explorer_link(&entry.signature, &cluster));
+            }
+            if let Some(last) = entries.last().filter(|_| entries.len() == limit) {
+                println!("\nOlder: voting-cli history {} --limit {} --before {}", poll_id, limit, last.signature);
+            }
+        }
+        Commands::DecodeTx { signature } => {
+            let signature = signature
+                .parse::<Signature>()
+                .map_err(|e| anyhow::anyhow!("Invalid signature: {}", e))?;
+            let instructions = voting_client.decode_transaction(&signature)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&instructions)?);
+                return Ok(());
+            }
+
+            println!("=== Transaction {} ===", signature);
+            println!("{}", utils::explorer_link(&signature.to_string(), &cluster));
+            if instructions.is_empty() {
+                println!("No instructions of program {} in this transaction", program_id);
+            }
+            for (index, instruction) in instructions.iter().enumerate() {
+                println!("\nInstruction {}: {}", index + 1, instruction.name);
+                match &instruction.args {
+                    Some(args) => {
+                        println!("  Args:");
+                        for line in args.lines() {
+                            println!("    {}", line);
+                        }
+                    }
+                    None => println!("  Data: {}", instruction.data),
+                }
+                println!("  Accounts:");
+                for account in &instruction.accounts {
+                    let signer = if account.is_signer { " (signer)" } else { "" };
+                    println!("    {}: {}{}", account.name, account.address, signer);
+                }
+            }
+        }
+        Commands::Airdrop { to, amount } => {
+            if matches!(cluster, Cluster::Mainnet) {
+                return Err(anyhow::anyhow!("Mainnet has no faucet; airdrops work on localnet and devnet"));
+            }
+            let recipient = match to {
+                Some(address) => address
+                    .parse::<Pubkey>()
+                    .map_err(|e| anyhow::anyhow!("Invalid recipient: {}", e))?,
+                None => payer.pubkey(),
+            };
+            if amount <= 0.0 {
+                return Err(anyhow::anyhow!("The airdrop amount must be positive"));
+            }
+            let lamports = sol_to_lamports(amount);
+            let before = voting_client.balance(&recipient)?;
+            if !json {
+                println!("Requesting {} SOL for {}...", amount, recipient);
+            }
+            let signature = voting_client.request_airdrop(&recipient, lamports)?;
+            let balance = voting_client.balance(&recipient)?;
+            if json {
+                let output = serde_json::json!({
+                    "recipient": recipient.to_string(),
+                    "lamports": lamports,
+                    "signature": signature.to_string(),
+                    "balance": balance,
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+            println!("✓ Airdropped {} SOL", amount);
+            println!("  Transaction: {}", signature);
+            println!("  Balance: {} SOL (was {} SOL)", lamports_to_sol(balance), lamports_to_sol(before));
+            if balance < before.saturating_add(lamports) {
+                println!("  The balance doesn't show the full airdrop yet; check again in a few seconds");
             }
         }
-        Commands::HasVoted { poll_id, voter } => {
+        Commands::Keygen { count, outdir, fund } => {
+            if count == 0 {
+                return Err(anyhow::anyhow!("--count must be at least 1"));
+            }
+            let lamports = match fund {
+                Some(amount) if amount <= 0.0 => return Err(anyhow::anyhow!("--fund must be positive")),
+                Some(amount) => sol_to_lamports(amount),
+                None => 0,
+            };
+            let directory = std::path::PathBuf::from(shellexpand::tilde(&outdir).to_string());
+            std::fs::create_dir_all(&directory)
+                .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", directory.display(), e))?;
+
+            // Keypairs are saved before any SOL is sent to them, and existing files are never replaced
+            let width = count.to_string().len();
+            let mut voters = Vec::with_capacity(count);
+            for number in 1..=count {
+                let path = directory.join(format!("voter-{:0width$}.json", number, width = width));
+                if path.exists() {
+                    return Err(anyhow::anyhow!("{} already exists; use an empty --outdir", path.display()));
+                }
+                let voter = Keypair::new();
+                write_keypair_file(&voter, &path)
+                    .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+                voters.push((voter.pubkey(), path));
+            }
+            println!("✓ Generated {} keypairs in {}", count, directory.display());
+
+            let mut funded = vec![0; count];
+            if lamports > 0 {
+                let recipients: Vec<Pubkey> = voters.iter().map(|(pubkey, _)| *pubkey).collect();
+                let outcomes = voting_client.fund_accounts(&recipients, lamports)?;
+                for (batch, outcome) in outcomes.into_iter().enumerate() {
+                    let first = batch * utils::TRANSFER_BATCH_SIZE;
+                    let last = (first + utils::TRANSFER_BATCH_SIZE).min(count);
+                    match outcome {
+                        Ok(signature) => {
+                            funded[first..last].fill(lamports);
+                            println!(
+                                "✓ Funded voters {}-{} with {} SOL each ({})",
+                                first + 1,
+                                last,
+                                lamports_to_sol(lamports),
+                                signature
+                            );
+                        }
+                        Err(e) => println!("✗ Voters {}-{} were not funded: {}", first + 1, last, e),
+                    }
+                }
+            }
+
+            let mut manifest = String::from("pubkey,keypair,funded_lamports\n");
+            for ((pubkey, path), funded_lamports) in voters.iter().zip(&funded) {
+                manifest.push_str(&format!("{},{},{}\n", pubkey, path.display(), funded_lamports));
+            }
+            let manifest_path = directory.join("manifest.csv");
+            std::fs::write(&manifest_path, manifest)
+                .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", manifest_path.display(), e))?;
+            println!("✓ Manifest written to {}", manifest_path.display());
+            println!("  Vote with them: voting-cli simulate-votes <POLL_ID> --keypairs {} --distribution ...", outdir);
+        }
+        Commands::Share { poll_id, candidate, png, link } => {
+            let poll = voting_client.get_poll(poll_id)?;
+            let candidate = candidate
+                .map(|name| voting_client.get_candidate_address(poll_id, &name).map(|address| (name, address)))
+                .transpose()?;
+            let link = utils::share_link(
+                link.as_deref(),
+                &program_id,
+                &poll,
+                candidate.as_ref().map(|(name, address)| (name.as_str(), address)),
+                &cluster.to_string(),
+            )?;
+            if let Some(path) = &png {
+                utils::save_qr_png(&link, path)?;
+            }
+            if json {
+                println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "link": link, "png": png }))?);
+                return Ok(());
+            }
+            println!("{}", utils::qr_text(&link)?);
+            println!("Poll {}: {}", poll_id, poll.question);
+            if let Some((name, _)) = &candidate {
+                println!("Prefilled candidate: {}", name);
+            }
+            println!("Link: {}", link);
+            if let Some(path) = png {
+                println!("✓ QR code saved to {}", path);
+            }
+        }
+        Commands::Config { .. } | Commands::Completions { .. } | Commands::CompleteValues { .. } => {
+            unreachable!("config and completions are handled before connecting")
+        }
+        Commands::VerifyReceipt { poll_id, voter, out } => {
+            let voter = voter.parse::<Pubkey>().map_err(|e| anyhow::anyhow!("Invalid voter: {}", e))?;
+            let (receipt_address, _) = utils::get_receipt_address(&program_id, poll_id, &voter);
+            let (slot, data) = voting_client.get_account_data_at_slot(&receipt_address)?;
+            let data = data.ok_or_else(|| {
+                anyhow::anyhow!("{} has no receipt in poll {} (receipt {})", voter, poll_id, receipt_address)
+            })?;
+            let proof = ReceiptProof::create(cluster.url(), &program_id, poll_id, &voter, slot, &data, &**payer)?;
+            let contents = serde_json::to_string_pretty(&proof)?;
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, contents).map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path, e))?;
+                    println!("✓ Proof of receipt {} at slot {} written to {}", receipt_address, slot, path);
+                    println!("  Signed by {}", proof.attester);
+                    println!("  Anyone can check it with: voting-cli verify-proof {}", path);
+                }
+                None => println!("{}", contents),
+            }
+        }
+        Commands::VerifyProof { path } => {
+            let contents =
+                std::fs::read_to_string(&path).map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path, e))?;
+            let proof: ReceiptProof =
+                serde_json::from_str(&contents).map_err(|e| anyhow::anyhow!("Invalid proof {}: {}", path, e))?;
+            proof.verify()?;
+            // The proof holds on its own; the cluster shows whether the receipt still reads the same
+            let (slot, current) = voting_client.get_account_data_at_slot(&proof.receipt_address()?)?;
+            let on_chain = match current {
+                Some(data) if data == proof.raw_data()? => "unchanged",
+                Some(_) => "changed",
+                None => "closed",
+            };
+
+            if json {
+                let output = serde_json::json!({
+                    "valid": true,
+                    "attester": proof.attester,
+                    "poll_id": proof.poll_id,
+                    "voter": proof.voter,
+                    "proof_slot": proof.slot,
+                    "checked_slot": slot,
+                    "on_chain": on_chain,
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+            println!("✓ Valid proof signed by {}", proof.attester);
+            println!(
+                "  Voter {} held receipt {} in poll {} at slot {}",
+                proof.voter, proof.receipt, proof.poll_id, proof.slot
+            );
+            println!("  Read from {}", proof.cluster);
+            println!("  Has voted: {}", proof.contents["has_voted"]);
+            println!("  Weight: {}", proof.contents["weight"]);
+            match on_chain {
+                "unchanged" => println!("✓ The receipt on the cluster still matches at slot {}", slot),
+                "changed" => println!("! The receipt on the cluster has changed since, e.g. by a claimed reward"),
+                _ => println!("! The receipt is no longer on the cluster; receipts may be closed after a poll"),
+            }
+            if proof.program_id != program_id.to_string() {
+                println!("! The proof is for program {}, not {}", proof.program_id, program_id);
+            }
+        }
+        Commands::HasVoted {
+            poll_id,
+            voters_file: Some(voters_file),
+            ..
+        } => {
+            let voters = utils::load_voter_list(&voters_file)?;
+            let voted = voting_client.has_voted_many(poll_id, &voters)?;
+            if json {
+                let output: Vec<_> = voters
+                    .iter()
+                    .zip(&voted)
+                    .map(|(voter, has_voted)| serde_json::json!({ "voter": voter.to_string(), "has_voted": has_voted }))
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!("voter,status");
+                for (voter, has_voted) in voters.iter().zip(&voted) {
+                    println!("{},{}", voter, if *has_voted { "voted" } else { "not voted" });
+                }
+            }
+        }
+        Commands::HasVoted { poll_id, voter, .. } => {
             let voter_pubkey = if let Some(voter_str) = voter {
                 voter_str.parse::<Pubkey>()?
             } else {
@@ -205,8 +3500,15 @@ fn main() -> Result<()> {
             };
             
             let has_voted = voting_client.has_voted(poll_id, voter_pubkey)?;
-            
-            if has_voted {
+
+            if json {
+                let output = serde_json::json!({
+                    "poll_id": poll_id,
+                    "voter": voter_pubkey.to_string(),
+                    "has_voted": has_voted,
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else if has_voted {
                 println!("✓ User {} has voted in poll {}", voter_pubkey, poll_id);
             } else {
                 println!("✗ User {} has not voted in poll {}", voter_pubkey, poll_id);
@@ -0,0 +1,203 @@
+use anchor_client::{
+    anchor_lang::{prelude::Pubkey, AccountDeserialize},
+    solana_sdk::{signature::Signature, signer::Signer},
+};
+use anyhow::Result;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::client::VoterReceipt;
+use crate::utils::{self, get_poll_address, get_receipt_address};
+
+/// Format of the proofs written by this version
+const PROOF_VERSION: u8 = 1;
+
+/// A voter receipt as read from the cluster at a slot, signed by whoever read it. The signature
+/// covers the receipt's raw data and where and when it was read; `contents` is a decoded copy
+/// for readers, checked against the data rather than signed.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReceiptProof {
+    pub version: u8,
+    /// RPC URL the receipt was read from
+    pub cluster: String,
+    pub program_id: String,
+    pub poll_id: u64,
+    pub voter: String,
+    pub receipt: String,
+    /// Slot of the RPC response the receipt was read in
+    pub slot: u64,
+    /// The receipt account's data, base64 encoded
+    pub data: String,
+    pub contents: serde_json::Value,
+    /// Signer of the proof
+    pub attester: String,
+    pub signature: String,
+}
+
+impl ReceiptProof {
+    /// Sign a proof of a receipt's data as read at `slot`
+    pub fn create(
+        cluster: &str,
+        program_id: &Pubkey,
+        poll_id: u64,
+        voter: &Pubkey,
+        slot: u64,
+        data: &[u8],
+        attester: &dyn Signer,
+    ) -> Result<Self> {
+        let contents = VoterReceipt::try_deserialize(&mut &data[..])
+            .map_err(|e| anyhow::anyhow!("The account is not a voter receipt: {}", e))?;
+        let mut proof = Self {
+            version: PROOF_VERSION,
+            cluster: cluster.to_string(),
+            program_id: program_id.to_string(),
+            poll_id,
+            voter: voter.to_string(),
+            receipt: get_receipt_address(program_id, poll_id, voter).0.to_string(),
+            slot,
+            data: base64::engine::general_purpose::STANDARD.encode(data),
+            contents: serde_json::to_value(&contents)?,
+            attester: attester.try_pubkey()?.to_string(),
+            signature: String::new(),
+        };
+        proof.signature = attester.try_sign_message(proof.message()?.as_bytes())?.to_string();
+        Ok(proof)
+    }
+
+    /// The text the attester signs, in a fixed layout so it doesn't depend on JSON formatting
+    fn message(&self) -> Result<String> {
+        Ok(format!(
+            "voting-dapp receipt proof v{}\ncluster: {}\nprogram: {}\npoll: {}\nvoter: {}\nreceipt: {}\nslot: {}\n\
+             data sha256: {}",
+            self.version,
+            self.cluster,
+            self.program_id,
+            self.poll_id,
+            self.voter,
+            self.receipt,
+            self.slot,
+            utils::to_hex(&utils::sha256(&self.raw_data()?))
+        ))
+    }
+
+    /// The receipt account's data
+    pub fn raw_data(&self) -> Result<Vec<u8>> {
+        base64::engine::general_purpose::STANDARD
+            .decode(&self.data)
+            .map_err(|e| anyhow::anyhow!("Invalid receipt data: {}", e))
+    }
+
+    pub fn receipt_address(&self) -> Result<Pubkey> {
+        self.receipt.parse().map_err(|e| anyhow::anyhow!("Invalid receipt address: {}", e))
+    }
+
+    /// Check the proof on its own: the attester's signature, that the receipt address is the
+    /// voter's receipt for the poll, and that the data is that receipt as `contents` shows it
+    pub fn verify(&self) -> Result<()> {
+        if self.version != PROOF_VERSION {
+            return Err(anyhow::anyhow!("Unsupported proof version {}", self.version));
+        }
+        let program_id: Pubkey =
+            self.program_id.parse().map_err(|e| anyhow::anyhow!("Invalid program ID: {}", e))?;
+        let voter: Pubkey = self.voter.parse().map_err(|e| anyhow::anyhow!("Invalid voter: {}", e))?;
+        let attester: Pubkey = self.attester.parse().map_err(|e| anyhow::anyhow!("Invalid attester: {}", e))?;
+        let signature: Signature =
+            self.signature.parse().map_err(|e| anyhow::anyhow!("Invalid signature: {}", e))?;
+
+        if !signature.verify(attester.as_ref(), self.message()?.as_bytes()) {
+            return Err(anyhow::anyhow!("The signature doesn't match attester {}", attester));
+        }
+        if self.receipt_address()? != get_receipt_address(&program_id, self.poll_id, &voter).0 {
+            return Err(anyhow::anyhow!("{} is not the receipt of {} in poll {}", self.receipt, voter, self.poll_id));
+        }
+        let receipt = VoterReceipt::try_deserialize(&mut &self.raw_data()?[..])
+            .map_err(|e| anyhow::anyhow!("The data is not a voter receipt: {}", e))?;
+        if receipt.poll != get_poll_address(&program_id, self.poll_id).0 || receipt.voter != voter {
+            return Err(anyhow::anyhow!("The receipt belongs to another poll or voter"));
+        }
+        // The decoded copy is unsigned, so it must say exactly what the signed data does
+        if serde_json::to_value(&receipt)? != self.contents {
+            return Err(anyhow::anyhow!("The contents don't match the signed receipt data"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_client::{
+        anchor_lang::{AnchorSerialize, Discriminator},
+        solana_sdk::signature::Keypair,
+    };
+
+    use super::*;
+
+    /// Account data of `voter`'s receipt in the poll, as the program stores it
+    fn receipt_data(program_id: &Pubkey, poll_id: u64, voter: &Pubkey) -> Vec<u8> {
+        let receipt = VoterReceipt {
+            poll: get_poll_address(program_id, poll_id).0,
+            voter: *voter,
+            has_voted: true,
+            reward_claimed: false,
+            version: 5,
+            weight: 1,
+            allocations: Vec::new(),
+            bump: get_receipt_address(program_id, poll_id, voter).1,
+            payer: *voter,
+        };
+        [VoterReceipt::DISCRIMINATOR.to_vec(), receipt.try_to_vec().unwrap()].concat()
+    }
+
+    fn test_proof(poll_id: u64, voter: &Pubkey, data: &[u8]) -> Result<ReceiptProof> {
+        let program_id = Pubkey::new_unique();
+        ReceiptProof::create("http://127.0.0.1:8899", &program_id, poll_id, voter, 42, data, &Keypair::new())
+    }
+
+    #[test]
+    fn created_proofs_verify() {
+        let program_id = Pubkey::new_unique();
+        let voter = Pubkey::new_unique();
+        let data = receipt_data(&program_id, 7, &voter);
+        let proof =
+            ReceiptProof::create("http://127.0.0.1:8899", &program_id, 7, &voter, 42, &data, &Keypair::new()).unwrap();
+
+        proof.verify().unwrap();
+        assert_eq!(proof.raw_data().unwrap(), data);
+        assert_eq!(proof.receipt_address().unwrap(), get_receipt_address(&program_id, 7, &voter).0);
+        assert_eq!(proof.contents["voter"], voter.to_string());
+    }
+
+    #[test]
+    fn verify_rejects_changed_proofs() {
+        let program_id = Pubkey::new_unique();
+        let voter = Pubkey::new_unique();
+        let data = receipt_data(&program_id, 7, &voter);
+        let create = || {
+            ReceiptProof::create("http://127.0.0.1:8899", &program_id, 7, &voter, 42, &data, &Keypair::new()).unwrap()
+        };
+
+        let mut proof = create();
+        proof.slot += 1;
+        assert!(proof.verify().unwrap_err().to_string().contains("signature"));
+
+        let mut proof = create();
+        proof.contents["weight"] = 100.into();
+        assert!(proof.verify().unwrap_err().to_string().contains("contents"));
+
+        let mut proof = create();
+        proof.version += 1;
+        assert!(proof.verify().is_err());
+    }
+
+    #[test]
+    fn proofs_must_name_the_receipt_owner() {
+        let voter = Pubkey::new_unique();
+        let other_program = Pubkey::new_unique();
+        let data = receipt_data(&other_program, 7, &voter);
+        // The receipt belongs to a poll of another program, so the proof's addresses don't match it
+        let proof = test_proof(7, &voter, &data).unwrap();
+        assert!(proof.verify().unwrap_err().to_string().contains("another poll or voter"));
+
+        assert!(test_proof(7, &voter, &[0; 16]).is_err());
+    }
+}
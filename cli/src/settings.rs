@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+
+use anchor_client::{
+    anchor_lang::prelude::Pubkey,
+    solana_sdk::commitment_config::CommitmentConfig,
+    Cluster,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Where profiles are saved
+pub const CONFIG_PATH: &str = "~/.config/voting-cli/config.toml";
+
+/// Profile used when `--profile` isn't given
+pub const DEFAULT_PROFILE: &str = "default";
+
+pub const DEFAULT_KEYPAIR: &str = "~/.config/solana/id.json";
+pub const DEFAULT_CLUSTER: &str = "localnet";
+pub const DEFAULT_PROGRAM_ID: &str = "ErWpLzQeDSoB1nuTs2x1d2yHA2AsBvZHg4nNkAusyNK8";
+pub const DEFAULT_COMMITMENT: &str = "confirmed";
+
+/// Contents of the config file: named profiles of connection settings
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Settings {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Settings a profile fills in for flags that aren't given
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Profile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keypair: Option<String>,
+    /// RPC URL, or localnet, devnet or mainnet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_url: Option<String>,
+    /// Websocket URL, for RPC providers that serve it apart from the RPC URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ws_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub program_id: Option<String>,
+    /// processed, confirmed or finalized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<String>,
+}
+
+/// A setting of a profile, named as in the config file
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileKey {
+    #[value(name = "keypair")]
+    Keypair,
+    #[value(name = "rpc_url")]
+    RpcUrl,
+    #[value(name = "ws_url")]
+    WsUrl,
+    #[value(name = "program_id")]
+    ProgramId,
+    #[value(name = "commitment")]
+    Commitment,
+}
+
+impl ProfileKey {
+    /// Name of the setting in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            ProfileKey::Keypair => "keypair",
+            ProfileKey::RpcUrl => "rpc_url",
+            ProfileKey::WsUrl => "ws_url",
+            ProfileKey::ProgramId => "program_id",
+            ProfileKey::Commitment => "commitment",
+        }
+    }
+}
+
+impl Settings {
+    /// Read the config file; a missing file has no profiles
+    pub fn load() -> Result<Self> {
+        let path = shellexpand::tilde(CONFIG_PATH).to_string();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).map_err(|e| anyhow::anyhow!("Invalid config {}: {}", path, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow::anyhow!("Failed to read config {}: {}", path, e)),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = shellexpand::tilde(CONFIG_PATH).to_string();
+        if let Some(directory) = std::path::Path::new(&path).parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        std::fs::write(&path, toml::to_string_pretty(self)?)
+            .map_err(|e| anyhow::anyhow!("Failed to write config {}: {}", path, e))
+    }
+
+    /// The named profile, or the default profile (empty if unset) when no name is given
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile> {
+        match name {
+            Some(name) => self
+                .profiles
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No profile named {} in {}", name, CONFIG_PATH)),
+            None => Ok(self.profiles.get(DEFAULT_PROFILE).cloned().unwrap_or_default()),
+        }
+    }
+}
+
+impl Profile {
+    pub fn get(&self, key: ProfileKey) -> Option<&String> {
+        match key {
+            ProfileKey::Keypair => self.keypair.as_ref(),
+            ProfileKey::RpcUrl => self.rpc_url.as_ref(),
+            ProfileKey::WsUrl => self.ws_url.as_ref(),
+            ProfileKey::ProgramId => self.program_id.as_ref(),
+            ProfileKey::Commitment => self.commitment.as_ref(),
+        }
+    }
+
+    /// Set a value after checking it parses
+    pub fn set(&mut self, key: ProfileKey, value: String) -> Result<()> {
+        match key {
+            ProfileKey::Keypair => self.keypair = Some(value),
+            ProfileKey::RpcUrl => {
+                parse_cluster(&value, None)?;
+                self.rpc_url = Some(value);
+            }
+            ProfileKey::WsUrl => {
+                check_ws_url(&value)?;
+                self.ws_url = Some(value);
+            }
+            ProfileKey::ProgramId => {
+                value
+                    .parse::<Pubkey>()
+                    .map_err(|e| anyhow::anyhow!("Invalid program ID: {}", e))?;
+                self.program_id = Some(value);
+            }
+            ProfileKey::Commitment => {
+                parse_commitment(&value)?;
+                self.commitment = Some(value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Each set value with its key, in config file order
+    pub fn entries(&self) -> Vec<(ProfileKey, &String)> {
+        [
+            ProfileKey::Keypair,
+            ProfileKey::RpcUrl,
+            ProfileKey::WsUrl,
+            ProfileKey::ProgramId,
+            ProfileKey::Commitment,
+        ]
+        .into_iter()
+            .filter_map(|key| self.get(key).map(|value| (key, value)))
+            .collect()
+    }
+}
+
+/// Parse a cluster given as localnet, devnet, mainnet or an RPC URL. Without `ws_url`, the
+/// websocket URL is derived from the RPC URL: the same host, with the port after its own if it
+/// has one.
+pub fn parse_cluster(value: &str, ws_url: Option<&str>) -> Result<Cluster> {
+    let cluster = match value {
+        "localnet" => Cluster::Localnet,
+        "devnet" => Cluster::Devnet,
+        "mainnet" => Cluster::Mainnet,
+        url if url.starts_with("http://") || url.starts_with("https://") => url
+            .parse::<Cluster>()
+            .map_err(|e| anyhow::anyhow!("Invalid RPC URL {}: {}", url, e))?,
+        _ => return Err(anyhow::anyhow!("Invalid cluster: {}", value)),
+    };
+    let Some(ws_url) = ws_url else {
+        return Ok(cluster);
+    };
+    check_ws_url(ws_url)?;
+    Ok(Cluster::Custom(cluster.url().to_string(), ws_url.to_string()))
+}
+
+fn check_ws_url(value: &str) -> Result<()> {
+    if !value.starts_with("ws://") && !value.starts_with("wss://") {
+        return Err(anyhow::anyhow!("Invalid websocket URL {}: it must start with ws:// or wss://", value));
+    }
+    Ok(())
+}
+
+/// Parse a commitment level: processed, confirmed or finalized
+pub fn parse_commitment(value: &str) -> Result<CommitmentConfig> {
+    match value {
+        "processed" => Ok(CommitmentConfig::processed()),
+        "confirmed" => Ok(CommitmentConfig::confirmed()),
+        "finalized" => Ok(CommitmentConfig::finalized()),
+        _ => Err(anyhow::anyhow!("Invalid commitment: {} (use processed, confirmed or finalized)", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cluster_takes_names_and_urls() {
+        assert_eq!(parse_cluster("devnet", None).unwrap().url(), Cluster::Devnet.url());
+        assert_eq!(parse_cluster("localnet", None).unwrap().url(), "http://127.0.0.1:8899");
+
+        let cluster = parse_cluster("http://rpc.example.com:8899", None).unwrap();
+        assert_eq!(cluster.url(), "http://rpc.example.com:8899");
+        assert!(cluster.ws_url().starts_with("ws://rpc.example.com:8900"));
+
+        let cluster = parse_cluster("https://rpc.example.com", Some("wss://ws.example.com")).unwrap();
+        assert_eq!(cluster.url(), "https://rpc.example.com");
+        assert_eq!(cluster.ws_url(), "wss://ws.example.com");
+
+        assert!(parse_cluster("testnet-ish", None).is_err());
+        assert!(parse_cluster("devnet", Some("https://ws.example.com")).is_err());
+    }
+
+    #[test]
+    fn parse_commitment_takes_the_three_levels() {
+        assert_eq!(parse_commitment("processed").unwrap(), CommitmentConfig::processed());
+        assert_eq!(parse_commitment("finalized").unwrap(), CommitmentConfig::finalized());
+        assert!(parse_commitment("max").is_err());
+    }
+
+    #[test]
+    fn profile_set_checks_values_and_lists_them_in_order() {
+        let mut profile = Profile::default();
+        profile.set(ProfileKey::Commitment, "finalized".to_string()).unwrap();
+        profile.set(ProfileKey::RpcUrl, "devnet".to_string()).unwrap();
+        assert!(profile.set(ProfileKey::RpcUrl, "nowhere".to_string()).is_err());
+        assert!(profile.set(ProfileKey::ProgramId, "not-a-key".to_string()).is_err());
+        assert!(profile.set(ProfileKey::WsUrl, "http://ws.example.com".to_string()).is_err());
+
+        assert_eq!(profile.get(ProfileKey::RpcUrl).map(String::as_str), Some("devnet"));
+        assert_eq!(profile.get(ProfileKey::ProgramId), None);
+        assert_eq!(
+            profile
+                .entries()
+                .into_iter()
+                .map(|(key, value)| (key.name(), value.as_str()))
+                .collect::<Vec<_>>(),
+            [("rpc_url", "devnet"), ("commitment", "finalized")]
+        );
+    }
+
+    #[test]
+    fn profile_falls_back_to_an_empty_default() {
+        let mut settings = Settings::default();
+        assert!(settings.profile(None).unwrap().entries().is_empty());
+        assert!(settings.profile(Some("work")).is_err());
+
+        let mut work = Profile::default();
+        work.set(ProfileKey::Keypair, "~/work.json".to_string()).unwrap();
+        settings.profiles.insert("work".to_string(), work);
+        assert_eq!(
+            settings.profile(Some("work")).unwrap().keypair.as_deref(),
+            Some("~/work.json")
+        );
+        assert!(settings.profile(None).unwrap().keypair.is_none());
+    }
+}
@@ -0,0 +1,253 @@
+use anchor_client::{
+    anchor_lang::{prelude::Pubkey, AnchorDeserialize, AnchorSerialize},
+    solana_sdk::keccak::hashv,
+};
+use anyhow::Result;
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT as G,
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::Identity,
+};
+use rand::rngs::OsRng;
+
+/// Exponential ElGamal ciphertext `(rG, mG + rP)`, matching the on-chain layout
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, Default)]
+pub struct ElGamalCiphertext {
+    pub ephemeral: [u8; 32],
+    pub masked: [u8; 32],
+}
+
+/// One candidate's entry on a shielded ballot and the proof that it encrypts 0 or 1
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ShieldedChoice {
+    pub ciphertext: ElGamalCiphertext,
+    pub proof: BitProof,
+}
+
+/// Disjunctive Chaum-Pedersen proof, one challenge and response per branch
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct BitProof {
+    pub challenge0: [u8; 32],
+    pub challenge1: [u8; 32],
+    pub response0: [u8; 32],
+    pub response1: [u8; 32],
+}
+
+/// Chaum-Pedersen proof of equal discrete logarithms
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct DleqProof {
+    pub challenge: [u8; 32],
+    pub response: [u8; 32],
+}
+
+/// A candidate's decrypted total and its proof of correct decryption
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ShieldedResult {
+    pub votes: u64,
+    pub proof: DleqProof,
+}
+
+/// Key pair that decrypts a shielded poll's totals
+pub struct ElGamalKeypair {
+    secret: Scalar,
+    public: RistrettoPoint,
+}
+
+impl ElGamalKeypair {
+    pub fn generate() -> Self {
+        Self::from_secret(Scalar::random(&mut OsRng))
+    }
+
+    fn from_secret(secret: Scalar) -> Self {
+        Self { secret, public: secret * G }
+    }
+
+    /// Read a key pair saved by `write_to_file`: the secret scalar as a JSON byte array
+    pub fn read_from_file(path: &str) -> Result<Self> {
+        let bytes: Vec<u8> = serde_json::from_str(&std::fs::read_to_string(shellexpand::tilde(path).as_ref())?)?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Shielded key file {} must hold 32 bytes", path))?;
+        let secret = Option::from(Scalar::from_canonical_bytes(bytes))
+            .ok_or_else(|| anyhow::anyhow!("Shielded key file {} does not hold a valid scalar", path))?;
+        Ok(Self::from_secret(secret))
+    }
+
+    pub fn write_to_file(&self, path: &str) -> Result<()> {
+        let json = serde_json::to_string(&self.secret.to_bytes().to_vec())?;
+        std::fs::write(shellexpand::tilde(path).as_ref(), json)?;
+        Ok(())
+    }
+
+    /// Compressed public key, as stored in the poll
+    pub fn public_key(&self) -> [u8; 32] {
+        self.public.compress().to_bytes()
+    }
+}
+
+// Fiat-Shamir challenge, matching the program: 512 bits of keccak reduced to a scalar
+fn challenge(transcript: &[&[u8]]) -> Scalar {
+    let mut wide = [0u8; 64];
+    for (half, label) in wide.chunks_mut(32).zip([b"lo", b"hi"]) {
+        let mut parts = transcript.to_vec();
+        parts.push(label);
+        half.copy_from_slice(&hashv(&parts).to_bytes());
+    }
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+fn decompress(bytes: &[u8; 32]) -> Result<RistrettoPoint> {
+    CompressedRistretto(*bytes)
+        .decompress()
+        .ok_or_else(|| anyhow::anyhow!("Invalid Ristretto point"))
+}
+
+// Prove the statement committed in `transcript`, log_G(target) == log_`base`(masked - value*G),
+// knowing that logarithm (`witness`)
+fn prove_dleq(transcript: &[&[u8]], base: &RistrettoPoint, witness: &Scalar) -> DleqProof {
+    let nonce = Scalar::random(&mut OsRng);
+    let (t, u) = ((nonce * G).compress().to_bytes(), (nonce * base).compress().to_bytes());
+
+    let mut parts = transcript.to_vec();
+    parts.extend_from_slice(&[&t[..], &u[..]]);
+    let e = challenge(&parts);
+
+    DleqProof {
+        challenge: e.to_bytes(),
+        response: (nonce + e * witness).to_bytes(),
+    }
+}
+
+// Prove that `(ephemeral, masked)` encrypts `value` (0 or 1) without revealing which,
+// simulating the other branch
+fn prove_bit(
+    key: &RistrettoPoint,
+    context: &[&[u8]],
+    ciphertext: &ElGamalCiphertext,
+    value: u64,
+    randomness: &Scalar,
+) -> Result<BitProof> {
+    let ephemeral = decompress(&ciphertext.ephemeral)?;
+    let masked = decompress(&ciphertext.masked)?;
+
+    // Simulated branch: pick the challenge and response, then derive its commitments
+    let fake = 1 - value as usize;
+    let (fake_challenge, fake_response) = (Scalar::random(&mut OsRng), Scalar::random(&mut OsRng));
+    let fake_shift = if fake == 1 { G } else { RistrettoPoint::identity() };
+    let fake_t = fake_response * G - fake_challenge * ephemeral;
+    let fake_u = fake_response * key - fake_challenge * (masked - fake_shift);
+
+    // Real branch: commit to a nonce
+    let nonce = Scalar::random(&mut OsRng);
+    let (real_t, real_u) = (nonce * G, nonce * key);
+
+    let mut commitments = [[0u8; 32]; 4];
+    commitments[2 * fake] = fake_t.compress().to_bytes();
+    commitments[2 * fake + 1] = fake_u.compress().to_bytes();
+    commitments[2 * (1 - fake)] = real_t.compress().to_bytes();
+    commitments[2 * (1 - fake) + 1] = real_u.compress().to_bytes();
+
+    let key_bytes = key.compress().to_bytes();
+    let mut transcript = context.to_vec();
+    transcript.extend_from_slice(&[&b"bit"[..], &key_bytes, &ciphertext.ephemeral, &ciphertext.masked]);
+    transcript.extend(commitments.iter().map(|c| &c[..]));
+    let e = challenge(&transcript);
+
+    let real_challenge = e - fake_challenge;
+    let real_response = nonce + real_challenge * randomness;
+    let (challenges, responses) = if fake == 1 {
+        ([real_challenge, fake_challenge], [real_response, fake_response])
+    } else {
+        ([fake_challenge, real_challenge], [fake_response, real_response])
+    };
+
+    Ok(BitProof {
+        challenge0: challenges[0].to_bytes(),
+        challenge1: challenges[1].to_bytes(),
+        response0: responses[0].to_bytes(),
+        response1: responses[1].to_bytes(),
+    })
+}
+
+/// Encrypt a ballot for the candidate at `choice` (ballot order) with its validity proofs,
+/// bound to the poll and voter
+pub fn encrypt_ballot(
+    key: &[u8; 32],
+    poll: &Pubkey,
+    voter: &Pubkey,
+    choice: usize,
+    candidate_count: usize,
+) -> Result<(Vec<ShieldedChoice>, DleqProof)> {
+    let public = decompress(key)?;
+    let context: [&[u8]; 2] = [poll.as_ref(), voter.as_ref()];
+
+    let mut choices = Vec::with_capacity(candidate_count);
+    let (mut total_randomness, mut sum_ephemeral, mut sum_masked) =
+        (Scalar::ZERO, RistrettoPoint::identity(), RistrettoPoint::identity());
+    for index in 0..candidate_count {
+        let value = (index == choice) as u64;
+        let randomness = Scalar::random(&mut OsRng);
+        let ephemeral = randomness * G;
+        let masked = Scalar::from(value) * G + randomness * public;
+        let ciphertext = ElGamalCiphertext {
+            ephemeral: ephemeral.compress().to_bytes(),
+            masked: masked.compress().to_bytes(),
+        };
+
+        let proof = prove_bit(&public, &context, &ciphertext, value, &randomness)?;
+        choices.push(ShieldedChoice { ciphertext, proof });
+        total_randomness += randomness;
+        sum_ephemeral += ephemeral;
+        sum_masked += masked;
+    }
+
+    // The entries sum to an encryption of one under the combined randomness
+    let (sum_ephemeral, sum_masked) = (sum_ephemeral.compress().to_bytes(), sum_masked.compress().to_bytes());
+    let mut transcript = context.to_vec();
+    transcript.extend_from_slice(&[&b"sum"[..], key, &sum_ephemeral, &sum_masked]);
+    let sum_proof = prove_dleq(&transcript, &public, &total_randomness);
+
+    Ok((choices, sum_proof))
+}
+
+/// Decrypt a poll's encrypted totals, searching counts up to `max_votes`, and prove each one
+pub fn decrypt_totals(
+    keypair: &ElGamalKeypair,
+    poll: &Pubkey,
+    totals: &[ElGamalCiphertext],
+    max_votes: u64,
+) -> Result<Vec<ShieldedResult>> {
+    let key = keypair.public_key();
+    totals
+        .iter()
+        .map(|total| {
+            let ephemeral = decompress(&total.ephemeral)?;
+            let plaintext = decompress(&total.masked)? - keypair.secret * ephemeral;
+
+            // Exponential ElGamal hides vG, so recover v by walking multiples of G
+            let mut point = RistrettoPoint::identity();
+            let votes = (0..=max_votes)
+                .find(|_| {
+                    let found = point == plaintext;
+                    point += G;
+                    found
+                })
+                .ok_or_else(|| anyhow::anyhow!("Total does not decrypt with this key"))?;
+
+            let proof = prove_dleq(
+                &[
+                    poll.as_ref(),
+                    &b"decrypt"[..],
+                    &key,
+                    &total.ephemeral,
+                    &total.masked,
+                    &votes.to_le_bytes(),
+                ],
+                &ephemeral,
+                &keypair.secret,
+            );
+            Ok(ShieldedResult { votes, proof })
+        })
+        .collect()
+}
@@ -0,0 +1,196 @@
+use anchor_client::{
+    anchor_lang::{prelude::Pubkey, AnchorSerialize},
+    solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        pubkey, system_program,
+    },
+};
+use anyhow::Result;
+
+/// Squads v4 multisig program
+pub const SQUADS_PROGRAM_ID: Pubkey = pubkey!("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+
+/// Vault the CLI acts from; Squads creates vault 0 with every multisig
+pub const VAULT_INDEX: u8 = 0;
+
+const SEED_PREFIX: &[u8] = b"multisig";
+const SEED_VAULT: &[u8] = b"vault";
+const SEED_TRANSACTION: &[u8] = b"transaction";
+const SEED_PROPOSAL: &[u8] = b"proposal";
+
+// Squads account and instruction discriminators
+const MULTISIG_DISCRIMINATOR: [u8; 8] = [224, 116, 121, 186, 68, 161, 79, 236];
+const VAULT_TRANSACTION_CREATE_DISCRIMINATOR: [u8; 8] = [48, 250, 78, 168, 208, 226, 218, 211];
+const PROPOSAL_CREATE_DISCRIMINATOR: [u8; 8] = [220, 60, 73, 224, 30, 108, 79, 159];
+
+// Discriminator, create key, config authority, threshold and time lock precede the transaction index
+const TRANSACTION_INDEX_OFFSET: usize = 8 + 32 + 32 + 2 + 4;
+
+#[derive(AnchorSerialize)]
+struct VaultTransactionCreateArgs {
+    vault_index: u8,
+    ephemeral_signers: u8,
+    transaction_message: Vec<u8>,
+    memo: Option<String>,
+}
+
+#[derive(AnchorSerialize)]
+struct ProposalCreateArgs {
+    transaction_index: u64,
+    draft: bool,
+}
+
+/// Derive a multisig's vault PDA, the account that signs its executed transactions
+pub fn get_vault_address(multisig: &Pubkey, vault_index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SEED_PREFIX, multisig.as_ref(), SEED_VAULT, &[vault_index]],
+        &SQUADS_PROGRAM_ID,
+    )
+}
+
+/// Derive the PDA of a multisig's vault transaction
+pub fn get_transaction_address(multisig: &Pubkey, transaction_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SEED_PREFIX, multisig.as_ref(), SEED_TRANSACTION, &transaction_index.to_le_bytes()],
+        &SQUADS_PROGRAM_ID,
+    )
+}
+
+/// Derive the PDA of the proposal members vote on for a vault transaction
+pub fn get_proposal_address(multisig: &Pubkey, transaction_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SEED_PREFIX,
+            multisig.as_ref(),
+            SEED_TRANSACTION,
+            &transaction_index.to_le_bytes(),
+            SEED_PROPOSAL,
+        ],
+        &SQUADS_PROGRAM_ID,
+    )
+}
+
+/// Read the index of the latest transaction from a multisig account
+pub fn transaction_index(data: &[u8]) -> Result<u64> {
+    if data.get(..8) != Some(&MULTISIG_DISCRIMINATOR[..]) {
+        return Err(anyhow::anyhow!("Account is not a Squads multisig"));
+    }
+    let bytes = data
+        .get(TRANSACTION_INDEX_OFFSET..TRANSACTION_INDEX_OFFSET + 8)
+        .ok_or_else(|| anyhow::anyhow!("Multisig account is too short"))?;
+    Ok(u64::from_le_bytes(bytes.try_into()?))
+}
+
+/// Compile instructions into the Squads transaction message a vault transaction executes. The
+/// vault is its only signer, so instructions needing any other signature are rejected.
+pub fn compile_vault_message(vault: &Pubkey, instructions: &[Instruction]) -> Result<Vec<u8>> {
+    // (key, is_signer, is_writable), each account keeping its strongest role across instructions
+    let mut accounts: Vec<(Pubkey, bool, bool)> = vec![(*vault, true, true)];
+    let mut add = |key: Pubkey, is_signer: bool, is_writable: bool| {
+        match accounts.iter_mut().find(|(existing, _, _)| *existing == key) {
+            Some(account) => {
+                account.1 |= is_signer;
+                account.2 |= is_writable;
+            }
+            None => accounts.push((key, is_signer, is_writable)),
+        }
+    };
+    for instruction in instructions {
+        for meta in &instruction.accounts {
+            if meta.is_signer && meta.pubkey != *vault {
+                return Err(anyhow::anyhow!(
+                    "{} must sign, but a multisig transaction can only sign as its vault",
+                    meta.pubkey
+                ));
+            }
+            add(meta.pubkey, meta.is_signer, meta.is_writable);
+        }
+        add(instruction.program_id, false, false);
+    }
+    // Signers first, writable before read-only within each; the sort is stable so the vault leads
+    accounts.sort_by_key(|(_, is_signer, is_writable)| (!is_signer, !is_writable));
+
+    let count = |filter: fn(&(Pubkey, bool, bool)) -> bool| accounts.iter().filter(|account| filter(account)).count();
+    let mut message = vec![
+        count(|account| account.1) as u8,
+        count(|account| account.1 && account.2) as u8,
+        count(|account| !account.1 && account.2) as u8,
+    ];
+    let account_count = u8::try_from(accounts.len())
+        .map_err(|_| anyhow::anyhow!("Too many accounts for one vault transaction"))?;
+    message.push(account_count);
+    for (key, _, _) in &accounts {
+        message.extend_from_slice(key.as_ref());
+    }
+
+    // Every key was added above, and there are at most 255
+    let index_of = |key: &Pubkey| {
+        accounts
+            .iter()
+            .position(|(existing, _, _)| existing == key)
+            .unwrap_or_default() as u8
+    };
+    message.push(u8::try_from(instructions.len())?);
+    for instruction in instructions {
+        message.push(index_of(&instruction.program_id));
+        message.push(u8::try_from(instruction.accounts.len())?);
+        for meta in &instruction.accounts {
+            message.push(index_of(&meta.pubkey));
+        }
+        message.extend_from_slice(&u16::try_from(instruction.data.len())?.to_le_bytes());
+        message.extend_from_slice(&instruction.data);
+    }
+    // No address lookup tables
+    message.push(0);
+    Ok(message)
+}
+
+/// Squads instruction storing a vault transaction for members to vote on
+pub fn vault_transaction_create_instruction(
+    multisig: &Pubkey,
+    transaction_index: u64,
+    creator: &Pubkey,
+    transaction_message: Vec<u8>,
+    memo: Option<String>,
+) -> Result<Instruction> {
+    let args = VaultTransactionCreateArgs {
+        vault_index: VAULT_INDEX,
+        ephemeral_signers: 0,
+        transaction_message,
+        memo,
+    };
+    let mut data = VAULT_TRANSACTION_CREATE_DISCRIMINATOR.to_vec();
+    args.serialize(&mut data)?;
+    Ok(Instruction {
+        program_id: SQUADS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*multisig, false),
+            AccountMeta::new(get_transaction_address(multisig, transaction_index).0, false),
+            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new(*creator, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data,
+    })
+}
+
+/// Squads instruction opening the proposal for a vault transaction
+pub fn proposal_create_instruction(multisig: &Pubkey, transaction_index: u64, creator: &Pubkey) -> Result<Instruction> {
+    let args = ProposalCreateArgs {
+        transaction_index,
+        draft: false,
+    };
+    let mut data = PROPOSAL_CREATE_DISCRIMINATOR.to_vec();
+    args.serialize(&mut data)?;
+    Ok(Instruction {
+        program_id: SQUADS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*multisig, false),
+            AccountMeta::new(get_proposal_address(multisig, transaction_index).0, false),
+            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new(*creator, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data,
+    })
+}
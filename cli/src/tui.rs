@@ -0,0 +1,363 @@
+use std::io::Stdout;
+use std::time::{Duration, Instant};
+
+use anchor_client::solana_sdk::signer::Signer;
+use anyhow::Result;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Sparkline},
+    Frame, Terminal,
+};
+
+use crate::client::{Candidate, CandidateOrder, Poll, PollFilter, VotingClient};
+use crate::utils;
+
+// How often the dashboard re-reads the selected poll's results
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+// Widest result bar, in characters
+const BAR_WIDTH: usize = 30;
+
+const HELP: &str = "↑/↓ select  Tab switch pane  v vote  a add candidate  f finalize  r reload  q quit";
+
+#[derive(PartialEq, Eq)]
+enum Focus {
+    Polls,
+    Candidates,
+}
+
+enum Mode {
+    Browse,
+    // Typing a new candidate as `name, party`
+    AddCandidate(String),
+    // Waiting for the user to accept the poll's vote fee
+    ConfirmVote,
+}
+
+struct App {
+    polls: Vec<Poll>,
+    poll_state: ListState,
+    results: Option<(Poll, Vec<Candidate>)>,
+    candidate_state: ListState,
+    focus: Focus,
+    mode: Mode,
+    status: String,
+}
+
+/// Run the dashboard until the user quits: listed polls on the left, the selected poll's live
+/// results and hourly turnout on the right
+pub fn run(voting_client: &VotingClient<Box<dyn Signer>>) -> Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = event_loop(&mut terminal, voting_client);
+
+    // Restore the terminal even if the dashboard failed
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+fn event_loop(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    voting_client: &VotingClient<Box<dyn Signer>>,
+) -> Result<()> {
+    let mut app = App::new(voting_client)?;
+    let mut last_refresh = Instant::now();
+
+    loop {
+        terminal.draw(|frame| draw(frame, &mut app))?;
+
+        let timeout = REFRESH_INTERVAL.saturating_sub(last_refresh.elapsed());
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !app.handle_key(voting_client, key.code) {
+                    return Ok(());
+                }
+            }
+        }
+        if last_refresh.elapsed() >= REFRESH_INTERVAL {
+            app.refresh_results(voting_client);
+            last_refresh = Instant::now();
+        }
+    }
+}
+
+impl App {
+    fn new(voting_client: &VotingClient<Box<dyn Signer>>) -> Result<Self> {
+        let mut app = App {
+            polls: Vec::new(),
+            poll_state: ListState::default(),
+            results: None,
+            candidate_state: ListState::default(),
+            focus: Focus::Polls,
+            mode: Mode::Browse,
+            status: String::new(),
+        };
+        app.reload_polls(voting_client)?;
+        Ok(app)
+    }
+
+    fn selected_poll_id(&self) -> Option<u64> {
+        self.poll_state.selected().and_then(|index| self.polls.get(index)).map(|poll| poll.poll_id)
+    }
+
+    fn selected_candidate(&self) -> Option<&Candidate> {
+        let (_, candidates) = self.results.as_ref()?;
+        candidates.get(self.candidate_state.selected()?)
+    }
+
+    fn reload_polls(&mut self, voting_client: &VotingClient<Box<dyn Signer>>) -> Result<()> {
+        self.polls = voting_client
+            .get_all_polls(&PollFilter::default())?
+            .into_iter()
+            .map(|(_, poll)| poll)
+            .collect();
+        let selected = self.poll_state.selected().unwrap_or(0);
+        self.poll_state
+            .select((!self.polls.is_empty()).then(|| selected.min(self.polls.len() - 1)));
+        self.refresh_results(voting_client);
+        Ok(())
+    }
+
+    fn refresh_results(&mut self, voting_client: &VotingClient<Box<dyn Signer>>) {
+        let Some(poll_id) = self.selected_poll_id() else {
+            self.results = None;
+            return;
+        };
+        match voting_client.get_poll_results(poll_id, CandidateOrder::Ballot) {
+            Ok((poll, candidates)) => {
+                let selected = self.candidate_state.selected().unwrap_or(0);
+                self.candidate_state
+                    .select((!candidates.is_empty()).then(|| selected.min(candidates.len() - 1)));
+                self.results = Some((poll, candidates));
+            }
+            Err(error) => self.status = format!("✗ {}", error),
+        }
+    }
+
+    // Handle one key press; returns false when the user quits
+    fn handle_key(&mut self, voting_client: &VotingClient<Box<dyn Signer>>, code: KeyCode) -> bool {
+        match &mut self.mode {
+            Mode::AddCandidate(input) => {
+                match code {
+                    KeyCode::Char(c) => input.push(c),
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Esc => self.mode = Mode::Browse,
+                    KeyCode::Enter => {
+                        let input = std::mem::take(input);
+                        self.mode = Mode::Browse;
+                        self.add_candidate(voting_client, &input);
+                    }
+                    _ => {}
+                }
+                return true;
+            }
+            Mode::ConfirmVote => {
+                self.mode = Mode::Browse;
+                if code == KeyCode::Char('y') {
+                    self.vote(voting_client);
+                } else {
+                    self.status = "Vote cancelled".to_string();
+                }
+                return true;
+            }
+            Mode::Browse => {}
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(voting_client, -1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(voting_client, 1),
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Polls => Focus::Candidates,
+                    Focus::Candidates => Focus::Polls,
+                }
+            }
+            KeyCode::Char('r') => {
+                if let Err(error) = self.reload_polls(voting_client) {
+                    self.status = format!("✗ {}", error);
+                }
+            }
+            KeyCode::Char('v') => {
+                let fee = match (&self.results, self.selected_candidate()) {
+                    (Some((poll, _)), Some(_)) => Some(poll.describe_vote_fee()),
+                    _ => None,
+                };
+                match fee {
+                    Some(Some(fee)) => {
+                        self.status = format!("Voting costs {}; press y to confirm", fee);
+                        self.mode = Mode::ConfirmVote;
+                    }
+                    Some(None) => self.vote(voting_client),
+                    None => self.status = "Select a candidate to vote for (Tab to switch pane)".to_string(),
+                }
+            }
+            KeyCode::Char('a') if self.selected_poll_id().is_some() => {
+                self.mode = Mode::AddCandidate(String::new());
+            }
+            KeyCode::Char('f') => self.finalize(voting_client),
+            _ => {}
+        }
+        true
+    }
+
+    fn move_selection(&mut self, voting_client: &VotingClient<Box<dyn Signer>>, step: isize) {
+        let (state, len) = match self.focus {
+            Focus::Polls => (&mut self.poll_state, self.polls.len()),
+            Focus::Candidates => (
+                &mut self.candidate_state,
+                self.results.as_ref().map_or(0, |(_, candidates)| candidates.len()),
+            ),
+        };
+        if len == 0 {
+            return;
+        }
+        let current = state.selected().unwrap_or(0) as isize;
+        state.select(Some((current + step).clamp(0, len as isize - 1) as usize));
+
+        if self.focus == Focus::Polls {
+            self.candidate_state.select(None);
+            self.refresh_results(voting_client);
+        }
+    }
+
+    fn vote(&mut self, voting_client: &VotingClient<Box<dyn Signer>>) {
+        let (Some(poll_id), Some(candidate)) = (self.selected_poll_id(), self.selected_candidate()) else {
+            return;
+        };
+        let name = candidate.name.clone();
+        self.status = match voting_client.vote(poll_id, name.clone(), None, Vec::new(), None) {
+            Ok(signature) => format!("✓ Voted for {} ({})", name, signature),
+            Err(error) => format!("✗ {}", error),
+        };
+        self.refresh_results(voting_client);
+    }
+
+    fn add_candidate(&mut self, voting_client: &VotingClient<Box<dyn Signer>>, input: &str) {
+        let Some(poll_id) = self.selected_poll_id() else {
+            return;
+        };
+        let (name, party) = input.split_once(',').unwrap_or((input, ""));
+        let name = name.trim().to_string();
+        self.status = match voting_client.add_candidate(
+            poll_id,
+            name.clone(),
+            party.trim().to_string(),
+            voting_client.payer_pubkey(),
+            String::new(),
+        ) {
+            Ok(signature) => format!("✓ Added {} ({})", name, signature),
+            Err(error) => format!("✗ {}", error),
+        };
+        self.refresh_results(voting_client);
+    }
+
+    fn finalize(&mut self, voting_client: &VotingClient<Box<dyn Signer>>) {
+        let Some(poll_id) = self.selected_poll_id() else {
+            return;
+        };
+        self.status = match voting_client.finalize_poll(poll_id) {
+            Ok(signature) => format!("✓ Poll {} finalized ({})", poll_id, signature),
+            Err(error) => format!("✗ {}", error),
+        };
+        self.refresh_results(voting_client);
+    }
+}
+
+fn pane(title: String, focused: bool) -> Block<'static> {
+    let border = if focused { Color::Yellow } else { Color::Reset };
+    Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border))
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(frame.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .split(rows[0]);
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(columns[1]);
+    let highlight = Style::default().add_modifier(Modifier::REVERSED);
+    let now = chrono::Utc::now().timestamp();
+
+    let polls: Vec<ListItem> = app
+        .polls
+        .iter()
+        .map(|poll| ListItem::new(format!("#{} {} [{:?}]", poll.poll_id, poll.question, poll.status_at(now))))
+        .collect();
+    let polls = List::new(polls)
+        .block(pane(format!("Polls ({})", app.polls.len()), app.focus == Focus::Polls))
+        .highlight_style(highlight);
+    frame.render_stateful_widget(polls, columns[0], &mut app.poll_state);
+
+    let (title, candidates, turnout) = match &app.results {
+        Some((poll, candidates)) => {
+            let leader = candidates.iter().map(|c| c.votes).max().unwrap_or(0).max(1);
+            let items: Vec<ListItem> = candidates
+                .iter()
+                .map(|candidate| {
+                    let bar = "█".repeat((candidate.votes as u128 * BAR_WIDTH as u128 / leader as u128) as usize);
+                    let marker = if candidate.is_winner { " ★" } else { "" };
+                    ListItem::new(format!(
+                        "{:<20} {:>10} {}{}",
+                        candidate.name,
+                        utils::format_votes(candidate.votes, poll.vote_scale()),
+                        bar,
+                        marker
+                    ))
+                })
+                .collect();
+            // Hours after the last vote are left off, as in `turnout-chart`
+            let hours = poll.vote_histogram.iter().rposition(|&count| count > 0).map_or(0, |last| last + 1);
+            let turnout: Vec<u64> = poll.vote_histogram[..hours].iter().map(|&count| count as u64).collect();
+            let title = format!(
+                "Poll {}: {} [{:?}], {} ballots",
+                poll.poll_id,
+                poll.question,
+                poll.status_at(now),
+                poll.total_votes
+            );
+            (title, items, turnout)
+        }
+        None => ("No poll selected".to_string(), Vec::new(), Vec::new()),
+    };
+    let candidates = List::new(candidates)
+        .block(pane(title, app.focus == Focus::Candidates))
+        .highlight_style(highlight);
+    frame.render_stateful_widget(candidates, right[0], &mut app.candidate_state);
+
+    let turnout = Sparkline::default()
+        .block(pane("Votes per hour".to_string(), false))
+        .data(&turnout)
+        .style(Style::default().fg(Color::Cyan));
+    frame.render_widget(turnout, right[1]);
+
+    let footer = match &app.mode {
+        Mode::AddCandidate(input) => format!("New candidate as name, party (Enter to add, Esc to cancel): {}_", input),
+        _ if app.status.is_empty() => HELP.to_string(),
+        _ => format!("{}\n{}", app.status, HELP),
+    };
+    frame.render_widget(Paragraph::new(footer).block(Block::default().borders(Borders::TOP)), rows[1]);
+}
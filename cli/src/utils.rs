@@ -1,8 +1,108 @@
-use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::{
+    bpf_loader_upgradeable,
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    hash::hash,
+    signature::{read_keypair_file, Signature},
+    signer::{Signer, SignerError},
+    transaction::Transaction,
+};
+use anchor_client::Cluster;
+use anyhow::Result;
+use base64::Engine;
+use serde::{Deserialize, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use solana_clap_utils::keypair::{parse_signer_source, SignerSourceKind};
+use solana_remote_wallet::{
+    remote_keypair::{generate_remote_keypair, RemoteKeypair},
+    remote_wallet::maybe_wallet_manager,
+};
+
+use crate::client::{Candidate, NewCandidate, Poll};
 
 pub const POLL_SEED: &[u8] = b"poll";
 pub const CANDIDATE_SEED: &[u8] = b"candidate";
 pub const RECEIPT_SEED: &[u8] = b"receipt";
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const REWARD_VAULT_SEED: &[u8] = b"reward_vault";
+pub const BALLOT_SEED: &[u8] = b"ballot";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const POLL_AUTHORITY_SEED: &[u8] = b"poll_authority";
+pub const POLL_PROPOSAL_SEED: &[u8] = b"poll_proposal";
+pub const CREATOR_PASS_SEED: &[u8] = b"creator_pass";
+pub const POLL_ARCHIVE_SEED: &[u8] = b"poll_archive";
+pub const SHIELDED_TALLY_SEED: &[u8] = b"shielded_tally";
+pub const STAKE_MARKER_SEED: &[u8] = b"stake_marker";
+pub const CONVICTION_LOCK_SEED: &[u8] = b"conviction_lock";
+pub const CONVICTION_VAULT_SEED: &[u8] = b"conviction_vault";
+pub const SESSION_SEED: &[u8] = b"session";
+pub const ACCESS_GRANT_SEED: &[u8] = b"access_grant";
+pub const WEIGHT_RECORD_SEED: &[u8] = b"weight_record";
+pub const CREATOR_STATS_SEED: &[u8] = b"creator_stats";
+pub const POLL_RESULT_SEED: &[u8] = b"poll_result";
+pub const COMPRESSED_VOTER_SEED: &[u8] = b"compressed_voter";
+pub const NOMINATION_SEED: &[u8] = b"nomination";
+pub const GOVERNANCE_LOCK_SEED: &[u8] = b"governance_lock";
+
+/// spl-governance program used by Realms
+pub const GOVERNANCE_PROGRAM_ID: &str = "GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw";
+
+/// Solana Attestation Service program
+pub const ATTESTATION_PROGRAM_ID: &str = "22zoJMtdu4tQc2PzL74ZUT7FrwgB1Udec8DdW4yw4BdG";
+
+/// Clockwork thread program that runs automatic finalization
+pub const THREAD_PROGRAM_ID: Pubkey = pubkey!("CLoCKyJ6DXBJqqu2VWx9RLbgnwwR6BMHHuyasVmfMzBh");
+
+/// Candidates registered per transaction by `add-candidates`, keeping transactions under the size limit
+pub const CANDIDATE_BATCH_SIZE: usize = 8;
+
+/// Access grants issued per transaction by `grant-access`, keeping transactions under the size limit
+pub const ACCESS_GRANT_BATCH_SIZE: usize = 10;
+
+/// Transfers made per transaction by `keygen --fund`, keeping transactions under the size limit
+pub const TRANSFER_BATCH_SIZE: usize = 16;
+
+/// Time after a poll ends before its receipts can be cranked closed (30 days)
+pub const RECEIPT_GRACE_PERIOD: i64 = 30 * 24 * 60 * 60;
+
+/// How far in the past a new poll's start time may be (5 minutes)
+pub const START_TIME_GRACE_PERIOD: i64 = 5 * 60;
+
+/// Longest candidate name, party and metadata URI the program stores, in bytes
+pub const MAX_CANDIDATE_NAME_LEN: usize = 50;
+pub const MAX_PARTY_LEN: usize = 30;
+pub const MAX_URI_LEN: usize = 200;
+
+/// Size of a poll account, `8 + Poll::INIT_SPACE` in the program
+pub const POLL_ACCOUNT_SPACE: usize = 1980;
+
+/// Size of a candidate account, `8 + Candidate::INIT_SPACE` in the program
+pub const CANDIDATE_ACCOUNT_SPACE: usize = 425;
+
+/// Metaplex Token Metadata program
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Derive the PDA for the global config account
+pub fn get_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+/// Derive the PDA for the program treasury account
+pub fn get_treasury_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED], program_id)
+}
+
+/// Derive the program data account that records the program's upgrade authority
+pub fn get_program_data_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID)
+}
+
+/// Derive the PDA that signs the program's self-CPI event instructions
+pub fn get_event_authority_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], program_id)
+}
 
 /// Derive the PDA for a poll account
 pub fn get_poll_address(program_id: &Pubkey, poll_id: u64) -> (Pubkey, u8) {
@@ -12,20 +112,9 @@ pub fn get_poll_address(program_id: &Pubkey, poll_id: u64) -> (Pubkey, u8) {
     )
 }
 
-/// Derive the PDA for a candidate account
-pub fn get_candidate_address(
-    program_id: &Pubkey,
-    poll_id: u64,
-    candidate_name: &str,
-) -> (Pubkey, u8) {
-    Pubkey::find_program_address(
-        &[
-            CANDIDATE_SEED,
-            &poll_id.to_le_bytes(),
-            candidate_name.as_bytes(),
-        ],
-        program_id,
-    )
+/// Derive the PDA for a candidate account from its ballot index
+pub fn get_candidate_address(program_id: &Pubkey, poll: &Pubkey, index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CANDIDATE_SEED, poll.as_ref(), &index.to_le_bytes()], program_id)
 }
 
 /// Derive the PDA for a voter receipt account
@@ -39,3 +128,1186 @@ pub fn get_receipt_address(
         program_id,
     )
 }
+
+/// Derive the PDA for a poll's reward token vault
+pub fn get_reward_vault_address(program_id: &Pubkey, poll: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REWARD_VAULT_SEED, poll.as_ref()], program_id)
+}
+
+/// Derive the PDA for a ballot account
+pub fn get_ballot_address(program_id: &Pubkey, ballot_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BALLOT_SEED, &ballot_id.to_le_bytes()], program_id)
+}
+
+/// Derive the PDA of a voter's session for a ballot
+pub fn get_session_address(program_id: &Pubkey, ballot: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SESSION_SEED, ballot.as_ref(), voter.as_ref()], program_id)
+}
+
+/// Derive the PDA of the grant letting a voter vote in an access-restricted poll
+pub fn get_access_grant_address(program_id: &Pubkey, poll: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ACCESS_GRANT_SEED, poll.as_ref(), voter.as_ref()], program_id)
+}
+
+/// Derive the PDA recording a voter's registration in a poll's receipt tree
+pub fn get_compressed_voter_address(program_id: &Pubkey, poll: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COMPRESSED_VOTER_SEED, poll.as_ref(), voter.as_ref()], program_id)
+}
+
+/// Derive the PDA marking that a wallet has nominated itself in an open-candidacy poll
+pub fn get_nomination_address(program_id: &Pubkey, poll: &Pubkey, nominee: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NOMINATION_SEED, poll.as_ref(), nominee.as_ref()], program_id)
+}
+
+/// Derive the PDA holding the weight an oracle published for a voter
+pub fn get_weight_record_address(program_id: &Pubkey, oracle: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WEIGHT_RECORD_SEED, oracle.as_ref(), voter.as_ref()], program_id)
+}
+
+/// Derive the PDA holding a poll's auditors and their attestations of its result
+pub fn get_poll_result_address(program_id: &Pubkey, poll: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POLL_RESULT_SEED, poll.as_ref()], program_id)
+}
+
+/// Derive the PDA of a wallet's creator pass
+pub fn get_creator_pass_address(program_id: &Pubkey, creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CREATOR_PASS_SEED, creator.as_ref()], program_id)
+}
+
+/// Derive the PDA tracking how many polls a wallet has created
+pub fn get_creator_stats_address(program_id: &Pubkey, creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CREATOR_STATS_SEED, creator.as_ref()], program_id)
+}
+
+/// Derive the PDA of a poll's archived summary
+pub fn get_poll_archive_address(program_id: &Pubkey, poll_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POLL_ARCHIVE_SEED, &poll_id.to_le_bytes()], program_id)
+}
+
+/// Derive the PDA holding a shielded poll's encrypted totals
+pub fn get_shielded_tally_address(program_id: &Pubkey, poll: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SHIELDED_TALLY_SEED, poll.as_ref()], program_id)
+}
+
+/// Derive the PDA marking a stake account as having voted in a stake-weighted poll
+pub fn get_stake_marker_address(program_id: &Pubkey, poll: &Pubkey, stake_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STAKE_MARKER_SEED, poll.as_ref(), stake_account.as_ref()], program_id)
+}
+
+/// Derive the PDA recording a voter's conviction lock in a poll
+pub fn get_conviction_lock_address(program_id: &Pubkey, poll: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONVICTION_LOCK_SEED, poll.as_ref(), voter.as_ref()], program_id)
+}
+
+/// Derive the token account holding a voter's locked conviction tokens
+pub fn get_conviction_vault_address(program_id: &Pubkey, poll: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONVICTION_VAULT_SEED, poll.as_ref(), voter.as_ref()], program_id)
+}
+
+/// Derive an spl-governance `TokenOwnerRecord` address for an owner's deposits in a realm
+pub fn get_token_owner_record_address(
+    governance_program: &Pubkey,
+    realm: &Pubkey,
+    governing_mint: &Pubkey,
+    owner: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"governance", realm.as_ref(), governing_mint.as_ref(), owner.as_ref()],
+        governance_program,
+    )
+}
+
+/// Derive an spl-governance realm's config account
+pub fn get_realm_config_address(governance_program: &Pubkey, realm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"realm-config", realm.as_ref()], governance_program)
+}
+
+/// Derive the program's lock authority over realm deposits
+pub fn get_governance_lock_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GOVERNANCE_LOCK_SEED], program_id)
+}
+
+/// Derive the Clockwork thread that finalizes a poll, owned by the poll PDA
+pub fn get_finalize_thread_address(poll: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"thread", poll.as_ref(), b"finalize"], &THREAD_PROGRAM_ID)
+}
+
+/// Derive the attestation an issuer (credential) made about a subject under a schema.
+/// The subject's wallet is used as the attestation nonce.
+pub fn get_attestation_address(
+    attestation_program: &Pubkey,
+    credential: &Pubkey,
+    schema: &Pubkey,
+    subject: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"attestation", credential.as_ref(), schema.as_ref(), subject.as_ref()],
+        attestation_program,
+    )
+}
+
+/// Derive the PDA staging a poll for council approval
+pub fn get_poll_proposal_address(program_id: &Pubkey, poll_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POLL_PROPOSAL_SEED, &poll_id.to_le_bytes()], program_id)
+}
+
+/// Derive the PDA holding a poll's proposal instruction
+pub fn get_proposal_address(program_id: &Pubkey, poll: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROPOSAL_SEED, poll.as_ref()], program_id)
+}
+
+/// Derive the PDA that signs a poll's proposal instruction
+pub fn get_poll_authority_address(program_id: &Pubkey, poll: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POLL_AUTHORITY_SEED, poll.as_ref()], program_id)
+}
+
+/// Derive the Token Metadata account for a mint
+pub fn get_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Derive the Token Metadata master edition account for a mint
+pub fn get_master_edition_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref(), b"edition"],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Off-chain Metaplex JSON for a poll winner's badge
+pub fn winner_badge_metadata(poll: &Poll, winner: &Candidate) -> serde_json::Value {
+    let decided = chrono::DateTime::from_timestamp(poll.end_time, 0)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+
+    serde_json::json!({
+        "name": format!("Poll #{} Winner", poll.poll_id),
+        "symbol": "WIN",
+        "description": format!("{} won \"{}\" on {}", winner.name, poll.question, decided),
+        "attributes": [
+            { "trait_type": "Poll", "value": poll.poll_id },
+            { "trait_type": "Question", "value": poll.question },
+            { "trait_type": "Winner", "value": winner.name },
+            { "trait_type": "Party", "value": winner.party },
+            { "trait_type": "Votes", "value": winner.votes },
+            { "trait_type": "Total votes", "value": poll.total_votes },
+            { "trait_type": "Decided", "value": decided },
+        ],
+    })
+}
+
+/// Election manifest used by `create-ballot`
+#[derive(Deserialize, Debug)]
+pub struct BallotManifest {
+    pub ballot_id: u64,
+    pub title: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub races: Vec<RaceManifest>,
+}
+
+/// A single race (poll) within a ballot manifest
+#[derive(Deserialize, Debug)]
+pub struct RaceManifest {
+    pub poll_id: u64,
+    pub question: String,
+    #[serde(default)]
+    pub description: String,
+    pub candidates: Vec<CandidateManifest>,
+}
+
+/// A candidate entry within a race manifest
+#[derive(Deserialize, Debug)]
+pub struct CandidateManifest {
+    pub name: String,
+    #[serde(default)]
+    pub party: String,
+}
+
+/// Load a ballot manifest from a JSON file
+pub fn load_ballot_manifest(path: &str) -> Result<BallotManifest> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read manifest {}: {}", path, e))?;
+    let manifest = serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("Invalid manifest {}: {}", path, e))?;
+    Ok(manifest)
+}
+
+/// A vote in one poll of a `vote-all` ballot file
+#[derive(Deserialize, Debug, Clone)]
+pub struct VoteSelection {
+    pub poll_id: u64,
+    pub candidate: String,
+}
+
+/// Load the votes of a `vote-all` ballot file: a JSON array of `{"poll_id", "candidate"}` objects
+pub fn load_vote_selections(path: &str) -> Result<Vec<VoteSelection>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read ballot {}: {}", path, e))?;
+    let votes = serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("Invalid ballot {}: {}", path, e))?;
+    Ok(votes)
+}
+
+/// Instruction a proposal poll executes if it passes, as written in a JSON file
+#[derive(Deserialize, Debug)]
+pub struct ProposalManifest {
+    pub program_id: String,
+    #[serde(default)]
+    pub accounts: Vec<ProposalAccountManifest>,
+    /// Instruction data, base64 encoded
+    #[serde(default)]
+    pub data: String,
+}
+
+/// An account of a proposal's instruction
+#[derive(Deserialize, Debug)]
+pub struct ProposalAccountManifest {
+    pub pubkey: String,
+    #[serde(default)]
+    pub is_signer: bool,
+    #[serde(default)]
+    pub is_writable: bool,
+}
+
+/// Load a proposal's instruction from a JSON file
+pub fn load_proposal_instruction(path: &str) -> Result<Instruction> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read instruction {}: {}", path, e))?;
+    let manifest: ProposalManifest = serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("Invalid instruction {}: {}", path, e))?;
+
+    let accounts = manifest
+        .accounts
+        .iter()
+        .map(|account| {
+            Ok(AccountMeta {
+                pubkey: account.pubkey.parse()?,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Instruction {
+        program_id: manifest.program_id.parse()?,
+        accounts,
+        data: base64::engine::general_purpose::STANDARD.decode(&manifest.data)?,
+    })
+}
+
+/// A transaction saved for signing or submitting later
+#[derive(Serialize, Deserialize, Debug)]
+struct TransactionFile {
+    /// Bincode-serialized transaction, base64 encoded
+    transaction: String,
+    /// Durable nonce the transaction advances, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nonce_account: Option<String>,
+}
+
+/// Load the signer named by `--keypair`: a keypair file, or a Ledger given as a wallet URI such as
+/// `usb://ledger?key=0`
+pub fn signer_from_path(path: &str) -> Result<Box<dyn Signer>> {
+    if !path.starts_with("usb://") {
+        let keypair = read_keypair_file(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read keypair from {}: {}", path, e))?;
+        return Ok(Box::new(keypair));
+    }
+
+    let source = parse_signer_source(path).map_err(|e| anyhow::anyhow!("Invalid wallet URI {}: {}", path, e))?;
+    let SignerSourceKind::Usb(locator) = source.kind else {
+        return Err(anyhow::anyhow!("Invalid wallet URI {}", path));
+    };
+    let wallet_manager = maybe_wallet_manager()?.ok_or_else(|| {
+        anyhow::anyhow!("No hardware wallet found; connect and unlock the Ledger and open its Solana app")
+    })?;
+    let keypair = generate_remote_keypair(
+        locator,
+        source.derivation_path.unwrap_or_default(),
+        &wallet_manager,
+        false,
+        "keypair",
+    )?;
+    Ok(Box::new(HardwareSigner(keypair)))
+}
+
+/// A hardware wallet that says what to check on the device before each signature. Its Solana
+/// app can't decode this program's instructions, so it asks to blind sign and shows only the
+/// message hash.
+struct HardwareSigner(RemoteKeypair);
+
+impl Signer for HardwareSigner {
+    fn try_pubkey(&self) -> std::result::Result<Pubkey, SignerError> {
+        self.0.try_pubkey()
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        println!("Approve the transaction on your Ledger ({})", self.0.pubkey);
+        println!("  Only approve if it shows message hash {}", hash(message));
+        self.0.try_sign_message(message)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+/// Write a transaction, with whatever signatures it has so far, to a JSON file
+pub fn write_transaction_file(path: &str, transaction: &Transaction, nonce_account: Option<&Pubkey>) -> Result<()> {
+    let file = TransactionFile {
+        transaction: base64::engine::general_purpose::STANDARD.encode(bincode::serialize(transaction)?),
+        nonce_account: nonce_account.map(|nonce_account| nonce_account.to_string()),
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&file)?)
+        .map_err(|e| anyhow::anyhow!("Failed to write transaction {}: {}", path, e))
+}
+
+/// Read a transaction written by `write_transaction_file`, with the nonce account it advances
+pub fn read_transaction_file(path: &str) -> Result<(Transaction, Option<Pubkey>)> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read transaction {}: {}", path, e))?;
+    let file: TransactionFile = serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("Invalid transaction file {}: {}", path, e))?;
+    let bytes = base64::engine::general_purpose::STANDARD.decode(&file.transaction)?;
+    let transaction = bincode::deserialize(&bytes)
+        .map_err(|e| anyhow::anyhow!("Invalid transaction in {}: {}", path, e))?;
+    let nonce_account = file.nonce_account.map(|nonce_account| nonce_account.parse()).transpose()?;
+    Ok((transaction, nonce_account))
+}
+
+/// Sign a transaction with those of `signers` it needs, leaving the other signatures as they are
+pub fn sign_transaction(transaction: &mut Transaction, signers: &[&dyn Signer]) -> Result<()> {
+    let required = &transaction.message.account_keys[..transaction.message.header.num_required_signatures as usize];
+    let signers: Vec<&dyn Signer> = signers
+        .iter()
+        .copied()
+        .filter(|signer| required.contains(&signer.pubkey()))
+        .collect();
+    let blockhash = transaction.message.recent_blockhash;
+    transaction.try_partial_sign(&signers, blockhash)?;
+    Ok(())
+}
+
+/// Merge the signatures of partially signed copies of one transaction into the first copy
+pub fn combine_signatures(copies: Vec<Transaction>) -> Result<Transaction> {
+    let mut copies = copies.into_iter();
+    let mut combined = copies.next().ok_or_else(|| anyhow::anyhow!("No signature files given"))?;
+    for copy in copies {
+        if copy.message != combined.message {
+            return Err(anyhow::anyhow!("The signature files are for different transactions"));
+        }
+        for (signature, other) in combined.signatures.iter_mut().zip(copy.signatures) {
+            if *signature == Signature::default() {
+                *signature = other;
+            }
+        }
+    }
+    Ok(combined)
+}
+
+/// The accounts whose signatures a transaction still lacks
+pub fn missing_signers(transaction: &Transaction) -> Vec<Pubkey> {
+    transaction
+        .message
+        .account_keys
+        .iter()
+        .zip(&transaction.signatures)
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(pubkey, _)| *pubkey)
+        .collect()
+}
+
+/// Fetch the raw contents of an off-chain metadata URI
+pub fn fetch_uri(uri: &str) -> Result<Vec<u8>> {
+    let response = reqwest::blocking::get(uri)
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| anyhow::anyhow!("Failed to fetch {}: {}", uri, e))?;
+    Ok(response.bytes()?.to_vec())
+}
+
+/// SHA-256 digest of a metadata document
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// A candidate row of an `add-candidates` JSON file
+#[derive(Deserialize, Debug)]
+struct CandidateRow {
+    name: String,
+    #[serde(default)]
+    party: String,
+    #[serde(default)]
+    metadata_uri: String,
+}
+
+/// Load candidates for `add-candidates`, each with the row it came from. A `.json` file holds an
+/// array of `{"name", "party", "metadata_uri"}` objects, numbered from 1. Anything else is read
+/// as CSV lines of `name,party,metadata_uri`, numbered by line; blank lines, `#` comments and a
+/// `name,...` header are skipped, and the party and metadata URI may be omitted.
+pub fn load_candidate_rows(path: &str) -> Result<Vec<(usize, NewCandidate)>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read candidates {}: {}", path, e))?;
+
+    if path.to_ascii_lowercase().ends_with(".json") {
+        let rows: Vec<CandidateRow> = serde_json::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Invalid candidates file {}: {}", path, e))?;
+        return Ok(rows
+            .into_iter()
+            .enumerate()
+            .map(|(number, row)| {
+                let entry = NewCandidate {
+                    name: row.name.trim().to_string(),
+                    party: row.party.trim().to_string(),
+                    metadata_uri: row.metadata_uri.trim().to_string(),
+                };
+                (number + 1, entry)
+            })
+            .collect());
+    }
+
+    let mut entries = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || (number == 0 && line.to_ascii_lowercase().starts_with("name,")) {
+            continue;
+        }
+        // The URI comes last, so it keeps any commas of its own
+        let mut fields = line.splitn(3, ',').map(|field| field.trim().trim_matches('"').to_string());
+        let entry = NewCandidate {
+            name: fields.next().unwrap_or_default(),
+            party: fields.next().unwrap_or_default(),
+            metadata_uri: fields.next().unwrap_or_default(),
+        };
+        entries.push((number + 1, entry));
+    }
+    Ok(entries)
+}
+
+/// Check a candidate against the program's length limits before it is submitted
+pub fn check_candidate_entry(entry: &NewCandidate) -> std::result::Result<(), String> {
+    if entry.name.is_empty() {
+        Err("no candidate name".to_string())
+    } else if entry.name.len() > MAX_CANDIDATE_NAME_LEN {
+        Err(format!("name is longer than {} bytes", MAX_CANDIDATE_NAME_LEN))
+    } else if entry.party.len() > MAX_PARTY_LEN {
+        Err(format!("party is longer than {} bytes", MAX_PARTY_LEN))
+    } else if entry.metadata_uri.len() > MAX_URI_LEN {
+        Err(format!("metadata URI is longer than {} bytes", MAX_URI_LEN))
+    } else {
+        Ok(())
+    }
+}
+
+/// Load wallets from a file with one address per line. Blank lines and `#` comments are skipped.
+pub fn load_voter_list(path: &str) -> Result<Vec<Pubkey>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read voters {}: {}", path, e))?;
+
+    let mut voters = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let voter = line
+            .parse::<Pubkey>()
+            .map_err(|_| anyhow::anyhow!("Line {} of {} is not a wallet address: {}", number + 1, path, line))?;
+        if !voters.contains(&voter) {
+            voters.push(voter);
+        }
+    }
+    Ok(voters)
+}
+
+/// Parse a duration given in seconds or with a unit suffix (`90s`, `30m`, `72h`, `7d`)
+pub fn parse_duration(value: &str) -> std::result::Result<i64, String> {
+    let (amount, unit) = match value.char_indices().last() {
+        Some((index, unit)) if unit.is_ascii_alphabetic() => (&value[..index], unit),
+        _ => (value, 's'),
+    };
+    let seconds_per_unit = match unit.to_ascii_lowercase() {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        _ => return Err(format!("Unknown duration unit in {}; use s, m, h or d", value)),
+    };
+    amount
+        .parse::<i64>()
+        .ok()
+        .filter(|amount| *amount > 0)
+        .and_then(|amount| amount.checked_mul(seconds_per_unit))
+        .ok_or_else(|| format!("Invalid duration: {}", value))
+}
+
+/// A span of seconds as its two largest units, e.g. `2d 5h` or `4m 30s`
+pub fn format_countdown(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let parts = [
+        (seconds / 86_400, "d"),
+        (seconds / 3_600 % 24, "h"),
+        (seconds / 60 % 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let first = parts.iter().position(|(amount, _)| *amount > 0).unwrap_or(parts.len() - 1);
+    parts[first..]
+        .iter()
+        .take(2)
+        .map(|(amount, unit)| format!("{}{}", amount, unit))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Number of single-character edits turning `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The name `name` was most likely a typo of, ignoring case: one it begins, or one within an
+/// edit per three characters
+pub fn closest_name<'a>(name: &str, names: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+    let allowed = (name.chars().count() / 3).max(1);
+    names
+        .into_iter()
+        .filter_map(|candidate| {
+            let lowercase = candidate.to_lowercase();
+            let distance = if lowercase.starts_with(&name) { 0 } else { edit_distance(&name, &lowercase) };
+            (distance <= allowed).then_some((distance, candidate))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Parse a gauge allocation given as `NAME=POINTS`
+pub fn parse_allocation(value: &str) -> std::result::Result<(String, u16), String> {
+    let (name, points) = value
+        .rsplit_once('=')
+        .ok_or_else(|| format!("Expected NAME=POINTS, got {}", value))?;
+    let points = points
+        .parse::<u16>()
+        .map_err(|_| format!("Invalid points for {}: {}", name, points))?;
+    Ok((name.to_string(), points))
+}
+
+/// Split `total` items across weights in proportion, giving leftovers to the largest remainders
+pub fn split_by_weight(weights: &[u16], total: usize) -> Vec<usize> {
+    let sum: u64 = weights.iter().map(|&weight| weight as u64).sum();
+    if sum == 0 {
+        return vec![0; weights.len()];
+    }
+    let shares: Vec<(usize, u64)> = weights
+        .iter()
+        .map(|&weight| {
+            let exact = weight as u64 * total as u64;
+            ((exact / sum) as usize, exact % sum)
+        })
+        .collect();
+    let mut counts: Vec<usize> = shares.iter().map(|(count, _)| *count).collect();
+    let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+    by_remainder.sort_by(|&a, &b| shares[b].1.cmp(&shares[a].1).then(a.cmp(&b)));
+    let leftover = total - counts.iter().sum::<usize>();
+    for &index in by_remainder.iter().take(leftover) {
+        counts[index] += 1;
+    }
+    counts
+}
+
+/// Parse an account for a poll's eligibility program, given as `ADDRESS` or `ADDRESS:mut`
+pub fn parse_verifier_account(value: &str) -> std::result::Result<AccountMeta, String> {
+    let (address, is_writable) = match value.strip_suffix(":mut") {
+        Some(address) => (address, true),
+        None => (value, false),
+    };
+    let pubkey = address
+        .parse::<Pubkey>()
+        .map_err(|_| format!("Invalid account address: {}", address))?;
+    Ok(AccountMeta {
+        pubkey,
+        is_signer: false,
+        is_writable,
+    })
+}
+
+/// How `get-results` prints a poll's results
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResultsFormat {
+    /// Human-readable summary
+    #[default]
+    Table,
+    /// One row per candidate, for spreadsheets
+    Csv,
+    /// A Markdown report for governance forums
+    Md,
+}
+
+/// Change in a poll's schedule that `when` waits for
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollTransition {
+    /// Voting opens: the poll is active and its start time has come
+    Start,
+    /// Voting closes: the end time has passed
+    End,
+}
+
+/// How log lines are written to stderr
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// One readable line per event
+    #[default]
+    Text,
+    /// One JSON object per event, for log aggregators
+    Json,
+}
+
+/// Logs shown without `--log-level` or `RUST_LOG`: the CLI's own from info up, others' warnings
+const DEFAULT_LOG_FILTER: &str = "warn,voting_cli=info";
+
+/// Send logs to stderr. `--log-level` sets the level of the CLI's own logs; without it, `RUST_LOG`
+/// directives apply, falling back to `DEFAULT_LOG_FILTER`. At debug level, every traced RPC call
+/// and transaction build logs its duration when it ends.
+pub fn init_logging(level: Option<tracing::Level>, format: LogFormat) -> Result<()> {
+    use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+
+    let filter = match level {
+        Some(level) => EnvFilter::try_new(format!("warn,voting_cli={}", level.as_str().to_lowercase()))?,
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER)),
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::CLOSE);
+    let initialized = match format {
+        LogFormat::Text => builder.try_init(),
+        LogFormat::Json => builder.json().try_init(),
+    };
+    initialized.map_err(|e| anyhow::anyhow!("Failed to set up logging: {}", e))
+}
+
+/// A tally in whole votes, or in the poll's weighted unit to two decimals
+pub fn format_votes(votes: u64, scale: u64) -> String {
+    if scale == 1 {
+        votes.to_string()
+    } else {
+        format!("{:.2}", votes as f64 / scale as f64)
+    }
+}
+
+// Each candidate's share of all counted votes, in percent
+fn vote_shares(candidates: &[Candidate]) -> Vec<f64> {
+    let total: u128 = candidates.iter().map(|c| c.votes as u128).sum();
+    candidates
+        .iter()
+        .map(|c| if total == 0 { 0.0 } else { c.votes as f64 * 100.0 / total as f64 })
+        .collect()
+}
+
+// Finalized winners, or the current leader of a running poll
+fn results_outcome(poll: &Poll, candidates: &[Candidate]) -> Option<String> {
+    let winners: Vec<&str> = candidates.iter().filter(|c| c.is_winner).map(|c| c.name.as_str()).collect();
+    if !winners.is_empty() {
+        return Some(format!("Winner(s): {}", winners.join(", ")));
+    }
+    let leader = candidates.iter().max_by_key(|c| c.votes)?;
+    Some(format!(
+        "Leading candidate: {} with {} votes",
+        leader.name,
+        format_votes(leader.votes, poll.vote_scale())
+    ))
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// A poll's results as CSV with a `candidate,party,votes,percentage` header
+pub fn results_csv(poll: &Poll, candidates: &[Candidate]) -> String {
+    let mut csv = String::from("candidate,party,votes,percentage\n");
+    for (candidate, share) in candidates.iter().zip(vote_shares(candidates)) {
+        csv.push_str(&format!(
+            "{},{},{},{:.2}\n",
+            csv_field(&candidate.name),
+            csv_field(&candidate.party),
+            format_votes(candidate.votes, poll.vote_scale()),
+            share
+        ));
+    }
+    csv
+}
+
+/// A poll's results as a Markdown report: question, results table, totals and the winner line
+pub fn results_markdown(poll: &Poll, candidates: &[Candidate]) -> String {
+    let cell = |value: &str| value.replace('|', "\\|");
+    let mut report = format!("## Poll {}: {}\n\n", poll.poll_id, cell(&poll.question));
+    if !poll.description.is_empty() {
+        report.push_str(&format!("{}\n\n", poll.description));
+    }
+    report.push_str("| Candidate | Party | Votes | Share |\n|---|---|---:|---:|\n");
+    for (candidate, share) in candidates.iter().zip(vote_shares(candidates)) {
+        report.push_str(&format!(
+            "| {} | {} | {} | {:.2}% |\n",
+            cell(&candidate.name),
+            cell(&candidate.party),
+            format_votes(candidate.votes, poll.vote_scale()),
+            share
+        ));
+    }
+
+    let ballots: u64 = candidates.iter().map(|c| c.raw_votes).sum();
+    report.push_str(&format!("\n**Total votes cast:** {}\n", ballots));
+    if let Some(outcome) = results_outcome(poll, candidates) {
+        report.push_str(&format!("\n**{}**\n", outcome));
+    }
+    report
+}
+
+/// Longest candidate name a results chart shows before cutting it short
+const CHART_NAME_WIDTH: usize = 24;
+
+/// A poll's results as horizontal bars `width` columns wide, scaled so the leader's bar is the
+/// longest, each followed by the votes and share. Leaders are starred.
+pub fn results_chart(poll: &Poll, candidates: &[Candidate], width: usize) -> String {
+    let top = candidates.iter().map(|c| c.votes).max().unwrap_or(0);
+    let names: Vec<String> = candidates
+        .iter()
+        .map(|c| {
+            if c.name.chars().count() > CHART_NAME_WIDTH {
+                format!("{}…", c.name.chars().take(CHART_NAME_WIDTH - 1).collect::<String>())
+            } else {
+                c.name.clone()
+            }
+        })
+        .collect();
+    let labels: Vec<String> = candidates
+        .iter()
+        .zip(vote_shares(candidates))
+        .map(|(c, share)| format!("{} ({:.1}%)", format_votes(c.votes, poll.vote_scale()), share))
+        .collect();
+    let name_width = names.iter().map(|name| name.chars().count()).max().unwrap_or(0);
+    let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+    // Name and bar are separated by two spaces, bar and label by one, and the star takes two
+    let bar_width = width.saturating_sub(name_width + label_width + 5).max(10);
+
+    let mut chart = format!("Poll {}: {}\n\n", poll.poll_id, poll.question);
+    for ((candidate, name), label) in candidates.iter().zip(&names).zip(&labels) {
+        let length = match top {
+            0 => 0,
+            top => (candidate.votes as u128 * bar_width as u128 / top as u128) as usize,
+        };
+        let star = if top > 0 && candidate.votes == top { " ★" } else { "" };
+        chart.push_str(&format!(
+            "{:<name_width$}  {:<bar_width$} {}{}\n",
+            name,
+            "█".repeat(length),
+            label,
+            star
+        ));
+    }
+
+    let ballots: u64 = candidates.iter().map(|c| c.raw_votes).sum();
+    chart.push_str(&format!("\nTotal votes cast: {}\n", ballots));
+    if let Some(outcome) = results_outcome(poll, candidates) {
+        chart.push_str(&format!("{}\n", outcome));
+    }
+    chart
+}
+
+/// A candidate's result in two polls. Votes are in each poll's unit and shares in percent; both
+/// are `None` for a poll the candidate didn't run in.
+#[derive(Serialize, Debug)]
+pub struct CandidateComparison {
+    pub name: String,
+    pub votes_a: Option<f64>,
+    pub votes_b: Option<f64>,
+    pub share_a: Option<f64>,
+    pub share_b: Option<f64>,
+}
+
+impl CandidateComparison {
+    pub fn votes_delta(&self) -> Option<f64> {
+        Some(self.votes_b? - self.votes_a?)
+    }
+
+    /// Change in vote share from the first poll to the second, in percentage points
+    pub fn share_delta(&self) -> Option<f64> {
+        Some(self.share_b? - self.share_a?)
+    }
+}
+
+/// Line up the candidates of two polls by name, ignoring case: the first poll's in its order,
+/// then those only on the second poll's ballot
+pub fn compare_candidates(
+    poll_a: &Poll,
+    candidates_a: &[Candidate],
+    poll_b: &Poll,
+    candidates_b: &[Candidate],
+) -> Vec<CandidateComparison> {
+    let votes = |poll: &Poll, candidate: &Candidate| candidate.votes as f64 / poll.vote_scale() as f64;
+    let key = |candidate: &Candidate| candidate.name.trim().to_lowercase();
+    let shares_b = vote_shares(candidates_b);
+    let mut matched = vec![false; candidates_b.len()];
+
+    let mut rows = Vec::new();
+    for (candidate, share) in candidates_a.iter().zip(vote_shares(candidates_a)) {
+        let other = candidates_b.iter().position(|other| key(other) == key(candidate));
+        if let Some(index) = other {
+            matched[index] = true;
+        }
+        rows.push(CandidateComparison {
+            name: candidate.name.clone(),
+            votes_a: Some(votes(poll_a, candidate)),
+            votes_b: other.map(|index| votes(poll_b, &candidates_b[index])),
+            share_a: Some(share),
+            share_b: other.map(|index| shares_b[index]),
+        });
+    }
+    for (index, candidate) in candidates_b.iter().enumerate().filter(|(index, _)| !matched[*index]) {
+        rows.push(CandidateComparison {
+            name: candidate.name.clone(),
+            votes_a: None,
+            votes_b: Some(votes(poll_b, candidate)),
+            share_a: None,
+            share_b: Some(shares_b[index]),
+        });
+    }
+    rows
+}
+
+/// Link to a poll for `share`. By default a solana-pay-style `solana:` URI naming the poll PDA,
+/// with the question as its label; with `base_url`, that page with the poll in its query string.
+/// Either way the candidate, when given, is prefilled by name and PDA.
+pub fn share_link(
+    base_url: Option<&str>,
+    program_id: &Pubkey,
+    poll: &Poll,
+    candidate: Option<(&str, &Pubkey)>,
+    cluster: &str,
+) -> Result<String> {
+    let (poll_address, _) = get_poll_address(program_id, poll.poll_id);
+    let mut link = match base_url {
+        Some(base_url) => {
+            let mut link =
+                reqwest::Url::parse(base_url).map_err(|e| anyhow::anyhow!("Invalid link {}: {}", base_url, e))?;
+            link.query_pairs_mut()
+                .append_pair("poll", &poll_address.to_string())
+                .append_pair("poll_id", &poll.poll_id.to_string());
+            link
+        }
+        None => {
+            let mut link = reqwest::Url::parse(&format!("solana:{}", poll_address))?;
+            link.query_pairs_mut().append_pair("label", &poll.question);
+            link
+        }
+    };
+    {
+        let mut query = link.query_pairs_mut();
+        query.append_pair("program", &program_id.to_string()).append_pair("cluster", cluster);
+        if let Some((name, address)) = candidate {
+            query.append_pair("candidate", name).append_pair("candidate_account", &address.to_string());
+        }
+    }
+    Ok(link.into())
+}
+
+/// Solana Explorer page of a transaction on `cluster`. Public clusters are recognized by RPC URL,
+/// so they keep their own page when given with a separate websocket URL.
+pub fn explorer_link(signature: &str, cluster: &Cluster) -> String {
+    let link = format!("https://explorer.solana.com/tx/{}", signature);
+    match cluster.url() {
+        url if url == Cluster::Mainnet.url() => link,
+        url if url == Cluster::Devnet.url() => format!("{}?cluster=devnet", link),
+        url if url == Cluster::Testnet.url() => format!("{}?cluster=testnet", link),
+        _ => match reqwest::Url::parse(&link) {
+            Ok(mut url) => {
+                url.query_pairs_mut().append_pair("cluster", "custom").append_pair("customUrl", cluster.url());
+                url.into()
+            }
+            Err(_) => link,
+        },
+    }
+}
+
+/// A QR code of `data` drawn with half-block characters, two rows of modules per line
+pub fn qr_text(data: &str) -> Result<String> {
+    use qrcode::render::unicode::Dense1x2;
+
+    let code = qrcode::QrCode::new(data).map_err(|e| anyhow::anyhow!("Can't encode a QR code: {}", e))?;
+    // Light modules are drawn as blocks, so the code scans on the usual dark terminal background
+    Ok(code.render::<Dense1x2>().dark_color(Dense1x2::Light).light_color(Dense1x2::Dark).build())
+}
+
+/// Save a QR code of `data` as a PNG, sized for print
+pub fn save_qr_png(data: &str, path: &str) -> Result<()> {
+    let code = qrcode::QrCode::new(data).map_err(|e| anyhow::anyhow!("Can't encode a QR code: {}", e))?;
+    code.render::<image::Luma<u8>>()
+        .min_dimensions(512, 512)
+        .build()
+        .save(path)
+        .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path, e))
+}
+
+/// Lowercase hex encoding of a byte slice
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Serde helpers for the `--json` output: keys as base58 and hashes as hex, as printed elsewhere
+
+pub fn serialize_pubkey<S: Serializer>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(key)
+}
+
+pub fn serialize_optional_pubkey<S: Serializer>(key: &Option<Pubkey>, serializer: S) -> Result<S::Ok, S::Error> {
+    match key {
+        Some(key) => serializer.collect_str(key),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn serialize_hex<S: Serializer>(bytes: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&to_hex(bytes))
+}
+
+// Serde only derives arrays of up to 32 elements
+pub fn serialize_histogram<S: Serializer>(buckets: &[u32; 48], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(buckets)
+}
+
+/// A poll as JSON, with its status as of `now` rather than the last stored one
+pub fn poll_to_json(poll: &Poll, now: i64) -> Result<serde_json::Value> {
+    let mut value = serde_json::to_value(poll)?;
+    value["status"] = serde_json::to_value(poll.status_at(now))?;
+    Ok(value)
+}
+
+/// Ask a yes/no question on the terminal; anything but `y` or `yes` declines
+pub fn confirm(prompt: &str) -> Result<bool> {
+    use std::io::Write;
+
+    print!("{} [y/N] ", prompt);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_client::anchor_lang::AnchorDeserialize;
+
+    use super::*;
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("alice", "alice"), 0);
+        assert_eq!(edit_distance("alice", "alise"), 1);
+        assert_eq!(edit_distance("alice", "alic"), 1);
+        assert_eq!(edit_distance("bob", "bobby"), 2);
+        assert_eq!(edit_distance("", "bob"), 3);
+    }
+
+    #[test]
+    fn closest_name_suggests_typos_and_prefixes() {
+        let names = ["Alice", "Bob", "Charlotte"];
+        assert_eq!(closest_name("alise", names), Some("Alice"));
+        assert_eq!(closest_name("char", names), Some("Charlotte"));
+        assert_eq!(closest_name(" BOB ", names), Some("Bob"));
+    }
+
+    #[test]
+    fn closest_name_ignores_distant_names() {
+        let names = ["Alice", "Bob"];
+        assert_eq!(closest_name("Zed", names), None);
+        assert_eq!(closest_name("  ", names), None);
+    }
+
+    #[test]
+    fn parse_duration_reads_units() {
+        assert_eq!(parse_duration("90"), Ok(90));
+        assert_eq!(parse_duration("90s"), Ok(90));
+        assert_eq!(parse_duration("30m"), Ok(30 * 60));
+        assert_eq!(parse_duration("72H"), Ok(72 * 60 * 60));
+        assert_eq!(parse_duration("7d"), Ok(7 * 24 * 60 * 60));
+    }
+
+    #[test]
+    fn parse_duration_rejects_bad_values() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("0h").is_err());
+        assert!(parse_duration("-5m").is_err());
+        assert!(parse_duration("3w").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration(&format!("{}d", i64::MAX)).is_err());
+    }
+
+    // Write `contents` to a scratch file with the given extension and load it as candidates
+    fn load_rows(extension: &str, contents: &str) -> Vec<(usize, NewCandidate)> {
+        let path = std::env::temp_dir().join(format!("voting-cli-candidates-{}.{}", std::process::id(), extension));
+        std::fs::write(&path, contents).unwrap();
+        let rows = load_candidate_rows(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        rows
+    }
+
+    fn candidate(name: &str, party: &str, metadata_uri: &str) -> NewCandidate {
+        NewCandidate {
+            name: name.to_string(),
+            party: party.to_string(),
+            metadata_uri: metadata_uri.to_string(),
+        }
+    }
+
+    #[test]
+    fn load_candidate_rows_reads_csv_lines() {
+        let rows = load_rows(
+            "csv",
+            "name,party,metadata_uri\n\n# incumbents\nAlice, Blue ,https://example.com/a.json?x=1,2\nBob\n\"Carol\",Red\n",
+        );
+        let rows: Vec<_> = rows
+            .into_iter()
+            .map(|(row, entry)| (row, entry.name, entry.party, entry.metadata_uri))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (4, "Alice".to_string(), "Blue".to_string(), "https://example.com/a.json?x=1,2".to_string()),
+                (5, "Bob".to_string(), String::new(), String::new()),
+                (6, "Carol".to_string(), "Red".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn load_candidate_rows_reads_json_arrays() {
+        let rows = load_rows(
+            "JSON",
+            r#"[{"name": " Alice ", "party": "Blue", "metadata_uri": "https://example.com/a.json"}, {"name": "Bob"}]"#,
+        );
+        let rows: Vec<_> = rows
+            .into_iter()
+            .map(|(row, entry)| (row, entry.name, entry.party, entry.metadata_uri))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (1, "Alice".to_string(), "Blue".to_string(), "https://example.com/a.json".to_string()),
+                (2, "Bob".to_string(), String::new(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_candidate_entry_enforces_program_limits() {
+        assert!(check_candidate_entry(&candidate("Alice", "Blue", "https://example.com/a.json")).is_ok());
+        assert!(check_candidate_entry(&candidate("", "Blue", "")).is_err());
+        assert!(check_candidate_entry(&candidate(&"a".repeat(MAX_CANDIDATE_NAME_LEN + 1), "", "")).is_err());
+        assert!(check_candidate_entry(&candidate("Alice", &"p".repeat(MAX_PARTY_LEN + 1), "")).is_err());
+        assert!(check_candidate_entry(&candidate("Alice", "", &"u".repeat(MAX_URI_LEN + 1))).is_err());
+    }
+
+    // A poll read from a zeroed account, which decodes to every field's default
+    fn test_poll(question: &str) -> Poll {
+        let mut poll = Poll::deserialize(&mut &[0u8; 4096][..]).unwrap();
+        poll.poll_id = 7;
+        poll.question = question.to_string();
+        poll
+    }
+
+    fn test_candidate(name: &str, party: &str, votes: u64) -> Candidate {
+        Candidate {
+            poll: Pubkey::default(),
+            name: name.to_string(),
+            party: party.to_string(),
+            votes,
+            payout_wallet: Pubkey::default(),
+            depositor: Pubkey::default(),
+            deposit: 0,
+            metadata_uri: String::new(),
+            version: 0,
+            raw_votes: votes,
+            index: 0,
+            is_winner: false,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn format_votes_shows_weighted_units() {
+        assert_eq!(format_votes(7, 1), "7");
+        assert_eq!(format_votes(15_000, 10_000), "1.50");
+    }
+
+    #[test]
+    fn results_csv_quotes_fields() {
+        let candidates = [test_candidate("Alice", "Blue", 3), test_candidate("Bob, Jr.", "Say \"hi\"", 1)];
+        assert_eq!(
+            results_csv(&test_poll("Mascot?"), &candidates),
+            "candidate,party,votes,percentage\nAlice,Blue,3,75.00\n\"Bob, Jr.\",\"Say \"\"hi\"\"\",1,25.00\n"
+        );
+    }
+
+    #[test]
+    fn results_markdown_reports_the_leader_or_winner() {
+        let poll = test_poll("Mascot | vote?");
+        let mut candidates = vec![test_candidate("Alice", "Blue", 3), test_candidate("Bob", "", 1)];
+        assert_eq!(
+            results_markdown(&poll, &candidates),
+            "## Poll 7: Mascot \\| vote?\n\n\
+             | Candidate | Party | Votes | Share |\n|---|---|---:|---:|\n\
+             | Alice | Blue | 3 | 75.00% |\n\
+             | Bob |  | 1 | 25.00% |\n\n\
+             **Total votes cast:** 4\n\n\
+             **Leading candidate: Alice with 3 votes**\n"
+        );
+
+        candidates[0].is_winner = true;
+        assert!(results_markdown(&poll, &candidates).ends_with("\n**Winner(s): Alice**\n"));
+    }
+
+    #[test]
+    fn results_chart_scales_bars_to_the_leader() {
+        let candidates = [test_candidate("Alice", "", 3), test_candidate("Bob", "", 1)];
+        assert_eq!(
+            results_chart(&test_poll("Mascot?"), &candidates, 40),
+            format!(
+                "Poll 7: Mascot?\n\n\
+                 Alice  {} 3 (75.0%) ★\n\
+                 Bob    {}{} 1 (25.0%)\n\n\
+                 Total votes cast: 4\n\
+                 Leading candidate: Alice with 3 votes\n",
+                "█".repeat(21),
+                "█".repeat(7),
+                " ".repeat(14)
+            )
+        );
+    }
+
+    #[test]
+    fn results_chart_shortens_long_names_and_skips_empty_bars() {
+        let candidates = [test_candidate(&"N".repeat(30), "", 0), test_candidate("Bob", "", 0)];
+        let chart = results_chart(&test_poll("Mascot?"), &candidates, 80);
+        assert!(chart.contains(&format!("{}…  ", "N".repeat(23))));
+        assert!(!chart.contains('█'));
+        assert!(!chart.contains('★'));
+    }
+}
@@ -0,0 +1,184 @@
+use anchor_client::solana_sdk::{native_token::lamports_to_sol, signer::Signer};
+use anyhow::Result;
+use dialoguer::{Confirm, Input, Select};
+
+use crate::client::{NewCandidate, PollFilter, PollOptions, VotingClient, WeightDecay};
+use crate::utils::{self, CANDIDATE_ACCOUNT_SPACE, POLL_ACCOUNT_SPACE};
+
+// Voting modes offered by the wizard, in the order they are listed
+const MODES: [&str; 3] = [
+    "Simple: one vote per wallet",
+    "Stake-weighted: votes count the voter's delegated SOL",
+    "Decaying: earlier votes weigh more",
+];
+
+/// Walk the user through creating a poll: question, schedule, voting mode and candidates.
+/// Shows the full configuration and its estimated cost, then creates the poll, adds the
+/// candidates and optionally activates it.
+pub fn run(voting_client: &VotingClient<Box<dyn Signer>>) -> Result<()> {
+    // Suggest the ID after the highest listed poll
+    let suggested_id = voting_client
+        .get_all_polls(&PollFilter::default())?
+        .last()
+        .map_or(1, |(_, poll)| poll.poll_id + 1);
+    let poll_id: u64 = Input::new()
+        .with_prompt("Poll ID")
+        .default(suggested_id)
+        .validate_with(|id: &u64| match voting_client.get_poll(*id) {
+            Ok(_) => Err(format!("Poll {} already exists", id)),
+            Err(_) => Ok(()),
+        })
+        .interact_text()?;
+
+    let question: String = Input::new()
+        .with_prompt("Question")
+        .validate_with(|question: &String| match question.trim().len() {
+            0 => Err("The question cannot be empty"),
+            1..=200 => Ok(()),
+            _ => Err("The question is limited to 200 bytes"),
+        })
+        .interact_text()?;
+    let description: String = Input::new()
+        .with_prompt("Description")
+        .allow_empty(true)
+        .validate_with(|description: &String| {
+            if description.len() <= 280 {
+                Ok(())
+            } else {
+                Err("The description is limited to 280 bytes")
+            }
+        })
+        .interact_text()?;
+
+    let start: String = Input::new()
+        .with_prompt("Start (Unix timestamp or now)")
+        .default("now".to_string())
+        .validate_with(|start: &String| {
+            if start.eq_ignore_ascii_case("now") || start.parse::<i64>().is_ok() {
+                Ok(())
+            } else {
+                Err("Enter a Unix timestamp or now")
+            }
+        })
+        .interact_text()?;
+    let duration: String = Input::new()
+        .with_prompt("Duration (e.g. 30m, 72h, 7d)")
+        .default("72h".to_string())
+        .validate_with(|duration: &String| utils::parse_duration(duration).map(|_| ()))
+        .interact_text()?;
+    let duration = utils::parse_duration(&duration).map_err(|e| anyhow::anyhow!(e))?;
+
+    let mode = Select::new().with_prompt("Voting mode").items(&MODES).default(0).interact()?;
+    let weight_decay = if mode == 2 {
+        let end_bps: u16 = Input::new()
+            .with_prompt("Weight of a vote cast at the very end, in basis points")
+            .default(5_000)
+            .validate_with(|end_bps: &u16| {
+                if *end_bps <= 10_000 {
+                    Ok(())
+                } else {
+                    Err("At most 10000 basis points")
+                }
+            })
+            .interact_text()?;
+        WeightDecay::Linear { end_bps }
+    } else {
+        WeightDecay::None
+    };
+
+    let mut candidates: Vec<NewCandidate> = Vec::new();
+    loop {
+        let name: String = Input::new()
+            .with_prompt(format!("Candidate {} name (empty to finish)", candidates.len() + 1))
+            .allow_empty(true)
+            .interact_text()?;
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            if candidates.is_empty() {
+                println!("A poll needs at least one candidate");
+                continue;
+            }
+            break;
+        }
+        if candidates.iter().any(|candidate| candidate.name == name) {
+            println!("{} is already on the ballot", name);
+            continue;
+        }
+        let party: String = Input::new().with_prompt("Party").allow_empty(true).interact_text()?;
+        let entry = NewCandidate {
+            name,
+            party: party.trim().to_string(),
+            metadata_uri: String::new(),
+        };
+        match utils::check_candidate_entry(&entry) {
+            Ok(()) => candidates.push(entry),
+            Err(reason) => println!("{} can't be added: {}", entry.name, reason),
+        }
+    }
+    let activate = Confirm::new()
+        .with_prompt("Activate the poll once its candidates are added?")
+        .default(true)
+        .interact()?;
+
+    // Rent for every account created, plus the configured creation fee
+    let poll_rent = voting_client.rent_exempt_minimum(POLL_ACCOUNT_SPACE)?;
+    let candidate_rent = voting_client.rent_exempt_minimum(CANDIDATE_ACCOUNT_SPACE)?;
+    let creation_fee = voting_client.get_config()?.poll_creation_fee;
+    let total_cost = poll_rent + candidate_rent * candidates.len() as u64 + creation_fee;
+
+    println!("\n=== New Poll {} ===", poll_id);
+    println!("Question: {}", question);
+    println!("Description: {}", description);
+    println!("Start: {}", start);
+    println!("Duration: {} seconds", duration);
+    println!("Mode: {}", MODES[mode]);
+    println!("Candidates:");
+    for candidate in &candidates {
+        println!("  • {} ({})", candidate.name, candidate.party);
+    }
+    println!("Activate: {}", if activate { "yes" } else { "no, stays a draft" });
+    println!(
+        "Estimated cost: {} SOL ({} poll rent, {} per candidate, {} creation fee, in lamports) plus transaction fees",
+        lamports_to_sol(total_cost),
+        poll_rent,
+        candidate_rent,
+        creation_fee
+    );
+
+    if !Confirm::new().with_prompt("Create this poll?").default(false).interact()? {
+        println!("Cancelled, nothing was submitted");
+        return Ok(());
+    }
+
+    // `now` is resolved only once confirmed, so time spent on the preview doesn't age the start
+    let start_time = if start.eq_ignore_ascii_case("now") {
+        voting_client.cluster_time()?
+    } else {
+        start.parse::<i64>()?
+    };
+    let end_time = start_time
+        .checked_add(duration)
+        .ok_or_else(|| anyhow::anyhow!("The duration is too long"))?;
+    let options = PollOptions {
+        stake_weighted: mode == 1,
+        weight_decay,
+        ..PollOptions::default()
+    };
+
+    let signature = voting_client.initialize_poll(poll_id, question, description, start_time, end_time, options)?;
+    println!("✓ Poll {} created", poll_id);
+    println!("  Transaction: {}", signature);
+
+    for outcome in voting_client.add_candidates(poll_id, &candidates)? {
+        let signature = outcome?;
+        println!("✓ Candidates added");
+        println!("  Transaction: {}", signature);
+    }
+
+    if activate {
+        let signature = voting_client.activate_poll(poll_id, None)?;
+        println!("✓ Poll activated, the candidate list is now frozen");
+        println!("  Transaction: {}", signature);
+    }
+    Ok(())
+}
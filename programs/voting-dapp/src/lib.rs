@@ -1,4 +1,20 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    bpf_loader_upgradeable,
+    instruction::Instruction,
+    program::{invoke, invoke_signed},
+    sysvar::{instructions as instructions_sysvar, stake_history as stake_history_sysvar},
+};
+use anchor_lang::system_program::{
+    allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use curve25519_dalek::scalar::Scalar;
+use solana_curve25519::{
+    ristretto::{add_ristretto, multiscalar_multiply_ristretto, validate_ristretto, PodRistrettoPoint},
+    scalar::PodScalar,
+};
+use solana_keccak_hasher as keccak;
 
 declare_id!("ErWpLzQeDSoB1nuTs2x1d2yHA2AsBvZHg4nNkAusyNK8");
 
@@ -6,12 +22,355 @@ declare_id!("ErWpLzQeDSoB1nuTs2x1d2yHA2AsBvZHg4nNkAusyNK8");
 const POLL_SEED: &[u8] = b"poll";
 const CANDIDATE_SEED: &[u8] = b"candidate";
 const RECEIPT_SEED: &[u8] = b"receipt";
+const CONFIG_SEED: &[u8] = b"config";
+const TREASURY_SEED: &[u8] = b"treasury";
+const REWARD_VAULT_SEED: &[u8] = b"reward_vault";
+const BALLOT_SEED: &[u8] = b"ballot";
+const PROPOSAL_SEED: &[u8] = b"proposal";
+const POLL_AUTHORITY_SEED: &[u8] = b"poll_authority";
+const POLL_PROPOSAL_SEED: &[u8] = b"poll_proposal";
+const CREATOR_PASS_SEED: &[u8] = b"creator_pass";
+const POLL_ARCHIVE_SEED: &[u8] = b"poll_archive";
+const SHIELDED_TALLY_SEED: &[u8] = b"shielded_tally";
+const STAKE_MARKER_SEED: &[u8] = b"stake_marker";
+const CONVICTION_LOCK_SEED: &[u8] = b"conviction_lock";
+const CONVICTION_VAULT_SEED: &[u8] = b"conviction_vault";
+const SESSION_SEED: &[u8] = b"session";
+const ACCESS_GRANT_SEED: &[u8] = b"access_grant";
+const WEIGHT_RECORD_SEED: &[u8] = b"weight_record";
+const CREATOR_STATS_SEED: &[u8] = b"creator_stats";
+const POLL_RESULT_SEED: &[u8] = b"poll_result";
+const COMPRESSED_VOTER_SEED: &[u8] = b"compressed_voter";
+const NOMINATION_SEED: &[u8] = b"nomination";
+const GOVERNANCE_LOCK_SEED: &[u8] = b"governance_lock";
+
+// Current layout versions, bumped whenever fields are appended
+const POLL_VERSION: u8 = 30;
+const CANDIDATE_VERSION: u8 = 5;
+const RECEIPT_VERSION: u8 = 5;
+
+// spl-account-compression and its noop log wrapper, used for compressed receipts
+const COMPRESSION_PROGRAM_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+const NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+// Instruction discriminators of the spl-account-compression program
+const INIT_EMPTY_MERKLE_TREE_DISCRIMINATOR: [u8; 8] = [191, 11, 119, 7, 180, 107, 220, 110];
+const APPEND_DISCRIMINATOR: [u8; 8] = [149, 120, 18, 222, 236, 225, 88, 203];
+const REPLACE_LEAF_DISCRIMINATOR: [u8; 8] = [204, 165, 76, 100, 73, 147, 0, 128];
+
+// Bubblegum, used to mint "I Voted" compressed NFTs
+const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+const MINT_V1_DISCRIMINATOR: [u8; 8] = [145, 98, 192, 118, 184, 147, 118, 104];
+
+// Token Metadata and the associated token program, used for winner badges
+const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+// Native stake program, whose accounts can back a voter's eligibility
+const STAKE_PROGRAM_ID: Pubkey = pubkey!("Stake11111111111111111111111111111111111111");
+
+// Share of the cluster's effective stake that may finish warming up each epoch
+const STAKE_WARMUP_RATE_BPS: u128 = 900;
+
+// spl-governance account type tags of token owner records
+const TOKEN_OWNER_RECORD_V1: u8 = 2;
+const TOKEN_OWNER_RECORD_V2: u8 = 17;
+// spl-governance `SetTokenOwnerRecordLock` instruction index and realm config PDA prefix
+const SET_TOKEN_OWNER_RECORD_LOCK: u8 = 31;
+const REALM_CONFIG_SEED: &[u8] = b"realm-config";
+
+// Solana Attestation Service account type tag of an attestation
+const ATTESTATION_ACCOUNT_TYPE: u8 = 2;
+
+// Clockwork thread program, which runs `finalize_poll` for polls scheduled to finalize
+// themselves. Threads are PDAs of `["thread", authority, id]` under it.
+const THREAD_PROGRAM_ID: Pubkey = pubkey!("CLoCKyJ6DXBJqqu2VWx9RLbgnwwR6BMHHuyasVmfMzBh");
+const THREAD_CREATE_DISCRIMINATOR: [u8; 8] = [54, 1, 238, 224, 71, 244, 252, 173];
+const THREAD_DELETE_DISCRIMINATOR: [u8; 8] = [146, 6, 95, 17, 35, 98, 44, 140];
+const THREAD_SEED: &[u8] = b"thread";
+const FINALIZE_THREAD_ID: &[u8] = b"finalize";
+
+// How long after a poll ends its finalization thread fires, in seconds
+const AUTO_FINALIZE_DELAY: i64 = 60;
+
+// Switchboard On-Demand, whose randomness accounts shuffle ballots and break ties. After the
+// discriminator they hold the authority, queue, seed slothash, seed slot, oracle, reveal slot
+// and the revealed value.
+const SWITCHBOARD_PROGRAM_ID: Pubkey = pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+const RANDOMNESS_DISCRIMINATOR: [u8; 8] = [10, 66, 229, 135, 220, 239, 217, 114];
+const RANDOMNESS_SEED_SLOT_OFFSET: usize = 104;
+const RANDOMNESS_REVEAL_SLOT_OFFSET: usize = 144;
+const RANDOMNESS_VALUE_OFFSET: usize = 152;
+
+// Slots a randomness request may stay unrevealed before anyone can cancel it (about five minutes)
+const RANDOMNESS_TIMEOUT_SLOTS: u64 = 750;
+
+// How long after a poll ends a tie waits for its tie-break before every tied candidate wins (7 days)
+const TIE_BREAK_WINDOW: i64 = 7 * 24 * 60 * 60;
+
+// `verify(voter)` instruction of a poll's eligibility program
+const VERIFY_DISCRIMINATOR: [u8; 8] = [133, 161, 141, 48, 120, 198, 88, 150];
+
+// Token Metadata instruction indexes
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+const CREATE_MASTER_EDITION_V3: u8 = 17;
+
+// Full vote weight in basis points, used by polls with weight decay
+const FULL_WEIGHT_BPS: u64 = 10_000;
+
+// Time after a poll ends before anyone may close its receipts (30 days)
+const RECEIPT_GRACE_PERIOD: i64 = 30 * 24 * 60 * 60;
+
+// Share of a closed receipt's rent paid to the cranker, in basis points
+const CRANK_REWARD_BPS: u64 = 1_000;
+
+// Size limits of the instruction a proposal executes
+const MAX_PROPOSAL_ACCOUNTS: usize = 10;
+const MAX_PROPOSAL_DATA_LEN: usize = 256;
+
+// Maximum number of council members approving poll creation
+const MAX_COUNCIL_MEMBERS: usize = 10;
+
+// Highest per-creator poll creation cap, since creator stats keep a timestamp per poll in the window
+const MAX_POLLS_PER_WINDOW: u16 = 20;
+
+// Most auditors a poll can register, one bit each in a result's attestation bitmap
+const MAX_AUDITORS: usize = 16;
+
+// Maximum number of candidate totals an archive can hold
+const MAX_ARCHIVED_CANDIDATES: usize = 20;
+
+// Self-nominations stop once a poll has this many candidates, so one transaction can still
+// finalize or archive it
+const MAX_OPEN_CANDIDATES: u64 = 20;
+
+// Shielded ballots carry a ciphertext and proof per candidate, so they only fit one transaction
+// for a handful of candidates
+const MAX_SHIELDED_CANDIDATES: u64 = 4;
+
+// Compressed Ristretto basepoint, the generator shielded ballots are encrypted against
+const RISTRETTO_BASEPOINT: [u8; 32] = [
+    0xe2, 0xf2, 0xae, 0x0a, 0x6a, 0xbc, 0x4e, 0x71, 0xa8, 0x84, 0xa9, 0x61, 0xc5, 0x00, 0x51, 0x5f,
+    0x58, 0xe3, 0x0b, 0x6a, 0xa5, 0x82, 0xdd, 0x8d, 0xb6, 0xa6, 0x59, 0x45, 0xe0, 0x8d, 0x2d, 0x76,
+];
+
+// Maximum number of races linked under one ballot
+const MAX_BALLOT_RACES: usize = 10;
+
+// Longest a session key may stay valid, in slots (about a day at 400ms slots)
+const MAX_SESSION_SLOTS: u64 = 216_000;
+
+// Poll categorization limits
+const MAX_CATEGORY_LEN: usize = 32;
+const MAX_POLL_TAGS: usize = 5;
+const MAX_TAG_LEN: usize = 16;
+
+// Maximum length of an off-chain metadata URI
+const MAX_URI_LEN: usize = 200;
+
+// Maximum length of a candidate name, in bytes
+const MAX_CANDIDATE_NAME_LEN: usize = 50;
+
+// How far in the past a new poll's start time may be, to allow for clock drift (5 minutes)
+const START_TIME_GRACE_PERIOD: i64 = 5 * 60;
+
+// Text limits: descriptions beyond the initially allocated length grow the account
+const MAX_QUESTION_LEN: usize = 200;
+const BASE_DESCRIPTION_LEN: usize = 280;
+const MAX_DESCRIPTION_LEN: usize = 4000;
+
+// Most candidates a single gauge vote may split its points across
+const MAX_GAUGE_ALLOCATIONS: usize = 10;
+
+// Extra time conviction votes keep their tokens locked past the poll's end (30 and 90 days)
+const CONVICTION_MONTH: i64 = 30 * 24 * 60 * 60;
+const CONVICTION_QUARTER: i64 = 90 * 24 * 60 * 60;
 
 #[program]
 pub mod voting_dapp {
     use super::*;
 
-    /// Initialize a new poll
+    /// Initialize the global program config and treasury
+    pub fn initialize_config(ctx: Context<InitializeConfig>, poll_creation_fee: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.poll_creation_fee = poll_creation_fee;
+        config.council = Vec::new();
+        config.council_threshold = 0;
+        config.restrict_creators = false;
+        config.max_poll_duration = 0;
+        config.max_polls_per_window = 0;
+        config.creation_window = 0;
+
+        ctx.accounts.treasury.total_collected = 0;
+
+        msg!("Config initialized with poll creation fee: {} lamports", poll_creation_fee);
+        Ok(())
+    }
+
+    /// Withdraw collected fees from the treasury
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        // Keep the treasury account rent-exempt
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+        let rent_minimum = Rent::get()?.minimum_balance(treasury_info.data_len());
+        let available = treasury_info.lamports().saturating_sub(rent_minimum);
+        require!(amount <= available, ErrorCode::InsufficientTreasuryFunds);
+
+        treasury_info.sub_lamports(amount)?;
+        ctx.accounts.recipient.add_lamports(amount)?;
+
+        // Every withdrawal is published so anyone can audit fund movements
+        emit_cpi!(TreasuryWithdrawal {
+            admin: ctx.accounts.admin.key(),
+            recipient: ctx.accounts.recipient.key(),
+            amount,
+            remaining: treasury_info.lamports(),
+        });
+
+        msg!("Withdrew {} lamports from the treasury", amount);
+        Ok(())
+    }
+
+    /// Change the fees the program charges into the treasury (admin only)
+    pub fn set_fee_schedule(ctx: Context<SetFeeSchedule>, poll_creation_fee: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_poll_creation_fee = config.poll_creation_fee;
+        config.poll_creation_fee = poll_creation_fee;
+
+        emit_cpi!(FeeScheduleUpdated {
+            admin: ctx.accounts.admin.key(),
+            previous_poll_creation_fee,
+            poll_creation_fee,
+        });
+
+        msg!("Poll creation fee changed from {} to {} lamports", previous_poll_creation_fee, poll_creation_fee);
+        Ok(())
+    }
+
+    /// Configure the council that must approve new polls. A threshold of 0 disables council mode.
+    pub fn set_council(ctx: Context<SetCouncil>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        require!(members.len() <= MAX_COUNCIL_MEMBERS, ErrorCode::TooManyCouncilMembers);
+        require!(threshold as usize <= members.len(), ErrorCode::InvalidCouncilThreshold);
+        require!(
+            members.iter().enumerate().all(|(i, member)| !members[..i].contains(member)),
+            ErrorCode::DuplicateCouncilMember
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.council = members;
+        config.council_threshold = threshold;
+
+        msg!("Council set: {} of {} approvals required", threshold, config.council.len());
+        Ok(())
+    }
+
+    /// Turn the creator allowlist on or off. While on, only wallets holding a creator pass can create polls.
+    pub fn set_creator_allowlist(ctx: Context<SetCreatorAllowlist>, enabled: bool) -> Result<()> {
+        ctx.accounts.config.restrict_creators = enabled;
+
+        msg!("Creator allowlist {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// Cap how far from creation a poll's end time may be, in seconds (0 removes the cap)
+    pub fn set_max_poll_duration(ctx: Context<SetMaxPollDuration>, max_poll_duration: i64) -> Result<()> {
+        require!(max_poll_duration >= 0, ErrorCode::InvalidTimeRange);
+        ctx.accounts.config.max_poll_duration = max_poll_duration;
+
+        msg!("Maximum poll duration set to {} seconds", max_poll_duration);
+        Ok(())
+    }
+
+    /// Cap how many polls each creator may create within a rolling window, in seconds
+    /// (a cap of 0 removes the limit)
+    pub fn set_creation_limit(
+        ctx: Context<SetCreationLimit>,
+        max_polls_per_window: u16,
+        creation_window: i64,
+    ) -> Result<()> {
+        require!(
+            max_polls_per_window <= MAX_POLLS_PER_WINDOW
+                && creation_window >= 0
+                && (max_polls_per_window == 0 || creation_window > 0),
+            ErrorCode::InvalidCreationLimit
+        );
+        let config = &mut ctx.accounts.config;
+        config.max_polls_per_window = max_polls_per_window;
+        config.creation_window = creation_window;
+
+        msg!(
+            "Creation limit set to {} polls per {} seconds",
+            max_polls_per_window,
+            creation_window
+        );
+        Ok(())
+    }
+
+    /// Issue a creator pass allowing a wallet to create polls
+    pub fn grant_creator_pass(ctx: Context<GrantCreatorPass>, creator: Pubkey) -> Result<()> {
+        let pass = &mut ctx.accounts.creator_pass;
+        pass.creator = creator;
+        pass.granted_at = Clock::get()?.unix_timestamp;
+
+        msg!("Creator pass granted to {}", creator);
+        Ok(())
+    }
+
+    /// Revoke a wallet's creator pass, returning its rent to the admin
+    pub fn revoke_creator_pass(ctx: Context<RevokeCreatorPass>) -> Result<()> {
+        msg!("Creator pass revoked from {}", ctx.accounts.creator_pass.creator);
+        Ok(())
+    }
+
+    /// Stage a poll for council approval before it can be created
+    pub fn propose_poll(ctx: Context<ProposePoll>, poll_id: u64, question: String) -> Result<()> {
+        require!(!question.trim().is_empty(), ErrorCode::EmptyQuestion);
+        require!(question.len() <= MAX_QUESTION_LEN, ErrorCode::QuestionTooLong);
+
+        let proposal = &mut ctx.accounts.poll_proposal;
+        proposal.poll_id = poll_id;
+        proposal.creator = ctx.accounts.creator.key();
+        proposal.question = question;
+        proposal.approvals = Vec::new();
+
+        msg!("Poll {} proposed for council approval", poll_id);
+        Ok(())
+    }
+
+    /// Withdraw a staged poll, returning its rent to the proposer. The admin may also clear
+    /// proposals, so a poll ID can't be held by a proposal the council will never approve.
+    pub fn cancel_poll_proposal(ctx: Context<CancelPollProposal>) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        require!(
+            authority == ctx.accounts.poll_proposal.creator || authority == ctx.accounts.config.admin,
+            ErrorCode::Unauthorized
+        );
+
+        msg!("Proposal for poll {} cancelled", ctx.accounts.poll_proposal.poll_id);
+        Ok(())
+    }
+
+    /// Record a council member's approval of a staged poll
+    pub fn approve_poll_creation(ctx: Context<ApprovePollCreation>) -> Result<()> {
+        let member = ctx.accounts.member.key();
+        require!(ctx.accounts.config.council.contains(&member), ErrorCode::NotCouncilMember);
+
+        let proposal = &mut ctx.accounts.poll_proposal;
+        require!(!proposal.approvals.contains(&member), ErrorCode::AlreadyApproved);
+        proposal.approvals.push(member);
+
+        msg!(
+            "Poll {} approved by {} ({} approvals)",
+            proposal.poll_id,
+            member,
+            proposal.approvals.len()
+        );
+        Ok(())
+    }
+
+    /// Initialize a new poll.
+    /// In council mode the poll must have been proposed and approved by enough council members;
+    /// the staging account is closed and its rent returned to the creator.
     pub fn initialize_poll(
         ctx: Context<InitializePoll>,
         poll_id: u64,
@@ -19,9 +378,205 @@ pub mod voting_dapp {
         description: String,
         start_time: i64,
         end_time: i64,
+        options: PollOptions,
     ) -> Result<()> {
-        // Validate that the start time is before the end time
+        let clock = Clock::get()?.unix_timestamp;
+        require!(!question.trim().is_empty(), ErrorCode::EmptyQuestion);
+        require!(question.len() <= MAX_QUESTION_LEN, ErrorCode::QuestionTooLong);
+        // Longer descriptions grow the account later, through `update_poll`
+        require!(description.len() <= BASE_DESCRIPTION_LEN, ErrorCode::DescriptionTooLong);
+
+        // Validate the voting window: ordered, not already well underway, and within the allowed length
         require!(start_time < end_time, ErrorCode::InvalidTimeRange);
+        require!(
+            start_time >= clock.saturating_sub(START_TIME_GRACE_PERIOD),
+            ErrorCode::StartTimeInPast
+        );
+        let max_duration = ctx.accounts.config.max_poll_duration;
+        require!(
+            max_duration == 0 || end_time <= clock.saturating_add(max_duration),
+            ErrorCode::PollTooLong
+        );
+        require!(options.max_extension >= 0, ErrorCode::InvalidTimeRange);
+        require!(options.category.len() <= MAX_CATEGORY_LEN, ErrorCode::CategoryTooLong);
+        require!(options.tags.len() <= MAX_POLL_TAGS, ErrorCode::TooManyTags);
+        require!(
+            options.tags.iter().all(|tag| tag.len() <= MAX_TAG_LEN),
+            ErrorCode::TagTooLong
+        );
+        require!(options.metadata_uri.len() <= MAX_URI_LEN, ErrorCode::UriTooLong);
+        require!(
+            !options.mint_receipt_nft || options.receipt_nft_tree != Pubkey::default(),
+            ErrorCode::MissingReceiptNftAccounts
+        );
+        if let WeightDecay::Linear { end_bps } = options.weight_decay {
+            require!(end_bps as u64 <= FULL_WEIGHT_BPS, ErrorCode::InvalidWeightDecay);
+        }
+        // Shielded ballots all count as one vote, so the key rules out weight decay
+        if options.shielded_key != [0u8; 32] {
+            require!(
+                validate_ristretto(&PodRistrettoPoint(options.shielded_key)),
+                ErrorCode::InvalidShieldedKey
+            );
+            require!(options.weight_decay == WeightDecay::None, ErrorCode::InvalidShieldedKey);
+        }
+        // Stake-weighted votes already carry their full weight in lamports
+        if options.stake_weighted {
+            require!(
+                options.weight_decay == WeightDecay::None && options.shielded_key == [0u8; 32],
+                ErrorCode::InvalidStakeWeighting
+            );
+        }
+        // Gauge points are the whole weight of a ballot
+        if options.gauge_budget > 0 {
+            require!(
+                options.weight_decay == WeightDecay::None
+                    && options.shielded_key == [0u8; 32]
+                    && !options.stake_weighted,
+                ErrorCode::InvalidGaugeBudget
+            );
+        }
+        // Conviction votes are weighted by the tokens locked behind them
+        if options.conviction_mint != Pubkey::default() {
+            require!(
+                options.weight_decay == WeightDecay::None
+                    && options.shielded_key == [0u8; 32]
+                    && !options.stake_weighted
+                    && options.gauge_budget == 0,
+                ErrorCode::InvalidConvictionMint
+            );
+        }
+        // Realm-backed polls take their weight from deposited governance tokens
+        if options.governance_realm != Pubkey::default() {
+            require!(
+                options.governance_program != Pubkey::default()
+                    && options.governance_mint != Pubkey::default()
+                    && options.weight_decay == WeightDecay::None
+                    && options.shielded_key == [0u8; 32]
+                    && !options.stake_weighted
+                    && options.gauge_budget == 0
+                    && options.conviction_mint == Pubkey::default(),
+                ErrorCode::InvalidGovernanceConfig
+            );
+        }
+        // Attestations are presented on the standard vote path only
+        if options.attestation_issuer != Pubkey::default() {
+            require!(
+                options.attestation_program != Pubkey::default()
+                    && options.attestation_schema != Pubkey::default()
+                    && options.shielded_key == [0u8; 32]
+                    && options.gauge_budget == 0
+                    && options.conviction_mint == Pubkey::default()
+                    && options.governance_realm == Pubkey::default(),
+                ErrorCode::InvalidAttestationConfig
+            );
+        }
+        // Oracle weights replace every other weighting and are presented on the standard vote path only
+        if options.weight_oracle != Pubkey::default() {
+            require!(
+                options.max_weight_age >= 0
+                    && options.weight_decay == WeightDecay::None
+                    && options.shielded_key == [0u8; 32]
+                    && !options.stake_weighted
+                    && options.gauge_budget == 0
+                    && options.conviction_mint == Pubkey::default()
+                    && options.governance_realm == Pubkey::default(),
+                ErrorCode::InvalidOracleConfig
+            );
+        }
+        // Vote fees go somewhere and are collected on the standard vote path only
+        if options.vote_fee > 0 {
+            require!(
+                options.vote_fee_recipient != Pubkey::default()
+                    && options.shielded_key == [0u8; 32]
+                    && options.gauge_budget == 0
+                    && options.conviction_mint == Pubkey::default()
+                    && options.governance_realm == Pubkey::default(),
+                ErrorCode::InvalidVoteFeeConfig
+            );
+        }
+        // Access grants are presented on the standard vote path only
+        if options.access_restricted {
+            require!(
+                options.shielded_key == [0u8; 32]
+                    && options.gauge_budget == 0
+                    && options.conviction_mint == Pubkey::default()
+                    && options.governance_realm == Pubkey::default(),
+                ErrorCode::InvalidAccessConfig
+            );
+        }
+        // An eligibility program vouches for voters on the standard vote path only
+        if let Some(eligibility_program) = options.eligibility_program {
+            require!(
+                eligibility_program != crate::ID
+                    && options.shielded_key == [0u8; 32]
+                    && options.gauge_budget == 0
+                    && options.conviction_mint == Pubkey::default()
+                    && options.governance_realm == Pubkey::default(),
+                ErrorCode::InvalidEligibilityProgram
+            );
+        }
+
+        let config = &ctx.accounts.config;
+        if config.restrict_creators {
+            require!(ctx.accounts.creator_pass.is_some(), ErrorCode::CreatorNotAllowed);
+        }
+        if config.council_threshold > 0 {
+            let proposal = ctx
+                .accounts
+                .poll_proposal
+                .as_ref()
+                .ok_or(ErrorCode::CouncilApprovalMissing)?;
+            require!(proposal.question == question, ErrorCode::CouncilApprovalMissing);
+
+            // Only approvals from current members count, in case the council changed since
+            let approvals = proposal
+                .approvals
+                .iter()
+                .filter(|member| config.council.contains(*member))
+                .count();
+            require!(
+                approvals >= config.council_threshold as usize,
+                ErrorCode::CouncilApprovalMissing
+            );
+        }
+        // Count the poll against the creator, forgetting polls that fell out of the rolling window
+        let max_polls = ctx.accounts.config.max_polls_per_window;
+        let window = ctx.accounts.config.creation_window;
+        let stats = &mut ctx.accounts.creator_stats;
+        stats.creator = ctx.accounts.creator.key();
+        stats.bump = ctx.bumps.creator_stats;
+        if max_polls > 0 {
+            stats
+                .recent_polls
+                .retain(|created_at| clock.saturating_sub(*created_at) < window);
+            require!(
+                stats.recent_polls.len() < max_polls as usize,
+                ErrorCode::CreationLimitReached
+            );
+            stats.recent_polls.push(clock);
+        }
+        stats.total_polls = stats.total_polls.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+        // Charge the poll creation fee into the treasury
+        let fee = ctx.accounts.config.poll_creation_fee;
+        if fee > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                    },
+                ),
+                fee,
+            )?;
+            let treasury = &mut ctx.accounts.treasury;
+            treasury.total_collected = treasury
+                .total_collected
+                .checked_add(fee)
+                .ok_or(ErrorCode::Overflow)?;
+        }
 
         let poll = &mut ctx.accounts.poll;
         poll.poll_id = poll_id;
@@ -31,19 +586,460 @@ pub mod voting_dapp {
         poll.start_time = start_time;
         poll.end_time = end_time;
         poll.candidate_count = 0;
+        poll.candidate_deposit_lamports = options.candidate_deposit_lamports;
+        poll.deposit_refund_threshold = options.deposit_refund_threshold;
+        poll.finalized = false;
+        poll.total_votes = 0;
+        poll.reward_mint = Pubkey::default();
+        poll.reward_pool_amount = 0;
+        poll.bounty_lamports = options.bounty_lamports;
+        poll.bounty_claimed = false;
+        poll.winner = Pubkey::default();
+        poll.category = options.category;
+        poll.tags = options.tags;
+        poll.metadata_uri = options.metadata_uri;
+        poll.metadata_hash = options.metadata_hash;
+        poll.version = POLL_VERSION;
+        poll.paused = false;
+        poll.paused_at = 0;
+        poll.receipt_tree = Pubkey::default();
+        poll.compressed_voter_count = 0;
+        poll.mint_receipt_nft = options.mint_receipt_nft;
+        poll.receipt_nft_tree = options.receipt_nft_tree;
+        poll.winner_badge_minted = false;
+        poll.min_voter_lamports = options.min_voter_lamports;
+        poll.min_stake_lamports = options.min_stake_lamports;
+        poll.weight_decay = options.weight_decay;
+        poll.eligible_voters = 0;
+        poll.turnout_bps = 0;
+        poll.status = PollStatus::Draft;
+        poll.max_votes_per_slot = options.max_votes_per_slot;
+        poll.last_vote_slot = 0;
+        poll.votes_in_slot = 0;
+        poll.allow_cpi = options.allow_cpi;
+        poll.shielded_key = options.shielded_key;
+        poll.shielded_published = false;
+        poll.stake_weighted = options.stake_weighted;
+        poll.snapshot_epoch = 0;
+        poll.gauge_budget = options.gauge_budget;
+        poll.conviction_mint = options.conviction_mint;
+        poll.governance_program = options.governance_program;
+        poll.governance_realm = options.governance_realm;
+        poll.governance_mint = options.governance_mint;
+        poll.eligibility_program = options.eligibility_program;
+        poll.attestation_program = options.attestation_program;
+        poll.attestation_issuer = options.attestation_issuer;
+        poll.attestation_schema = options.attestation_schema;
+        poll.visibility = options.visibility;
+        poll.access_restricted = options.access_restricted;
+        poll.weight_oracle = options.weight_oracle;
+        poll.max_weight_age = options.max_weight_age;
+        poll.vote_fee = options.vote_fee;
+        poll.vote_fee_mint = options.vote_fee_mint;
+        poll.vote_fee_recipient = options.vote_fee_recipient;
+        poll.max_extension = options.max_extension;
+        poll.extended_by = 0;
+        poll.first_vote_at = 0;
+        poll.last_vote_at = 0;
+        poll.vote_histogram = [0; 48];
+        poll.open_candidacy = options.open_candidacy;
+        poll.bump = ctx.bumps.poll;
+
+        // Escrow the winner bounty in the poll account
+        if options.bounty_lamports > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: poll.to_account_info(),
+                    },
+                ),
+                options.bounty_lamports,
+            )?;
+        }
         
         msg!("Poll initialized with ID: {}", poll_id);
         Ok(())
     }
 
+    /// Edit a poll's question or description while it is still a draft, or move its end time.
+    /// The end time moves freely until the first vote; after that it can only be extended, by
+    /// at most the poll's `max_extension` in total.
+    /// The account grows to fit, with the creator paying any extra rent; it never shrinks.
+    pub fn update_poll(
+        ctx: Context<UpdatePoll>,
+        question: Option<String>,
+        description: Option<String>,
+        end_time: Option<i64>,
+    ) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let max_duration = ctx.accounts.config.max_poll_duration;
+        let poll = &mut ctx.accounts.poll;
+        if question.is_some() || description.is_some() {
+            require!(poll.status == PollStatus::Draft, ErrorCode::PollNotDraft);
+        }
+
+        if let Some(end_time) = end_time {
+            require!(poll.status_at(clock) != PollStatus::Closed, ErrorCode::PollEnded);
+            require!(end_time > poll.start_time && end_time > clock, ErrorCode::InvalidTimeRange);
+            require!(
+                max_duration == 0 || end_time <= clock.saturating_add(max_duration),
+                ErrorCode::PollTooLong
+            );
+            move_end_time(poll, end_time)?;
+        }
+        if let Some(question) = question {
+            require!(!question.trim().is_empty(), ErrorCode::EmptyQuestion);
+            require!(question.len() <= MAX_QUESTION_LEN, ErrorCode::QuestionTooLong);
+            poll.question = question;
+        }
+        if let Some(description) = description {
+            require!(description.len() <= MAX_DESCRIPTION_LEN, ErrorCode::DescriptionTooLong);
+            poll.description = description;
+        }
+
+        msg!("Poll {} updated", poll.poll_id);
+        Ok(())
+    }
+
+    /// Freeze a draft poll's candidate list and open it for voting
+    pub fn activate_poll(ctx: Context<ActivatePoll>) -> Result<()> {
+        let poll = &mut ctx.accounts.poll;
+        require!(poll.status == PollStatus::Draft, ErrorCode::PollNotDraft);
+        require!(poll.candidate_count > 0, ErrorCode::NoCandidates);
+        require!(!poll.randomness_pending, ErrorCode::RandomnessPending);
+
+        poll.status = PollStatus::Active;
+        // Stake-weighted polls count each delegation as it stood when voting opened
+        if poll.stake_weighted {
+            poll.snapshot_epoch = Clock::get()?.epoch;
+        }
+
+        msg!("Poll {} activated with {} candidates", poll.poll_id, poll.candidate_count);
+        Ok(())
+    }
+
+    /// Register a Clockwork thread that calls `finalize_poll` shortly after the poll ends, so
+    /// results become canonical without anyone cranking. The candidate list must be frozen, so
+    /// this runs once the poll is active; every candidate is passed in `remaining_accounts` in
+    /// ballot order. `fee_lamports` funds the thread's execution fees.
+    pub fn schedule_finalization<'info>(
+        ctx: Context<'_, '_, '_, 'info, ScheduleFinalization<'info>>,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        let poll = &ctx.accounts.poll;
+        require!(poll.status == PollStatus::Active, ErrorCode::PollNotActive);
+        require!(poll.finalize_thread == Pubkey::default(), ErrorCode::FinalizationAlreadyScheduled);
+        require!(
+            ctx.remaining_accounts.len() as u64 == poll.candidate_count,
+            ErrorCode::CandidateListMismatch
+        );
+
+        let poll_key = poll.key();
+        let mut finalize_metas = vec![SerializableAccountMeta {
+            pubkey: poll_key,
+            is_signer: false,
+            is_writable: true,
+        }];
+        for (index, info) in ctx.remaining_accounts.iter().enumerate() {
+            let (candidate_key, _) = Pubkey::find_program_address(
+                &[CANDIDATE_SEED, poll_key.as_ref(), (index as u16).to_le_bytes().as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(info.key(), candidate_key, ErrorCode::CandidateListMismatch);
+            finalize_metas.push(SerializableAccountMeta {
+                pubkey: candidate_key,
+                is_signer: false,
+                is_writable: true,
+            });
+        }
+
+        let args = ThreadCreateArgs {
+            amount: fee_lamports,
+            id: FINALIZE_THREAD_ID.to_vec(),
+            instructions: vec![SerializableInstruction {
+                program_id: crate::ID,
+                accounts: finalize_metas,
+                data: anchor_lang::InstructionData::data(&crate::instruction::FinalizePoll {}),
+            }],
+            trigger: ThreadTrigger::Timestamp {
+                unix_ts: poll.end_time + AUTO_FINALIZE_DELAY,
+            },
+        };
+        let mut data = THREAD_CREATE_DISCRIMINATOR.to_vec();
+        args.serialize(&mut data)
+            .map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotSerialize)?;
+
+        let accounts = &ctx.accounts;
+        let instruction = Instruction {
+            program_id: THREAD_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(poll_key, true), // authority
+                AccountMeta::new(accounts.creator.key(), true), // payer
+                AccountMeta::new_readonly(accounts.system_program.key(), false),
+                AccountMeta::new(accounts.thread.key(), false),
+            ],
+            data,
+        };
+        let poll_id_bytes = poll.poll_id.to_le_bytes();
+        invoke_signed(
+            &instruction,
+            &[
+                accounts.poll.to_account_info(),
+                accounts.creator.to_account_info(),
+                accounts.system_program.to_account_info(),
+                accounts.thread.to_account_info(),
+                accounts.thread_program.to_account_info(),
+            ],
+            &[&[POLL_SEED, poll_id_bytes.as_ref(), &[poll.bump]]],
+        )?;
+
+        let poll = &mut ctx.accounts.poll;
+        poll.finalize_thread = ctx.accounts.thread.key();
+        msg!("Poll {} finalizes itself after {}", poll.poll_id, poll.end_time + AUTO_FINALIZE_DELAY);
+        Ok(())
+    }
+
+    /// Delete a poll's finalization thread, returning its rent and unspent fees to the creator.
+    /// Use it to cancel automatic finalization, or to clean up once the thread has run.
+    pub fn cancel_finalization(ctx: Context<CancelFinalization>) -> Result<()> {
+        let poll = &ctx.accounts.poll;
+        let instruction = Instruction {
+            program_id: THREAD_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(poll.key(), true), // authority
+                AccountMeta::new(ctx.accounts.creator.key(), false), // close_to
+                AccountMeta::new(ctx.accounts.thread.key(), false),
+            ],
+            data: THREAD_DELETE_DISCRIMINATOR.to_vec(),
+        };
+        let poll_id_bytes = poll.poll_id.to_le_bytes();
+        invoke_signed(
+            &instruction,
+            &[
+                ctx.accounts.poll.to_account_info(),
+                ctx.accounts.creator.to_account_info(),
+                ctx.accounts.thread.to_account_info(),
+                ctx.accounts.thread_program.to_account_info(),
+            ],
+            &[&[POLL_SEED, poll_id_bytes.as_ref(), &[poll.bump]]],
+        )?;
+
+        let poll = &mut ctx.accounts.poll;
+        poll.finalize_thread = Pubkey::default();
+        msg!("Finalization thread of poll {} deleted", poll.poll_id);
+        Ok(())
+    }
+
+    /// Bind a committed, not yet revealed Switchboard randomness account to the poll. A draft
+    /// poll draws its ballot order from it; an ended, unfinalized poll draws its tie-break.
+    /// Until the value is applied the poll cannot be activated or finalized.
+    /// Only the creator orders the ballot, but anyone may request a tie-break, so a creator
+    /// cannot wait out the tie-break window to have every tied candidate win.
+    pub fn request_randomness(ctx: Context<RequestRandomness>) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let poll = &mut ctx.accounts.poll;
+        require!(!poll.randomness_pending, ErrorCode::RandomnessPending);
+        let ballot_order = poll.status == PollStatus::Draft && poll.ballot_order_seed == [0u8; 32];
+        let tie_break = clock > poll.end_time && !poll.finalized && poll.tie_break_seed == [0u8; 32];
+        require!(ballot_order || tie_break, ErrorCode::RandomnessNotNeeded);
+        if poll.status == PollStatus::Draft {
+            require_keys_eq!(ctx.accounts.requester.key(), poll.creator, ErrorCode::Unauthorized);
+        }
+
+        // The value must still be unknown: committed after its last reveal
+        let (seed_slot, reveal_slot, _) = read_randomness(&ctx.accounts.randomness)?;
+        require!(seed_slot > reveal_slot, ErrorCode::InvalidRandomness);
+
+        poll.randomness_account = ctx.accounts.randomness.key();
+        poll.randomness_seed_slot = seed_slot;
+        poll.randomness_pending = true;
+
+        msg!("Poll {} awaits randomness committed at slot {}", poll.poll_id, seed_slot);
+        Ok(())
+    }
+
+    /// Apply the revealed value of the poll's randomness request. Anyone may call this, so the
+    /// creator cannot withhold an unwelcome draw.
+    pub fn apply_randomness(ctx: Context<ApplyRandomness>) -> Result<()> {
+        let poll_key = ctx.accounts.poll.key();
+        let poll = &mut ctx.accounts.poll;
+        require!(poll.randomness_pending, ErrorCode::InvalidRandomness);
+
+        let (seed_slot, reveal_slot, value) = read_randomness(&ctx.accounts.randomness)?;
+        require!(seed_slot == poll.randomness_seed_slot, ErrorCode::InvalidRandomness);
+        require!(reveal_slot > seed_slot, ErrorCode::RandomnessNotRevealed);
+
+        let ballot_order = poll.status == PollStatus::Draft;
+        if ballot_order {
+            poll.ballot_order_seed = value;
+        } else {
+            poll.tie_break_seed = value;
+        }
+        poll.randomness_pending = false;
+
+        emit_cpi!(RandomnessApplied {
+            poll: poll_key,
+            randomness_account: poll.randomness_account,
+            seed_slot,
+            value,
+            ballot_order,
+        });
+        msg!("Randomness revealed at slot {} applied to poll {}", reveal_slot, poll.poll_id);
+        Ok(())
+    }
+
+    /// Drop a randomness request the oracle has not revealed within `RANDOMNESS_TIMEOUT_SLOTS` of
+    /// its commitment, so a new one can be made or the poll finalized. Anyone may call this, but a
+    /// revealed value can only be applied, never discarded.
+    pub fn cancel_randomness(ctx: Context<CancelRandomness>) -> Result<()> {
+        let poll = &mut ctx.accounts.poll;
+        require!(poll.randomness_pending, ErrorCode::InvalidRandomness);
+        let expires_at = poll.randomness_seed_slot.saturating_add(RANDOMNESS_TIMEOUT_SLOTS);
+        require!(Clock::get()?.slot > expires_at, ErrorCode::RandomnessNotExpired);
+
+        // A closed or recommitted account can never reveal this request's value
+        let randomness = &ctx.accounts.randomness;
+        if *randomness.owner == SWITCHBOARD_PROGRAM_ID {
+            let (seed_slot, reveal_slot, _) = read_randomness(randomness)?;
+            require!(
+                seed_slot != poll.randomness_seed_slot || reveal_slot <= seed_slot,
+                ErrorCode::RandomnessRevealed
+            );
+        }
+        poll.randomness_pending = false;
+
+        msg!("Randomness request {} of poll {} cancelled", poll.randomness_account, poll.poll_id);
+        Ok(())
+    }
+
+    /// Appoint (or with `None`, remove) a candidate manager who may register candidates
+    /// on the creator's behalf but cannot otherwise change the poll
+    pub fn set_candidate_manager(ctx: Context<SetCandidateManager>, manager: Option<Pubkey>) -> Result<()> {
+        let poll = &mut ctx.accounts.poll;
+        poll.candidate_manager = manager;
+
+        match manager {
+            Some(manager) => msg!("Candidate manager of poll {} set to {}", poll.poll_id, manager),
+            None => msg!("Candidate manager of poll {} removed", poll.poll_id),
+        }
+        Ok(())
+    }
+
+    /// Let a voter vote in an access-restricted poll
+    pub fn grant_access(ctx: Context<GrantAccess>, voter: Pubkey) -> Result<()> {
+        // Grantees are the electorate of a restricted poll
+        let poll = &mut ctx.accounts.poll;
+        if poll.access_restricted {
+            poll.eligible_voters = poll.eligible_voters.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        }
+
+        let grant = &mut ctx.accounts.access_grant;
+        grant.poll = ctx.accounts.poll.key();
+        grant.voter = voter;
+        grant.granted_at = Clock::get()?.unix_timestamp;
+        grant.bump = ctx.bumps.access_grant;
+
+        msg!("Access to poll {} granted to {}", ctx.accounts.poll.poll_id, voter);
+        Ok(())
+    }
+
+    /// Withdraw a voter's access grant, returning its rent to the creator. Votes already
+    /// cast still count.
+    pub fn revoke_access(ctx: Context<RevokeAccess>) -> Result<()> {
+        let poll = &mut ctx.accounts.poll;
+        if poll.access_restricted {
+            poll.eligible_voters = poll.eligible_voters.saturating_sub(1);
+        }
+
+        msg!(
+            "Access to poll {} revoked from {}",
+            ctx.accounts.poll.poll_id,
+            ctx.accounts.access_grant.voter
+        );
+        Ok(())
+    }
+
+    /// Publish (or refresh) a voter's weight as an oracle. Polls naming the signer as their
+    /// weight oracle count each of the voter's votes with this weight.
+    pub fn publish_weight(ctx: Context<PublishWeight>, voter: Pubkey, weight: u64) -> Result<()> {
+        let record = &mut ctx.accounts.weight_record;
+        record.oracle = ctx.accounts.oracle.key();
+        record.voter = voter;
+        record.weight = weight;
+        record.updated_at = Clock::get()?.unix_timestamp;
+        record.bump = ctx.bumps.weight_record;
+
+        msg!("Weight of {} set to {}", voter, weight);
+        Ok(())
+    }
+
+    /// Temporarily stop a live poll from accepting votes
+    pub fn pause_poll(ctx: Context<SetPollPaused>) -> Result<()> {
+        let poll = &mut ctx.accounts.poll;
+        require!(!poll.finalized, ErrorCode::PollAlreadyFinalized);
+        require!(poll.status == PollStatus::Active, ErrorCode::PollNotActive);
+        require!(!poll.paused, ErrorCode::PollPaused);
+
+        poll.paused = true;
+        poll.paused_at = Clock::get()?.unix_timestamp;
+
+        msg!("Poll {} paused", poll.poll_id);
+        Ok(())
+    }
+
+    /// Resume a paused poll, optionally extending `end_time` by the time spent paused. Once votes
+    /// are in, that extension counts against the poll's `max_extension` like any other.
+    pub fn resume_poll(ctx: Context<SetPollPaused>, extend_end_time: bool) -> Result<()> {
+        let poll = &mut ctx.accounts.poll;
+        require!(poll.paused, ErrorCode::PollNotPaused);
+
+        if extend_end_time {
+            let paused_for = Clock::get()?.unix_timestamp.saturating_sub(poll.paused_at);
+            let end_time = poll.end_time.checked_add(paused_for).ok_or(ErrorCode::Overflow)?;
+            move_end_time(poll, end_time)?;
+        }
+        poll.paused = false;
+        poll.paused_at = 0;
+
+        msg!("Poll {} resumed, ends at {}", poll.poll_id, poll.end_time);
+        Ok(())
+    }
+
     /// Add a candidate to a poll
     pub fn initialize_candidate(
         ctx: Context<InitializeCandidate>,
         candidate_name: String,
         candidate_party: String,
+        payout_wallet: Pubkey,
+        metadata_uri: String,
     ) -> Result<()> {
-        // Only the poll creator can initialize a candidate
-        require_keys_eq!(ctx.accounts.poll.creator, ctx.accounts.creator.key(), ErrorCode::Unauthorized);
+        // With open candidacy anyone may nominate themselves, paying out to their own wallet
+        let signer = ctx.accounts.creator.key();
+        let poll = &ctx.accounts.poll;
+        let self_nomination = poll.open_candidacy
+            && payout_wallet == signer
+            && poll.creator != signer
+            && poll.candidate_manager != Some(signer);
+        check_candidate_registration(poll, &signer, self_nomination, &candidate_name, &metadata_uri)?;
+
+        // A self-nominee stands once per poll, marked by an empty nomination PDA
+        if self_nomination {
+            require!(poll.candidate_count < MAX_OPEN_CANDIDATES, ErrorCode::TooManyCandidates);
+            let nomination = ctx.accounts.nomination.as_ref().ok_or(ErrorCode::InvalidNomination)?;
+            let nomination_bump = ctx.bumps.nomination.ok_or(ErrorCode::InvalidNomination)?;
+            require_keys_neq!(*nomination.owner, crate::ID, ErrorCode::InvalidNomination);
+            let poll_key = poll.key();
+            create_pda_account(
+                &ctx.accounts.creator.to_account_info(),
+                &nomination.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                0,
+                &crate::ID,
+                &[&[NOMINATION_SEED, poll_key.as_ref(), signer.as_ref(), &[nomination_bump]]],
+            )?;
+        }
 
         let poll = &mut ctx.accounts.poll;
         let candidate = &mut ctx.accounts.candidate;
@@ -52,89 +1048,3553 @@ pub mod voting_dapp {
         candidate.name = candidate_name.clone();
         candidate.party = candidate_party;
         candidate.votes = 0;
+        candidate.raw_votes = 0;
+        candidate.is_winner = false;
+        candidate.payout_wallet = payout_wallet;
+        candidate.metadata_uri = metadata_uri;
+        candidate.version = CANDIDATE_VERSION;
+        candidate.index = u16::try_from(poll.candidate_count).map_err(|_| ErrorCode::TooManyCandidates)?;
+        candidate.depositor = ctx.accounts.creator.key();
+        candidate.deposit = poll.candidate_deposit_lamports;
+        candidate.bump = ctx.bumps.candidate;
+
+        // Escrow the registration deposit in the candidate account
+        if candidate.deposit > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: candidate.to_account_info(),
+                    },
+                ),
+                candidate.deposit,
+            )?;
+        }
 
         // Increment the candidate count on the poll account
-        poll.candidate_count = poll.candidate_count.checked_add(1).unwrap();
+        poll.candidate_count = poll.candidate_count.checked_add(1).ok_or(ErrorCode::Overflow)?;
         
         msg!("Candidate {} added to poll {}", candidate.name, poll.poll_id);
         Ok(())
     }
 
-    /// Cast a vote for a candidate
-    pub fn vote(ctx: Context<Vote>) -> Result<()> {
-        let clock = Clock::get()?.unix_timestamp;
+    /// Add several candidates in one transaction. `remaining_accounts` holds each candidate's
+    /// not yet created PDA, writable, in the order of `candidates`, starting at the poll's next
+    /// index. Batch-registered candidates pay out to the signer and have no metadata URI.
+    pub fn initialize_candidates_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeCandidatesBatch<'info>>,
+        candidates: Vec<CandidateEntry>,
+    ) -> Result<()> {
+        require!(
+            !candidates.is_empty() && candidates.len() == ctx.remaining_accounts.len(),
+            ErrorCode::InvalidCandidateBatch
+        );
+        let signer = ctx.accounts.creator.key();
+        let poll_key = ctx.accounts.poll.key();
+        let candidate_space = 8 + Candidate::INIT_SPACE;
+
+        for (entry, candidate_info) in candidates.into_iter().zip(ctx.remaining_accounts.iter()) {
+            check_candidate_registration(&ctx.accounts.poll, &signer, false, &entry.name, "")?;
+            let poll = &mut ctx.accounts.poll;
+
+            // Each account must be the canonical PDA for the next ballot index
+            let index = u16::try_from(poll.candidate_count).map_err(|_| ErrorCode::TooManyCandidates)?;
+            let index_bytes = index.to_le_bytes();
+            let (candidate_key, bump) = Pubkey::find_program_address(
+                &[CANDIDATE_SEED, poll_key.as_ref(), index_bytes.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(candidate_info.key(), candidate_key, ErrorCode::InvalidCandidateBatch);
+
+            create_pda_account(
+                &ctx.accounts.creator.to_account_info(),
+                candidate_info,
+                &ctx.accounts.system_program.to_account_info(),
+                candidate_space,
+                &crate::ID,
+                &[&[CANDIDATE_SEED, poll_key.as_ref(), index_bytes.as_ref(), &[bump]]],
+            )?;
+
+            // The registration deposit is escrowed alongside the rent
+            let deposit = poll.candidate_deposit_lamports;
+            if deposit > 0 {
+                transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.creator.to_account_info(),
+                            to: candidate_info.clone(),
+                        },
+                    ),
+                    deposit,
+                )?;
+            }
+
+            let candidate = Candidate {
+                poll: poll_key,
+                name: entry.name,
+                party: entry.party,
+                votes: 0,
+                payout_wallet: signer,
+                depositor: signer,
+                deposit,
+                metadata_uri: String::new(),
+                version: CANDIDATE_VERSION,
+                raw_votes: 0,
+                index,
+                is_winner: false,
+                bump,
+            };
+            candidate.try_serialize(&mut &mut candidate_info.try_borrow_mut_data()?[..])?;
+            poll.candidate_count = poll.candidate_count.checked_add(1).ok_or(ErrorCode::Overflow)?;
+            msg!("Candidate {} added to poll {}", candidate.name, poll.poll_id);
+        }
+        Ok(())
+    }
+
+    /// Update a candidate's off-chain metadata URI while the poll is a draft
+    pub fn update_candidate_metadata(
+        ctx: Context<UpdateCandidateMetadata>,
+        metadata_uri: String,
+    ) -> Result<()> {
         let poll = &ctx.accounts.poll;
 
-        // Check if the current time is within the poll's active period
-        require!(clock >= poll.start_time && clock <= poll.end_time, ErrorCode::PollNotActive);
+        // Only the poll creator can edit candidates, and only before the poll is activated
+        require_keys_eq!(poll.creator, ctx.accounts.creator.key(), ErrorCode::Unauthorized);
+        require!(poll.status == PollStatus::Draft, ErrorCode::PollNotDraft);
+        require!(metadata_uri.len() <= MAX_URI_LEN, ErrorCode::UriTooLong);
+
+        let candidate = &mut ctx.accounts.candidate;
+        candidate.metadata_uri = metadata_uri;
+
+        msg!("Metadata updated for candidate {}", candidate.name);
+        Ok(())
+    }
+
+    /// Cast a vote for a candidate, minting an "I Voted" cNFT to the voter if the poll opted in
+    pub fn vote<'info>(ctx: Context<'_, '_, '_, 'info, Vote<'info>>) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let poll_key = ctx.accounts.poll.key();
+        let voter = ctx.accounts.voter.key();
+
+        check_vote_origin(&ctx.accounts.poll, &ctx.accounts.instructions)?;
+        check_single_vote(&poll_key, &voter, &ctx.accounts.instructions)?;
+        let verified = verify_eligibility(
+            &ctx.accounts.poll,
+            &ctx.accounts.voter,
+            ctx.accounts.eligibility_program.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        check_voter_eligibility(
+            &ctx.accounts.poll,
+            &ctx.accounts.voter,
+            &VoterCredentials {
+                stake_account: ctx.accounts.stake_account.as_ref().map(|stake| stake.as_ref()),
+                attestation: ctx.accounts.attestation.as_ref().map(|attestation| attestation.as_ref()),
+                eligibility_verified: verified,
+                access_granted: ctx.accounts.access_grant.is_some(),
+                weight_record: ctx.accounts.weight_record.as_deref(),
+                pays_fee: true,
+            },
+        )?;
+        collect_vote_fee(ctx.accounts)?;
+        let weight = if ctx.accounts.poll.stake_weighted {
+            claim_snapshot_stake(ctx.accounts, poll_key)?
+        } else if ctx.accounts.poll.is_oracle_weighted() {
+            ctx.accounts.weight_record.as_ref().ok_or(ErrorCode::InvalidWeightRecord)?.weight
+        } else {
+            ctx.accounts.poll.vote_weight(clock)
+        };
+        record_vote(
+            &mut ctx.accounts.poll,
+            poll_key,
+            &mut ctx.accounts.candidate,
+            &mut ctx.accounts.voter_receipt,
+            voter,
+            weight,
+            clock,
+        )?;
+        ctx.accounts.voter_receipt.bump = ctx.bumps.voter_receipt;
+        ctx.accounts.voter_receipt.payer = ctx.accounts.payer.key();
+        emit_cpi!(tally_event(&ctx.accounts.poll, poll_key, &ctx.accounts.candidate, ctx.accounts.candidate.key()));
+
+        if ctx.accounts.poll.mint_receipt_nft {
+            mint_receipt_nft(ctx.accounts)?;
+        }
+
+        msg!("Vote cast successfully");
+        Ok(())
+    }
+
+    /// Attach an spl-account-compression tree to the poll for rent-free voter receipts.
+    /// The tree account must already be allocated for the compression program.
+    pub fn init_receipt_tree(
+        ctx: Context<ManageReceiptTree>,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        let poll = &mut ctx.accounts.poll;
+        require!(poll.receipt_tree == Pubkey::default(), ErrorCode::ReceiptTreeAlreadySet);
+        require!(poll.total_votes == 0, ErrorCode::VotingAlreadyStarted);
+        // Compressed votes carry no credentials, so gated or special-mode polls can't use a tree
+        require!(poll.takes_plain_votes(), ErrorCode::ReceiptTreeNeedsPlainVotes);
+        require!(poll.reward_mint == Pubkey::default(), ErrorCode::RewardsNeedReceipts);
+        poll.receipt_tree = ctx.accounts.tree.merkle_tree.key();
+
+        let mut data = INIT_EMPTY_MERKLE_TREE_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&max_depth.to_le_bytes());
+        data.extend_from_slice(&max_buffer_size.to_le_bytes());
+        invoke_compression(poll, &ctx.accounts.tree, data, &[])?;
+
+        msg!("Receipt tree {} attached to poll {}", poll.receipt_tree, poll.poll_id);
+        Ok(())
+    }
+
+    /// Register a voter for compressed voting by appending their unvoted leaf to the receipt tree.
+    /// The voter's registration PDA records their leaf, so each voter can be registered only once.
+    pub fn register_compressed_voter(ctx: Context<RegisterCompressedVoter>, voter: Pubkey) -> Result<()> {
+        let poll_key = ctx.accounts.poll.key();
+        let poll = &mut ctx.accounts.poll;
+        require_keys_eq!(
+            ctx.accounts.tree.merkle_tree.key(),
+            poll.receipt_tree,
+            ErrorCode::ReceiptTreeMismatch
+        );
+
+        let index = poll.compressed_voter_count;
+        let leaf = receipt_leaf(&poll_key, &voter, &Pubkey::default());
+        let mut data = APPEND_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&leaf);
+        invoke_compression(poll, &ctx.accounts.tree, data, &[])?;
+        poll.compressed_voter_count = index.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        poll.eligible_voters = poll.eligible_voters.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+        let registration = &mut ctx.accounts.registration;
+        registration.poll = poll_key;
+        registration.voter = voter;
+        registration.index = index;
+        registration.bump = ctx.bumps.registration;
+
+        emit_cpi!(ReceiptLeafSet { poll: poll_key, index, leaf });
+        msg!("Voter {} registered at leaf {}", voter, index);
+        Ok(())
+    }
+
+    /// Vote without a receipt account: the voter's unvoted leaf is replaced by
+    /// `receipt_leaf(poll, voter, candidate)`. The replacement requires a proof that the unvoted
+    /// leaf still exists, so a second vote fails. Proof nodes are passed in `remaining_accounts`.
+    pub fn vote_compressed<'info>(
+        ctx: Context<'_, '_, '_, 'info, VoteCompressed<'info>>,
+        root: [u8; 32],
+        index: u32,
+    ) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let poll_key = ctx.accounts.poll.key();
+        let voter = ctx.accounts.voter.key();
+        let candidate_key = ctx.accounts.candidate.key();
+        require_keys_eq!(
+            ctx.accounts.tree.merkle_tree.key(),
+            ctx.accounts.poll.receipt_tree,
+            ErrorCode::ReceiptTreeMismatch
+        );
+
+        check_vote_origin(&ctx.accounts.poll, &ctx.accounts.instructions)?;
+        check_voter_eligibility(&ctx.accounts.poll, &ctx.accounts.voter, &VoterCredentials::default())?;
+        let weight = ctx.accounts.poll.vote_weight(clock);
+        tally_vote(&mut ctx.accounts.poll, poll_key, &mut ctx.accounts.candidate, weight, clock)?;
+        emit_cpi!(tally_event(&ctx.accounts.poll, poll_key, &ctx.accounts.candidate, candidate_key));
+
+        let previous_leaf = receipt_leaf(&poll_key, &voter, &Pubkey::default());
+        let leaf = receipt_leaf(&poll_key, &voter, &candidate_key);
+        let mut data = REPLACE_LEAF_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&root);
+        data.extend_from_slice(&previous_leaf);
+        data.extend_from_slice(&leaf);
+        data.extend_from_slice(&index.to_le_bytes());
+        invoke_compression(
+            &ctx.accounts.poll,
+            &ctx.accounts.tree,
+            data,
+            ctx.remaining_accounts,
+        )?;
+
+        emit_cpi!(ReceiptLeafSet { poll: poll_key, index, leaf });
+        msg!("Compressed vote cast successfully");
+        Ok(())
+    }
+
+    /// Upgrade a poll, candidate or receipt created with an older layout.
+    /// New fields are appended, so the account is zero-extended (zero is every new field's default)
+    /// and stamped with the current version. The payer covers any extra rent.
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        let info = ctx.accounts.account.to_account_info();
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::UnsupportedAccount);
+
+        let discriminator = info.try_borrow_data()?.get(..8).map(|d| d.to_vec());
+        match discriminator.as_deref() {
+            // Configs predating the council, allowlist and creation limit leave them off
+            Some(d) if d == Config::DISCRIMINATOR => {
+                migrate_layout::<Config>(ctx.accounts, 8 + Config::INIT_SPACE, |_| {})?
+            }
+            Some(d) if d == Poll::DISCRIMINATOR => migrate_layout::<Poll>(
+                ctx.accounts,
+                // Descriptions grown past the base length keep their extra room
+                Poll::space_for_description(stored_description_len(&info)?),
+                |poll| {
+                    // Every voter registered so far is part of the electorate
+                    if poll.version < 8 {
+                        poll.eligible_voters = poll.compressed_voter_count as u64;
+                    }
+                    // Polls predating the lifecycle were open for voting from creation
+                    if poll.version < 9 {
+                        poll.status = if poll.finalized { PollStatus::Closed } else { PollStatus::Active };
+                    }
+                    // Newer fields read as zero, which gives older polls the defaults:
+                    // - they only accept top-level votes
+                    // - they are not shielded, stake-weighted, gauge, conviction or realm-backed
+                    // - they have no candidate manager, eligibility program or attestation gate
+                    // - they have no finalization thread or randomness
+                    // - they stay listed and open to every voter, with no oracle weight or vote fee
+                    // - their end time stays fixed once votes are in
+                    // - their vote histogram starts empty
+                    // - only the creator adds candidates
+                    // The bump is the one field that needs computing.
+                    if poll.version < 20 {
+                        let poll_id_bytes = poll.poll_id.to_le_bytes();
+                        poll.bump = Pubkey::find_program_address(&[POLL_SEED, poll_id_bytes.as_ref()], &crate::ID).1;
+                    }
+                    poll.version = POLL_VERSION;
+                },
+            )?,
+            Some(d) if d == Candidate::DISCRIMINATOR => migrate_layout::<Candidate>(
+                ctx.accounts,
+                8 + Candidate::INIT_SPACE,
+                |candidate| {
+                    // Candidates predating raw tallies only ever received full-weight votes
+                    if candidate.version < 2 {
+                        candidate.raw_votes = candidate.votes;
+                    }
+                    // The registration order of older candidates is unknown, so they keep index 0.
+                    // Winners of polls finalized before flagging keep `is_winner` unset.
+                    // Candidates registered before index addressing live at their name-derived PDA.
+                    if candidate.version < 5 {
+                        let index_bytes = candidate.index.to_le_bytes();
+                        let (index_address, index_bump) = Pubkey::find_program_address(
+                            &[CANDIDATE_SEED, candidate.poll.as_ref(), index_bytes.as_ref()],
+                            &crate::ID,
+                        );
+                        candidate.bump = if index_address == info.key() {
+                            index_bump
+                        } else {
+                            Pubkey::find_program_address(
+                                &[CANDIDATE_SEED, candidate.poll.as_ref(), candidate.name.as_bytes()],
+                                &crate::ID,
+                            )
+                            .1
+                        };
+                    }
+                    candidate.version = CANDIDATE_VERSION;
+                },
+            )?,
+            Some(d) if d == VoterReceipt::DISCRIMINATOR => migrate_layout::<VoterReceipt>(
+                ctx.accounts,
+                VoterReceipt::space_for(stored_allocation_count(&info)?),
+                |receipt| {
+                    // Receipts predating weights were all simple one-vote ballots
+                    if receipt.version < 2 {
+                        receipt.weight = 1;
+                    }
+                    // and none of them split points, so their allocations stay empty
+                    if receipt.version < 4 {
+                        receipt.bump = Pubkey::find_program_address(
+                            &[RECEIPT_SEED, receipt.poll.as_ref(), receipt.voter.as_ref()],
+                            &crate::ID,
+                        )
+                        .1;
+                    }
+                    // Receipts predating sponsored rent were paid for by their voter
+                    if receipt.version < 5 {
+                        receipt.payer = receipt.voter;
+                    }
+                    receipt.version = RECEIPT_VERSION;
+                },
+            )?,
+            _ => return err!(ErrorCode::UnsupportedAccount),
+        }
+
+        msg!("Migrated account {}", info.key());
+        Ok(())
+    }
+
+    /// Create a ballot linking several polls (races) under one election
+    pub fn initialize_ballot(
+        ctx: Context<InitializeBallot>,
+        ballot_id: u64,
+        title: String,
+    ) -> Result<()> {
+        let races = ctx.remaining_accounts;
+        require!(
+            !races.is_empty() && races.len() <= MAX_BALLOT_RACES,
+            ErrorCode::InvalidBallotRaces
+        );
+
+        // Every race must be a poll owned by the ballot creator. Ballot votes carry no
+        // credentials, so a race must take plain votes or no one could fill the ballot in.
+        let creator = ctx.accounts.creator.key();
+        let mut polls: Vec<Pubkey> = Vec::with_capacity(races.len());
+        for info in races.iter() {
+            require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidBallotRaces);
+            let poll = Poll::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            require_keys_eq!(poll.creator, creator, ErrorCode::Unauthorized);
+            require!(poll.takes_plain_votes(), ErrorCode::RaceNeedsCredentials);
+            require!(!polls.contains(info.key), ErrorCode::InvalidBallotRaces);
+            polls.push(info.key());
+        }
+
+        let ballot = &mut ctx.accounts.ballot;
+        ballot.ballot_id = ballot_id;
+        ballot.creator = creator;
+        ballot.title = title;
+        ballot.polls = polls;
+
+        msg!("Ballot {} created with {} races", ballot_id, ballot.polls.len());
+        Ok(())
+    }
+
+    /// Vote in every race of a ballot in one transaction. Races were checked to take plain votes
+    /// when the ballot was created.
+    /// `remaining_accounts` holds `[poll, candidate, voter_receipt]` for each race, in ballot order.
+    pub fn vote_ballot<'info>(ctx: Context<'_, '_, '_, 'info, VoteBallot<'info>>) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let ballot = &ctx.accounts.ballot;
+        let voter = ctx.accounts.voter.key();
+        let races = ctx.remaining_accounts;
+        require!(races.len() == ballot.polls.len() * 3, ErrorCode::InvalidBallotRaces);
+
+        let receipt_space = 8 + VoterReceipt::INIT_SPACE;
+
+        for (race, accounts) in ballot.polls.iter().zip(races.chunks(3)) {
+            let (poll_info, candidate_info, receipt_info) = (&accounts[0], &accounts[1], &accounts[2]);
+            require_keys_eq!(poll_info.key(), *race, ErrorCode::InvalidBallotRaces);
+            require_keys_eq!(*poll_info.owner, crate::ID, ErrorCode::InvalidBallotRaces);
+            require_keys_eq!(*candidate_info.owner, crate::ID, ErrorCode::InvalidBallotRaces);
+
+            // The receipt must be the canonical, not yet created PDA for this race
+            let (receipt_key, receipt_bump) = Pubkey::find_program_address(
+                &[RECEIPT_SEED, race.as_ref(), voter.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(receipt_info.key(), receipt_key, ErrorCode::InvalidBallotRaces);
+            require!(receipt_info.data_is_empty(), ErrorCode::AlreadyVoted);
+
+            let mut poll = Poll::try_deserialize(&mut &poll_info.try_borrow_data()?[..])?;
+            check_vote_origin(&poll, &ctx.accounts.instructions)?;
+            check_voter_eligibility(&poll, &ctx.accounts.voter, &VoterCredentials::default())?;
+            let mut candidate = Candidate::try_deserialize(&mut &candidate_info.try_borrow_data()?[..])?;
+            let mut receipt = VoterReceipt {
+                poll: Pubkey::default(),
+                voter: Pubkey::default(),
+                has_voted: false,
+                reward_claimed: false,
+                version: 0,
+                weight: 0,
+                allocations: Vec::new(),
+                bump: receipt_bump,
+                payer: Pubkey::default(),
+            };
+            let weight = poll.vote_weight(clock);
+            record_vote(&mut poll, *race, &mut candidate, &mut receipt, voter, weight, clock)?;
+            emit_cpi!(tally_event(&poll, *race, &candidate, candidate_info.key()));
+
+            create_pda_account(
+                &ctx.accounts.voter.to_account_info(),
+                receipt_info,
+                &ctx.accounts.system_program.to_account_info(),
+                receipt_space,
+                &crate::ID,
+                &[&[RECEIPT_SEED, race.as_ref(), voter.as_ref(), &[receipt_bump]]],
+            )?;
 
-        // Increment the candidate's vote count
-        ctx.accounts.candidate.votes = ctx.accounts.candidate.votes.checked_add(1).unwrap();
+            poll.try_serialize(&mut &mut poll_info.try_borrow_mut_data()?[..])?;
+            candidate.try_serialize(&mut &mut candidate_info.try_borrow_mut_data()?[..])?;
+            receipt.try_serialize(&mut &mut receipt_info.try_borrow_mut_data()?[..])?;
+        }
+
+        msg!("Ballot {} cast with {} choices", ballot.ballot_id, ballot.polls.len());
+        Ok(())
+    }
+
+    /// Let a short-lived session key vote on the voter's behalf in the races of one ballot
+    /// until `expiry_slot`, so a hardware wallet only signs once per election
+    pub fn create_session(ctx: Context<CreateSession>, session_key: Pubkey, expiry_slot: u64) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        require!(
+            expiry_slot > slot && expiry_slot - slot <= MAX_SESSION_SLOTS,
+            ErrorCode::InvalidSessionExpiry
+        );
+
+        let session = &mut ctx.accounts.session;
+        session.voter = ctx.accounts.voter.key();
+        session.ballot = ctx.accounts.ballot.key();
+        session.session_key = session_key;
+        session.expiry_slot = expiry_slot;
+        session.bump = ctx.bumps.session;
+
+        msg!("Session key {} valid until slot {}", session_key, expiry_slot);
+        Ok(())
+    }
+
+    /// Revoke a session key before it expires, returning the session rent to the voter
+    pub fn revoke_session(_ctx: Context<RevokeSession>) -> Result<()> {
+        msg!("Session revoked");
+        Ok(())
+    }
+
+    /// Vote in one race of a ballot with a session key. The receipt is the voter's, as if they
+    /// had signed; the session key pays its rent. Ballot races take plain votes, so the session
+    /// presents no credentials.
+    pub fn vote_with_session(ctx: Context<VoteWithSession>) -> Result<()> {
+        let clock = Clock::get()?;
+        let poll_key = ctx.accounts.poll.key();
+        let session = &ctx.accounts.session;
+        require!(clock.slot <= session.expiry_slot, ErrorCode::SessionExpired);
+        require!(ctx.accounts.ballot.polls.contains(&poll_key), ErrorCode::PollNotInSession);
+
+        check_vote_origin(&ctx.accounts.poll, &ctx.accounts.instructions)?;
+        check_voter_eligibility(&ctx.accounts.poll, &ctx.accounts.voter, &VoterCredentials::default())?;
+        let weight = ctx.accounts.poll.vote_weight(clock.unix_timestamp);
+        record_vote(
+            &mut ctx.accounts.poll,
+            poll_key,
+            &mut ctx.accounts.candidate,
+            &mut ctx.accounts.voter_receipt,
+            session.voter,
+            weight,
+            clock.unix_timestamp,
+        )?;
+        ctx.accounts.voter_receipt.bump = ctx.bumps.voter_receipt;
+        ctx.accounts.voter_receipt.payer = ctx.accounts.session_key.key();
+        emit_cpi!(tally_event(&ctx.accounts.poll, poll_key, &ctx.accounts.candidate, ctx.accounts.candidate.key()));
+
+        msg!("Vote cast with a session key");
+        Ok(())
+    }
+
+    /// Split a gauge poll's point budget across several candidates in one vote.
+    /// The candidates are passed, writable, in `remaining_accounts`, one per entry of `points`.
+    /// Every entry must be nonzero and together they must spend exactly the poll's budget.
+    pub fn vote_gauge<'info>(
+        ctx: Context<'_, '_, '_, 'info, VoteGauge<'info>>,
+        points: Vec<u16>,
+    ) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let poll_key = ctx.accounts.poll.key();
+        let voter = ctx.accounts.voter.key();
+
+        check_vote_origin(&ctx.accounts.poll, &ctx.accounts.instructions)?;
+        check_voter_eligibility(&ctx.accounts.poll, &ctx.accounts.voter, &VoterCredentials::default())?;
+        let poll = &mut ctx.accounts.poll;
+        require!(poll.gauge_budget > 0, ErrorCode::PollNotGauge);
+        admit_ballot(poll, clock)?;
+
+        require!(
+            !points.is_empty()
+                && points.len() <= MAX_GAUGE_ALLOCATIONS
+                && points.len() == ctx.remaining_accounts.len(),
+            ErrorCode::InvalidGaugeAllocation
+        );
+        require!(
+            points.iter().all(|&p| p > 0)
+                && points.iter().map(|&p| p as u64).sum::<u64>() == poll.gauge_budget as u64,
+            ErrorCode::InvalidGaugeAllocation
+        );
+
+        // Credit each candidate with its points; every candidate counts the ballot once
+        let mut allocations = Vec::with_capacity(points.len());
+        for (info, &amount) in ctx.remaining_accounts.iter().zip(points.iter()) {
+            require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidGaugeAllocation);
+            require!(info.is_writable, ErrorCode::InvalidGaugeAllocation);
+            require!(
+                allocations.iter().all(|a: &GaugeAllocation| a.candidate != info.key()),
+                ErrorCode::InvalidGaugeAllocation
+            );
+
+            let mut candidate = Candidate::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            require_keys_eq!(candidate.poll, poll_key, ErrorCode::CandidatePollMismatch);
+            candidate.votes = add_weight(candidate.votes, amount as u64);
+            candidate.raw_votes = candidate.raw_votes.checked_add(1).ok_or(ErrorCode::Overflow)?;
+            emit_cpi!(tally_event(poll, poll_key, &candidate, info.key()));
+            candidate.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+            allocations.push(GaugeAllocation { candidate: info.key(), points: amount });
+        }
+        poll.total_votes = poll.total_votes.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+        // Initialize the voter receipt to prevent double voting
+        let receipt = &mut ctx.accounts.voter_receipt;
+        receipt.poll = poll_key;
+        receipt.voter = voter;
+        receipt.has_voted = true;
+        receipt.reward_claimed = false;
+        receipt.version = RECEIPT_VERSION;
+        receipt.weight = poll.gauge_budget as u64;
+        receipt.bump = ctx.bumps.voter_receipt;
+        receipt.payer = voter;
+        receipt.allocations = allocations;
+
+        msg!("Gauge vote split across {} candidates", points.len());
+        Ok(())
+    }
+
+    /// Vote in a conviction poll by locking `amount` of the poll's tokens for `lock`.
+    /// Longer locks multiply the vote's weight; the tokens come back through `unlock`.
+    pub fn vote_conviction(ctx: Context<VoteConviction>, amount: u64, lock: LockPeriod) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let poll_key = ctx.accounts.poll.key();
+        let voter = ctx.accounts.voter.key();
+        require!(amount > 0, ErrorCode::InvalidConvictionAmount);
+
+        check_vote_origin(&ctx.accounts.poll, &ctx.accounts.instructions)?;
+        check_voter_eligibility(&ctx.accounts.poll, &ctx.accounts.voter, &VoterCredentials::default())?;
+        let poll = &mut ctx.accounts.poll;
+        admit_ballot(poll, clock)?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.voter_token_account.to_account_info(),
+                    to: ctx.accounts.lock_vault.to_account_info(),
+                    authority: ctx.accounts.voter.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        // Widen so the largest locks saturate instead of failing the vote
+        let weight = (amount as u128 * lock.multiplier() as u128).min(u64::MAX as u128) as u64;
+        let candidate_key = ctx.accounts.candidate.key();
+        let candidate = &mut ctx.accounts.candidate;
+        credit_vote(poll, candidate, weight)?;
+        emit_cpi!(tally_event(poll, poll_key, candidate, candidate_key));
+
+        let conviction_lock = &mut ctx.accounts.conviction_lock;
+        conviction_lock.poll = poll_key;
+        conviction_lock.voter = voter;
+        conviction_lock.amount = amount;
+        conviction_lock.multiplier = lock.multiplier();
+        conviction_lock.expiry = poll.end_time.saturating_add(lock.duration());
+        conviction_lock.unlocked = false;
+
+        // Initialize the voter receipt to prevent double voting
+        let receipt = &mut ctx.accounts.voter_receipt;
+        receipt.poll = poll_key;
+        receipt.voter = voter;
+        receipt.has_voted = true;
+        receipt.reward_claimed = false;
+        receipt.version = RECEIPT_VERSION;
+        receipt.weight = weight;
+        receipt.bump = ctx.bumps.voter_receipt;
+        receipt.payer = voter;
+
+        msg!(
+            "Conviction vote of {} tokens at {}x, locked until {}",
+            amount,
+            lock.multiplier(),
+            conviction_lock.expiry
+        );
+        Ok(())
+    }
+
+    /// Return a conviction vote's tokens once its lock has expired.
+    /// The lock record is kept, marked unlocked, so the vote's weight stays auditable.
+    pub fn unlock(ctx: Context<Unlock>) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let conviction_lock = &ctx.accounts.conviction_lock;
+        require!(!conviction_lock.unlocked, ErrorCode::AlreadyUnlocked);
+        require!(clock >= conviction_lock.expiry, ErrorCode::TokensStillLocked);
+
+        let (poll_key, voter) = (conviction_lock.poll, conviction_lock.voter);
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            CONVICTION_LOCK_SEED,
+            poll_key.as_ref(),
+            voter.as_ref(),
+            &[ctx.bumps.conviction_lock],
+        ]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.lock_vault.to_account_info(),
+                    to: ctx.accounts.voter_token_account.to_account_info(),
+                    authority: ctx.accounts.conviction_lock.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            ctx.accounts.lock_vault.amount,
+        )?;
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::CloseAccount {
+                account: ctx.accounts.lock_vault.to_account_info(),
+                destination: ctx.accounts.voter.to_account_info(),
+                authority: ctx.accounts.conviction_lock.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        let conviction_lock = &mut ctx.accounts.conviction_lock;
+        conviction_lock.unlocked = true;
+
+        msg!("Unlocked {} tokens for {}", conviction_lock.amount, voter);
+        Ok(())
+    }
+
+    /// Vote in a realm-backed poll, weighted by the governing tokens the voter has deposited
+    /// in the poll's spl-governance realm. The voter's `TokenOwnerRecord` is passed as proof.
+    /// The deposit is locked in the realm until the poll can no longer take votes, so the same
+    /// tokens can't be withdrawn and voted again from another wallet. The realm must list the
+    /// program's governance lock PDA among its token owner record lock authorities.
+    pub fn vote_governance(ctx: Context<VoteGovernance>) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let poll_key = ctx.accounts.poll.key();
+        let voter = ctx.accounts.voter.key();
+
+        check_vote_origin(&ctx.accounts.poll, &ctx.accounts.instructions)?;
+        check_voter_eligibility(&ctx.accounts.poll, &ctx.accounts.voter, &VoterCredentials::default())?;
+        let weight = governance_power_of(&ctx.accounts.poll, &ctx.accounts.token_owner_record, &voter)?;
+        lock_governance_deposit(ctx.accounts, ctx.bumps.lock_authority)?;
+
+        let poll = &mut ctx.accounts.poll;
+        admit_ballot(poll, clock)?;
+        let candidate_key = ctx.accounts.candidate.key();
+        let candidate = &mut ctx.accounts.candidate;
+        credit_vote(poll, candidate, weight)?;
+        emit_cpi!(tally_event(poll, poll_key, candidate, candidate_key));
+
+        // Initialize the voter receipt to prevent double voting
+        let receipt = &mut ctx.accounts.voter_receipt;
+        receipt.poll = poll_key;
+        receipt.voter = voter;
+        receipt.has_voted = true;
+        receipt.reward_claimed = false;
+        receipt.version = RECEIPT_VERSION;
+        receipt.weight = weight;
+        receipt.bump = ctx.bumps.voter_receipt;
+        receipt.payer = voter;
+
+        msg!("Governance vote cast with {} deposited tokens", weight);
+        Ok(())
+    }
+
+    /// Finalize a poll once its voting period has ended.
+    /// Every candidate of the poll must be passed in `remaining_accounts`, writable so the
+    /// winners (several, on a tie) can be flagged. `poll.winner` records the one registered first.
+    /// A poll without votes has no winner, so its bounty goes back to the creator.
+    pub fn finalize_poll(ctx: Context<FinalizePoll>) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let poll = &mut ctx.accounts.poll;
+
+        require!(clock > poll.end_time, ErrorCode::PollNotEnded);
+        require!(!poll.finalized, ErrorCode::PollAlreadyFinalized);
+        require!(!poll.is_shielded() || poll.shielded_published, ErrorCode::ShieldedResultsPending);
+        require!(!poll.randomness_pending, ErrorCode::RandomnessPending);
+        require!(
+            ctx.remaining_accounts.len() as u64 == poll.candidate_count,
+            ErrorCode::CandidateListMismatch
+        );
+        // Every ballot must have arrived within the voting window; polls migrated from before
+        // vote times were recorded have none to check
+        if poll.first_vote_at != 0 {
+            require!(
+                poll.start_time <= poll.first_vote_at
+                    && poll.first_vote_at <= poll.last_vote_at
+                    && poll.last_vote_at <= poll.end_time,
+                ErrorCode::VoteOutsideWindow
+            );
+        }
+
+        // Find the candidate with the most votes
+        let mut candidates: Vec<(&AccountInfo, Candidate)> = Vec::with_capacity(ctx.remaining_accounts.len());
+        let mut winner: Option<(Pubkey, u64)> = None;
+        for info in ctx.remaining_accounts.iter() {
+            require_keys_eq!(*info.owner, crate::ID, ErrorCode::CandidateListMismatch);
+            let candidate = Candidate::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            require_keys_eq!(candidate.poll, poll.key(), ErrorCode::CandidateListMismatch);
+            require!(
+                candidates.iter().all(|(seen, _)| seen.key != info.key),
+                ErrorCode::CandidateListMismatch
+            );
+
+            if winner.is_none_or(|(_, votes)| candidate.votes > votes) {
+                winner = Some((info.key(), candidate.votes));
+            }
+            candidates.push((info, candidate));
+        }
+
+        // Nobody wins with no votes, however many candidates tie at zero
+        winner = winner.filter(|(_, top_votes)| *top_votes > 0);
+
+        // A tie needs a revealed tie-break seed, which picks the tied candidate with the lowest
+        // rank as the sole winner. If none is applied within the tie-break window, every tied
+        // candidate is flagged and the lowest index is recorded, so the caller's account order
+        // never decides.
+        if let Some((_, top_votes)) = winner {
+            let mut tied: Vec<(&AccountInfo, Candidate)> =
+                candidates.into_iter().filter(|(_, c)| c.votes == top_votes).collect();
+            tied.sort_by_key(|(info, c)| (c.index, info.key()));
+            if tied.len() > 1 {
+                if poll.tie_break_seed != [0u8; 32] {
+                    tied.sort_by_key(|(_, c)| random_rank(&poll.tie_break_seed, c.index));
+                    tied.truncate(1);
+                } else {
+                    require!(
+                        clock > poll.end_time.saturating_add(TIE_BREAK_WINDOW),
+                        ErrorCode::TieBreakRequired
+                    );
+                }
+            }
+            winner = Some((tied[0].0.key(), top_votes));
+            for (info, mut candidate) in tied {
+                require!(info.is_writable, ErrorCode::CandidateListMismatch);
+                candidate.is_winner = true;
+                candidate.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+            }
+        }
+
+        poll.winner = winner.map(|(key, _)| key).unwrap_or_default();
+        poll.finalized = true;
+        poll.status = PollStatus::Closed;
+
+        // Turnout is only meaningful when voters registered, and is capped at 100%
+        if poll.eligible_voters > 0 {
+            let turnout = poll.total_votes as u128 * FULL_WEIGHT_BPS as u128 / poll.eligible_voters as u128;
+            poll.turnout_bps = turnout.min(FULL_WEIGHT_BPS as u128) as u16;
+        }
+
+        if poll.first_vote_at != 0 {
+            msg!(
+                "{} votes cast between {} and {}",
+                poll.total_votes,
+                poll.first_vote_at,
+                poll.last_vote_at
+            );
+        }
+        msg!("Poll {} finalized, winner: {}", poll.poll_id, poll.winner);
+        Ok(())
+    }
+
+    /// Mint a one-of-one Metaplex NFT to the winner's payout wallet.
+    /// Anyone may call this once after finalization; the caller pays for the new accounts.
+    pub fn mint_winner_badge(ctx: Context<MintWinnerBadge>, uri: String) -> Result<()> {
+        let poll = &ctx.accounts.poll;
+        require!(poll.finalized, ErrorCode::PollNotFinalized);
+        require!(!poll.winner_badge_minted, ErrorCode::WinnerBadgeAlreadyMinted);
+        require!(uri.len() <= MAX_URI_LEN, ErrorCode::UriTooLong);
+
+        let accounts = &ctx.accounts;
+        let (payer, mint, poll_info) = (
+            accounts.payer.key(),
+            accounts.badge_mint.key(),
+            accounts.poll.to_account_info(),
+        );
+        let (badge_token_account, _) = Pubkey::find_program_address(
+            &[accounts.payout_wallet.key().as_ref(), token::ID.as_ref(), mint.as_ref()],
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        );
+        require_keys_eq!(
+            accounts.badge_token_account.key(),
+            badge_token_account,
+            ErrorCode::InvalidBountyRecipient
+        );
+
+        let poll_id_bytes = poll.poll_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[POLL_SEED, poll_id_bytes.as_ref(), &[poll.bump]]];
+
+        // Create the payout wallet's token account (CreateIdempotent) and mint the single badge
+        invoke(
+            &Instruction {
+                program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new(badge_token_account, false),
+                    AccountMeta::new_readonly(accounts.payout_wallet.key(), false),
+                    AccountMeta::new_readonly(mint, false),
+                    AccountMeta::new_readonly(accounts.system_program.key(), false),
+                    AccountMeta::new_readonly(accounts.token_program.key(), false),
+                ],
+                data: vec![1],
+            },
+            &[
+                accounts.payer.to_account_info(),
+                accounts.badge_token_account.to_account_info(),
+                accounts.payout_wallet.to_account_info(),
+                accounts.badge_mint.to_account_info(),
+                accounts.system_program.to_account_info(),
+                accounts.token_program.to_account_info(),
+                accounts.associated_token_program.to_account_info(),
+            ],
+        )?;
+        token::mint_to(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: accounts.badge_mint.to_account_info(),
+                    to: accounts.badge_token_account.to_account_info(),
+                    authority: poll_info.clone(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        // Metadata account, with the poll PDA as mint and update authority
+        let metadata = BadgeMetadataArgs {
+            name: format!("Poll #{} Winner", poll.poll_id),
+            symbol: "WIN".to_string(),
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+            is_mutable: false,
+            collection_details: None,
+        };
+        let mut data = vec![CREATE_METADATA_ACCOUNT_V3];
+        metadata
+            .serialize(&mut data)
+            .map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotSerialize)?;
+        invoke_signed(
+            &Instruction {
+                program_id: TOKEN_METADATA_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new(accounts.metadata.key(), false),
+                    AccountMeta::new_readonly(mint, false),
+                    AccountMeta::new_readonly(poll_info.key(), true), // mint authority
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(poll_info.key(), true), // update authority
+                    AccountMeta::new_readonly(accounts.system_program.key(), false),
+                ],
+                data,
+            },
+            &[
+                accounts.metadata.to_account_info(),
+                accounts.badge_mint.to_account_info(),
+                poll_info.clone(),
+                accounts.payer.to_account_info(),
+                accounts.system_program.to_account_info(),
+                accounts.token_metadata_program.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+
+        // Master edition with a max supply of zero makes the badge a one-of-one
+        let mut data = vec![CREATE_MASTER_EDITION_V3];
+        Some(0u64)
+            .serialize(&mut data)
+            .map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotSerialize)?;
+        invoke_signed(
+            &Instruction {
+                program_id: TOKEN_METADATA_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new(accounts.master_edition.key(), false),
+                    AccountMeta::new(mint, false),
+                    AccountMeta::new_readonly(poll_info.key(), true), // update authority
+                    AccountMeta::new_readonly(poll_info.key(), true), // mint authority
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new(accounts.metadata.key(), false),
+                    AccountMeta::new_readonly(accounts.token_program.key(), false),
+                    AccountMeta::new_readonly(accounts.system_program.key(), false),
+                ],
+                data,
+            },
+            &[
+                accounts.master_edition.to_account_info(),
+                accounts.badge_mint.to_account_info(),
+                poll_info,
+                accounts.payer.to_account_info(),
+                accounts.metadata.to_account_info(),
+                accounts.token_program.to_account_info(),
+                accounts.system_program.to_account_info(),
+                accounts.token_metadata_program.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+
+        let poll = &mut ctx.accounts.poll;
+        poll.winner_badge_minted = true;
+
+        msg!("Winner badge {} minted for poll {}", mint, poll.poll_id);
+        Ok(())
+    }
+
+    /// Register the auditors who may attest the poll's result once it is finalized. Auditors
+    /// are fixed before the poll is activated, so voters know who will check the outcome.
+    pub fn register_auditors(ctx: Context<RegisterAuditors>, auditors: Vec<Pubkey>) -> Result<()> {
+        require!(ctx.accounts.poll.status == PollStatus::Draft, ErrorCode::PollNotDraft);
+        require!(
+            !auditors.is_empty() && auditors.len() <= MAX_AUDITORS,
+            ErrorCode::InvalidAuditors
+        );
+        require!(
+            auditors.iter().enumerate().all(|(i, auditor)| !auditors[..i].contains(auditor)),
+            ErrorCode::InvalidAuditors
+        );
+
+        let result = &mut ctx.accounts.poll_result;
+        result.poll = ctx.accounts.poll.key();
+        result.auditors = auditors;
+        result.bump = ctx.bumps.poll_result;
+
+        msg!(
+            "{} auditors registered for poll {}",
+            result.auditors.len(),
+            ctx.accounts.poll.poll_id
+        );
+        Ok(())
+    }
+
+    /// Attest a finalized poll's result as one of its registered auditors. The auditor states
+    /// the winner and vote total they checked, which must match the poll's.
+    pub fn attest_result(ctx: Context<AttestResult>, winner: Pubkey, total_votes: u64) -> Result<()> {
+        let poll = &ctx.accounts.poll;
+        require!(poll.finalized, ErrorCode::PollNotFinalized);
+        require!(
+            winner == poll.winner && total_votes == poll.total_votes,
+            ErrorCode::ResultMismatch
+        );
+
+        let auditor = ctx.accounts.auditor.key();
+        let result = &mut ctx.accounts.poll_result;
+        let index = result
+            .auditors
+            .iter()
+            .position(|registered| *registered == auditor)
+            .ok_or(ErrorCode::NotAuditor)?;
+        let bit = 1u16 << index;
+        require!(result.attestations & bit == 0, ErrorCode::AlreadyAttested);
+        result.attestations |= bit;
+        result.attestation_count = result.attestation_count.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        result.winner = winner;
+        result.total_votes = total_votes;
+
+        msg!(
+            "Result of poll {} attested by {} ({} of {})",
+            poll.poll_id,
+            auditor,
+            result.attestation_count,
+            result.auditors.len()
+        );
+        Ok(())
+    }
+
+    /// Pay the escrowed bounty to the winner's payout wallet.
+    /// If the poll ended without a winner, the bounty returns to the creator.
+    pub fn claim_bounty(ctx: Context<ClaimBounty>) -> Result<()> {
+        let poll = &mut ctx.accounts.poll;
+        require!(poll.finalized, ErrorCode::PollNotFinalized);
+        require!(poll.bounty_lamports > 0 && !poll.bounty_claimed, ErrorCode::NoBountyToClaim);
+
+        let expected_recipient = if poll.winner == Pubkey::default() {
+            poll.creator
+        } else {
+            let candidate = ctx.accounts.candidate.as_ref().ok_or(ErrorCode::InvalidBountyRecipient)?;
+            require_keys_eq!(candidate.key(), poll.winner, ErrorCode::InvalidBountyRecipient);
+            candidate.payout_wallet
+        };
+        require_keys_eq!(ctx.accounts.recipient.key(), expected_recipient, ErrorCode::InvalidBountyRecipient);
+
+        let amount = poll.bounty_lamports;
+        poll.bounty_claimed = true;
+        poll.sub_lamports(amount)?;
+        ctx.accounts.recipient.add_lamports(amount)?;
+
+        msg!("Bounty of {} lamports paid to {}", amount, expected_recipient);
+        Ok(())
+    }
+
+    /// Fund the poll's SPL token reward pool for voters
+    pub fn fund_reward_pool(ctx: Context<FundRewardPool>, amount: u64) -> Result<()> {
+        let poll = &mut ctx.accounts.poll;
+
+        // Only the poll creator can fund the pool, and only before finalization
+        require_keys_eq!(poll.creator, ctx.accounts.creator.key(), ErrorCode::Unauthorized);
+        require!(poll.receipt_tree == Pubkey::default(), ErrorCode::RewardsNeedReceipts);
+        require!(!poll.finalized, ErrorCode::PollAlreadyFinalized);
+
+        // The first funding fixes the reward mint for the poll
+        let mint = ctx.accounts.mint.key();
+        if poll.reward_mint == Pubkey::default() {
+            poll.reward_mint = mint;
+        }
+        require_keys_eq!(poll.reward_mint, mint, ErrorCode::RewardMintMismatch);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.creator_token_account.to_account_info(),
+                    to: ctx.accounts.reward_vault.to_account_info(),
+                    authority: ctx.accounts.creator.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        poll.reward_pool_amount = poll
+            .reward_pool_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!("Reward pool for poll {} funded with {} tokens", poll.poll_id, amount);
+        Ok(())
+    }
+
+    /// Claim a voter's share of the reward pool after finalization
+    pub fn claim_reward(ctx: Context<ClaimReward>) -> Result<()> {
+        let poll = &ctx.accounts.poll;
+        require!(poll.finalized, ErrorCode::PollNotFinalized);
+
+        let receipt = &mut ctx.accounts.voter_receipt;
+        require!(!receipt.reward_claimed, ErrorCode::RewardAlreadyClaimed);
+        receipt.reward_claimed = true;
+
+        // Every receipt entitles the voter to an equal share of the pool
+        let share = poll.reward_pool_amount.checked_div(poll.receipt_votes()).unwrap_or(0);
+        require!(share > 0, ErrorCode::NoRewardToClaim);
+
+        let poll_id_bytes = poll.poll_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[POLL_SEED, poll_id_bytes.as_ref(), &[poll.bump]]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.reward_vault.to_account_info(),
+                    to: ctx.accounts.voter_token_account.to_account_info(),
+                    authority: ctx.accounts.poll.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            share,
+        )?;
+
+        msg!("Voter {} claimed {} reward tokens", ctx.accounts.voter.key(), share);
+        Ok(())
+    }
+
+    /// Settle a candidate's registration deposit after finalization
+    pub fn claim_deposit(ctx: Context<ClaimDeposit>) -> Result<()> {
+        let poll = &ctx.accounts.poll;
+        require!(poll.finalized, ErrorCode::PollNotFinalized);
+
+        let candidate = &mut ctx.accounts.candidate;
+        let amount = candidate.deposit;
+        require!(amount > 0, ErrorCode::NoDepositToClaim);
+        candidate.deposit = 0;
+
+        // Refund candidates that reached the threshold, forfeit the rest to the treasury
+        let refunded = candidate.votes >= poll.deposit_refund_threshold;
+        candidate.sub_lamports(amount)?;
+        if refunded {
+            ctx.accounts.depositor.add_lamports(amount)?;
+        } else {
+            ctx.accounts.treasury.add_lamports(amount)?;
+            let treasury = &mut ctx.accounts.treasury;
+            treasury.total_collected = treasury
+                .total_collected
+                .checked_add(amount)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+
+        msg!(
+            "Deposit of {} lamports {} for candidate {}",
+            amount,
+            if refunded { "refunded" } else { "forfeited" },
+            candidate.name
+        );
+        Ok(())
+    }
+
+    /// Close a receipt once its poll has been over for the grace period and any reward on it was claimed.
+    /// Anyone may call this; the cranker keeps a cut of the rent and the rest returns to whoever paid it.
+    pub fn crank_close_receipt(ctx: Context<CrankCloseReceipt>) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let poll = &ctx.accounts.poll;
+        require!(
+            clock > poll.end_time.saturating_add(RECEIPT_GRACE_PERIOD),
+            ErrorCode::ReceiptGracePeriodActive
+        );
+
+        // Closing the receipt would forfeit the voter's share of the reward pool
+        let share = poll.reward_pool_amount.checked_div(poll.receipt_votes()).unwrap_or(0);
+        require!(ctx.accounts.voter_receipt.reward_claimed || share == 0, ErrorCode::RewardUnclaimed);
+
+        // The remaining lamports go to the payer when the account closes
+        let rent = ctx.accounts.voter_receipt.get_lamports();
+        let reward = rent.checked_mul(CRANK_REWARD_BPS).ok_or(ErrorCode::Overflow)? / FULL_WEIGHT_BPS;
+        ctx.accounts.voter_receipt.sub_lamports(reward)?;
+        ctx.accounts.cranker.add_lamports(reward)?;
+
+        msg!(
+            "Receipt of {} for poll {} closed, {} lamports to the cranker",
+            ctx.accounts.voter_receipt.voter,
+            poll.poll_id,
+            reward
+        );
+        Ok(())
+    }
+
+    /// Create the encrypted per-candidate totals of a shielded poll once its candidate list is frozen
+    pub fn init_shielded_tally(ctx: Context<InitShieldedTally>) -> Result<()> {
+        let poll = &ctx.accounts.poll;
+        require!(poll.is_shielded(), ErrorCode::PollNotShielded);
+        require!(poll.status == PollStatus::Active, ErrorCode::PollNotActive);
+
+        // Every total starts as an encryption of zero: the identity point in both halves
+        let tally = &mut ctx.accounts.tally;
+        tally.poll = poll.key();
+        tally.totals = vec![ElGamalCiphertext::default(); poll.candidate_count as usize];
+
+        msg!("Shielded tally opened for poll {}", poll.poll_id);
+        Ok(())
+    }
+
+    /// Cast a secret ballot in a shielded poll: one ElGamal ciphertext per candidate, in ballot
+    /// order, each proven to encrypt 0 or 1, plus a proof that they sum to exactly one vote.
+    /// The ciphertexts are added to the encrypted totals without revealing the choice.
+    pub fn vote_shielded(
+        ctx: Context<VoteShielded>,
+        choices: Vec<ShieldedChoice>,
+        sum_proof: DleqProof,
+    ) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let poll_key = ctx.accounts.poll.key();
+        let voter = ctx.accounts.voter.key();
+
+        check_vote_origin(&ctx.accounts.poll, &ctx.accounts.instructions)?;
+        check_voter_eligibility(&ctx.accounts.poll, &ctx.accounts.voter, &VoterCredentials::default())?;
+        let poll = &mut ctx.accounts.poll;
+        admit_ballot(poll, clock)?;
+
+        let tally = &mut ctx.accounts.tally;
+        require!(choices.len() == tally.totals.len(), ErrorCode::InvalidShieldedBallot);
+
+        // Verify each entry against the poll's key, binding the proofs to this poll and voter
+        let key = poll.shielded_key;
+        let context: [&[u8]; 2] = [poll_key.as_ref(), voter.as_ref()];
+        let mut sum = ElGamalCiphertext::default();
+        for (total, choice) in tally.totals.iter_mut().zip(choices.iter()) {
+            verify_bit_proof(&key, &context, choice)?;
+            *total = total.add_ciphertext(&choice.ciphertext)?;
+            sum = sum.add_ciphertext(&choice.ciphertext)?;
+        }
+
+        // The ballot's total randomness links its halves: log_G(ephemeral) == log_key(masked - G)
+        let mut transcript = context.to_vec();
+        transcript.extend_from_slice(&[&b"sum"[..], &key, &sum.ephemeral, &sum.masked]);
+        verify_dleq_proof(&sum_proof, &transcript, &sum.ephemeral, &key, &sum.masked, 1)?;
+
+        poll.total_votes = poll.total_votes.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+        // Initialize the voter receipt to prevent double voting
+        let receipt = &mut ctx.accounts.voter_receipt;
+        receipt.poll = poll_key;
+        receipt.voter = voter;
+        receipt.has_voted = true;
+        receipt.reward_claimed = false;
+        receipt.version = RECEIPT_VERSION;
+        receipt.weight = 1;
+        receipt.bump = ctx.bumps.voter_receipt;
+        receipt.payer = voter;
+
+        msg!("Shielded vote cast successfully");
+        Ok(())
+    }
+
+    /// Publish the decrypted totals of a shielded poll after it ends. Each total carries a proof
+    /// that it is the decryption of the on-chain sum under the poll's key, so anyone holding the
+    /// key (the creator or a committee) may submit it. Candidates are passed, writable and in
+    /// ballot order, in `remaining_accounts`; their tallies are then finalized as usual.
+    pub fn publish_shielded_results(
+        ctx: Context<PublishShieldedResults>,
+        results: Vec<ShieldedResult>,
+    ) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let poll = &mut ctx.accounts.poll;
+        let poll_key = poll.key();
+        require!(poll.is_shielded(), ErrorCode::PollNotShielded);
+        require!(clock > poll.end_time, ErrorCode::PollNotEnded);
+        require!(!poll.shielded_published, ErrorCode::ShieldedResultsPublished);
+
+        let totals = &ctx.accounts.tally.totals;
+        require!(
+            results.len() == totals.len() && ctx.remaining_accounts.len() == totals.len(),
+            ErrorCode::CandidateListMismatch
+        );
+
+        let key = poll.shielded_key;
+        let mut counted = 0u64;
+        for (index, ((info, total), result)) in
+            ctx.remaining_accounts.iter().zip(totals.iter()).zip(results.iter()).enumerate()
+        {
+            require_keys_eq!(*info.owner, crate::ID, ErrorCode::CandidateListMismatch);
+            let mut candidate = Candidate::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            require_keys_eq!(candidate.poll, poll_key, ErrorCode::CandidateListMismatch);
+            require!(candidate.index as usize == index, ErrorCode::CandidateListMismatch);
+
+            // The decryption key links the poll's key to the total: log_G(key) == log_ephemeral(masked - votes*G)
+            verify_dleq_proof(
+                &result.proof,
+                &[
+                    poll_key.as_ref(),
+                    &b"decrypt"[..],
+                    &key,
+                    &total.ephemeral,
+                    &total.masked,
+                    &result.votes.to_le_bytes(),
+                ],
+                &key,
+                &total.ephemeral,
+                &total.masked,
+                result.votes,
+            )?;
+
+            candidate.votes = result.votes;
+            candidate.raw_votes = result.votes;
+            candidate.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+            counted = counted.checked_add(result.votes).ok_or(ErrorCode::Overflow)?;
+        }
+
+        // Every ballot is proven to hold exactly one vote
+        require!(counted == poll.total_votes, ErrorCode::InvalidShieldedProof);
+        poll.shielded_published = true;
+
+        msg!("Shielded results of poll {} published", poll.poll_id);
+        Ok(())
+    }
+
+    /// Condense a settled poll into a `PollArchive` and close the poll, its candidates and its
+    /// reward vault, returning their rent to the creator. Every candidate must be passed,
+    /// writable, in `remaining_accounts`. Receipts can no longer be cranked once the poll is gone,
+    /// so archiving waits for the receipt grace period. The archive retires the poll ID for good.
+    pub fn archive_poll<'info>(ctx: Context<'_, '_, 'info, 'info, ArchivePoll<'info>>) -> Result<()> {
+        let clock = Clock::get()?.unix_timestamp;
+        let poll = &ctx.accounts.poll;
+        require!(poll.finalized, ErrorCode::PollNotFinalized);
+        require!(
+            clock > poll.end_time.saturating_add(RECEIPT_GRACE_PERIOD),
+            ErrorCode::ArchiveGracePeriodActive
+        );
+        require!(poll.bounty_lamports == 0 || poll.bounty_claimed, ErrorCode::UnsettledPollFunds);
+        // Every receipt's share of a funded pool must have been claimed. Only the remainder the
+        // equal shares can't split may be left in the vault.
+        if poll.reward_pool_amount > 0 {
+            let vault = ctx.accounts.reward_vault.as_ref().ok_or(ErrorCode::UnsettledPollFunds)?;
+            let unclaimable =
+                poll.reward_pool_amount.checked_rem(poll.receipt_votes()).unwrap_or(poll.reward_pool_amount);
+            require!(vault.amount <= unclaimable, ErrorCode::UnsettledPollFunds);
+        }
+        // Once created, the vault is closed along with the poll
+        if poll.reward_mint != Pubkey::default() {
+            require!(ctx.accounts.reward_vault.is_some(), ErrorCode::UnsettledPollFunds);
+        }
+        require!(
+            ctx.remaining_accounts.len() as u64 == poll.candidate_count,
+            ErrorCode::CandidateListMismatch
+        );
+        require!(
+            ctx.remaining_accounts.len() <= MAX_ARCHIVED_CANDIDATES,
+            ErrorCode::TooManyCandidatesToArchive
+        );
+
+        // Record each candidate's totals, then close it
+        let mut candidates: Vec<(u16, ArchivedCandidate)> = Vec::with_capacity(ctx.remaining_accounts.len());
+        let mut closed: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+        for info in ctx.remaining_accounts.iter() {
+            require!(!closed.contains(info.key), ErrorCode::CandidateListMismatch);
+            let candidate = Account::<Candidate>::try_from(info)?;
+            require_keys_eq!(candidate.poll, poll.key(), ErrorCode::CandidateListMismatch);
+            require!(candidate.deposit == 0, ErrorCode::UnsettledPollFunds);
+
+            candidates.push((
+                candidate.index,
+                ArchivedCandidate {
+                    name: candidate.name.clone(),
+                    votes: candidate.votes,
+                    raw_votes: candidate.raw_votes,
+                    is_winner: candidate.is_winner,
+                },
+            ));
+            closed.push(info.key());
+            candidate.close(ctx.accounts.creator.to_account_info())?;
+        }
+        candidates.sort_by_key(|(index, _)| *index);
+
+        // Return the remainder to the creator and close the vault
+        if let Some(vault) = ctx.accounts.reward_vault.as_ref() {
+            let token_program = ctx.accounts.token_program.as_ref().ok_or(ErrorCode::UnsettledPollFunds)?;
+            let poll_id_bytes = poll.poll_id.to_le_bytes();
+            let signer_seeds: &[&[&[u8]]] = &[&[POLL_SEED, poll_id_bytes.as_ref(), &[poll.bump]]];
+            if vault.amount > 0 {
+                let creator_token_account =
+                    ctx.accounts.creator_token_account.as_ref().ok_or(ErrorCode::UnsettledPollFunds)?;
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        token_program.to_account_info(),
+                        token::Transfer {
+                            from: vault.to_account_info(),
+                            to: creator_token_account.to_account_info(),
+                            authority: poll.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    vault.amount,
+                )?;
+            }
+            token::close_account(CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token::CloseAccount {
+                    account: vault.to_account_info(),
+                    destination: ctx.accounts.creator.to_account_info(),
+                    authority: poll.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        }
+
+        let archive = &mut ctx.accounts.archive;
+        archive.poll_id = poll.poll_id;
+        archive.creator = poll.creator;
+        archive.question = poll.question.clone();
+        archive.winner = poll.winner;
+        archive.total_votes = poll.total_votes;
+        archive.end_time = poll.end_time;
+        archive.archived_at = clock;
+        archive.candidates = candidates.into_iter().map(|(_, candidate)| candidate).collect();
+
+        msg!("Poll {} archived, {} candidates closed", poll.poll_id, closed.len());
+        Ok(())
+    }
+
+    /// Attach an instruction to a draft poll, to be executed if `yes_candidate` wins enough votes.
+    /// The poll's authority PDA signs the instruction wherever it is listed as a signer.
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        threshold_bps: u16,
+        program_id: Pubkey,
+        accounts: Vec<ProposalAccountMeta>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        require!(ctx.accounts.poll.status == PollStatus::Draft, ErrorCode::PollNotDraft);
+        require!(threshold_bps as u64 <= FULL_WEIGHT_BPS, ErrorCode::InvalidThreshold);
+        require!(
+            accounts.len() <= MAX_PROPOSAL_ACCOUNTS && data.len() <= MAX_PROPOSAL_DATA_LEN,
+            ErrorCode::ProposalTooLarge
+        );
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.poll = ctx.accounts.poll.key();
+        proposal.yes_candidate = ctx.accounts.yes_candidate.key();
+        proposal.threshold_bps = threshold_bps;
+        proposal.program_id = program_id;
+        proposal.accounts = accounts;
+        proposal.data = data;
+        proposal.executed = false;
+
+        msg!("Proposal attached to poll {}", ctx.accounts.poll.poll_id);
+        Ok(())
+    }
+
+    /// Execute a finalized poll's proposal if the yes candidate received more than the
+    /// threshold share of ballots. Anyone may call this; the instruction's accounts are passed in
+    /// `remaining_accounts` in the order they were stored.
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+    ) -> Result<()> {
+        let poll = &ctx.accounts.poll;
+        require!(poll.finalized, ErrorCode::PollNotFinalized);
+
+        let proposal = &mut ctx.accounts.proposal;
+        require!(!proposal.executed, ErrorCode::ProposalAlreadyExecuted);
+
+        // Compare ballots rather than weights so decaying polls use the same scale
+        let yes_votes = ctx.accounts.yes_candidate.raw_votes as u128;
+        let required = poll.total_votes as u128 * proposal.threshold_bps as u128;
+        require!(
+            poll.total_votes > 0 && yes_votes * FULL_WEIGHT_BPS as u128 > required,
+            ErrorCode::ProposalNotPassed
+        );
+
+        require!(
+            ctx.remaining_accounts.len() == proposal.accounts.len(),
+            ErrorCode::ProposalAccountMismatch
+        );
+        let mut metas = Vec::with_capacity(proposal.accounts.len());
+        for (meta, info) in proposal.accounts.iter().zip(ctx.remaining_accounts) {
+            require_keys_eq!(meta.pubkey, info.key(), ErrorCode::ProposalAccountMismatch);
+            metas.push(AccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            });
+        }
+        let instruction = Instruction {
+            program_id: proposal.program_id,
+            accounts: metas,
+            data: proposal.data.clone(),
+        };
+        proposal.executed = true;
+
+        let mut infos = ctx.remaining_accounts.to_vec();
+        infos.push(ctx.accounts.target_program.to_account_info());
+        let poll_key = poll.key();
+        invoke_signed(
+            &instruction,
+            &infos,
+            &[&[POLL_AUTHORITY_SEED, poll_key.as_ref(), &[ctx.bumps.poll_authority]]],
+        )?;
+
+        msg!("Proposal of poll {} executed", poll.poll_id);
+        Ok(())
+    }
+}
+
+// Move a poll's end time. A scheduled finalization thread would still fire at the old end time,
+// and once voters have seen the deadline it can only be pushed back within the poll's window.
+fn move_end_time(poll: &mut Poll, end_time: i64) -> Result<()> {
+    require!(poll.finalize_thread == Pubkey::default(), ErrorCode::FinalizationAlreadyScheduled);
+    if poll.total_votes > 0 {
+        require!(end_time >= poll.end_time, ErrorCode::EndTimeLocked);
+        let extended_by = poll.extended_by.checked_add(end_time - poll.end_time).ok_or(ErrorCode::Overflow)?;
+        require!(extended_by <= poll.max_extension, ErrorCode::ExtensionTooLong);
+        poll.extended_by = extended_by;
+    }
+    poll.end_time = end_time;
+    Ok(())
+}
+
+// Shared vote logic for single-poll and ballot voting
+fn record_vote(
+    poll: &mut Poll,
+    poll_key: Pubkey,
+    candidate: &mut Candidate,
+    receipt: &mut VoterReceipt,
+    voter: Pubkey,
+    weight: u64,
+    now: i64,
+) -> Result<()> {
+    // Polls with a receipt tree track voters in its leaves, so a receipt vote would be a second ballot
+    require!(poll.receipt_tree == Pubkey::default(), ErrorCode::PollHasReceiptTree);
+    tally_vote(poll, poll_key, candidate, weight, now)?;
+
+    // Initialize the voter receipt to prevent double voting
+    receipt.poll = poll_key;
+    receipt.voter = voter;
+    receipt.has_voted = true;
+    receipt.reward_claimed = false;
+    receipt.version = RECEIPT_VERSION;
+    receipt.weight = weight;
+    receipt.payer = voter;
+    Ok(())
+}
+
+// Reject votes cast through another program unless the poll opted into composability.
+// The instructions sysvar only lists top-level instructions, so when `vote` runs as a CPI
+// the current entry belongs to the calling program.
+fn check_vote_origin(poll: &Poll, instructions: &AccountInfo) -> Result<()> {
+    if poll.allow_cpi {
+        return Ok(());
+    }
+    let current = instructions_sysvar::get_instruction_relative(0, instructions)?;
+    require_keys_eq!(current.program_id, crate::ID, ErrorCode::VoteViaCpi);
+    Ok(())
+}
+
+// Reject transactions packing more than one `vote` for the same poll and voter, so a client
+// cannot stack ballots ahead of the receipt. Only `vote` instructions naming this poll and voter
+// count: a batch voting once in each of several polls passes.
+fn check_single_vote(poll: &Pubkey, voter: &Pubkey, instructions: &AccountInfo) -> Result<()> {
+    let (mut votes, mut index) = (0, 0);
+    while let Ok(entry) = instructions_sysvar::load_instruction_at_checked(index, instructions) {
+        index += 1;
+        // `vote` lists the poll first and the voter fourth
+        let same_vote = entry.program_id == crate::ID
+            && entry.data.starts_with(instruction::Vote::DISCRIMINATOR)
+            && entry.accounts.first().is_some_and(|meta| meta.pubkey == *poll)
+            && entry.accounts.get(3).is_some_and(|meta| meta.pubkey == *voter);
+        if same_vote {
+            votes += 1;
+        }
+    }
+    require!(votes <= 1, ErrorCode::DuplicateVote);
+    Ok(())
+}
+
+// Only the poll creator or its candidate manager can add a candidate (or, with open candidacy,
+// a self-nominee), and only while the poll is a draft
+fn check_candidate_registration(
+    poll: &Poll,
+    signer: &Pubkey,
+    self_nomination: bool,
+    name: &str,
+    metadata_uri: &str,
+) -> Result<()> {
+    require!(
+        self_nomination || poll.creator == *signer || poll.candidate_manager == Some(*signer),
+        ErrorCode::Unauthorized
+    );
+    require!(poll.status == PollStatus::Draft, ErrorCode::PollNotDraft);
+    require!(!name.trim().is_empty(), ErrorCode::EmptyCandidateName);
+    require!(name.len() <= MAX_CANDIDATE_NAME_LEN, ErrorCode::CandidateNameTooLong);
+    require!(metadata_uri.len() <= MAX_URI_LEN, ErrorCode::UriTooLong);
+    require!(
+        !poll.is_shielded() || poll.candidate_count < MAX_SHIELDED_CANDIDATES,
+        ErrorCode::TooManyCandidates
+    );
+    Ok(())
+}
+
+// What a voter presents to meet the poll's requirements. Only `vote` takes any of it; the
+// other vote instructions present the empty default.
+#[derive(Default)]
+struct VoterCredentials<'a, 'info> {
+    stake_account: Option<&'a AccountInfo<'info>>,
+    attestation: Option<&'a AccountInfo<'info>>,
+    eligibility_verified: bool,
+    access_granted: bool,
+    weight_record: Option<&'a WeightRecord>,
+    // The instruction collects the poll's vote fee
+    pays_fee: bool,
+}
+
+// Enforce the poll's anti-sybil thresholds. Stake-gated and stake-weighted polls need a native
+// stake account controlled by the voter, oracle-weighted polls a fresh weight record and fee
+// polls a paid fee, so they can only be voted on through `vote`.
+fn check_voter_eligibility(poll: &Poll, voter: &AccountInfo, credentials: &VoterCredentials) -> Result<()> {
+    require!(
+        voter.lamports() >= poll.min_voter_lamports,
+        ErrorCode::InsufficientVoterBalance
+    );
+    if poll.eligibility_program.is_some() {
+        require!(credentials.eligibility_verified, ErrorCode::EligibilityProgramMissing);
+    }
+    if poll.access_restricted {
+        require!(credentials.access_granted, ErrorCode::AccessNotGranted);
+    }
+    if poll.vote_fee > 0 {
+        require!(credentials.pays_fee, ErrorCode::VoteFeeUnpaid);
+    }
+
+    if poll.min_stake_lamports > 0 {
+        let stake_account = credentials.stake_account.ok_or(ErrorCode::InvalidStakeAccount)?;
+        let delegated = active_stake_of(stake_account, voter.key)?;
+        require!(delegated >= poll.min_stake_lamports, ErrorCode::InsufficientStake);
+    }
+    if poll.stake_weighted {
+        require!(credentials.stake_account.is_some(), ErrorCode::InvalidStakeAccount);
+    }
+    if poll.attestation_issuer != Pubkey::default() {
+        let attestation = credentials.attestation.ok_or(ErrorCode::InvalidAttestation)?;
+        check_attestation(poll, attestation, voter.key)?;
+    }
+    if poll.is_oracle_weighted() {
+        let record = credentials.weight_record.ok_or(ErrorCode::InvalidWeightRecord)?;
+        require_keys_eq!(record.oracle, poll.weight_oracle, ErrorCode::InvalidWeightRecord);
+        require_keys_eq!(record.voter, *voter.key, ErrorCode::InvalidWeightRecord);
+        require!(record.weight > 0, ErrorCode::InvalidWeightRecord);
+        let age = Clock::get()?.unix_timestamp.saturating_sub(record.updated_at);
+        require!(
+            poll.max_weight_age == 0 || age <= poll.max_weight_age,
+            ErrorCode::StaleWeightRecord
+        );
+    }
+    Ok(())
+}
+
+// Charge the poll's vote fee to the payer: lamports straight to the recipient wallet, or
+// tokens from the payer's token account to the recipient token account
+fn collect_vote_fee(accounts: &Vote) -> Result<()> {
+    let poll = &accounts.poll;
+    if poll.vote_fee == 0 {
+        return Ok(());
+    }
+    let recipient = accounts.fee_recipient.as_ref().ok_or(ErrorCode::InvalidFeeAccount)?;
+    require_keys_eq!(recipient.key(), poll.vote_fee_recipient, ErrorCode::InvalidFeeAccount);
+
+    if poll.vote_fee_mint == Pubkey::default() {
+        transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                Transfer {
+                    from: accounts.payer.to_account_info(),
+                    to: recipient.to_account_info(),
+                },
+            ),
+            poll.vote_fee,
+        )
+    } else {
+        let source = accounts.fee_source.as_ref().ok_or(ErrorCode::InvalidFeeAccount)?;
+        let token_program = accounts.token_program.as_ref().ok_or(ErrorCode::InvalidFeeAccount)?;
+        require_keys_eq!(source.mint, poll.vote_fee_mint, ErrorCode::InvalidFeeAccount);
+        // The token program rejects a recipient account of another mint
+        token::transfer(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token::Transfer {
+                    from: source.to_account_info(),
+                    to: recipient.to_account_info(),
+                    authority: accounts.payer.to_account_info(),
+                },
+            ),
+            poll.vote_fee,
+        )
+    }
+}
+
+// Check that `attestation` is an unexpired Solana Attestation Service attestation about
+// `subject` from the poll's issuer (credential) and schema. The layout is a u8 account type,
+// then the nonce (the subject), credential and schema, a length-prefixed data blob, the
+// signer and the expiry (0 for none).
+fn check_attestation(poll: &Poll, attestation: &AccountInfo, subject: &Pubkey) -> Result<()> {
+    require_keys_eq!(*attestation.owner, poll.attestation_program, ErrorCode::InvalidAttestation);
+    let data = attestation.try_borrow_data()?;
+    require!(data.len() >= 101, ErrorCode::InvalidAttestation);
+    require!(data[0] == ATTESTATION_ACCOUNT_TYPE, ErrorCode::InvalidAttestation);
+
+    let nonce = &data[1..33];
+    let credential = &data[33..65];
+    let schema = &data[65..97];
+    require!(nonce == subject.as_ref(), ErrorCode::InvalidAttestation);
+    require!(credential == poll.attestation_issuer.as_ref(), ErrorCode::InvalidAttestation);
+    require!(schema == poll.attestation_schema.as_ref(), ErrorCode::InvalidAttestation);
+
+    // Skip the attested data and the signer to reach the expiry
+    let data_len = u32::from_le_bytes(data[97..101].try_into().unwrap()) as usize;
+    let expiry_offset = 101usize
+        .checked_add(data_len)
+        .and_then(|offset| offset.checked_add(32))
+        .ok_or(ErrorCode::InvalidAttestation)?;
+    let expiry = data
+        .get(expiry_offset..expiry_offset + 8)
+        .ok_or(ErrorCode::InvalidAttestation)?;
+    let expiry = i64::from_le_bytes(expiry.try_into().unwrap());
+    require!(
+        expiry == 0 || expiry > Clock::get()?.unix_timestamp,
+        ErrorCode::AttestationExpired
+    );
+    Ok(())
+}
+
+// Read a Switchboard randomness account's seed slot, reveal slot and (possibly stale) value
+fn read_randomness(randomness: &AccountInfo) -> Result<(u64, u64, [u8; 32])> {
+    let data = randomness.try_borrow_data()?;
+    require!(
+        data.len() >= RANDOMNESS_VALUE_OFFSET + 32 && data[..8] == RANDOMNESS_DISCRIMINATOR,
+        ErrorCode::InvalidRandomness
+    );
+    let read_slot = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let value = data[RANDOMNESS_VALUE_OFFSET..RANDOMNESS_VALUE_OFFSET + 32].try_into().unwrap();
+    Ok((read_slot(RANDOMNESS_SEED_SLOT_OFFSET), read_slot(RANDOMNESS_REVEAL_SLOT_OFFSET), value))
+}
+
+// Rank of a candidate under a random seed; lower ranks come first on the ballot and win ties
+fn random_rank(seed: &[u8; 32], index: u16) -> [u8; 32] {
+    keccak::hashv(&[seed.as_ref(), index.to_le_bytes().as_ref()]).to_bytes()
+}
+
+// Ask the poll's eligibility program to vouch for the voter by calling its `verify(voter)`
+// instruction with the voter followed by `extra_accounts`, none of them as signers.
+// Any error from the verifier rejects the vote. Returns whether a verifier ran.
+fn verify_eligibility<'info>(
+    poll: &Poll,
+    voter: &AccountInfo<'info>,
+    verifier: Option<&UncheckedAccount<'info>>,
+    extra_accounts: &[AccountInfo<'info>],
+) -> Result<bool> {
+    let Some(program_id) = poll.eligibility_program else {
+        return Ok(false);
+    };
+    let verifier = verifier.ok_or(ErrorCode::EligibilityProgramMissing)?;
+    require_keys_eq!(verifier.key(), program_id, ErrorCode::EligibilityProgramMissing);
+
+    let mut accounts = vec![AccountMeta::new_readonly(voter.key(), false)];
+    accounts.extend(extra_accounts.iter().map(|info| AccountMeta {
+        pubkey: info.key(),
+        is_signer: false,
+        is_writable: info.is_writable,
+    }));
+    let mut data = VERIFY_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(voter.key.as_ref());
+
+    let mut infos = vec![voter.clone()];
+    infos.extend_from_slice(extra_accounts);
+    infos.push(verifier.to_account_info());
+    invoke(&Instruction { program_id, accounts, data }, &infos)?;
+    Ok(true)
+}
+
+// Delegated lamports and the epochs the delegation started and stopped warming
+struct StakeDelegation {
+    stake: u64,
+    activation_epoch: u64,
+    deactivation_epoch: u64,
+}
+
+// Delegation of a native stake account whose staker or withdrawer is `owner`.
+// Reads the bincode `StakeStateV2::Stake` layout: a u32 tag, then `Meta`
+// (rent reserve, staker, withdrawer, lockup), then the `Delegation`.
+fn stake_delegation_of(stake_account: &AccountInfo, owner: &Pubkey) -> Result<StakeDelegation> {
+    require_keys_eq!(*stake_account.owner, STAKE_PROGRAM_ID, ErrorCode::InvalidStakeAccount);
+    let data = stake_account.try_borrow_data()?;
+    require!(data.len() >= 180, ErrorCode::InvalidStakeAccount);
+
+    let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let tag = u32::from_le_bytes(data[0..4].try_into().unwrap());
+    let staker = &data[12..44];
+    let withdrawer = &data[44..76];
+
+    require!(tag == 2, ErrorCode::InvalidStakeAccount);
+    require!(
+        staker == owner.as_ref() || withdrawer == owner.as_ref(),
+        ErrorCode::InvalidStakeAccount
+    );
+    Ok(StakeDelegation {
+        stake: read_u64(156),
+        activation_epoch: read_u64(164),
+        deactivation_epoch: read_u64(172),
+    })
+}
+
+// Lamports actively delegated by a native stake account whose staker or withdrawer is `owner`
+fn active_stake_of(stake_account: &AccountInfo, owner: &Pubkey) -> Result<u64> {
+    let delegation = stake_delegation_of(stake_account, owner)?;
+    require!(delegation.deactivation_epoch == u64::MAX, ErrorCode::InsufficientStake);
+    Ok(delegation.stake)
+}
+
+// Cluster-wide (effective, activating) stake recorded for `epoch` in the stake history sysvar.
+// The sysvar is a bincode vector of (epoch, effective, activating, deactivating), newest first.
+fn stake_history_entry(history: &[u8], epoch: u64) -> Option<(u64, u64)> {
+    let read_u64 = |offset: usize| -> Option<u64> {
+        Some(u64::from_le_bytes(history.get(offset..offset + 8)?.try_into().ok()?))
+    };
+    let len = read_u64(0)? as usize;
+
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = (low + high) / 2;
+        let entry = 8 + mid * 32;
+        match read_u64(entry)?.cmp(&epoch) {
+            std::cmp::Ordering::Equal => return Some((read_u64(entry + 8)?, read_u64(entry + 16)?)),
+            std::cmp::Ordering::Greater => low = mid + 1,
+            std::cmp::Ordering::Less => high = mid,
+        }
+    }
+    None
+}
+
+// Effective stake of a delegation at `epoch`, replaying the runtime's warmup against the
+// cluster totals in the stake history (at the current warmup rate)
+fn effective_stake_at(delegation: &StakeDelegation, epoch: u64, stake_history: &AccountInfo) -> Result<u64> {
+    // Bootstrap stake was effective from genesis
+    if delegation.activation_epoch == u64::MAX {
+        return Ok(delegation.stake);
+    }
+    if epoch <= delegation.activation_epoch || delegation.deactivation_epoch <= epoch {
+        return Ok(0);
+    }
+
+    let history = stake_history.try_borrow_data()?;
+    // History no longer covering the activation epoch means the stake is long since warm
+    let Some((mut cluster_effective, mut cluster_activating)) =
+        stake_history_entry(&history, delegation.activation_epoch)
+    else {
+        return Ok(delegation.stake);
+    };
+
+    let mut effective = 0u64;
+    let mut current_epoch = delegation.activation_epoch;
+    loop {
+        current_epoch += 1;
+        if cluster_activating == 0 {
+            break;
+        }
+        // The delegation warms up in proportion to its share of the cluster's activating stake
+        let remaining = (delegation.stake - effective) as u128;
+        let newly_effective = remaining * cluster_effective as u128 * STAKE_WARMUP_RATE_BPS
+            / (cluster_activating as u128 * FULL_WEIGHT_BPS as u128);
+        effective = effective
+            .saturating_add((newly_effective as u64).max(1))
+            .min(delegation.stake);
+        if effective == delegation.stake || current_epoch >= epoch {
+            break;
+        }
+        match stake_history_entry(&history, current_epoch) {
+            Some(entry) => (cluster_effective, cluster_activating) = entry,
+            None => break,
+        }
+    }
+    Ok(effective)
+}
+
+// Weight of a stake-weighted vote: the voter's stake that was effective at the poll's
+// snapshot epoch. A marker PDA per stake account stops the same stake counting twice.
+fn claim_snapshot_stake(accounts: &Vote, poll_key: Pubkey) -> Result<u64> {
+    let stake_account = accounts.stake_account.as_ref().ok_or(ErrorCode::InvalidStakeAccount)?;
+    let stake_history = accounts.stake_history.as_ref().ok_or(ErrorCode::InvalidStakeAccount)?;
+    let stake_marker = accounts.stake_marker.as_ref().ok_or(ErrorCode::InvalidStakeAccount)?;
+
+    let delegation = stake_delegation_of(stake_account, accounts.voter.key)?;
+    let weight = effective_stake_at(&delegation, accounts.poll.snapshot_epoch, stake_history)?;
+    require!(weight > 0, ErrorCode::InsufficientStake);
+
+    let stake_key = stake_account.key();
+    let (marker_key, marker_bump) = Pubkey::find_program_address(
+        &[STAKE_MARKER_SEED, poll_key.as_ref(), stake_key.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(stake_marker.key(), marker_key, ErrorCode::InvalidStakeAccount);
+    require_keys_neq!(*stake_marker.owner, crate::ID, ErrorCode::StakeAlreadyCounted);
+
+    create_pda_account(
+        &accounts.payer.to_account_info(),
+        &stake_marker.to_account_info(),
+        &accounts.system_program.to_account_info(),
+        0,
+        &crate::ID,
+        &[&[STAKE_MARKER_SEED, poll_key.as_ref(), stake_key.as_ref(), &[marker_bump]]],
+    )?;
+    Ok(weight)
+}
+
+// Create an account at a PDA. `create_account` fails on any address holding lamports, so an
+// address someone has already funded is topped up to rent exemption, allocated and assigned
+// instead.
+fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        return create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount { from: payer.clone(), to: account.clone() },
+                signer_seeds,
+            ),
+            rent,
+            space as u64,
+            owner,
+        );
+    }
+
+    let shortfall = rent.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        transfer(
+            CpiContext::new(system_program.clone(), Transfer { from: payer.clone(), to: account.clone() }),
+            shortfall,
+        )?;
+    }
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate { account_to_allocate: account.clone() },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    assign(
+        CpiContext::new_with_signer(system_program.clone(), Assign { account_to_assign: account.clone() }, signer_seeds),
+        owner,
+    )
+}
+
+// Check the poll is open and credit the candidate, independent of how the receipt is stored
+fn tally_vote(
+    poll: &mut Poll,
+    poll_key: Pubkey,
+    candidate: &mut Candidate,
+    weight: u64,
+    now: i64,
+) -> Result<()> {
+    // Shielded polls only take encrypted ballots, gauge polls only split points, and
+    // conviction and realm-backed polls have their own weighted instructions
+    require!(!poll.is_shielded(), ErrorCode::PollShielded);
+    require!(poll.gauge_budget == 0, ErrorCode::PollIsGauge);
+    require!(poll.conviction_mint == Pubkey::default(), ErrorCode::PollIsConviction);
+    require!(poll.governance_realm == Pubkey::default(), ErrorCode::PollIsGovernance);
+    require_keys_eq!(candidate.poll, poll_key, ErrorCode::CandidatePollMismatch);
+    admit_ballot(poll, now)?;
+
+    credit_vote(poll, candidate, weight)
+}
+
+// Credit the candidate with the ballot's weight and count the ballot on both
+fn credit_vote(poll: &mut Poll, candidate: &mut Candidate, weight: u64) -> Result<()> {
+    candidate.votes = add_weight(candidate.votes, weight);
+    candidate.raw_votes = candidate.raw_votes.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    poll.total_votes = poll.total_votes.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    Ok(())
+}
+
+// Add a ballot's weight to a candidate's tally. Weights (stake, tokens, oracle scores) can be
+// huge, so the tally saturates at u64::MAX rather than failing every later vote in the poll;
+// ballot counts stay exact and error on overflow.
+fn add_weight(votes: u64, weight: u64) -> u64 {
+    (votes as u128 + weight as u128).min(u64::MAX as u128) as u64
+}
+
+// Governing tokens `owner` has deposited in the poll's realm, read from their spl-governance
+// `TokenOwnerRecord`. V1 and V2 records share the leading layout: a u8 account type, then the
+// realm, governing mint, owner and deposit amount.
+fn governance_power_of(poll: &Poll, record: &AccountInfo, owner: &Pubkey) -> Result<u64> {
+    require!(poll.governance_realm != Pubkey::default(), ErrorCode::PollNotGovernance);
+    require_keys_eq!(*record.owner, poll.governance_program, ErrorCode::InvalidTokenOwnerRecord);
+    let data = record.try_borrow_data()?;
+    require!(data.len() >= 105, ErrorCode::InvalidTokenOwnerRecord);
+    require!(
+        data[0] == TOKEN_OWNER_RECORD_V1 || data[0] == TOKEN_OWNER_RECORD_V2,
+        ErrorCode::InvalidTokenOwnerRecord
+    );
+
+    let realm = &data[1..33];
+    let mint = &data[33..65];
+    let record_owner = &data[65..97];
+    require!(realm == poll.governance_realm.as_ref(), ErrorCode::InvalidTokenOwnerRecord);
+    require!(mint == poll.governance_mint.as_ref(), ErrorCode::InvalidTokenOwnerRecord);
+    require!(record_owner == owner.as_ref(), ErrorCode::InvalidTokenOwnerRecord);
+
+    let deposited = u64::from_le_bytes(data[97..105].try_into().unwrap());
+    require!(deposited > 0, ErrorCode::InsufficientGovernancePower);
+    Ok(deposited)
+}
+
+// Lock a realm voter's deposit until the latest end the poll's extension window allows. Locks
+// are keyed by the authority and a one-byte id, so the id is the low byte of the poll ID; two
+// running polls sharing it would share one lock, expiring with the poll voted in last.
+fn lock_governance_deposit(accounts: &VoteGovernance, lock_bump: u8) -> Result<()> {
+    let poll = &accounts.poll;
+    let expiry = poll.end_time.saturating_add(poll.max_extension.saturating_sub(poll.extended_by));
+    let mut data = vec![SET_TOKEN_OWNER_RECORD_LOCK, poll.poll_id.to_le_bytes()[0], 1];
+    data.extend_from_slice(&expiry.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: poll.governance_program,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.realm.key(), false),
+            AccountMeta::new_readonly(accounts.realm_config.key(), false),
+            AccountMeta::new(accounts.token_owner_record.key(), false),
+            AccountMeta::new_readonly(accounts.lock_authority.key(), true),
+            AccountMeta::new(accounts.voter.key(), true), // payer
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+        ],
+        data,
+    };
+    invoke_signed(
+        &instruction,
+        &[
+            accounts.realm.to_account_info(),
+            accounts.realm_config.to_account_info(),
+            accounts.token_owner_record.to_account_info(),
+            accounts.lock_authority.to_account_info(),
+            accounts.voter.to_account_info(),
+            accounts.system_program.to_account_info(),
+            accounts.governance_program.to_account_info(),
+        ],
+        &[&[GOVERNANCE_LOCK_SEED, &[lock_bump]]],
+    )?;
+    Ok(())
+}
+
+// Check the poll is accepting ballots, count this one against the per-slot limit and record
+// when it arrived, both exactly and in the hourly histogram
+fn admit_ballot(poll: &mut Poll, now: i64) -> Result<()> {
+    // Check the poll has been activated and the current time is within its voting period
+    require!(poll.status == PollStatus::Active, ErrorCode::PollNotActive);
+    require!(now >= poll.start_time && now <= poll.end_time, ErrorCode::PollNotActive);
+    require!(!poll.paused, ErrorCode::PollPaused);
+
+    // Reject bursts beyond the poll's per-slot limit
+    if poll.max_votes_per_slot > 0 {
+        let slot = Clock::get()?.slot;
+        if slot != poll.last_vote_slot {
+            poll.last_vote_slot = slot;
+            poll.votes_in_slot = 0;
+        }
+        require!(poll.votes_in_slot < poll.max_votes_per_slot, ErrorCode::VoteRateLimited);
+        poll.votes_in_slot += 1;
+    }
+
+    if poll.first_vote_at == 0 {
+        poll.first_vote_at = now;
+    }
+    poll.last_vote_at = now;
+
+    // Ballots after the histogram's last hour all land in its last bucket
+    let hour = (now.saturating_sub(poll.start_time) / 3600) as usize;
+    let bucket = hour.min(poll.vote_histogram.len() - 1);
+    poll.vote_histogram[bucket] = poll.vote_histogram[bucket].saturating_add(1);
+    Ok(())
+}
+
+// Parse a proof scalar, rejecting non-canonical encodings
+fn shielded_scalar(bytes: &[u8; 32]) -> Result<Scalar> {
+    Option::from(Scalar::from_canonical_bytes(*bytes)).ok_or_else(|| error!(ErrorCode::InvalidShieldedProof))
+}
+
+// Fiat-Shamir challenge: 512 bits of keccak over the transcript, reduced to a scalar
+fn shielded_challenge(transcript: &[&[u8]]) -> Scalar {
+    let mut wide = [0u8; 64];
+    for (half, label) in wide.chunks_mut(32).zip([b"lo", b"hi"]) {
+        let mut parts = transcript.to_vec();
+        parts.push(label);
+        half.copy_from_slice(&keccak::hashv(&parts).to_bytes());
+    }
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+// Multiscalar multiplication through the curve25519 syscall; fails on invalid points
+fn ristretto_msm(scalars: &[Scalar], points: &[[u8; 32]]) -> Result<[u8; 32]> {
+    let scalars: Vec<PodScalar> = scalars.iter().map(|scalar| PodScalar(scalar.to_bytes())).collect();
+    let points: Vec<PodRistrettoPoint> = points.iter().map(|point| PodRistrettoPoint(*point)).collect();
+    multiscalar_multiply_ristretto(&scalars, &points)
+        .map(|point| point.0)
+        .ok_or_else(|| error!(ErrorCode::InvalidShieldedProof))
+}
+
+// Disjunctive Chaum-Pedersen proof that a ballot entry encrypts 0 or 1 under `key`.
+// Branch j claims `ephemeral = rG` and `masked - jG = r*key`; only one branch is real.
+fn verify_bit_proof(key: &[u8; 32], context: &[&[u8]], choice: &ShieldedChoice) -> Result<()> {
+    let ciphertext = &choice.ciphertext;
+    let proof = &choice.proof;
+    let (e0, e1) = (shielded_scalar(&proof.challenge0)?, shielded_scalar(&proof.challenge1)?);
+    let (z0, z1) = (shielded_scalar(&proof.response0)?, shielded_scalar(&proof.response1)?);
+
+    let t0 = ristretto_msm(&[z0, -e0], &[RISTRETTO_BASEPOINT, ciphertext.ephemeral])?;
+    let u0 = ristretto_msm(&[z0, -e0], &[*key, ciphertext.masked])?;
+    let t1 = ristretto_msm(&[z1, -e1], &[RISTRETTO_BASEPOINT, ciphertext.ephemeral])?;
+    let u1 = ristretto_msm(&[z1, -e1, e1], &[*key, ciphertext.masked, RISTRETTO_BASEPOINT])?;
+
+    let mut transcript = context.to_vec();
+    transcript.extend_from_slice(&[
+        &b"bit"[..],
+        key,
+        &ciphertext.ephemeral,
+        &ciphertext.masked,
+        &t0,
+        &u0,
+        &t1,
+        &u1,
+    ]);
+    require!(e0 + e1 == shielded_challenge(&transcript), ErrorCode::InvalidShieldedProof);
+    Ok(())
+}
+
+// Chaum-Pedersen proof that log_G(`target`) == log_`base`(`masked` - `value`*G).
+// `transcript` must commit to the whole statement; the proof's commitments are appended to it.
+fn verify_dleq_proof(
+    proof: &DleqProof,
+    transcript: &[&[u8]],
+    target: &[u8; 32],
+    base: &[u8; 32],
+    masked: &[u8; 32],
+    value: u64,
+) -> Result<()> {
+    let e = shielded_scalar(&proof.challenge)?;
+    let z = shielded_scalar(&proof.response)?;
+
+    let t = ristretto_msm(&[z, -e], &[RISTRETTO_BASEPOINT, *target])?;
+    let u = ristretto_msm(&[z, -e, e * Scalar::from(value)], &[*base, *masked, RISTRETTO_BASEPOINT])?;
+
+    let mut parts = transcript.to_vec();
+    parts.extend_from_slice(&[&t[..], &u[..]]);
+    require!(e == shielded_challenge(&parts), ErrorCode::InvalidShieldedProof);
+    Ok(())
+}
+
+// A candidate's running totals, published with `emit_cpi!` so indexers can follow a poll
+// without polling accounts
+fn tally_event(poll: &Poll, poll_key: Pubkey, candidate: &Candidate, candidate_key: Pubkey) -> TallyUpdated {
+    TallyUpdated {
+        poll: poll_key,
+        candidate: candidate_key,
+        votes: candidate.votes,
+        raw_votes: candidate.raw_votes,
+        total_votes: poll.total_votes,
+    }
+}
+
+/// Leaf recorded for a compressed receipt. `candidate` is the default key until the voter has voted.
+pub fn receipt_leaf(poll: &Pubkey, voter: &Pubkey, candidate: &Pubkey) -> [u8; 32] {
+    keccak::hashv(&[poll.as_ref(), voter.as_ref(), candidate.as_ref()]).to_bytes()
+}
+
+// Invoke spl-account-compression on the poll's receipt tree, signing as the poll PDA
+fn invoke_compression<'info>(
+    poll: &Account<'info, Poll>,
+    tree: &ReceiptTreeAccounts<'info>,
+    data: Vec<u8>,
+    proof: &[AccountInfo<'info>],
+) -> Result<()> {
+    let mut metas = vec![
+        AccountMeta::new(tree.merkle_tree.key(), false),
+        AccountMeta::new_readonly(poll.key(), true),
+        AccountMeta::new_readonly(tree.log_wrapper.key(), false),
+    ];
+    metas.extend(proof.iter().map(|node| AccountMeta::new_readonly(node.key(), false)));
+
+    let mut infos = vec![
+        tree.merkle_tree.to_account_info(),
+        poll.to_account_info(),
+        tree.log_wrapper.to_account_info(),
+        tree.compression_program.to_account_info(),
+    ];
+    infos.extend_from_slice(proof);
+
+    let instruction = Instruction {
+        program_id: COMPRESSION_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+    let poll_id_bytes = poll.poll_id.to_le_bytes();
+    invoke_signed(
+        &instruction,
+        &infos,
+        &[&[POLL_SEED, poll_id_bytes.as_ref(), &[poll.bump]]],
+    )?;
+    Ok(())
+}
+
+// Clockwork `thread_create` arguments: the thread's fee funding, id, instructions and trigger
+#[derive(AnchorSerialize)]
+struct ThreadCreateArgs {
+    amount: u64,
+    id: Vec<u8>,
+    instructions: Vec<SerializableInstruction>,
+    trigger: ThreadTrigger,
+}
+
+#[derive(AnchorSerialize)]
+struct SerializableInstruction {
+    program_id: Pubkey,
+    accounts: Vec<SerializableAccountMeta>,
+    data: Vec<u8>,
+}
+
+#[derive(AnchorSerialize)]
+struct SerializableAccountMeta {
+    pubkey: Pubkey,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+// Clockwork `Trigger`; only `Timestamp` is used, the other variants keep its index at 5
+#[allow(dead_code)]
+#[derive(AnchorSerialize)]
+enum ThreadTrigger {
+    Account { address: Pubkey, offset: u64, size: u64 },
+    Cron { schedule: String, skippable: bool },
+    Now,
+    Slot { slot: u64 },
+    Epoch { epoch: u64 },
+    Timestamp { unix_ts: i64 },
+}
+
+// Bubblegum `MetadataArgs`; enums are encoded by variant index and structs as tuples
+#[derive(AnchorSerialize)]
+struct ReceiptNftMetadata {
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    primary_sale_happened: bool,
+    is_mutable: bool,
+    edition_nonce: Option<u8>,
+    token_standard: Option<u8>,
+    collection: Option<(bool, Pubkey)>,
+    uses: Option<(u8, u64, u64)>,
+    token_program_version: u8,
+    creators: Vec<(Pubkey, bool, u8)>,
+}
+
+// Mint an "I Voted" cNFT to the voter from the poll's Bubblegum tree, signing as its tree delegate
+fn mint_receipt_nft(accounts: &Vote) -> Result<()> {
+    let (
+        Some(tree_authority),
+        Some(merkle_tree),
+        Some(bubblegum_program),
+        Some(log_wrapper),
+        Some(compression_program),
+    ) = (
+        accounts.tree_authority.as_ref(),
+        accounts.merkle_tree.as_ref(),
+        accounts.bubblegum_program.as_ref(),
+        accounts.log_wrapper.as_ref(),
+        accounts.compression_program.as_ref(),
+    )
+    else {
+        return err!(ErrorCode::MissingReceiptNftAccounts);
+    };
+    let poll = &accounts.poll;
+    require_keys_eq!(merkle_tree.key(), poll.receipt_nft_tree, ErrorCode::ReceiptTreeMismatch);
+
+    let metadata = ReceiptNftMetadata {
+        name: format!("I Voted #{}", poll.poll_id),
+        symbol: "VOTE".to_string(),
+        uri: poll.metadata_uri.clone(),
+        seller_fee_basis_points: 0,
+        primary_sale_happened: false,
+        is_mutable: false,
+        edition_nonce: None,
+        token_standard: Some(0), // NonFungible
+        collection: None,
+        uses: None,
+        token_program_version: 0, // Original
+        creators: Vec::new(),
+    };
+    let mut data = MINT_V1_DISCRIMINATOR.to_vec();
+    metadata
+        .serialize(&mut data)
+        .map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotSerialize)?;
+
+    let voter = accounts.voter.key();
+    let instruction = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(tree_authority.key(), false),
+            AccountMeta::new_readonly(voter, false), // leaf owner
+            AccountMeta::new_readonly(voter, false), // leaf delegate
+            AccountMeta::new(merkle_tree.key(), false),
+            AccountMeta::new(accounts.payer.key(), true), // payer
+            AccountMeta::new_readonly(poll.key(), true), // tree delegate
+            AccountMeta::new_readonly(log_wrapper.key(), false),
+            AccountMeta::new_readonly(compression_program.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+        ],
+        data,
+    };
+    let poll_id_bytes = poll.poll_id.to_le_bytes();
+    invoke_signed(
+        &instruction,
+        &[
+            tree_authority.to_account_info(),
+            accounts.voter.to_account_info(),
+            merkle_tree.to_account_info(),
+            accounts.payer.to_account_info(),
+            poll.to_account_info(),
+            log_wrapper.to_account_info(),
+            compression_program.to_account_info(),
+            accounts.system_program.to_account_info(),
+            bubblegum_program.to_account_info(),
+        ],
+        &[&[POLL_SEED, poll_id_bytes.as_ref(), &[poll.bump]]],
+    )?;
+    Ok(())
+}
+
+// Token Metadata `CreateMetadataAccountArgsV3`: a `DataV2` followed by `is_mutable` and
+// `collection_details`; enums are encoded by variant index and structs as tuples
+#[derive(AnchorSerialize)]
+struct BadgeMetadataArgs {
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Option<Vec<(Pubkey, bool, u8)>>,
+    collection: Option<(bool, Pubkey)>,
+    uses: Option<(u8, u64, u64)>,
+    is_mutable: bool,
+    collection_details: Option<(u8, u64)>,
+}
+
+// Length of a poll's description, read from the raw account so any layout version works. The
+// poll ID and creator come after the discriminator, then the question and the description.
+fn stored_description_len(info: &AccountInfo) -> Result<usize> {
+    let data = info.try_borrow_data()?;
+    let read_len = |offset: usize| -> Result<usize> {
+        let bytes = data.get(offset..offset + 4).ok_or(ErrorCode::UnsupportedAccount)?;
+        let mut len = [0u8; 4];
+        len.copy_from_slice(bytes);
+        Ok(u32::from_le_bytes(len) as usize)
+    };
+    let question_len = read_len(8 + 8 + 32)?;
+    read_len(8 + 8 + 32 + 4 + question_len)
+}
+
+// Number of gauge allocations a stored receipt holds. Receipts older than gauge votes end
+// before the allocation list and hold none.
+fn stored_allocation_count(info: &AccountInfo) -> Result<usize> {
+    let data = info.try_borrow_data()?;
+    let offset = 8 + 32 + 32 + 1 + 1 + 1 + 8;
+    Ok(data.get(offset..offset + 4).map_or(0, |bytes| {
+        let mut len = [0u8; 4];
+        len.copy_from_slice(bytes);
+        u32::from_le_bytes(len) as usize
+    }))
+}
+
+// Grow an account to the current layout (topping up rent) and stamp its new version
+fn migrate_layout<T: AccountSerialize + AccountDeserialize>(
+    accounts: &MigrateAccount,
+    target_len: usize,
+    update: impl FnOnce(&mut T),
+) -> Result<()> {
+    let info = accounts.account.to_account_info();
+
+    if target_len > info.data_len() {
+        let required = Rent::get()?.minimum_balance(target_len);
+        let top_up = required.saturating_sub(info.lamports());
+        if top_up > 0 {
+            transfer(
+                CpiContext::new(
+                    accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: accounts.payer.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        info.resize(target_len)?;
+    }
+
+    let mut account = T::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    update(&mut account);
+    account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+// Account validation structs
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        seeds = [CONFIG_SEED],
+        bump,
+        space = 8 + Config::INIT_SPACE
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [TREASURY_SEED],
+        bump,
+        space = 8 + Treasury::INIT_SPACE
+    )]
+    pub treasury: Account<'info, Treasury>,
+    /// Only the program's upgrade authority can claim the admin role
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    // Only the config admin can withdraw from the treasury
+    #[account(seeds = [CONFIG_SEED], bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump)]
+    pub treasury: Account<'info, Treasury>,
+    pub admin: Signer<'info>,
+    /// CHECK: Any account can receive the withdrawn lamports
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetFeeSchedule<'info> {
+    #[account(mut, seeds = [CONFIG_SEED], bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct InitializePoll<'info> {
+    #[account(
+        init,
+        payer = creator,
+        seeds = [POLL_SEED, poll_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Poll::INIT_SPACE
+    )]
+    pub poll: Account<'info, Poll>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [TREASURY_SEED], bump)]
+    pub treasury: Account<'info, Treasury>,
+    // Only required in council mode; proposals are unique per poll ID and closed once used
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+        close = creator,
+        constraint = poll_proposal.poll_id == poll_id @ ErrorCode::CouncilApprovalMissing
+    )]
+    pub poll_proposal: Option<Account<'info, PollProposal>>,
+    // Only required while the creator allowlist is on; passes are only issued by the admin
+    #[account(constraint = creator_pass.creator == creator.key() @ ErrorCode::CreatorNotAllowed)]
+    pub creator_pass: Option<Account<'info, CreatorPass>>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        seeds = [CREATOR_STATS_SEED, creator.key().as_ref()],
+        bump,
+        space = 8 + CreatorStats::INIT_SPACE
+    )]
+    pub creator_stats: Account<'info, CreatorStats>,
+    /// CHECK: Only checked to be empty; an archived poll's ID is never reused
+    #[account(
+        seeds = [POLL_ARCHIVE_SEED, poll_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = poll_archive.data_is_empty() @ ErrorCode::PollIdArchived
+    )]
+    pub poll_archive: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCreatorAllowlist<'info> {
+    #[account(mut, seeds = [CONFIG_SEED], bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxPollDuration<'info> {
+    #[account(mut, seeds = [CONFIG_SEED], bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCreationLimit<'info> {
+    #[account(mut, seeds = [CONFIG_SEED], bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct GrantCreatorPass<'info> {
+    #[account(seeds = [CONFIG_SEED], bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [CREATOR_PASS_SEED, creator.as_ref()],
+        bump,
+        space = 8 + CreatorPass::INIT_SPACE
+    )]
+    pub creator_pass: Account<'info, CreatorPass>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeCreatorPass<'info> {
+    #[account(seeds = [CONFIG_SEED], bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [CREATOR_PASS_SEED, creator_pass.creator.as_ref()],
+        bump,
+        close = admin
+    )]
+    pub creator_pass: Account<'info, CreatorPass>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCouncil<'info> {
+    #[account(mut, seeds = [CONFIG_SEED], bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(poll_id: u64)]
+pub struct ProposePoll<'info> {
+    #[account(
+        init,
+        payer = creator,
+        seeds = [POLL_PROPOSAL_SEED, poll_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + PollProposal::INIT_SPACE
+    )]
+    pub poll_proposal: Account<'info, PollProposal>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPollProposal<'info> {
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POLL_PROPOSAL_SEED, poll_proposal.poll_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = creator,
+        close = creator
+    )]
+    pub poll_proposal: Account<'info, PollProposal>,
+    /// CHECK: Receives the proposal's rent; validated against `poll_proposal.creator`
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApprovePollCreation<'info> {
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [POLL_PROPOSAL_SEED, poll_proposal.poll_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub poll_proposal: Account<'info, PollProposal>,
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(question: Option<String>, description: Option<String>)]
+pub struct UpdatePoll<'info> {
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+        // Never shrunk: the poll escrows the bounty in its own lamports, and a shrink would
+        // refund everything above the smaller rent minimum to the creator
+        realloc = Poll::space_for_description(
+            description.as_ref().map_or(poll.description.len(), |d| d.len())
+        ).max(poll.to_account_info().data_len()),
+        realloc::payer = creator,
+        realloc::zero = false
+    )]
+    pub poll: Account<'info, Poll>,
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ActivatePoll<'info> {
+    #[account(mut, has_one = creator @ ErrorCode::Unauthorized)]
+    pub poll: Account<'info, Poll>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ScheduleFinalization<'info> {
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+        seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()],
+        bump = poll.bump
+    )]
+    pub poll: Account<'info, Poll>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: The poll's thread PDA, created and validated by the thread program
+    #[account(
+        mut,
+        seeds = [THREAD_SEED, poll.key().as_ref(), FINALIZE_THREAD_ID],
+        bump,
+        seeds::program = THREAD_PROGRAM_ID
+    )]
+    pub thread: UncheckedAccount<'info>,
+    /// CHECK: Checked against the thread program ID
+    #[account(address = THREAD_PROGRAM_ID)]
+    pub thread_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelFinalization<'info> {
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+        seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()],
+        bump = poll.bump
+    )]
+    pub poll: Account<'info, Poll>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: Matched against `poll.finalize_thread`
+    #[account(mut, address = poll.finalize_thread @ ErrorCode::FinalizationNotScheduled)]
+    pub thread: UncheckedAccount<'info>,
+    /// CHECK: Checked against the thread program ID
+    #[account(address = THREAD_PROGRAM_ID)]
+    pub thread_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRandomness<'info> {
+    #[account(mut)]
+    pub poll: Account<'info, Poll>,
+    // The creator for a ballot order; anyone for a tie-break
+    pub requester: Signer<'info>,
+    /// CHECK: Switchboard randomness account, parsed in the handler
+    #[account(owner = SWITCHBOARD_PROGRAM_ID @ ErrorCode::InvalidRandomness)]
+    pub randomness: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRandomness<'info> {
+    #[account(mut)]
+    pub poll: Account<'info, Poll>,
+    /// CHECK: Matched against `poll.randomness_account`; parsed in the handler while Switchboard owns it
+    #[account(address = poll.randomness_account @ ErrorCode::InvalidRandomness)]
+    pub randomness: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ApplyRandomness<'info> {
+    #[account(mut)]
+    pub poll: Account<'info, Poll>,
+    /// CHECK: Matched against `poll.randomness_account` and parsed in the handler
+    #[account(
+        address = poll.randomness_account @ ErrorCode::InvalidRandomness,
+        owner = SWITCHBOARD_PROGRAM_ID @ ErrorCode::InvalidRandomness
+    )]
+    pub randomness: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPollPaused<'info> {
+    #[account(mut, has_one = creator @ ErrorCode::Unauthorized)]
+    pub poll: Account<'info, Poll>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCandidateManager<'info> {
+    #[account(mut, has_one = creator @ ErrorCode::Unauthorized)]
+    pub poll: Account<'info, Poll>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(voter: Pubkey)]
+pub struct GrantAccess<'info> {
+    #[account(mut, has_one = creator @ ErrorCode::Unauthorized)]
+    pub poll: Account<'info, Poll>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [ACCESS_GRANT_SEED, poll.key().as_ref(), voter.as_ref()],
+        bump,
+        space = 8 + AccessGrant::INIT_SPACE
+    )]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAccess<'info> {
+    #[account(mut, has_one = creator @ ErrorCode::Unauthorized)]
+    pub poll: Account<'info, Poll>,
+    #[account(
+        mut,
+        close = creator,
+        seeds = [ACCESS_GRANT_SEED, poll.key().as_ref(), access_grant.voter.as_ref()],
+        bump = access_grant.bump,
+        has_one = poll
+    )]
+    pub access_grant: Account<'info, AccessGrant>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(voter: Pubkey)]
+pub struct PublishWeight<'info> {
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        seeds = [WEIGHT_RECORD_SEED, oracle.key().as_ref(), voter.as_ref()],
+        bump,
+        space = 8 + WeightRecord::INIT_SPACE
+    )]
+    pub weight_record: Account<'info, WeightRecord>,
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Candidates are addressed by their ballot index, so names may be long, non-ASCII or renamed
+#[derive(Accounts)]
+pub struct InitializeCandidate<'info> {
+    #[account(mut)]
+    pub poll: Account<'info, Poll>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [CANDIDATE_SEED, poll.key().as_ref(), (poll.candidate_count as u16).to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Candidate::INIT_SPACE
+    )]
+    pub candidate: Account<'info, Candidate>,
+    // The poll creator, its candidate manager or, with open candidacy, a self-nominee, who pays
+    // for the candidate and its deposit
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    // Only needed for a self-nomination
+    /// CHECK: Empty PDA created in the handler once the wallet has stood
+    #[account(mut, seeds = [NOMINATION_SEED, poll.key().as_ref(), creator.key().as_ref()], bump)]
+    pub nomination: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCandidatesBatch<'info> {
+    #[account(mut)]
+    pub poll: Account<'info, Poll>,
+    // The poll creator or its candidate manager, who pays for the candidates and their deposits
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// The receipt tree and the programs needed to modify it
+#[derive(Accounts)]
+pub struct ReceiptTreeAccounts<'info> {
+    /// CHECK: Owned and validated by the compression program; matched against `poll.receipt_tree`
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    /// CHECK: Checked against the spl-account-compression program ID
+    #[account(address = COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: Checked against the noop program ID
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ManageReceiptTree<'info> {
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+        seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()],
+        bump = poll.bump
+    )]
+    pub poll: Account<'info, Poll>,
+    pub creator: Signer<'info>,
+    pub tree: ReceiptTreeAccounts<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(voter: Pubkey)]
+pub struct RegisterCompressedVoter<'info> {
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+        seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()],
+        bump = poll.bump
+    )]
+    pub poll: Account<'info, Poll>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [COMPRESSED_VOTER_SEED, poll.key().as_ref(), voter.as_ref()],
+        bump,
+        space = 8 + CompressedVoter::INIT_SPACE
+    )]
+    pub registration: Account<'info, CompressedVoter>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub tree: ReceiptTreeAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VoteCompressed<'info> {
+    #[account(mut, seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    #[account(mut)]
+    pub candidate: Account<'info, Candidate>,
+    pub voter: Signer<'info>,
+    pub tree: ReceiptTreeAccounts<'info>,
+    /// CHECK: Checked against the instructions sysvar ID
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: Owner and discriminator are validated in the handler
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(ballot_id: u64)]
+pub struct InitializeBallot<'info> {
+    #[account(
+        init,
+        payer = creator,
+        seeds = [BALLOT_SEED, ballot_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Ballot::INIT_SPACE
+    )]
+    pub ballot: Account<'info, Ballot>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VoteBallot<'info> {
+    pub ballot: Account<'info, Ballot>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Checked against the instructions sysvar ID
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSession<'info> {
+    pub ballot: Account<'info, Ballot>,
+    #[account(
+        init,
+        payer = voter,
+        seeds = [SESSION_SEED, ballot.key().as_ref(), voter.key().as_ref()],
+        bump,
+        space = 8 + Session::INIT_SPACE
+    )]
+    pub session: Account<'info, Session>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    #[account(
+        mut,
+        close = voter,
+        seeds = [SESSION_SEED, session.ballot.as_ref(), voter.key().as_ref()],
+        bump = session.bump,
+        has_one = voter
+    )]
+    pub session: Account<'info, Session>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VoteWithSession<'info> {
+    #[account(mut, seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    // Candidates registered before index addressing still live at their name-derived PDA
+    #[account(
+        mut,
+        has_one = poll @ ErrorCode::CandidatePollMismatch,
+        constraint = candidate.is_at(&candidate.key()) @ ErrorCode::CandidatePollMismatch
+    )]
+    pub candidate: Account<'info, Candidate>,
+    pub ballot: Account<'info, Ballot>,
+    #[account(
+        seeds = [SESSION_SEED, ballot.key().as_ref(), voter.key().as_ref()],
+        bump = session.bump,
+        has_one = voter,
+        has_one = session_key
+    )]
+    pub session: Account<'info, Session>,
+    #[account(
+        init,
+        payer = session_key,
+        seeds = [RECEIPT_SEED, poll.key().as_ref(), voter.key().as_ref()],
+        bump,
+        space = 8 + VoterReceipt::INIT_SPACE
+    )]
+    pub voter_receipt: Account<'info, VoterReceipt>,
+    /// CHECK: The voter who created the session; checked against `session.voter`
+    pub voter: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Checked against the instructions sysvar ID
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizePoll<'info> {
+    #[account(mut)]
+    pub poll: Account<'info, Poll>,
+}
+
+#[derive(Accounts)]
+pub struct MintWinnerBadge<'info> {
+    #[account(mut, seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    #[account(has_one = poll, constraint = winner.key() == poll.winner @ ErrorCode::NotPollWinner)]
+    pub winner: Account<'info, Candidate>,
+    /// CHECK: Validated against the winner's payout wallet
+    #[account(address = winner.payout_wallet @ ErrorCode::InvalidBountyRecipient)]
+    pub payout_wallet: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = poll,
+        mint::freeze_authority = poll
+    )]
+    pub badge_mint: Account<'info, Mint>,
+    /// CHECK: Must be the payout wallet's associated token account; created in the handler
+    #[account(mut)]
+    pub badge_token_account: UncheckedAccount<'info>,
+    /// CHECK: Metadata PDA, derived and validated by Token Metadata
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+    /// CHECK: Master edition PDA, derived and validated by Token Metadata
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: Checked against the associated token program ID
+    #[account(address = ASSOCIATED_TOKEN_PROGRAM_ID)]
+    pub associated_token_program: UncheckedAccount<'info>,
+    /// CHECK: Checked against the Token Metadata program ID
+    #[account(address = TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterAuditors<'info> {
+    #[account(has_one = creator @ ErrorCode::Unauthorized)]
+    pub poll: Account<'info, Poll>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [POLL_RESULT_SEED, poll.key().as_ref()],
+        bump,
+        space = 8 + PollResult::INIT_SPACE
+    )]
+    pub poll_result: Account<'info, PollResult>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AttestResult<'info> {
+    pub poll: Account<'info, Poll>,
+    #[account(
+        mut,
+        seeds = [POLL_RESULT_SEED, poll.key().as_ref()],
+        bump = poll_result.bump,
+        has_one = poll
+    )]
+    pub poll_result: Account<'info, PollResult>,
+    pub auditor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimBounty<'info> {
+    #[account(mut)]
+    pub poll: Account<'info, Poll>,
+    #[account(has_one = poll)]
+    pub candidate: Option<Account<'info, Candidate>>,
+    /// CHECK: Validated against the winner's payout wallet or the poll creator
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDeposit<'info> {
+    pub poll: Account<'info, Poll>,
+    #[account(mut, has_one = poll, has_one = depositor)]
+    pub candidate: Account<'info, Candidate>,
+    /// CHECK: Validated against `candidate.depositor`
+    #[account(mut)]
+    pub depositor: UncheckedAccount<'info>,
+    #[account(mut, seeds = [TREASURY_SEED], bump)]
+    pub treasury: Account<'info, Treasury>,
+}
+
+#[derive(Accounts)]
+pub struct CrankCloseReceipt<'info> {
+    #[account(seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    #[account(
+        mut,
+        seeds = [RECEIPT_SEED, poll.key().as_ref(), voter_receipt.voter.as_ref()],
+        bump = voter_receipt.bump,
+        has_one = payer,
+        close = payer
+    )]
+    pub voter_receipt: Account<'info, VoterReceipt>,
+    /// CHECK: Receives the receipt's remaining rent; validated against `voter_receipt.payer`
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitShieldedTally<'info> {
+    #[account(has_one = creator @ ErrorCode::Unauthorized)]
+    pub poll: Account<'info, Poll>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [SHIELDED_TALLY_SEED, poll.key().as_ref()],
+        bump,
+        space = 8 + ShieldedTally::INIT_SPACE
+    )]
+    pub tally: Account<'info, ShieldedTally>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VoteShielded<'info> {
+    #[account(mut, seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    #[account(mut, seeds = [SHIELDED_TALLY_SEED, poll.key().as_ref()], bump, has_one = poll)]
+    pub tally: Account<'info, ShieldedTally>,
+    #[account(
+        init,
+        payer = voter,
+        seeds = [RECEIPT_SEED, poll.key().as_ref(), voter.key().as_ref()],
+        bump,
+        space = 8 + VoterReceipt::INIT_SPACE
+    )]
+    pub voter_receipt: Account<'info, VoterReceipt>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Checked against the instructions sysvar ID
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(points: Vec<u16>)]
+pub struct VoteGauge<'info> {
+    #[account(mut, seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    #[account(
+        init,
+        payer = voter,
+        seeds = [RECEIPT_SEED, poll.key().as_ref(), voter.key().as_ref()],
+        bump,
+        space = VoterReceipt::space_for(points.len())
+    )]
+    pub voter_receipt: Account<'info, VoterReceipt>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Checked against the instructions sysvar ID
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VoteConviction<'info> {
+    #[account(mut, seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    #[account(mut, has_one = poll @ ErrorCode::CandidatePollMismatch)]
+    pub candidate: Account<'info, Candidate>,
+    #[account(
+        init,
+        payer = voter,
+        seeds = [RECEIPT_SEED, poll.key().as_ref(), voter.key().as_ref()],
+        bump,
+        space = 8 + VoterReceipt::INIT_SPACE
+    )]
+    pub voter_receipt: Account<'info, VoterReceipt>,
+    #[account(
+        init,
+        payer = voter,
+        seeds = [CONVICTION_LOCK_SEED, poll.key().as_ref(), voter.key().as_ref()],
+        bump,
+        space = 8 + ConvictionLock::INIT_SPACE
+    )]
+    pub conviction_lock: Account<'info, ConvictionLock>,
+    #[account(
+        init,
+        payer = voter,
+        seeds = [CONVICTION_VAULT_SEED, poll.key().as_ref(), voter.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = conviction_lock
+    )]
+    pub lock_vault: Account<'info, TokenAccount>,
+    #[account(address = poll.conviction_mint @ ErrorCode::PollNotConviction)]
+    pub mint: Account<'info, Mint>,
+    #[account(mut, constraint = voter_token_account.mint == mint.key() @ ErrorCode::PollNotConviction)]
+    pub voter_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Checked against the instructions sysvar ID
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VoteGovernance<'info> {
+    #[account(mut, seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    #[account(mut, has_one = poll @ ErrorCode::CandidatePollMismatch)]
+    pub candidate: Account<'info, Candidate>,
+    #[account(
+        init,
+        payer = voter,
+        seeds = [RECEIPT_SEED, poll.key().as_ref(), voter.key().as_ref()],
+        bump,
+        space = 8 + VoterReceipt::INIT_SPACE
+    )]
+    pub voter_receipt: Account<'info, VoterReceipt>,
+    /// CHECK: spl-governance record, checked against the poll's realm and the voter in the handler
+    #[account(mut)]
+    pub token_owner_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Checked against the instructions sysvar ID
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    /// CHECK: Checked against the poll's realm; validated by spl-governance
+    #[account(address = poll.governance_realm @ ErrorCode::InvalidTokenOwnerRecord)]
+    pub realm: UncheckedAccount<'info>,
+    /// CHECK: The realm's config PDA, listing the lock authorities it accepts
+    #[account(
+        seeds = [REALM_CONFIG_SEED, realm.key().as_ref()],
+        bump,
+        seeds::program = poll.governance_program
+    )]
+    pub realm_config: UncheckedAccount<'info>,
+    /// CHECK: Program-wide PDA that signs deposit locks
+    #[account(seeds = [GOVERNANCE_LOCK_SEED], bump)]
+    pub lock_authority: UncheckedAccount<'info>,
+    /// CHECK: Checked against the poll's governance program
+    #[account(address = poll.governance_program @ ErrorCode::InvalidTokenOwnerRecord)]
+    pub governance_program: UncheckedAccount<'info>,
+}
 
-        // Initialize the voter receipt to prevent double voting
-        ctx.accounts.voter_receipt.poll = poll.key();
-        ctx.accounts.voter_receipt.voter = ctx.accounts.voter.key();
-        ctx.accounts.voter_receipt.has_voted = true;
+#[derive(Accounts)]
+pub struct Unlock<'info> {
+    #[account(
+        mut,
+        seeds = [CONVICTION_LOCK_SEED, conviction_lock.poll.as_ref(), voter.key().as_ref()],
+        bump,
+        has_one = voter @ ErrorCode::Unauthorized
+    )]
+    pub conviction_lock: Account<'info, ConvictionLock>,
+    #[account(
+        mut,
+        seeds = [CONVICTION_VAULT_SEED, conviction_lock.poll.as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub lock_vault: Account<'info, TokenAccount>,
+    #[account(mut, constraint = voter_token_account.mint == lock_vault.mint @ ErrorCode::PollNotConviction)]
+    pub voter_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
 
-        msg!("Vote cast successfully");
-        Ok(())
-    }
+#[derive(Accounts)]
+pub struct PublishShieldedResults<'info> {
+    #[account(mut)]
+    pub poll: Account<'info, Poll>,
+    #[account(seeds = [SHIELDED_TALLY_SEED, poll.key().as_ref()], bump, has_one = poll)]
+    pub tally: Account<'info, ShieldedTally>,
 }
 
-// Account validation structs
 #[derive(Accounts)]
-#[instruction(poll_id: u64)]
-pub struct InitializePoll<'info> {
+pub struct ArchivePoll<'info> {
+    #[account(
+        mut,
+        seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()],
+        bump = poll.bump,
+        has_one = creator @ ErrorCode::Unauthorized,
+        close = creator
+    )]
+    pub poll: Account<'info, Poll>,
     #[account(
         init,
         payer = creator,
-        seeds = [POLL_SEED, poll_id.to_le_bytes().as_ref()],
+        seeds = [POLL_ARCHIVE_SEED, poll.poll_id.to_le_bytes().as_ref()],
         bump,
-        space = 8 + Poll::INIT_SPACE
+        space = PollArchive::space_for(poll.candidate_count as usize)
     )]
+    pub archive: Account<'info, PollArchive>,
+    #[account(mut, seeds = [REWARD_VAULT_SEED, poll.key().as_ref()], bump)]
+    pub reward_vault: Option<Account<'info, TokenAccount>>,
+    // Receives whatever the equal reward shares left in the vault
+    #[account(mut, constraint = creator_token_account.mint == poll.reward_mint @ ErrorCode::RewardMintMismatch)]
+    pub creator_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(has_one = creator @ ErrorCode::Unauthorized)]
     pub poll: Account<'info, Poll>,
+    #[account(has_one = poll @ ErrorCode::CandidatePollMismatch)]
+    pub yes_candidate: Account<'info, Candidate>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [PROPOSAL_SEED, poll.key().as_ref()],
+        bump,
+        space = 8 + Proposal::INIT_SPACE
+    )]
+    pub proposal: Account<'info, Proposal>,
     #[account(mut)]
     pub creator: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(candidate_name: String)]
-pub struct InitializeCandidate<'info> {
+pub struct ExecuteProposal<'info> {
+    pub poll: Account<'info, Poll>,
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, poll.key().as_ref()],
+        bump,
+        has_one = poll,
+        has_one = yes_candidate
+    )]
+    pub proposal: Account<'info, Proposal>,
+    pub yes_candidate: Account<'info, Candidate>,
+    /// CHECK: PDA that signs the proposal's instruction on behalf of the poll
+    #[account(seeds = [POLL_AUTHORITY_SEED, poll.key().as_ref()], bump)]
+    pub poll_authority: UncheckedAccount<'info>,
+    /// CHECK: Validated against `proposal.program_id`
+    #[account(address = proposal.program_id @ ErrorCode::ProposalAccountMismatch)]
+    pub target_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewardPool<'info> {
     #[account(mut)]
     pub poll: Account<'info, Poll>,
+    pub mint: Account<'info, Mint>,
     #[account(
-        init,
+        init_if_needed,
         payer = creator,
-        seeds = [CANDIDATE_SEED, poll.key().as_ref(), candidate_name.as_bytes()],
+        seeds = [REWARD_VAULT_SEED, poll.key().as_ref()],
         bump,
-        space = 8 + Candidate::INIT_SPACE
+        token::mint = mint,
+        token::authority = poll
     )]
-    pub candidate: Account<'info, Candidate>,
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(mut, constraint = creator_token_account.mint == mint.key() @ ErrorCode::RewardMintMismatch)]
+    pub creator_token_account: Account<'info, TokenAccount>,
     #[account(mut)]
     pub creator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct ClaimReward<'info> {
+    #[account(seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()], bump = poll.bump)]
+    pub poll: Account<'info, Poll>,
+    #[account(
+        mut,
+        seeds = [RECEIPT_SEED, poll.key().as_ref(), voter.key().as_ref()],
+        bump = voter_receipt.bump
+    )]
+    pub voter_receipt: Account<'info, VoterReceipt>,
+    #[account(
+        mut,
+        seeds = [REWARD_VAULT_SEED, poll.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(mut, constraint = voter_token_account.mint == poll.reward_mint @ ErrorCode::RewardMintMismatch)]
+    pub voter_token_account: Account<'info, TokenAccount>,
+    pub voter: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCandidateMetadata<'info> {
+    pub poll: Account<'info, Poll>,
+    #[account(mut, has_one = poll)]
+    pub candidate: Account<'info, Candidate>,
+    pub creator: Signer<'info>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Vote<'info> {
+    #[account(mut, seeds = [POLL_SEED, poll.poll_id.to_le_bytes().as_ref()], bump = poll.bump)]
     pub poll: Account<'info, Poll>,
-    #[account(mut)]
+    // Candidates registered before index addressing still live at their name-derived PDA
+    #[account(
+        mut,
+        has_one = poll @ ErrorCode::CandidatePollMismatch,
+        constraint = candidate.is_at(&candidate.key()) @ ErrorCode::CandidatePollMismatch
+    )]
     pub candidate: Account<'info, Candidate>,
     #[account(
         init,
-        payer = voter,
+        payer = payer,
         seeds = [RECEIPT_SEED, poll.key().as_ref(), voter.key().as_ref()],
         bump,
         space = 8 + VoterReceipt::INIT_SPACE
     )]
     pub voter_receipt: Account<'info, VoterReceipt>,
-    #[account(mut)]
     pub voter: Signer<'info>,
+    // Pays the receipt rent and any stake marker or cNFT costs; the voter themselves unless
+    // an organization sponsors the vote
+    #[account(mut)]
+    pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
+    // Only needed when the poll mints "I Voted" cNFTs
+    /// CHECK: Bubblegum tree config PDA, validated by Bubblegum
+    #[account(mut)]
+    pub tree_authority: Option<UncheckedAccount<'info>>,
+    /// CHECK: Matched against `poll.receipt_nft_tree`
+    #[account(mut)]
+    pub merkle_tree: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against the Bubblegum program ID
+    #[account(address = BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against the noop program ID
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub log_wrapper: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against the spl-account-compression program ID
+    #[account(address = COMPRESSION_PROGRAM_ID)]
+    pub compression_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: Native stake account, parsed in the handler; only needed for stake-gated polls
+    pub stake_account: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against the instructions sysvar ID
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    // Only needed when the poll is stake-weighted
+    /// CHECK: Checked against the stake history sysvar ID
+    #[account(address = stake_history_sysvar::ID)]
+    pub stake_history: Option<UncheckedAccount<'info>>,
+    /// CHECK: Empty PDA created in the handler once the stake account has voted
+    #[account(mut)]
+    pub stake_marker: Option<UncheckedAccount<'info>>,
+    // Only needed when the poll names an eligibility program; the verifier's own
+    // accounts follow in `remaining_accounts`
+    /// CHECK: Checked against `poll.eligibility_program` in the handler
+    pub eligibility_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: Attestation about the voter, parsed in the handler; only needed for attestation-gated polls
+    pub attestation: Option<UncheckedAccount<'info>>,
+    // Only needed when the poll is access-restricted; grants are only issued by the creator
+    #[account(
+        constraint = access_grant.poll == poll.key() && access_grant.voter == voter.key()
+            @ ErrorCode::AccessNotGranted
+    )]
+    pub access_grant: Option<Account<'info, AccessGrant>>,
+    // Only needed when the poll is oracle-weighted; checked against the poll's oracle in the handler
+    pub weight_record: Option<Account<'info, WeightRecord>>,
+    // Only needed when the poll charges a vote fee; SPL fees also need the payer's token account
+    // and the token program
+    /// CHECK: Checked against `poll.vote_fee_recipient` in the handler
+    #[account(mut)]
+    pub fee_recipient: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub fee_source: Option<Account<'info, TokenAccount>>,
+    pub token_program: Option<Program<'info, Token>>,
 }
 
 // Data structures
+// Global program settings, managed by the admin
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub poll_creation_fee: u64,
+    // Members who approve new polls, and how many must approve (0 disables council mode)
+    #[max_len(10)]
+    pub council: Vec<Pubkey>,
+    pub council_threshold: u8,
+    // Only wallets holding a `CreatorPass` may create polls
+    pub restrict_creators: bool,
+    // Longest a poll may run from creation to end time, in seconds (0 for no limit)
+    pub max_poll_duration: i64,
+    // Most polls a creator may create within any rolling window of this many seconds (0 for
+    // no limit)
+    pub max_polls_per_window: u16,
+    pub creation_window: i64,
+}
+
+// A creator's poll creation history, used to enforce the creation limit
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorStats {
+    pub creator: Pubkey,
+    pub total_polls: u64,
+    // Creation times of the creator's polls still inside the window, oldest first; only kept
+    // while a limit is set
+    #[max_len(20)]
+    pub recent_polls: Vec<i64>,
+    pub bump: u8,
+}
+
+// Admin-issued permission for a wallet to create polls while the allowlist is on
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorPass {
+    pub creator: Pubkey,
+    pub granted_at: i64,
+}
+
+// Program-owned account holding collected fees
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub total_collected: u64,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Poll {
@@ -147,6 +4607,272 @@ pub struct Poll {
     pub start_time: i64,
     pub end_time: i64,
     pub candidate_count: u64,
+    pub candidate_deposit_lamports: u64,
+    pub deposit_refund_threshold: u64,
+    pub finalized: bool,
+    pub total_votes: u64,
+    pub reward_mint: Pubkey,
+    pub reward_pool_amount: u64,
+    pub bounty_lamports: u64,
+    pub bounty_claimed: bool,
+    pub winner: Pubkey,
+    #[max_len(32)]
+    pub category: String,
+    #[max_len(5, 16)]
+    pub tags: Vec<String>,
+    // Off-chain long-form metadata and the SHA-256 of its contents
+    #[max_len(200)]
+    pub metadata_uri: String,
+    pub metadata_hash: [u8; 32],
+    pub version: u8,
+    pub paused: bool,
+    pub paused_at: i64,
+    // Optional spl-account-compression tree holding rent-free voter receipts
+    pub receipt_tree: Pubkey,
+    pub compressed_voter_count: u32,
+    // Bubblegum tree delegated to the poll PDA for minting "I Voted" cNFTs
+    pub mint_receipt_nft: bool,
+    pub receipt_nft_tree: Pubkey,
+    pub winner_badge_minted: bool,
+    // Anti-sybil thresholds on the voter's balance and native stake (0 disables)
+    pub min_voter_lamports: u64,
+    pub min_stake_lamports: u64,
+    pub weight_decay: WeightDecay,
+    // Registered electorate (compressed registrations or access grants) and the share of it that
+    // voted, set at finalization. Polls anyone may vote in have no electorate and keep 0.
+    pub eligible_voters: u64,
+    pub turnout_bps: u16,
+    pub status: PollStatus,
+    // Anti-burst guard: votes accepted per slot (0 disables) and the current slot's count
+    pub max_votes_per_slot: u32,
+    pub last_vote_slot: u64,
+    pub votes_in_slot: u32,
+    // Whether other programs may cast votes through CPI
+    pub allow_cpi: bool,
+    // ElGamal public key of a shielded poll (zero for plaintext polls) and whether its
+    // decrypted totals have been published
+    pub shielded_key: [u8; 32],
+    pub shielded_published: bool,
+    // Weigh each vote by the voter's delegated stake, as effective at the epoch voting opened
+    pub stake_weighted: bool,
+    pub snapshot_epoch: u64,
+    // Points each gauge voter splits across candidates (0 for one-choice polls)
+    pub gauge_budget: u16,
+    // Token voters lock behind conviction votes (default key when disabled)
+    pub conviction_mint: Pubkey,
+    // spl-governance realm whose deposited tokens weigh votes (default key when disabled),
+    // with the program that owns it and the governing mint that counts
+    pub governance_program: Pubkey,
+    pub governance_realm: Pubkey,
+    pub governance_mint: Pubkey,
+    // Registrar allowed to add candidates alongside the creator
+    pub candidate_manager: Option<Pubkey>,
+    // Program whose `verify(voter)` instruction must succeed for each vote
+    pub eligibility_program: Option<Pubkey>,
+    // Voters must present an attestation from this issuer (credential) and schema, owned by
+    // the attestation program (default keys when disabled)
+    pub attestation_program: Pubkey,
+    pub attestation_issuer: Pubkey,
+    pub attestation_schema: Pubkey,
+    // Canonical PDA bump, stored so constraints and signers skip the bump search
+    pub bump: u8,
+    // Clockwork thread that finalizes the poll after it ends (default key when not scheduled)
+    pub finalize_thread: Pubkey,
+    // Latest Switchboard randomness request, kept so anyone can check the draws against it
+    pub randomness_account: Pubkey,
+    pub randomness_seed_slot: u64,
+    pub randomness_pending: bool,
+    // Revealed values ranking candidates for display and for breaking exact ties (zero if
+    // never drawn); see `random_rank`
+    pub ballot_order_seed: [u8; 32],
+    pub tie_break_seed: [u8; 32],
+    // Whether the poll shows up when polls are listed; unlisted polls are reached by ID
+    pub visibility: PollVisibility,
+    // Only voters holding an `AccessGrant` from the creator may vote
+    pub access_restricted: bool,
+    // Oracle whose published `WeightRecord`s weigh votes (default key when disabled), and the
+    // oldest record it accepts, in seconds (0 for no limit)
+    pub weight_oracle: Pubkey,
+    pub max_weight_age: i64,
+    // Charged to the payer of each standard vote (0 for none), in lamports or in base units of
+    // the fee mint (default key for SOL), and sent to the recipient: a wallet for SOL fees, a
+    // token account for SPL fees
+    pub vote_fee: u64,
+    pub vote_fee_mint: Pubkey,
+    pub vote_fee_recipient: Pubkey,
+    // How far the end time may be pushed back in total once votes are in, in seconds, and how
+    // much of that has been used
+    pub max_extension: i64,
+    pub extended_by: i64,
+    // When the first and latest ballots were admitted (0 before the first vote)
+    pub first_vote_at: i64,
+    pub last_vote_at: i64,
+    // Ballots admitted in each hour since the start time; the last bucket also counts every
+    // later hour
+    pub vote_histogram: [u32; 48],
+    // Anyone may register themselves as a candidate while the poll is a draft
+    pub open_candidacy: bool,
+}
+
+impl Poll {
+    /// Account size needed to hold a description of the given length
+    pub fn space_for_description(description_len: usize) -> usize {
+        8 + Poll::INIT_SPACE + description_len.saturating_sub(BASE_DESCRIPTION_LEN)
+    }
+
+    /// Lifecycle stage at `now`; an active poll closes once its end time has passed
+    pub fn status_at(&self, now: i64) -> PollStatus {
+        match self.status {
+            PollStatus::Active if now > self.end_time => PollStatus::Closed,
+            status => status,
+        }
+    }
+
+    /// Whether votes are cast as encrypted ballots
+    pub fn is_shielded(&self) -> bool {
+        self.shielded_key != [0u8; 32]
+    }
+
+    /// Whether votes are weighed by the poll's oracle
+    pub fn is_oracle_weighted(&self) -> bool {
+        self.weight_oracle != Pubkey::default()
+    }
+
+    /// Ballots that left a `VoterReceipt` and so can claim a reward share. Receipt-tree polls
+    /// keep their ballots in the tree instead.
+    pub fn receipt_votes(&self) -> u64 {
+        if self.receipt_tree == Pubkey::default() {
+            self.total_votes
+        } else {
+            0
+        }
+    }
+
+    /// Whether a vote needs nothing beyond the voter's signature: no eligibility gate, fee,
+    /// stake or oracle weight, and none of the modes with their own vote instruction
+    pub fn takes_plain_votes(&self) -> bool {
+        self.eligibility_program.is_none()
+            && !self.access_restricted
+            && self.vote_fee == 0
+            && self.min_stake_lamports == 0
+            && !self.stake_weighted
+            && self.attestation_issuer == Pubkey::default()
+            && !self.is_oracle_weighted()
+            && !self.is_shielded()
+            && self.gauge_budget == 0
+            && self.conviction_mint == Pubkey::default()
+            && self.governance_realm == Pubkey::default()
+    }
+
+    /// Weight credited to a vote cast at `now`. Decaying polls count in basis points of a vote.
+    pub fn vote_weight(&self, now: i64) -> u64 {
+        match self.weight_decay {
+            WeightDecay::None => 1,
+            WeightDecay::Linear { end_bps } => {
+                let elapsed = now.saturating_sub(self.start_time).max(0) as u128;
+                let duration = self.end_time.saturating_sub(self.start_time).max(1) as u128;
+                let drop = (FULL_WEIGHT_BPS - end_bps as u64) as u128;
+                FULL_WEIGHT_BPS - (drop * elapsed.min(duration) / duration) as u64
+            }
+        }
+    }
+}
+
+// How a vote's weight changes over the voting period
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum WeightDecay {
+    None,
+    // Weight falls linearly from 100% at start_time to `end_bps` basis points at end_time
+    Linear { end_bps: u16 },
+}
+
+// Lifecycle of a poll: candidates are added in Draft, votes are cast while Active
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PollStatus {
+    Draft,
+    Active,
+    Closed,
+}
+
+// Whether a poll is listed alongside the others or only found by its ID
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PollVisibility {
+    Public,
+    Unlisted,
+}
+
+// One candidate of a batch registration
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CandidateEntry {
+    pub name: String,
+    pub party: String,
+}
+
+// Optional settings chosen by the creator at poll creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PollOptions {
+    pub candidate_deposit_lamports: u64,
+    pub deposit_refund_threshold: u64,
+    pub bounty_lamports: u64,
+    pub category: String,
+    pub tags: Vec<String>,
+    pub metadata_uri: String,
+    pub metadata_hash: [u8; 32],
+    pub mint_receipt_nft: bool,
+    pub receipt_nft_tree: Pubkey,
+    pub min_voter_lamports: u64,
+    pub min_stake_lamports: u64,
+    pub weight_decay: WeightDecay,
+    pub max_votes_per_slot: u32,
+    pub allow_cpi: bool,
+    pub shielded_key: [u8; 32],
+    pub stake_weighted: bool,
+    pub gauge_budget: u16,
+    pub conviction_mint: Pubkey,
+    pub governance_program: Pubkey,
+    pub governance_realm: Pubkey,
+    pub governance_mint: Pubkey,
+    pub eligibility_program: Option<Pubkey>,
+    pub attestation_program: Pubkey,
+    pub attestation_issuer: Pubkey,
+    pub attestation_schema: Pubkey,
+    pub visibility: PollVisibility,
+    pub access_restricted: bool,
+    pub weight_oracle: Pubkey,
+    pub max_weight_age: i64,
+    pub vote_fee: u64,
+    pub vote_fee_mint: Pubkey,
+    pub vote_fee_recipient: Pubkey,
+    pub max_extension: i64,
+    pub open_candidacy: bool,
+}
+
+// How long a conviction vote keeps its tokens locked past the poll's end
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LockPeriod {
+    PollEnd,
+    Month,
+    Quarter,
+}
+
+impl LockPeriod {
+    /// Weight multiplier earned by the lock
+    pub fn multiplier(&self) -> u8 {
+        match self {
+            LockPeriod::PollEnd => 1,
+            LockPeriod::Month => 2,
+            LockPeriod::Quarter => 4,
+        }
+    }
+
+    /// Seconds the tokens stay locked after the poll ends
+    pub fn duration(&self) -> i64 {
+        match self {
+            LockPeriod::PollEnd => 0,
+            LockPeriod::Month => CONVICTION_MONTH,
+            LockPeriod::Quarter => CONVICTION_QUARTER,
+        }
+    }
 }
 
 // Account to store candidate details and votes, linked to a Poll PDA
@@ -159,6 +4885,32 @@ pub struct Candidate {
     #[max_len(30)]
     pub party: String,
     pub votes: u64,
+    pub payout_wallet: Pubkey,
+    pub depositor: Pubkey,
+    pub deposit: u64,
+    #[max_len(200)]
+    pub metadata_uri: String,
+    pub version: u8,
+    // Ballots received, regardless of weight; `votes` holds the weighted tally
+    pub raw_votes: u64,
+    // Position on the ballot, in registration order
+    pub index: u16,
+    // Set at finalization on each candidate tied for the most votes
+    pub is_winner: bool,
+    // Canonical PDA bump
+    pub bump: u8,
+}
+
+impl Candidate {
+    /// Whether `address` is this candidate's PDA, derived from its ballot index or, for
+    /// candidates registered before index addressing, from its name
+    pub fn is_at(&self, address: &Pubkey) -> bool {
+        let index_bytes = self.index.to_le_bytes();
+        [index_bytes.as_ref(), self.name.as_bytes()].iter().any(|seed| {
+            Pubkey::create_program_address(&[CANDIDATE_SEED, self.poll.as_ref(), seed, &[self.bump]], &crate::ID)
+                .is_ok_and(|derived| derived == *address)
+        })
+    }
 }
 
 // Account to prevent double voting for a specific poll and voter
@@ -168,6 +4920,290 @@ pub struct VoterReceipt {
     pub poll: Pubkey,
     pub voter: Pubkey,
     pub has_voted: bool,
+    pub reward_claimed: bool,
+    pub version: u8,
+    // Votes credited to the candidate by this ballot
+    pub weight: u64,
+    // How a gauge vote split its points (empty for other ballots)
+    #[max_len(0)]
+    pub allocations: Vec<GaugeAllocation>,
+    // Canonical PDA bump
+    pub bump: u8,
+    // Who paid the receipt's rent and gets it back when the receipt is closed
+    pub payer: Pubkey,
+}
+
+impl VoterReceipt {
+    /// Account size needed to record the given number of gauge allocations
+    pub fn space_for(allocation_count: usize) -> usize {
+        8 + VoterReceipt::INIT_SPACE + allocation_count.min(MAX_GAUGE_ALLOCATIONS) * GaugeAllocation::INIT_SPACE
+    }
+}
+
+// Tokens behind a conviction vote: what was locked, for how long and at which multiplier.
+// It owns the vault holding the tokens and outlives the unlock as an audit record.
+#[account]
+#[derive(InitSpace)]
+pub struct ConvictionLock {
+    pub poll: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+    pub multiplier: u8,
+    pub expiry: i64,
+    pub unlocked: bool,
+}
+
+// Points a gauge vote gave to one candidate
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct GaugeAllocation {
+    pub candidate: Pubkey,
+    pub points: u16,
+}
+
+// Election grouping several polls so voters can fill in every race at once
+#[account]
+#[derive(InitSpace)]
+pub struct Ballot {
+    pub ballot_id: u64,
+    pub creator: Pubkey,
+    #[max_len(100)]
+    pub title: String,
+    #[max_len(10)]
+    pub polls: Vec<Pubkey>,
+}
+
+// Short-lived key a voter lets sign votes for them in one ballot's races
+#[account]
+#[derive(InitSpace)]
+pub struct Session {
+    pub voter: Pubkey,
+    pub ballot: Pubkey,
+    pub session_key: Pubkey,
+    pub expiry_slot: u64,
+    pub bump: u8,
+}
+
+// Creator-issued permission for a wallet to vote in an access-restricted poll
+#[account]
+#[derive(InitSpace)]
+pub struct AccessGrant {
+    pub poll: Pubkey,
+    pub voter: Pubkey,
+    pub granted_at: i64,
+    pub bump: u8,
+}
+
+// A voter registered in a poll's receipt tree, and the leaf holding their receipt
+#[account]
+#[derive(InitSpace)]
+pub struct CompressedVoter {
+    pub poll: Pubkey,
+    pub voter: Pubkey,
+    pub index: u32,
+    pub bump: u8,
+}
+
+// A voter's weight as published by an oracle, shared by every poll naming that oracle
+#[account]
+#[derive(InitSpace)]
+pub struct WeightRecord {
+    pub oracle: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+// Encrypted per-candidate totals of a shielded poll, in ballot order
+#[account]
+#[derive(InitSpace)]
+pub struct ShieldedTally {
+    pub poll: Pubkey,
+    #[max_len(4)]
+    pub totals: Vec<ElGamalCiphertext>,
+}
+
+// Exponential ElGamal ciphertext of `m` under key P on Ristretto: (rG, mG + rP), compressed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct ElGamalCiphertext {
+    pub ephemeral: [u8; 32],
+    pub masked: [u8; 32],
+}
+
+impl ElGamalCiphertext {
+    /// Homomorphic sum: the ciphertext of the two plaintexts added together
+    pub fn add_ciphertext(&self, other: &ElGamalCiphertext) -> Result<ElGamalCiphertext> {
+        let add = |a: [u8; 32], b: [u8; 32]| {
+            add_ristretto(&PodRistrettoPoint(a), &PodRistrettoPoint(b))
+                .map(|point| point.0)
+                .ok_or_else(|| error!(ErrorCode::InvalidShieldedBallot))
+        };
+        Ok(ElGamalCiphertext {
+            ephemeral: add(self.ephemeral, other.ephemeral)?,
+            masked: add(self.masked, other.masked)?,
+        })
+    }
+}
+
+// One candidate's entry on a shielded ballot and the proof that it encrypts 0 or 1
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ShieldedChoice {
+    pub ciphertext: ElGamalCiphertext,
+    pub proof: BitProof,
+}
+
+// Disjunctive Chaum-Pedersen proof, one challenge and response per branch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BitProof {
+    pub challenge0: [u8; 32],
+    pub challenge1: [u8; 32],
+    pub response0: [u8; 32],
+    pub response1: [u8; 32],
+}
+
+// Chaum-Pedersen proof of equal discrete logarithms
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DleqProof {
+    pub challenge: [u8; 32],
+    pub response: [u8; 32],
+}
+
+// A candidate's decrypted shielded total and its proof of correct decryption
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ShieldedResult {
+    pub votes: u64,
+    pub proof: DleqProof,
+}
+
+// A poll's registered auditors and their sign-offs on its finalized result. Bit `i` of
+// `attestations` is set once `auditors[i]` has attested; the attested winner and vote total
+// stay readable after the poll is archived.
+#[account]
+#[derive(InitSpace)]
+pub struct PollResult {
+    pub poll: Pubkey,
+    #[max_len(16)]
+    pub auditors: Vec<Pubkey>,
+    pub attestations: u16,
+    pub attestation_count: u8,
+    pub winner: Pubkey,
+    pub total_votes: u64,
+    pub bump: u8,
+}
+
+// Compact record of an archived poll, kept after its poll and candidate accounts are closed
+#[account]
+#[derive(InitSpace)]
+pub struct PollArchive {
+    pub poll_id: u64,
+    pub creator: Pubkey,
+    #[max_len(200)]
+    pub question: String,
+    pub winner: Pubkey,
+    pub total_votes: u64,
+    pub end_time: i64,
+    pub archived_at: i64,
+    #[max_len(0)]
+    pub candidates: Vec<ArchivedCandidate>,
+}
+
+impl PollArchive {
+    /// Account size needed to hold the given number of candidates
+    pub fn space_for(candidate_count: usize) -> usize {
+        8 + PollArchive::INIT_SPACE + candidate_count.min(MAX_ARCHIVED_CANDIDATES) * ArchivedCandidate::INIT_SPACE
+    }
+}
+
+// Final totals of one candidate, in ballot order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct ArchivedCandidate {
+    #[max_len(50)]
+    pub name: String,
+    pub votes: u64,
+    pub raw_votes: u64,
+    pub is_winner: bool,
+}
+
+// Poll awaiting council approval before it can be created
+#[account]
+#[derive(InitSpace)]
+pub struct PollProposal {
+    pub poll_id: u64,
+    pub creator: Pubkey,
+    #[max_len(200)]
+    pub question: String,
+    #[max_len(10)]
+    pub approvals: Vec<Pubkey>,
+}
+
+// Instruction a proposal poll executes if it passes
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub poll: Pubkey,
+    pub yes_candidate: Pubkey,
+    // Share of ballots the yes candidate must exceed, in basis points
+    pub threshold_bps: u16,
+    pub program_id: Pubkey,
+    #[max_len(10)]
+    pub accounts: Vec<ProposalAccountMeta>,
+    #[max_len(256)]
+    pub data: Vec<u8>,
+    pub executed: bool,
+}
+
+// Serializable form of an `AccountMeta`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct ProposalAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+// Events, emitted through self-CPI so they land in the transaction's inner instructions
+// A compressed receipt leaf was appended or replaced; clients rebuild the tree from these
+#[event]
+pub struct ReceiptLeafSet {
+    pub poll: Pubkey,
+    pub index: u32,
+    pub leaf: [u8; 32],
+}
+
+// A Switchboard value was applied to a poll, as its ballot order seed or its tie-break seed
+#[event]
+pub struct RandomnessApplied {
+    pub poll: Pubkey,
+    pub randomness_account: Pubkey,
+    pub seed_slot: u64,
+    pub value: [u8; 32],
+    pub ballot_order: bool,
+}
+
+// A vote was counted; carries the candidate's new totals and the poll's ballot count
+#[event]
+pub struct TallyUpdated {
+    pub poll: Pubkey,
+    pub candidate: Pubkey,
+    pub votes: u64,
+    pub raw_votes: u64,
+    pub total_votes: u64,
+}
+
+// Lamports left the treasury; `remaining` is the treasury balance afterwards
+#[event]
+pub struct TreasuryWithdrawal {
+    pub admin: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+// The admin changed the fees charged into the treasury
+#[event]
+pub struct FeeScheduleUpdated {
+    pub admin: Pubkey,
+    pub previous_poll_creation_fee: u64,
+    pub poll_creation_fee: u64,
 }
 
 // Error handling
@@ -179,4 +5215,260 @@ pub enum ErrorCode {
     Unauthorized,
     #[msg("The poll is not currently active for voting.")]
     PollNotActive,
+    #[msg("The treasury does not hold enough withdrawable lamports.")]
+    InsufficientTreasuryFunds,
+    #[msg("The poll has not ended yet.")]
+    PollNotEnded,
+    #[msg("The poll has already been finalized.")]
+    PollAlreadyFinalized,
+    #[msg("The poll has not been finalized yet.")]
+    PollNotFinalized,
+    #[msg("There is no deposit left to claim.")]
+    NoDepositToClaim,
+    #[msg("The token mint does not match the poll's reward mint.")]
+    RewardMintMismatch,
+    #[msg("The reward for this receipt has already been claimed.")]
+    RewardAlreadyClaimed,
+    #[msg("There is no reward to claim for this poll.")]
+    NoRewardToClaim,
+    #[msg("The accounts passed do not match the poll's candidate list.")]
+    CandidateListMismatch,
+    #[msg("There is no bounty left to claim.")]
+    NoBountyToClaim,
+    #[msg("The bounty recipient does not match the poll's winner.")]
+    InvalidBountyRecipient,
+    #[msg("The candidate does not belong to this poll.")]
+    CandidatePollMismatch,
+    #[msg("The accounts passed do not match the ballot's races.")]
+    InvalidBallotRaces,
+    #[msg("You have already voted in this poll.")]
+    AlreadyVoted,
+    #[msg("The poll category is too long.")]
+    CategoryTooLong,
+    #[msg("A poll can have at most 5 tags.")]
+    TooManyTags,
+    #[msg("A poll tag is too long.")]
+    TagTooLong,
+    #[msg("The metadata URI is too long.")]
+    UriTooLong,
+    #[msg("Voting has already started for this poll.")]
+    VotingAlreadyStarted,
+    #[msg("The poll question is too long.")]
+    QuestionTooLong,
+    #[msg("The poll description is too long.")]
+    DescriptionTooLong,
+    #[msg("This account type cannot be migrated.")]
+    UnsupportedAccount,
+    #[msg("The poll is paused.")]
+    PollPaused,
+    #[msg("The poll is not paused.")]
+    PollNotPaused,
+    #[msg("The poll already has a receipt tree.")]
+    ReceiptTreeAlreadySet,
+    #[msg("The merkle tree is not this poll's receipt tree.")]
+    ReceiptTreeMismatch,
+    #[msg("The accounts needed to mint the receipt NFT are missing.")]
+    MissingReceiptNftAccounts,
+    #[msg("The winner badge has already been minted.")]
+    WinnerBadgeAlreadyMinted,
+    #[msg("The candidate is not the poll's winner.")]
+    NotPollWinner,
+    #[msg("The voter's balance is below the poll's minimum.")]
+    InsufficientVoterBalance,
+    #[msg("The voter's active stake is below the poll's minimum.")]
+    InsufficientStake,
+    #[msg("The stake account is not an active delegation controlled by the voter.")]
+    InvalidStakeAccount,
+    #[msg("The weight decay must end between 0 and 10000 basis points.")]
+    InvalidWeightDecay,
+    #[msg("Receipts can only be closed once the poll's grace period has passed.")]
+    ReceiptGracePeriodActive,
+    #[msg("This action is only allowed while the poll is a draft.")]
+    PollNotDraft,
+    #[msg("A poll needs at least one candidate to be activated.")]
+    NoCandidates,
+    #[msg("The threshold must be between 0 and 10000 basis points.")]
+    InvalidThreshold,
+    #[msg("The proposal instruction has too many accounts or too much data.")]
+    ProposalTooLarge,
+    #[msg("The proposal has already been executed.")]
+    ProposalAlreadyExecuted,
+    #[msg("The proposal did not reach its threshold.")]
+    ProposalNotPassed,
+    #[msg("The accounts passed do not match the proposal's instruction.")]
+    ProposalAccountMismatch,
+    #[msg("The council can have at most 10 members.")]
+    TooManyCouncilMembers,
+    #[msg("The council threshold cannot exceed the number of members.")]
+    InvalidCouncilThreshold,
+    #[msg("Council members must be unique.")]
+    DuplicateCouncilMember,
+    #[msg("The signer is not a council member.")]
+    NotCouncilMember,
+    #[msg("This council member has already approved the poll.")]
+    AlreadyApproved,
+    #[msg("The poll has not been approved by enough council members.")]
+    CouncilApprovalMissing,
+    #[msg("This wallet does not hold a creator pass.")]
+    CreatorNotAllowed,
+    #[msg("Too many votes in this slot, try again shortly.")]
+    VoteRateLimited,
+    #[msg("The poll question cannot be empty.")]
+    EmptyQuestion,
+    #[msg("The candidate name cannot be empty.")]
+    EmptyCandidateName,
+    #[msg("The start time is too far in the past.")]
+    StartTimeInPast,
+    #[msg("The end time is further away than the maximum poll duration allows.")]
+    PollTooLong,
+    #[msg("The poll has reached the maximum number of candidates.")]
+    TooManyCandidates,
+    #[msg("Polls can only be archived once the receipt grace period has passed.")]
+    ArchiveGracePeriodActive,
+    #[msg("Settle the poll's bounty, deposits and reward pool before archiving it.")]
+    UnsettledPollFunds,
+    #[msg("Polls with more than 20 candidates cannot be archived.")]
+    TooManyCandidatesToArchive,
+    #[msg("This poll only accepts votes sent directly, not through another program.")]
+    VoteViaCpi,
+    #[msg("The shielded key must be a valid Ristretto point, and shielded polls cannot use weight decay.")]
+    InvalidShieldedKey,
+    #[msg("This poll does not use shielded voting.")]
+    PollNotShielded,
+    #[msg("This poll only accepts shielded ballots.")]
+    PollShielded,
+    #[msg("A shielded ballot needs one valid ciphertext per candidate.")]
+    InvalidShieldedBallot,
+    #[msg("A shielded voting proof failed to verify.")]
+    InvalidShieldedProof,
+    #[msg("The shielded results have already been published.")]
+    ShieldedResultsPublished,
+    #[msg("Publish the decrypted shielded results before finalizing.")]
+    ShieldedResultsPending,
+    #[msg("Stake-weighted polls cannot use weight decay or shielded ballots.")]
+    InvalidStakeWeighting,
+    #[msg("This stake account has already voted in the poll.")]
+    StakeAlreadyCounted,
+    #[msg("Gauge polls cannot use weight decay, stake weighting or shielded ballots.")]
+    InvalidGaugeBudget,
+    #[msg("This poll is not a gauge poll.")]
+    PollNotGauge,
+    #[msg("This gauge poll only accepts votes that split its point budget.")]
+    PollIsGauge,
+    #[msg("Gauge points must go to distinct candidates of the poll, each nonzero, summing to the budget.")]
+    InvalidGaugeAllocation,
+    #[msg("Conviction polls cannot use weight decay, stake weighting, gauge points or shielded ballots.")]
+    InvalidConvictionMint,
+    #[msg("This poll does not take conviction votes in this token.")]
+    PollNotConviction,
+    #[msg("This conviction poll only accepts votes backed by locked tokens.")]
+    PollIsConviction,
+    #[msg("A conviction vote must lock a nonzero amount of tokens.")]
+    InvalidConvictionAmount,
+    #[msg("The tokens are still locked.")]
+    TokensStillLocked,
+    #[msg("These tokens have already been unlocked.")]
+    AlreadyUnlocked,
+    #[msg("Realm-backed polls need a governance program, realm and mint, and no other weighting.")]
+    InvalidGovernanceConfig,
+    #[msg("This poll is not backed by a governance realm.")]
+    PollNotGovernance,
+    #[msg("This poll only accepts votes backed by a governance token owner record.")]
+    PollIsGovernance,
+    #[msg("The token owner record does not belong to the voter in the poll's realm and mint.")]
+    InvalidTokenOwnerRecord,
+    #[msg("The voter has no governing tokens deposited in the realm.")]
+    InsufficientGovernancePower,
+    #[msg("Eligibility programs only apply to standard votes and cannot be this program.")]
+    InvalidEligibilityProgram,
+    #[msg("This poll requires its eligibility program to verify the voter.")]
+    EligibilityProgramMissing,
+    #[msg("Attestation-gated polls need an attestation program, issuer and schema, and only take standard votes.")]
+    InvalidAttestationConfig,
+    #[msg("The voter must present an attestation about them from the poll's issuer and schema.")]
+    InvalidAttestation,
+    #[msg("The voter's attestation has expired.")]
+    AttestationExpired,
+    #[msg("The candidate name is too long.")]
+    CandidateNameTooLong,
+    #[msg("A candidate batch needs one new candidate account per entry, in ballot order.")]
+    InvalidCandidateBatch,
+    #[msg("A session must expire in the future and within the maximum session length.")]
+    InvalidSessionExpiry,
+    #[msg("The session key has expired.")]
+    SessionExpired,
+    #[msg("The session does not cover this poll.")]
+    PollNotInSession,
+    #[msg("The poll already has a finalization thread.")]
+    FinalizationAlreadyScheduled,
+    #[msg("The poll has no finalization thread.")]
+    FinalizationNotScheduled,
+    #[msg("The poll is waiting for its randomness to be applied.")]
+    RandomnessPending,
+    #[msg("Randomness is only drawn for a draft poll's ballot order or an ended poll's tie-break.")]
+    RandomnessNotNeeded,
+    #[msg("The account is not the poll's pending Switchboard randomness request.")]
+    InvalidRandomness,
+    #[msg("The randomness has not been revealed yet.")]
+    RandomnessNotRevealed,
+    #[msg("Access-restricted polls only take standard votes.")]
+    InvalidAccessConfig,
+    #[msg("The voter has not been granted access to this poll.")]
+    AccessNotGranted,
+    #[msg("Oracle-weighted polls need a non-negative maximum record age, use no other weighting and only take standard votes.")]
+    InvalidOracleConfig,
+    #[msg("The voter must present a nonzero weight record published by the poll's oracle.")]
+    InvalidWeightRecord,
+    #[msg("The voter's weight record is older than the poll accepts.")]
+    StaleWeightRecord,
+    #[msg("Arithmetic overflow.")]
+    Overflow,
+    #[msg("The transaction votes more than once for the same poll and voter.")]
+    DuplicateVote,
+    #[msg("Vote fees need a recipient, and polls charging one only take standard votes.")]
+    InvalidVoteFeeConfig,
+    #[msg("This poll charges a vote fee, which only standard votes pay.")]
+    VoteFeeUnpaid,
+    #[msg("The fee accounts do not match the poll's vote fee.")]
+    InvalidFeeAccount,
+    #[msg("Creation limits allow at most 20 polls per window, and need a positive window.")]
+    InvalidCreationLimit,
+    #[msg("The creator has reached the poll creation limit for now; try again later.")]
+    CreationLimitReached,
+    #[msg("Auditors must be 1 to 16 distinct keys.")]
+    InvalidAuditors,
+    #[msg("The signer is not a registered auditor of this poll.")]
+    NotAuditor,
+    #[msg("This auditor has already attested the result.")]
+    AlreadyAttested,
+    #[msg("The attested winner or vote total does not match the poll's result.")]
+    ResultMismatch,
+    #[msg("The poll has ended, so its end time can no longer change.")]
+    PollEnded,
+    #[msg("Votes have been cast, so the end time can only be extended.")]
+    EndTimeLocked,
+    #[msg("The extension goes past the poll's maximum extension window.")]
+    ExtensionTooLong,
+    #[msg("The poll recorded votes outside its voting window.")]
+    VoteOutsideWindow,
+    #[msg("Ballot races must take plain votes, without eligibility gates, fees, stake or special voting modes.")]
+    RaceNeedsCredentials,
+    #[msg("Receipt trees need a poll that takes plain votes, without eligibility gates, fees, stake or special voting modes.")]
+    ReceiptTreeNeedsPlainVotes,
+    #[msg("This poll records votes in its receipt tree and only takes compressed votes.")]
+    PollHasReceiptTree,
+    #[msg("The top candidates are tied; request a tie-break before finalizing.")]
+    TieBreakRequired,
+    #[msg("The randomness request has not timed out yet.")]
+    RandomnessNotExpired,
+    #[msg("The randomness has been revealed; apply it instead.")]
+    RandomnessRevealed,
+    #[msg("This poll ID belongs to an archived poll and cannot be reused.")]
+    PollIdArchived,
+    #[msg("Reward pools are shared by vote receipts, which receipt-tree polls don't keep.")]
+    RewardsNeedReceipts,
+    #[msg("A self-nominee stands once per poll and must pass their unused nomination account.")]
+    InvalidNomination,
+    #[msg("The voter's reward share must be claimed before their receipt can be closed.")]
+    RewardUnclaimed,
 }
@@ -0,0 +1,220 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  MINT_SIZE,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
+  getAssociatedTokenAddressSync,
+  unpackAccount,
+} from "@solana/spl-token";
+import { BankrunProvider } from "anchor-bankrun";
+import { Clock, ProgramTestContext, startAnchor } from "solana-bankrun";
+import { VotingDapp } from "../target/types/voting_dapp";
+import { assert } from "chai";
+
+const IDL = require("../target/idl/voting_dapp.json");
+
+// Archiving waits out the 30-day receipt grace period, so these tests run on bankrun,
+// where the clock can be moved forward
+describe("archive", () => {
+  const RECEIPT_GRACE_PERIOD = 30 * 24 * 60 * 60;
+  const BPF_LOADER_UPGRADEABLE = new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+  const pollId = new anchor.BN(1);
+
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<VotingDapp>;
+  let creator: anchor.web3.PublicKey;
+
+  // Poll options with every optional feature disabled
+  const defaultPollOptions = () => ({
+    candidateDepositLamports: new anchor.BN(0),
+    depositRefundThreshold: new anchor.BN(0),
+    bountyLamports: new anchor.BN(0),
+    category: "",
+    tags: [],
+    metadataUri: "",
+    metadataHash: new Array(32).fill(0),
+    mintReceiptNft: false,
+    receiptNftTree: anchor.web3.PublicKey.default,
+    minVoterLamports: new anchor.BN(0),
+    minStakeLamports: new anchor.BN(0),
+    weightDecay: { none: {} },
+    maxVotesPerSlot: 0,
+    allowCpi: false,
+    shieldedKey: new Array(32).fill(0),
+    stakeWeighted: false,
+    gaugeBudget: 0,
+    convictionMint: anchor.web3.PublicKey.default,
+    governanceProgram: anchor.web3.PublicKey.default,
+    governanceRealm: anchor.web3.PublicKey.default,
+    governanceMint: anchor.web3.PublicKey.default,
+    eligibilityProgram: null,
+    attestationProgram: anchor.web3.PublicKey.default,
+    attestationIssuer: anchor.web3.PublicKey.default,
+    attestationSchema: anchor.web3.PublicKey.default,
+    visibility: { public: {} },
+    accessRestricted: false,
+    weightOracle: anchor.web3.PublicKey.default,
+    maxWeightAge: new anchor.BN(0),
+    voteFee: new anchor.BN(0),
+    voteFeeMint: anchor.web3.PublicKey.default,
+    voteFeeRecipient: anchor.web3.PublicKey.default,
+    maxExtension: new anchor.BN(0),
+    openCandidacy: false,
+  });
+
+  const pda = (...seeds: Buffer[]) => anchor.web3.PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+  const now = async () => Number((await context.banksClient.getClock()).unixTimestamp);
+
+  const warpTo = async (unixTimestamp: number) => {
+    const clock = await context.banksClient.getClock();
+    context.setClock(
+      new Clock(clock.slot + BigInt(1), clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, BigInt(unixTimestamp))
+    );
+  };
+
+  const tokenBalance = async (address: anchor.web3.PublicKey) => {
+    const account = await context.banksClient.getAccount(address);
+    return Number(unpackAccount(address, { ...account, data: Buffer.from(account.data) }).amount);
+  };
+
+  const fund = (wallet: anchor.web3.PublicKey) =>
+    context.setAccount(wallet, {
+      lamports: anchor.web3.LAMPORTS_PER_SOL,
+      data: Buffer.alloc(0),
+      owner: anchor.web3.SystemProgram.programId,
+      executable: false,
+    });
+
+  before(async () => {
+    context = await startAnchor("", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<VotingDapp>(IDL, provider);
+    creator = provider.wallet.publicKey;
+
+    // Bankrun loads the program without a loader record, so give it one naming the creator as
+    // upgrade authority: the tag, the deploy slot, then the optional authority
+    const programData = Buffer.alloc(45);
+    programData.writeUInt32LE(3, 0);
+    programData.writeUInt8(1, 12);
+    creator.toBuffer().copy(programData, 13);
+    context.setAccount(
+      anchor.web3.PublicKey.findProgramAddressSync([program.programId.toBuffer()], BPF_LOADER_UPGRADEABLE)[0],
+      { lamports: anchor.web3.LAMPORTS_PER_SOL, data: programData, owner: BPF_LOADER_UPGRADEABLE, executable: false }
+    );
+
+    await program.methods.initializeConfig(new anchor.BN(0)).accounts({ admin: creator }).rpc();
+  });
+
+  it("Closes the reward vault, returns its remainder and retires the poll ID", async () => {
+    const start = await now();
+    const endTime = start + 60;
+    const pollPda = pda(Buffer.from("poll"), pollId.toArrayLike(Buffer, "le", 8));
+    const candidates = [0, 1].map((index) =>
+      pda(Buffer.from("candidate"), pollPda.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 2))
+    );
+    const rewardVault = pda(Buffer.from("reward_vault"), pollPda.toBuffer());
+    const archivePda = pda(Buffer.from("poll_archive"), pollId.toArrayLike(Buffer, "le", 8));
+
+    await program.methods
+      .initializePoll(pollId, "Archived poll", "Ends with dust.", new anchor.BN(start - 10), new anchor.BN(endTime), defaultPollOptions())
+      .accounts({ creator })
+      .rpc();
+    for (const [index, name] of ["Ivy", "Jon"].entries()) {
+      await program.methods
+        .initializeCandidate(name, "", creator, "")
+        .accounts({ poll: pollPda, candidate: candidates[index], creator })
+        .rpc();
+    }
+
+    // Ten tokens shared by three receipts leave one that can't be split
+    const mint = anchor.web3.Keypair.generate();
+    const creatorTokenAccount = getAssociatedTokenAddressSync(mint.publicKey, creator);
+    const rent = await context.banksClient.getRent();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: creator,
+          newAccountPubkey: mint.publicKey,
+          lamports: Number(rent.minimumBalance(BigInt(MINT_SIZE))),
+          space: MINT_SIZE,
+          programId: TOKEN_PROGRAM_ID,
+        }),
+        createInitializeMint2Instruction(mint.publicKey, 0, creator, null),
+        createAssociatedTokenAccountInstruction(creator, creatorTokenAccount, creator, mint.publicKey),
+        createMintToInstruction(mint.publicKey, creatorTokenAccount, creator, 10)
+      ),
+      [mint]
+    );
+    await program.methods
+      .fundRewardPool(new anchor.BN(10))
+      .accounts({ poll: pollPda, mint: mint.publicKey, creatorTokenAccount, creator })
+      .rpc();
+
+    await program.methods.activatePoll().accounts({ poll: pollPda, creator }).rpc();
+    const voters = [0, 0, 1].map((index) => ({ keypair: anchor.web3.Keypair.generate(), candidate: candidates[index] }));
+    for (const { keypair, candidate } of voters) {
+      fund(keypair.publicKey);
+      await program.methods
+        .vote()
+        .accounts({ poll: pollPda, candidate, voter: keypair.publicKey, payer: keypair.publicKey })
+        .signers([keypair])
+        .rpc();
+    }
+
+    await warpTo(endTime + 1);
+    await program.methods
+      .finalizePoll()
+      .accounts({ poll: pollPda })
+      .remainingAccounts(candidates.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .rpc();
+    for (const { keypair } of voters) {
+      const voterTokenAccount = getAssociatedTokenAddressSync(mint.publicKey, keypair.publicKey);
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          createAssociatedTokenAccountInstruction(creator, voterTokenAccount, keypair.publicKey, mint.publicKey)
+        )
+      );
+      await program.methods
+        .claimReward()
+        .accounts({ poll: pollPda, voterTokenAccount, voter: keypair.publicKey })
+        .signers([keypair])
+        .rpc();
+    }
+
+    await warpTo(endTime + RECEIPT_GRACE_PERIOD + 1);
+    await program.methods
+      .archivePoll()
+      .accounts({
+        poll: pollPda,
+        archive: archivePda,
+        rewardVault,
+        creatorTokenAccount,
+        creator,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(candidates.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .rpc();
+
+    const archive = await program.account.pollArchive.fetch(archivePda);
+    assert.equal(archive.totalVotes.toNumber(), 3);
+    assert.deepEqual(archive.candidates.map((candidate) => candidate.votes.toNumber()), [2, 1]);
+    assert.isNull(await context.banksClient.getAccount(pollPda));
+    assert.isNull(await context.banksClient.getAccount(rewardVault));
+    assert.equal(await tokenBalance(creatorTokenAccount), 1);
+
+    try {
+      await program.methods
+        .initializePoll(pollId, "Reused ID", "Same seeds.", new anchor.BN(endTime + RECEIPT_GRACE_PERIOD), new anchor.BN(endTime + RECEIPT_GRACE_PERIOD + 60), defaultPollOptions())
+        .accounts({ creator })
+        .rpc();
+      assert.fail("An archived poll's ID should not be reused.");
+    } catch (error) {
+      assert.include(error.message, "PollIdArchived");
+    }
+  });
+});
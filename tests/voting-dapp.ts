@@ -2,6 +2,14 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { VotingDapp } from "../target/types/voting_dapp";
 import { assert } from "chai";
+import { keccak_256 } from "@noble/hashes/sha3";
+import {
+  createAssociatedTokenAccount,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  mintTo,
+} from "@solana/spl-token";
 
 describe("voting-dapp", () => {
   // Configure the client to use the local cluster.
@@ -15,6 +23,47 @@ describe("voting-dapp", () => {
   const pollId = new anchor.BN(1);
   const candidateName = "Alice";
   const candidateParty = "Blue";
+  const pollCreationFee = new anchor.BN(10_000_000);
+
+  // Poll options with every optional feature disabled
+  const defaultPollOptions = () => ({
+    candidateDepositLamports: new anchor.BN(0),
+    depositRefundThreshold: new anchor.BN(0),
+    bountyLamports: new anchor.BN(0),
+    category: "",
+    tags: [],
+    metadataUri: "",
+    metadataHash: new Array(32).fill(0),
+    mintReceiptNft: false,
+    receiptNftTree: anchor.web3.PublicKey.default,
+    minVoterLamports: new anchor.BN(0),
+    minStakeLamports: new anchor.BN(0),
+    weightDecay: { none: {} },
+    maxVotesPerSlot: 0,
+    allowCpi: false,
+    shieldedKey: new Array(32).fill(0),
+    stakeWeighted: false,
+    gaugeBudget: 0,
+    convictionMint: anchor.web3.PublicKey.default,
+    governanceProgram: anchor.web3.PublicKey.default,
+    governanceRealm: anchor.web3.PublicKey.default,
+    governanceMint: anchor.web3.PublicKey.default,
+    eligibilityProgram: null,
+    attestationProgram: anchor.web3.PublicKey.default,
+    attestationIssuer: anchor.web3.PublicKey.default,
+    attestationSchema: anchor.web3.PublicKey.default,
+    visibility: { public: {} },
+    accessRestricted: false,
+    weightOracle: anchor.web3.PublicKey.default,
+    maxWeightAge: new anchor.BN(0),
+    voteFee: new anchor.BN(0),
+    voteFeeMint: anchor.web3.PublicKey.default,
+    voteFeeRecipient: anchor.web3.PublicKey.default,
+    maxExtension: new anchor.BN(0),
+    openCandidacy: false,
+  });
+
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
 
   // Helper function to derive PDAs
   const getPollPda = async (pollId: anchor.BN) => {
@@ -24,9 +73,9 @@ describe("voting-dapp", () => {
     );
   };
 
-  const getCandidatePda = async (pollKey: anchor.web3.PublicKey, name: string) => {
+  const getCandidatePda = async (pollKey: anchor.web3.PublicKey, index: number) => {
     return anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("candidate"), pollKey.toBuffer(), Buffer.from(name)],
+      [Buffer.from("candidate"), pollKey.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 2)],
       program.programId
     );
   };
@@ -38,12 +87,48 @@ describe("voting-dapp", () => {
     );
   };
 
+  const [treasuryPda] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("treasury")],
+    program.programId
+  );
+
+  it("Rejects config initialization by anyone but the upgrade authority", async () => {
+    const impostor = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(impostor.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(airdrop);
+
+    try {
+      await program.methods
+        .initializeConfig(pollCreationFee)
+        .accounts({
+          admin: impostor.publicKey,
+        })
+        .signers([impostor])
+        .rpc();
+      assert.fail("Only the upgrade authority should initialize the config.");
+    } catch (error) {
+      assert.include(error.message, "Unauthorized");
+    }
+  });
+
+  it("Initializes the config and treasury", async () => {
+    await program.methods
+      .initializeConfig(pollCreationFee)
+      .accounts({
+        admin: creator.publicKey,
+      })
+      .rpc();
+
+    const treasuryAccount = await program.account.treasury.fetch(treasuryPda);
+    assert.equal(treasuryAccount.totalCollected.toNumber(), 0);
+  });
+
   it("Is initialized!", async () => {
     // Test successful poll initialization
     const now = new anchor.BN(Math.floor(Date.now() / 1000));
     const startTime = now.sub(new anchor.BN(10)); // Start 10 seconds ago
     const endTime = now.add(new anchor.BN(3600)); // End in 1 hour
-    const [pollPda] = await getPollPda(pollId);
+    const [pollPda, pollBump] = await getPollPda(pollId);
 
     await program.methods
       .initializePoll(
@@ -51,7 +136,8 @@ describe("voting-dapp", () => {
         "Should we use Anchor?",
         "A simple yes/no poll.",
         startTime,
-        endTime
+        endTime,
+        { ...defaultPollOptions(), category: "governance", tags: ["dao", "tooling"] }
       )
       .accounts({
         creator: creator.publicKey,
@@ -62,21 +148,156 @@ describe("voting-dapp", () => {
     assert.equal(pollAccount.pollId.toString(), pollId.toString());
     assert.equal(pollAccount.creator.toBase58(), creator.publicKey.toBase58());
     assert.equal(pollAccount.candidateCount.toNumber(), 0);
+    assert.equal(pollAccount.category, "governance");
+    assert.deepEqual(pollAccount.tags, ["dao", "tooling"]);
+    assert.equal(pollAccount.version, 30);
+    assert.equal(pollAccount.bump, pollBump);
+    assert.deepEqual(pollAccount.status, { draft: {} });
+    assert.isFalse(pollAccount.paused);
+
+    // The creation fee should have been routed into the treasury
+    const treasuryAccount = await program.account.treasury.fetch(treasuryPda);
+    assert.equal(treasuryAccount.totalCollected.toString(), pollCreationFee.toString());
+  });
+
+  it("Rejects an empty poll question", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    try {
+      await program.methods
+        .initializePoll(new anchor.BN(10), "   ", "Blank question.", new anchor.BN(now), new anchor.BN(now + 3600), defaultPollOptions())
+        .accounts({ creator: creator.publicKey })
+        .rpc();
+      assert.fail("A blank question should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "EmptyQuestion");
+    }
+  });
+
+  it("Rejects a description longer than a new poll holds", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    try {
+      await program.methods
+        .initializePoll(new anchor.BN(10), "Long poll", "x".repeat(281), new anchor.BN(now), new anchor.BN(now + 3600), defaultPollOptions())
+        .accounts({ creator: creator.publicKey })
+        .rpc();
+      assert.fail("An over-long description should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "DescriptionTooLong");
+    }
+  });
+
+  it("Rejects stake weighting combined with weight decay", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    try {
+      await program.methods
+        .initializePoll(new anchor.BN(11), "Stake vote", "Decaying stake.", new anchor.BN(now), new anchor.BN(now + 3600), {
+          ...defaultPollOptions(),
+          stakeWeighted: true,
+          weightDecay: { linear: { endBps: 5000 } },
+        })
+        .accounts({ creator: creator.publicKey })
+        .rpc();
+      assert.fail("Stake-weighted polls should not decay.");
+    } catch (error) {
+      assert.include(error.message, "InvalidStakeWeighting");
+    }
+  });
+
+  it("Rejects an eligibility program on a gauge poll", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    try {
+      await program.methods
+        .initializePoll(new anchor.BN(14), "Verified gauge", "Split points.", new anchor.BN(now), new anchor.BN(now + 3600), {
+          ...defaultPollOptions(),
+          gaugeBudget: 100,
+          eligibilityProgram: anchor.web3.Keypair.generate().publicKey,
+        })
+        .accounts({ creator: creator.publicKey })
+        .rpc();
+      assert.fail("Gauge votes never call the eligibility program.");
+    } catch (error) {
+      assert.include(error.message, "InvalidEligibilityProgram");
+    }
+  });
+
+  it("Rejects an attestation issuer without a schema", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    try {
+      await program.methods
+        .initializePoll(new anchor.BN(15), "Members only", "Attested voters.", new anchor.BN(now), new anchor.BN(now + 3600), {
+          ...defaultPollOptions(),
+          attestationProgram: anchor.web3.Keypair.generate().publicKey,
+          attestationIssuer: anchor.web3.Keypair.generate().publicKey,
+        })
+        .accounts({ creator: creator.publicKey })
+        .rpc();
+      assert.fail("Attestation gates need a schema.");
+    } catch (error) {
+      assert.include(error.message, "InvalidAttestationConfig");
+    }
+  });
+
+  it("Lets the admin withdraw from the treasury", async () => {
+    const recipient = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .withdrawTreasury(pollCreationFee)
+      .accounts({
+        admin: creator.publicKey,
+        recipient: recipient.publicKey,
+      })
+      .rpc();
+
+    const balance = await provider.connection.getBalance(recipient.publicKey);
+    assert.equal(balance, pollCreationFee.toNumber());
+  });
+
+  it("Lets the admin change the fee schedule", async () => {
+    const [configPda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+
+    await program.methods
+      .setFeeSchedule(new anchor.BN(0))
+      .accounts({ admin: creator.publicKey })
+      .rpc();
+    assert.equal((await program.account.config.fetch(configPda)).pollCreationFee.toNumber(), 0);
+
+    try {
+      await program.methods
+        .setFeeSchedule(new anchor.BN(1))
+        .accounts({ admin: voter.publicKey })
+        .signers([voter])
+        .rpc();
+      assert.fail("Only the admin may change fees.");
+    } catch (error) {
+      assert.include(error.message, "Unauthorized");
+    }
+
+    await program.methods
+      .setFeeSchedule(pollCreationFee)
+      .accounts({ admin: creator.publicKey })
+      .rpc();
   });
 
   it("Can initialize candidate and vote successfully", async () => {
     // Initialize a candidate
     const [pollPda] = await getPollPda(pollId);
-    const [candidatePda] = await getCandidatePda(pollPda, candidateName);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
 
     await program.methods
-      .initializeCandidate(candidateName, candidateParty)
+      .initializeCandidate(candidateName, candidateParty, creator.publicKey, "https://example.com/alice.json")
       .accounts({
         poll: pollPda,
+        candidate: candidatePda,
         creator: creator.publicKey,
       })
       .rpc();
 
+    // Open the poll for voting
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
     // Airdrop SOL to the voter for the vote transaction
     await provider.connection.requestAirdrop(voter.publicKey, 2000000000);
     // Wait for airdrop to confirm
@@ -84,8 +305,14 @@ describe("voting-dapp", () => {
       await provider.connection.requestAirdrop(voter.publicKey, 2000000000)
     );
 
-    // Vote for the candidate
+    // Vote for the candidate, listening for the running tally
     const [receiptPda] = await getReceiptPda(pollPda, voter.publicKey);
+    const tallyEvent = new Promise<any>((resolve) => {
+      const listener = program.addEventListener("tallyUpdated", (event) => {
+        program.removeEventListener(listener);
+        resolve(event);
+      });
+    });
 
     await program.methods
       .vote()
@@ -93,6 +320,7 @@ describe("voting-dapp", () => {
         poll: pollPda,
         candidate: candidatePda,
         voter: voter.publicKey,
+        payer: voter.publicKey,
       })
       .signers([voter])
       .rpc();
@@ -100,14 +328,27 @@ describe("voting-dapp", () => {
     // Verify vote count and receipt
     const candidateAccount = await program.account.candidate.fetch(candidatePda);
     assert.equal(candidateAccount.votes.toNumber(), 1);
+    assert.equal(candidateAccount.rawVotes.toNumber(), 1);
+    assert.equal(candidateAccount.index, 0);
+    assert.equal(candidateAccount.metadataUri, "https://example.com/alice.json");
     const receiptAccount = await program.account.voterReceipt.fetch(receiptPda);
     assert.isTrue(receiptAccount.hasVoted);
+    assert.equal(receiptAccount.weight.toNumber(), 1);
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.isAbove(pollAccount.firstVoteAt.toNumber(), 0);
+    assert.equal(pollAccount.lastVoteAt.toNumber(), pollAccount.firstVoteAt.toNumber());
+    assert.equal(pollAccount.voteHistogram.reduce((sum, count) => sum + count, 0), 1);
+
+    const tally = await tallyEvent;
+    assert.equal(tally.candidate.toBase58(), candidatePda.toBase58());
+    assert.equal(tally.votes.toNumber(), 1);
+    assert.equal(tally.totalVotes.toNumber(), 1);
   });
 
   it("Fails on double voting", async () => {
     // Test that a second vote fails
     const [pollPda] = await getPollPda(pollId);
-    const [candidatePda] = await getCandidatePda(pollPda, candidateName);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
     const [receiptPda] = await getReceiptPda(pollPda, voter.publicKey);
 
     try {
@@ -117,6 +358,7 @@ describe("voting-dapp", () => {
           poll: pollPda,
           candidate: candidatePda,
           voter: voter.publicKey,
+          payer: voter.publicKey,
         })
         .signers([voter])
         .rpc();
@@ -127,4 +369,1607 @@ describe("voting-dapp", () => {
       assert.include(error.message, "already in use", "Expected account already in use error for double voting.");
     }
   });
+
+  it("Rejects votes while the poll is paused", async () => {
+    const [pollPda] = await getPollPda(pollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const lateVoter = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(lateVoter.publicKey, 1_000_000_000)
+    );
+
+    await program.methods
+      .pausePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    try {
+      await program.methods
+        .vote()
+        .accounts({ poll: pollPda, candidate: candidatePda, voter: lateVoter.publicKey, payer: lateVoter.publicKey })
+        .signers([lateVoter])
+        .rpc();
+      assert.fail("The vote should have been rejected while paused.");
+    } catch (error) {
+      assert.include(error.message, "PollPaused");
+    }
+
+    const before = await program.account.poll.fetch(pollPda);
+    await sleep(1000);
+    await program.methods
+      .resumePoll(true)
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    const after = await program.account.poll.fetch(pollPda);
+    assert.isFalse(after.paused);
+    assert.isAbove(after.endTime.toNumber(), before.endTime.toNumber());
+  });
+
+  it("Lets a sponsor pay the receipt rent", async () => {
+    const [pollPda] = await getPollPda(pollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    // The voter holds no SOL and only signs to prove identity
+    const sponsoredVoter = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .vote()
+      .accounts({
+        poll: pollPda,
+        candidate: candidatePda,
+        voter: sponsoredVoter.publicKey,
+        payer: creator.publicKey,
+      })
+      .signers([sponsoredVoter])
+      .rpc();
+
+    const [receiptPda] = await getReceiptPda(pollPda, sponsoredVoter.publicKey);
+    const receiptAccount = await program.account.voterReceipt.fetch(receiptPda);
+    assert.equal(receiptAccount.voter.toBase58(), sponsoredVoter.publicKey.toBase58());
+    assert.equal(await provider.connection.getBalance(sponsoredVoter.publicKey), 0);
+  });
+
+  it("Rejects two votes for the same poll in one transaction", async () => {
+    const [pollPda] = await getPollPda(pollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const doubleVoter = anchor.web3.Keypair.generate();
+
+    const voteIx = await program.methods
+      .vote()
+      .accounts({
+        poll: pollPda,
+        candidate: candidatePda,
+        voter: doubleVoter.publicKey,
+        payer: creator.publicKey,
+      })
+      .instruction();
+    const tx = new anchor.web3.Transaction().add(voteIx, voteIx);
+
+    try {
+      await provider.sendAndConfirm(tx, [doubleVoter]);
+      assert.fail("Two votes for the same poll should be rejected.");
+    } catch (error) {
+      assert.include(error.message + (error.logs ?? []).join("\n"), "DuplicateVote");
+    }
+  });
+
+  it("Freezes the candidate list once the poll is active", async () => {
+    const [pollPda] = await getPollPda(pollId);
+
+    try {
+      await program.methods
+        .initializeCandidate("Dave", "Purple", creator.publicKey, "")
+        .accounts({ poll: pollPda, candidate: (await getCandidatePda(pollPda, 1))[0], creator: creator.publicKey })
+        .rpc();
+      assert.fail("Candidates should not be added to an active poll.");
+    } catch (error) {
+      assert.include(error.message, "PollNotDraft");
+    }
+
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.deepEqual(pollAccount.status, { active: {} });
+    assert.equal(pollAccount.candidateCount.toNumber(), 1);
+  });
+
+  const payoutWallet = anchor.web3.Keypair.generate();
+  const bounty = new anchor.BN(20_000_000);
+
+  it("Forfeits a candidate deposit below the refund threshold", async () => {
+    const depositPollId = new anchor.BN(2);
+    const deposit = new anchor.BN(5_000_000);
+    const now = Math.floor(Date.now() / 1000);
+    const [pollPda] = await getPollPda(depositPollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+
+    await program.methods
+      .initializePoll(
+        depositPollId,
+        "Short poll",
+        "Ends almost immediately.",
+        new anchor.BN(now - 10),
+        new anchor.BN(now + 2),
+        {
+          ...defaultPollOptions(),
+          candidateDepositLamports: deposit,
+          depositRefundThreshold: new anchor.BN(1),
+          bountyLamports: bounty,
+        }
+      )
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+
+    await program.methods
+      .initializeCandidate("Bob", "Green", payoutWallet.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+      .rpc();
+
+    const candidateAccount = await program.account.candidate.fetch(candidatePda);
+    assert.equal(candidateAccount.deposit.toString(), deposit.toString());
+
+    // Wait for the poll to end before finalizing
+    await sleep(4000);
+    await program.methods
+      .finalizePoll()
+      .accounts({ poll: pollPda })
+      .remainingAccounts([{ pubkey: candidatePda, isSigner: false, isWritable: true }])
+      .rpc();
+
+    const before = await program.account.treasury.fetch(treasuryPda);
+    await program.methods
+      .claimDeposit()
+      .accounts({ poll: pollPda, candidate: candidatePda, depositor: creator.publicKey })
+      .rpc();
+
+    // Bob received no votes, so the deposit goes to the treasury
+    const after = await program.account.treasury.fetch(treasuryPda);
+    assert.equal(after.totalCollected.sub(before.totalCollected).toString(), deposit.toString());
+  });
+
+  it("Returns the bounty to the creator when nobody voted", async () => {
+    const [pollPda] = await getPollPda(new anchor.BN(2));
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+
+    // Bob is the only candidate but received no votes, so the poll has no winner
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.ok(pollAccount.winner.equals(anchor.web3.PublicKey.default));
+    assert.deepEqual(pollAccount.status, { closed: {} });
+    const candidateAccount = await program.account.candidate.fetch(candidatePda);
+    assert.isFalse(candidateAccount.isWinner);
+
+    try {
+      await program.methods
+        .claimBounty()
+        .accounts({ poll: pollPda, candidate: candidatePda, recipient: payoutWallet.publicKey })
+        .rpc();
+      assert.fail("A candidate without votes should not receive the bounty.");
+    } catch (error) {
+      assert.include(error.message, "InvalidBountyRecipient");
+    }
+
+    const before = await provider.connection.getAccountInfo(pollPda);
+    await program.methods
+      .claimBounty()
+      .accounts({ poll: pollPda, candidate: null, recipient: creator.publicKey })
+      .rpc();
+    const after = await provider.connection.getAccountInfo(pollPda);
+    assert.equal(before.lamports - after.lamports, bounty.toNumber());
+  });
+
+  it("Refuses to finalize a tie without a tie-break", async () => {
+    const tiePollId = new anchor.BN(28);
+    const secondVoter = anchor.web3.Keypair.generate();
+    const now = Math.floor(Date.now() / 1000);
+    const [pollPda] = await getPollPda(tiePollId);
+    const [firstPda] = await getCandidatePda(pollPda, 0);
+    const [secondPda] = await getCandidatePda(pollPda, 1);
+
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(secondVoter.publicKey, 1_000_000_000)
+    );
+    await program.methods
+      .initializePoll(tiePollId, "Tied poll", "Ends level.", new anchor.BN(now - 10), new anchor.BN(now + 6), defaultPollOptions())
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    for (const [index, name] of ["Gus", "Hal"].entries()) {
+      const [candidatePda] = await getCandidatePda(pollPda, index);
+      await program.methods
+        .initializeCandidate(name, "", creator.publicKey, "")
+        .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+        .rpc();
+    }
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+    for (const [ballot, candidatePda] of [[voter, firstPda], [secondVoter, secondPda]] as const) {
+      await program.methods
+        .vote()
+        .accounts({ poll: pollPda, candidate: candidatePda, voter: ballot.publicKey, payer: ballot.publicKey })
+        .signers([ballot])
+        .rpc();
+    }
+
+    // Neither the account order nor the caller may settle the tie
+    await sleep(7000);
+    try {
+      await program.methods
+        .finalizePoll()
+        .accounts({ poll: pollPda })
+        .remainingAccounts([
+          { pubkey: secondPda, isSigner: false, isWritable: true },
+          { pubkey: firstPda, isSigner: false, isWritable: true },
+        ])
+        .rpc();
+      assert.fail("A tie without a tie-break should not finalize.");
+    } catch (error) {
+      assert.include(error.message, "TieBreakRequired");
+    }
+
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.isFalse(pollAccount.finalized);
+    assert.isFalse((await program.account.candidate.fetch(firstPda)).isWinner);
+  });
+
+  it("Records auditor attestations of a finalized result", async () => {
+    const auditedPollId = new anchor.BN(23);
+    const [pollPda] = await getPollPda(auditedPollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const now = Math.floor(Date.now() / 1000);
+    const auditor = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .initializePoll(auditedPollId, "Audited poll", "Checked by an auditor.", new anchor.BN(now - 10), new anchor.BN(now + 2), defaultPollOptions())
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .initializeCandidate("Carol", "", creator.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .registerAuditors([auditor.publicKey])
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    await sleep(4000);
+    await program.methods
+      .finalizePoll()
+      .accounts({ poll: pollPda })
+      .remainingAccounts([{ pubkey: candidatePda, isSigner: false, isWritable: true }])
+      .rpc();
+
+    // Nobody voted, so the attested result has no winner
+    try {
+      await program.methods
+        .attestResult(anchor.web3.PublicKey.default, new anchor.BN(99))
+        .accounts({ poll: pollPda, auditor: auditor.publicKey })
+        .signers([auditor])
+        .rpc();
+      assert.fail("An attestation of the wrong total should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "ResultMismatch");
+    }
+    await program.methods
+      .attestResult(anchor.web3.PublicKey.default, new anchor.BN(0))
+      .accounts({ poll: pollPda, auditor: auditor.publicKey })
+      .signers([auditor])
+      .rpc();
+
+    const [resultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("poll_result"), pollPda.toBuffer()],
+      program.programId
+    );
+    const result = await program.account.pollResult.fetch(resultPda);
+    assert.equal(result.attestationCount, 1);
+    assert.equal(result.attestations, 1);
+  });
+
+  it("Only schedules finalization threads for active polls", async () => {
+    const threadProgram = new anchor.web3.PublicKey("CLoCKyJ6DXBJqqu2VWx9RLbgnwwR6BMHHuyasVmfMzBh");
+    const [pollPda] = await getPollPda(new anchor.BN(2));
+    const [threadPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("thread"), pollPda.toBuffer(), Buffer.from("finalize")],
+      threadProgram
+    );
+
+    try {
+      await program.methods
+        .scheduleFinalization(new anchor.BN(1_000_000))
+        .accounts({ poll: pollPda, creator: creator.publicKey, thread: threadPda, threadProgram })
+        .remainingAccounts([{ pubkey: (await getCandidatePda(pollPda, 0))[0], isSigner: false, isWritable: false }])
+        .rpc();
+      assert.fail("A closed poll should not get a finalization thread.");
+    } catch (error) {
+      assert.include(error.message, "PollNotActive");
+    }
+
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.isTrue(pollAccount.finalizeThread.equals(anchor.web3.PublicKey.default));
+  });
+
+  it("Only accepts Switchboard randomness accounts", async () => {
+    const [pollPda] = await getPollPda(new anchor.BN(2));
+
+    try {
+      await program.methods
+        .requestRandomness()
+        .accounts({ poll: pollPda, requester: creator.publicKey, randomness: payoutWallet.publicKey })
+        .rpc();
+      assert.fail("A wallet should not be accepted as a randomness account.");
+    } catch (error) {
+      assert.include(error.message, "InvalidRandomness");
+    }
+
+    // With no request pending there is nothing to cancel
+    try {
+      await program.methods
+        .cancelRandomness()
+        .accounts({ poll: pollPda, randomness: anchor.web3.PublicKey.default })
+        .rpc();
+      assert.fail("Cancelling without a pending request should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "InvalidRandomness");
+    }
+
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.isFalse(pollAccount.randomnessPending);
+    assert.deepEqual(pollAccount.tieBreakSeed, new Array(32).fill(0));
+  });
+
+  it("Votes in every race of a ballot at once", async () => {
+    const ballotId = new anchor.BN(1);
+    const raceIds = [new anchor.BN(3), new anchor.BN(4)];
+    const now = Math.floor(Date.now() / 1000);
+    const raceAccounts = [];
+
+    for (const raceId of raceIds) {
+      const [pollPda] = await getPollPda(raceId);
+      await program.methods
+        .initializePoll(
+          raceId,
+          `Race ${raceId.toString()}`,
+          "Part of a ballot.",
+          new anchor.BN(now - 10),
+          new anchor.BN(now + 3600),
+          defaultPollOptions()
+        )
+        .accounts({ creator: creator.publicKey })
+        .rpc();
+      await program.methods
+        .initializeCandidate("Carol", "Red", creator.publicKey, "")
+        .accounts({ poll: pollPda, candidate: (await getCandidatePda(pollPda, 0))[0], creator: creator.publicKey })
+        .rpc();
+      await program.methods
+        .activatePoll()
+        .accounts({ poll: pollPda, creator: creator.publicKey })
+        .rpc();
+      raceAccounts.push(pollPda);
+    }
+
+    await program.methods
+      .initializeBallot(ballotId, "General election")
+      .accounts({ creator: creator.publicKey })
+      .remainingAccounts(raceAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+      .rpc();
+
+    const [ballotPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("ballot"), ballotId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const choices = [];
+    for (const pollPda of raceAccounts) {
+      const [candidatePda] = await getCandidatePda(pollPda, 0);
+      const [receiptPda] = await getReceiptPda(pollPda, voter.publicKey);
+      choices.push(
+        { pubkey: pollPda, isSigner: false, isWritable: true },
+        { pubkey: candidatePda, isSigner: false, isWritable: true },
+        { pubkey: receiptPda, isSigner: false, isWritable: true }
+      );
+    }
+
+    // Lamports sent to a receipt address ahead of time must not block the vote
+    const [firstReceiptPda] = await getReceiptPda(raceAccounts[0], voter.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: creator.publicKey,
+          toPubkey: firstReceiptPda,
+          lamports: 1_000_000,
+        })
+      )
+    );
+
+    await program.methods
+      .voteBallot()
+      .accounts({ ballot: ballotPda, voter: voter.publicKey })
+      .remainingAccounts(choices)
+      .signers([voter])
+      .rpc();
+
+    for (const pollPda of raceAccounts) {
+      const pollAccount = await program.account.poll.fetch(pollPda);
+      assert.equal(pollAccount.totalVotes.toNumber(), 1);
+      const [receiptPda] = await getReceiptPda(pollPda, voter.publicKey);
+      const receiptAccount = await program.account.voterReceipt.fetch(receiptPda);
+      assert.isTrue(receiptAccount.hasVoted);
+    }
+  });
+
+  it("Casts votes in several polls with one transaction", async () => {
+    const pollIds = [new anchor.BN(33), new anchor.BN(34)];
+    const now = Math.floor(Date.now() / 1000);
+    const polls = [];
+
+    for (const id of pollIds) {
+      const [pollPda] = await getPollPda(id);
+      const [candidatePda] = await getCandidatePda(pollPda, 0);
+      await program.methods
+        .initializePoll(id, `Question ${id.toString()}`, "Voted on together.", new anchor.BN(now - 10), new anchor.BN(now + 3600), defaultPollOptions())
+        .accounts({ creator: creator.publicKey })
+        .rpc();
+      await program.methods
+        .initializeCandidate("Lena", "", creator.publicKey, "")
+        .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+        .rpc();
+      await program.methods
+        .activatePoll()
+        .accounts({ poll: pollPda, creator: creator.publicKey })
+        .rpc();
+      polls.push({ pollPda, candidatePda });
+    }
+
+    // One `vote` instruction per poll in the same transaction, as `vote-all` sends them
+    const vote = ({ pollPda, candidatePda }) =>
+      program.methods
+        .vote()
+        .accounts({ poll: pollPda, candidate: candidatePda, voter: voter.publicKey, payer: voter.publicKey });
+    const voteAll = async () =>
+      vote(polls[1])
+        .preInstructions([await vote(polls[0]).instruction()])
+        .signers([voter])
+        .rpc();
+    await voteAll();
+
+    for (const { pollPda, candidatePda } of polls) {
+      assert.equal((await program.account.candidate.fetch(candidatePda)).votes.toNumber(), 1);
+      assert.isTrue((await program.account.voterReceipt.fetch((await getReceiptPda(pollPda, voter.publicKey))[0])).hasVoted);
+    }
+
+    // The votes land together or not at all
+    try {
+      await voteAll();
+      assert.fail("Voting twice should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "already in use");
+    }
+    for (const { candidatePda } of polls) {
+      assert.equal((await program.account.candidate.fetch(candidatePda)).votes.toNumber(), 1);
+    }
+  });
+
+  it("Emits tally events as self-CPI data rather than logs", async () => {
+    const eventPollId = new anchor.BN(35);
+    const [pollPda] = await getPollPda(eventPollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const now = Math.floor(Date.now() / 1000);
+    // Anchor's EVENT_IX_TAG, which prefixes every emit_cpi! instruction
+    const eventIxTag = Buffer.from("e445a52e51cb9a1d", "hex");
+
+    await program.methods
+      .initializePoll(eventPollId, "Indexed poll", "Read from inner instructions.", new anchor.BN(now - 10), new anchor.BN(now + 3600), defaultPollOptions())
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .initializeCandidate("Milo", "", creator.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+    const signature = await program.methods
+      .vote()
+      .accounts({ poll: pollPda, candidate: candidatePda, voter: voter.publicKey, payer: voter.publicKey })
+      .signers([voter])
+      .rpc({ commitment: "confirmed" });
+
+    const transaction = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const accountKeys = transaction.transaction.message.getAccountKeys();
+    const events = transaction.meta.innerInstructions
+      .flatMap((inner) => inner.instructions)
+      .filter((instruction) => accountKeys.get(instruction.programIdIndex).equals(program.programId))
+      .map((instruction) => Buffer.from(anchor.utils.bytes.bs58.decode(instruction.data)))
+      .filter((data) => data.subarray(0, 8).equals(eventIxTag))
+      .map((data) => program.coder.events.decode(data.subarray(8).toString("base64")));
+
+    assert.equal(events.length, 1);
+    assert.ok(events[0].data.poll.equals(pollPda));
+    assert.ok(events[0].data.candidate.equals(candidatePda));
+    assert.equal(events[0].data.votes.toNumber(), 1);
+    assert.isFalse(transaction.meta.logMessages.some((log) => log.startsWith("Program data:")));
+  });
+
+  it("Votes through a session key until it is revoked", async () => {
+    const ballotId = new anchor.BN(1);
+    const [ballotPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("ballot"), ballotId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const sessionVoter = anchor.web3.Keypair.generate();
+    const sessionKey = anchor.web3.Keypair.generate();
+    for (const wallet of [sessionVoter, sessionKey]) {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(wallet.publicKey, 1_000_000_000)
+      );
+    }
+    const [sessionPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("session"), ballotPda.toBuffer(), sessionVoter.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const slot = await provider.connection.getSlot();
+    await program.methods
+      .createSession(sessionKey.publicKey, new anchor.BN(slot + 1000))
+      .accounts({ ballot: ballotPda, voter: sessionVoter.publicKey })
+      .signers([sessionVoter])
+      .rpc();
+
+    // The session key signs alone; the receipt still belongs to the voter
+    const [firstRace, secondRace] = [(await getPollPda(new anchor.BN(3)))[0], (await getPollPda(new anchor.BN(4)))[0]];
+    await program.methods
+      .voteWithSession()
+      .accounts({
+        poll: firstRace,
+        candidate: (await getCandidatePda(firstRace, 0))[0],
+        ballot: ballotPda,
+        session: sessionPda,
+        voter: sessionVoter.publicKey,
+        sessionKey: sessionKey.publicKey,
+      })
+      .signers([sessionKey])
+      .rpc();
+
+    const [receiptPda] = await getReceiptPda(firstRace, sessionVoter.publicKey);
+    const receiptAccount = await program.account.voterReceipt.fetch(receiptPda);
+    assert.equal(receiptAccount.voter.toBase58(), sessionVoter.publicKey.toBase58());
+    assert.isTrue(receiptAccount.hasVoted);
+
+    await program.methods
+      .revokeSession()
+      .accounts({ session: sessionPda, voter: sessionVoter.publicKey })
+      .signers([sessionVoter])
+      .rpc();
+
+    try {
+      await program.methods
+        .voteWithSession()
+        .accounts({
+          poll: secondRace,
+          candidate: (await getCandidatePda(secondRace, 0))[0],
+          ballot: ballotPda,
+          session: sessionPda,
+          voter: sessionVoter.publicKey,
+          sessionKey: sessionKey.publicKey,
+        })
+        .signers([sessionKey])
+        .rpc();
+      assert.fail("A revoked session key should not be able to vote.");
+    } catch (error) {
+      assert.include(error.message, "AccountNotInitialized");
+    }
+  });
+
+  it("Splits gauge points across candidates", async () => {
+    const gaugePollId = new anchor.BN(12);
+    const [pollPda] = await getPollPda(gaugePollId);
+    const now = Math.floor(Date.now() / 1000);
+
+    await program.methods
+      .initializePoll(gaugePollId, "Q3 priorities", "Split 100 points.", new anchor.BN(now - 10), new anchor.BN(now + 3600), {
+        ...defaultPollOptions(),
+        gaugeBudget: 100,
+      })
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    const candidates = [];
+    for (const [index, name] of ["Docs", "Tooling"].entries()) {
+      const [candidatePda] = await getCandidatePda(pollPda, index);
+      await program.methods
+        .initializeCandidate(name, "Team", creator.publicKey, "")
+        .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+        .rpc();
+      candidates.push(candidatePda);
+    }
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    await program.methods
+      .voteGauge([60, 40])
+      .accounts({ poll: pollPda, voter: voter.publicKey })
+      .remainingAccounts(candidates.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .signers([voter])
+      .rpc();
+
+    const docs = await program.account.candidate.fetch(candidates[0]);
+    const tooling = await program.account.candidate.fetch(candidates[1]);
+    assert.equal(docs.votes.toNumber(), 60);
+    assert.equal(tooling.votes.toNumber(), 40);
+
+    const [receiptPda] = await getReceiptPda(pollPda, voter.publicKey);
+    const receiptAccount = await program.account.voterReceipt.fetch(receiptPda);
+    assert.equal(receiptAccount.weight.toNumber(), 100);
+    assert.deepEqual(
+      receiptAccount.allocations.map((a) => a.points),
+      [60, 40]
+    );
+  });
+
+  it("Lets a candidate manager register candidates and nothing else", async () => {
+    const managedPollId = new anchor.BN(13);
+    const [pollPda] = await getPollPda(managedPollId);
+    const now = Math.floor(Date.now() / 1000);
+
+    await program.methods
+      .initializePoll(managedPollId, "Board seats", "Managed slate.", new anchor.BN(now - 10), new anchor.BN(now + 3600), defaultPollOptions())
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .setCandidateManager(voter.publicKey)
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    await program.methods
+      .initializeCandidate("Carol", "Green", voter.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: voter.publicKey })
+      .signers([voter])
+      .rpc();
+    const candidateAccount = await program.account.candidate.fetch(candidatePda);
+    assert.ok(candidateAccount.depositor.equals(voter.publicKey));
+
+    try {
+      await program.methods
+        .activatePoll()
+        .accounts({ poll: pollPda, creator: voter.publicKey })
+        .signers([voter])
+        .rpc();
+      assert.fail("The candidate manager should not be able to activate the poll.");
+    } catch (error) {
+      assert.include(error.message, "Unauthorized");
+    }
+  });
+
+  it("Records an unlisted poll's visibility", async () => {
+    const unlistedPollId = new anchor.BN(17);
+    const [pollPda] = await getPollPda(unlistedPollId);
+    const now = Math.floor(Date.now() / 1000);
+
+    await program.methods
+      .initializePoll(unlistedPollId, "Offsite location", "Team only.", new anchor.BN(now - 10), new anchor.BN(now + 3600), {
+        ...defaultPollOptions(),
+        visibility: { unlisted: {} },
+      })
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.deepEqual(pollAccount.visibility, { unlisted: {} });
+  });
+
+  it("Only counts votes from voters granted access", async () => {
+    const restrictedPollId = new anchor.BN(18);
+    const [pollPda] = await getPollPda(restrictedPollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const now = Math.floor(Date.now() / 1000);
+
+    await program.methods
+      .initializePoll(restrictedPollId, "Budget sign-off", "Leads only.", new anchor.BN(now - 10), new anchor.BN(now + 3600), {
+        ...defaultPollOptions(),
+        accessRestricted: true,
+      })
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .initializeCandidate("Approve", "", creator.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    try {
+      await program.methods
+        .vote()
+        .accounts({ poll: pollPda, candidate: candidatePda, voter: voter.publicKey, payer: voter.publicKey })
+        .signers([voter])
+        .rpc();
+      assert.fail("A voter without a grant should not be able to vote.");
+    } catch (error) {
+      assert.include(error.message, "AccessNotGranted");
+    }
+
+    const [accessGrantPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("access_grant"), pollPda.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .grantAccess(voter.publicKey)
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .vote()
+      .accounts({
+        poll: pollPda,
+        candidate: candidatePda,
+        voter: voter.publicKey,
+        payer: voter.publicKey,
+        accessGrant: accessGrantPda,
+      })
+      .signers([voter])
+      .rpc();
+
+    const candidateAccount = await program.account.candidate.fetch(candidatePda);
+    assert.equal(candidateAccount.votes.toNumber(), 1);
+    // The grantee is the poll's whole electorate
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.equal(pollAccount.eligibleVoters.toNumber(), 1);
+  });
+
+  it("Weighs votes by the oracle's published weight", async () => {
+    const oraclePollId = new anchor.BN(19);
+    const [pollPda] = await getPollPda(oraclePollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const now = Math.floor(Date.now() / 1000);
+    const oracle = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(oracle.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+
+    await program.methods
+      .initializePoll(oraclePollId, "Grant round", "Reputation-weighted.", new anchor.BN(now - 10), new anchor.BN(now + 3600), {
+        ...defaultPollOptions(),
+        weightOracle: oracle.publicKey,
+        maxWeightAge: new anchor.BN(600),
+      })
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .initializeCandidate("Indexer", "Infra", creator.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    await program.methods
+      .publishWeight(voter.publicKey, new anchor.BN(42))
+      .accounts({ oracle: oracle.publicKey })
+      .signers([oracle])
+      .rpc();
+    const [weightRecordPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("weight_record"), oracle.publicKey.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .vote()
+      .accounts({
+        poll: pollPda,
+        candidate: candidatePda,
+        voter: voter.publicKey,
+        payer: voter.publicKey,
+        weightRecord: weightRecordPda,
+      })
+      .signers([voter])
+      .rpc();
+
+    const candidateAccount = await program.account.candidate.fetch(candidatePda);
+    assert.equal(candidateAccount.votes.toNumber(), 42);
+    assert.equal(candidateAccount.rawVotes.toNumber(), 1);
+  });
+
+  it("Charges the vote fee to the payer", async () => {
+    const feePollId = new anchor.BN(20);
+    const [pollPda] = await getPollPda(feePollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const now = Math.floor(Date.now() / 1000);
+    const charity = anchor.web3.Keypair.generate();
+    const fee = anchor.web3.LAMPORTS_PER_SOL / 100;
+
+    await program.methods
+      .initializePoll(feePollId, "Charity pick", "Every vote donates.", new anchor.BN(now - 10), new anchor.BN(now + 3600), {
+        ...defaultPollOptions(),
+        voteFee: new anchor.BN(fee),
+        voteFeeRecipient: charity.publicKey,
+      })
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .initializeCandidate("Food bank", "", creator.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    await program.methods
+      .vote()
+      .accounts({
+        poll: pollPda,
+        candidate: candidatePda,
+        voter: voter.publicKey,
+        payer: voter.publicKey,
+        feeRecipient: charity.publicKey,
+      })
+      .signers([voter])
+      .rpc();
+
+    assert.equal(await provider.connection.getBalance(charity.publicKey), fee);
+  });
+
+  it("Registers a batch of candidates in one transaction", async () => {
+    const batchPollId = new anchor.BN(16);
+    const [pollPda] = await getPollPda(batchPollId);
+    const now = Math.floor(Date.now() / 1000);
+
+    await program.methods
+      .initializePoll(batchPollId, "City council", "Large ballot.", new anchor.BN(now - 10), new anchor.BN(now + 3600), defaultPollOptions())
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+
+    const entries = [
+      { name: "Erin", party: "Gold" },
+      { name: "Frank", party: "Silver" },
+      { name: "Grace", party: "" },
+    ];
+    const candidates = await Promise.all(entries.map(async (_, index) => (await getCandidatePda(pollPda, index))[0]));
+    await program.methods
+      .initializeCandidatesBatch(entries)
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .remainingAccounts(candidates.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .rpc();
+
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.equal(pollAccount.candidateCount.toNumber(), 3);
+    const frank = await program.account.candidate.fetch(candidates[1]);
+    assert.equal(frank.name, "Frank");
+    assert.equal(frank.index, 1);
+    assert.ok(frank.payoutWallet.equals(creator.publicKey));
+  });
+
+  it("Registers batch candidates at addresses funded ahead of time", async () => {
+    const batchPollId = new anchor.BN(30);
+    const [pollPda] = await getPollPda(batchPollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const now = Math.floor(Date.now() / 1000);
+
+    await program.methods
+      .initializePoll(batchPollId, "Prefunded", "Someone sent lamports first.", new anchor.BN(now - 10), new anchor.BN(now + 3600), defaultPollOptions())
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: voter.publicKey,
+          toPubkey: candidatePda,
+          lamports: 1_000_000,
+        })
+      ),
+      [voter]
+    );
+
+    await program.methods
+      .initializeCandidatesBatch([{ name: "Ida", party: "" }])
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .remainingAccounts([{ pubkey: candidatePda, isSigner: false, isWritable: true }])
+      .rpc();
+
+    const candidateAccount = await program.account.candidate.fetch(candidatePda);
+    assert.equal(candidateAccount.name, "Ida");
+    assert.equal(candidateAccount.index, 0);
+  });
+
+  it("Grows the poll account for a longer description", async () => {
+    const draftPollId = new anchor.BN(5);
+    const now = Math.floor(Date.now() / 1000);
+    const [pollPda] = await getPollPda(draftPollId);
+
+    await program.methods
+      .initializePoll(
+        draftPollId,
+        "Upcoming poll",
+        "Short description.",
+        new anchor.BN(now + 3600),
+        new anchor.BN(now + 7200),
+        defaultPollOptions()
+      )
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    const before = await provider.connection.getAccountInfo(pollPda);
+
+    const longDescription = "x".repeat(500);
+    await program.methods
+      .updatePoll(null, longDescription, null)
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    const after = await provider.connection.getAccountInfo(pollPda);
+    assert.equal(after.data.length - before.data.length, 500 - 280);
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.equal(pollAccount.description, longDescription);
+  });
+
+  it("Keeps the escrowed bounty when the description shrinks", async () => {
+    const shrinkPollId = new anchor.BN(26);
+    const shrinkPayout = anchor.web3.Keypair.generate();
+    const now = Math.floor(Date.now() / 1000);
+    const [pollPda] = await getPollPda(shrinkPollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+
+    await program.methods
+      .initializePoll(shrinkPollId, "Bountied poll", "Short description.", new anchor.BN(now - 10), new anchor.BN(now + 8), {
+        ...defaultPollOptions(),
+        bountyLamports: bounty,
+      })
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    const before = await provider.connection.getAccountInfo(pollPda);
+
+    // Growing and then shrinking the description must not refund the bounty to the creator
+    await program.methods
+      .updatePoll(null, "x".repeat(500), null)
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .updatePoll(null, "Short again.", null)
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+    const after = await provider.connection.getAccountInfo(pollPda);
+    assert.equal(after.data.length - before.data.length, 500 - 280);
+    assert.isAtLeast(after.lamports, before.lamports);
+
+    await program.methods
+      .initializeCandidate("Erin", "", shrinkPayout.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .vote()
+      .accounts({ poll: pollPda, candidate: candidatePda, voter: voter.publicKey, payer: voter.publicKey })
+      .signers([voter])
+      .rpc();
+
+    // Wait for the poll to end before finalizing
+    await sleep(9000);
+    await program.methods
+      .finalizePoll()
+      .accounts({ poll: pollPda })
+      .remainingAccounts([{ pubkey: candidatePda, isSigner: false, isWritable: true }])
+      .rpc();
+    await program.methods
+      .claimBounty()
+      .accounts({ poll: pollPda, candidate: candidatePda, recipient: shrinkPayout.publicKey })
+      .rpc();
+
+    const balance = await provider.connection.getBalance(shrinkPayout.publicKey);
+    assert.equal(balance, bounty.toNumber());
+  });
+
+  it("Takes only compressed votes once a receipt tree is attached", async () => {
+    const treePollId = new anchor.BN(27);
+    const tree = anchor.web3.Keypair.generate();
+    const now = Math.floor(Date.now() / 1000);
+    const [pollPda] = await getPollPda(treePollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const [registrationPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("compressed_voter"), pollPda.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    const compressionProgram = new anchor.web3.PublicKey("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+    await program.methods
+      .initializePoll(treePollId, "Compressed", "Receipts live in a tree.", new anchor.BN(now - 10), new anchor.BN(now + 3600), defaultPollOptions())
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .initializeCandidate("Fay", "", creator.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+      .rpc();
+
+    // A depth 3 tree with an 8 entry change log and no canopy
+    const maxDepth = 3;
+    const space = 56 + 24 + 9 * (32 * maxDepth + 40);
+    await program.methods
+      .initReceiptTree(maxDepth, 8)
+      .accounts({ poll: pollPda, creator: creator.publicKey, tree: { merkleTree: tree.publicKey } })
+      .preInstructions([
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: creator.publicKey,
+          newAccountPubkey: tree.publicKey,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(space),
+          space,
+          programId: compressionProgram,
+        }),
+      ])
+      .signers([tree])
+      .rpc();
+    await program.methods
+      .registerCompressedVoter(voter.publicKey)
+      .accounts({ poll: pollPda, creator: creator.publicKey, tree: { merkleTree: tree.publicKey } })
+      .rpc();
+
+    // A second registration would give the voter a second unvoted leaf
+    try {
+      await program.methods
+        .registerCompressedVoter(voter.publicKey)
+        .accounts({ poll: pollPda, creator: creator.publicKey, tree: { merkleTree: tree.publicKey } })
+        .rpc();
+      assert.fail("Registering a voter twice should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "already in use");
+    }
+    const registration = await program.account.compressedVoter.fetch(registrationPda);
+    assert.equal(registration.index, 0);
+
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    // The receipt path would let a registered voter vote a second time
+    try {
+      await program.methods
+        .vote()
+        .accounts({ poll: pollPda, candidate: candidatePda, voter: voter.publicKey, payer: voter.publicKey })
+        .signers([voter])
+        .rpc();
+      assert.fail("A receipt vote on a tree poll should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "PollHasReceiptTree");
+    }
+
+    // The voter's unvoted leaf is the only one, at index 0, next to empty subtrees
+    const hash = (...parts: Uint8Array[]) => Buffer.from(keccak_256(Buffer.concat(parts)));
+    const emptyNodes = [Buffer.alloc(32)];
+    for (let level = 1; level < maxDepth; level++) {
+      emptyNodes.push(hash(emptyNodes[level - 1], emptyNodes[level - 1]));
+    }
+    const unvoted = hash(pollPda.toBuffer(), voter.publicKey.toBuffer(), anchor.web3.PublicKey.default.toBuffer());
+    const rootOver = (leaf: Buffer) => emptyNodes.reduce((node, empty) => hash(node, empty), leaf);
+    const proof = emptyNodes.map((node) => ({
+      pubkey: new anchor.web3.PublicKey(node),
+      isSigner: false,
+      isWritable: false,
+    }));
+    const voteCompressed = (root: Buffer) =>
+      program.methods
+        .voteCompressed(Array.from(root), 0)
+        .accounts({ poll: pollPda, candidate: candidatePda, voter: voter.publicKey, tree: { merkleTree: tree.publicKey } })
+        .remainingAccounts(proof)
+        .signers([voter])
+        .rpc();
+
+    await voteCompressed(rootOver(unvoted));
+
+    // The unvoted leaf is gone, so a second compressed vote has nothing to replace
+    const voted = hash(pollPda.toBuffer(), voter.publicKey.toBuffer(), candidatePda.toBuffer());
+    let rejected = false;
+    try {
+      await voteCompressed(rootOver(voted));
+    } catch {
+      rejected = true;
+    }
+    assert.isTrue(rejected, "A second compressed vote should be rejected.");
+
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.equal(pollAccount.totalVotes.toNumber(), 1);
+    assert.equal(pollAccount.compressedVoterCount, 1);
+  });
+
+  it("Shares the reward pool among receipt votes only", async () => {
+    const rewardPollId = new anchor.BN(29);
+    const now = Math.floor(Date.now() / 1000);
+    const [pollPda] = await getPollPda(rewardPollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const [treePollPda] = await getPollPda(new anchor.BN(27));
+    const payer = creator.payer;
+
+    const mint = await createMint(provider.connection, payer, creator.publicKey, null, 0);
+    const creatorTokenAccount = await createAssociatedTokenAccount(provider.connection, payer, mint, creator.publicKey);
+    await mintTo(provider.connection, payer, mint, creatorTokenAccount, payer, 20);
+
+    // The receipt-tree poll keeps no receipts to claim with
+    try {
+      await program.methods
+        .fundRewardPool(new anchor.BN(10))
+        .accounts({ poll: treePollPda, mint, creatorTokenAccount, creator: creator.publicKey })
+        .rpc();
+      assert.fail("A receipt-tree poll should not take a reward pool.");
+    } catch (error) {
+      assert.include(error.message, "RewardsNeedReceipts");
+    }
+
+    await program.methods
+      .initializePoll(rewardPollId, "Rewarded", "Voters share ten tokens.", new anchor.BN(now - 10), new anchor.BN(now + 8), defaultPollOptions())
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .initializeCandidate("Kim", "", creator.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+      .rpc();
+
+    // Funding twice tops up the same vault
+    for (const amount of [6, 4]) {
+      await program.methods
+        .fundRewardPool(new anchor.BN(amount))
+        .accounts({ poll: pollPda, mint, creatorTokenAccount, creator: creator.publicKey })
+        .rpc();
+    }
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    const secondVoter = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(secondVoter.publicKey, 1_000_000_000)
+    );
+    for (const ballot of [voter, secondVoter]) {
+      await program.methods
+        .vote()
+        .accounts({ poll: pollPda, candidate: candidatePda, voter: ballot.publicKey, payer: ballot.publicKey })
+        .signers([ballot])
+        .rpc();
+    }
+
+    await sleep(9000);
+    await program.methods
+      .finalizePoll()
+      .accounts({ poll: pollPda })
+      .remainingAccounts([{ pubkey: candidatePda, isSigner: false, isWritable: true }])
+      .rpc();
+
+    const voterTokenAccount = await createAssociatedTokenAccount(provider.connection, payer, mint, voter.publicKey);
+    await program.methods
+      .claimReward()
+      .accounts({ poll: pollPda, voterTokenAccount, voter: voter.publicKey })
+      .signers([voter])
+      .rpc();
+    assert.equal(Number((await getAccount(provider.connection, voterTokenAccount)).amount), 5);
+
+    try {
+      await program.methods
+        .claimReward()
+        .accounts({ poll: pollPda, voterTokenAccount, voter: voter.publicKey })
+        .signers([voter])
+        .rpc();
+      assert.fail("A receipt should claim its share only once.");
+    } catch (error) {
+      assert.include(error.message, "RewardAlreadyClaimed");
+    }
+  });
+
+  it("Only extends the end time within the window once votes are in", async () => {
+    const extendPollId = new anchor.BN(24);
+    const [pollPda] = await getPollPda(extendPollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const now = Math.floor(Date.now() / 1000);
+    const end = now + 3600;
+
+    await program.methods
+      .initializePoll(extendPollId, "Extendable", "Can run a little longer.", new anchor.BN(now - 10), new anchor.BN(end), {
+        ...defaultPollOptions(),
+        maxExtension: new anchor.BN(600),
+      })
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .initializeCandidate("Dave", "", creator.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+
+    // Before any vote the end time moves freely, even earlier
+    await program.methods
+      .updatePoll(null, null, new anchor.BN(end - 600))
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .vote()
+      .accounts({ poll: pollPda, candidate: candidatePda, voter: voter.publicKey, payer: voter.publicKey })
+      .signers([voter])
+      .rpc();
+
+    try {
+      await program.methods
+        .updatePoll(null, null, new anchor.BN(end - 1200))
+        .accounts({ poll: pollPda, creator: creator.publicKey })
+        .rpc();
+      assert.fail("Shortening a poll with votes should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "EndTimeLocked");
+    }
+    await program.methods
+      .updatePoll(null, null, new anchor.BN(end))
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+    try {
+      await program.methods
+        .updatePoll(null, null, new anchor.BN(end + 1))
+        .accounts({ poll: pollPda, creator: creator.publicKey })
+        .rpc();
+      assert.fail("Extending past the window should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "ExtensionTooLong");
+    }
+
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.equal(pollAccount.endTime.toNumber(), end);
+    assert.equal(pollAccount.extendedBy.toNumber(), 600);
+  });
+
+  it("Executes a passed proposal's instruction", async () => {
+    const proposalPollId = new anchor.BN(6);
+    const now = Math.floor(Date.now() / 1000);
+    const [pollPda] = await getPollPda(proposalPollId);
+    const [yesPda] = await getCandidatePda(pollPda, 0);
+    const [authorityPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("poll_authority"), pollPda.toBuffer()],
+      program.programId
+    );
+    const recipient = anchor.web3.Keypair.generate();
+    const amount = 1_000_000_000;
+
+    await program.methods
+      .initializePoll(proposalPollId, "Send the grant?", "Referendum.", new anchor.BN(now - 10), new anchor.BN(now + 3), defaultPollOptions())
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .initializeCandidate("Yes", "", creator.publicKey, "")
+      .accounts({ poll: pollPda, candidate: yesPda, creator: creator.publicKey })
+      .rpc();
+
+    // Pay the grant from the poll authority if the poll passes
+    const transfer = anchor.web3.SystemProgram.transfer({
+      fromPubkey: authorityPda,
+      toPubkey: recipient.publicKey,
+      lamports: amount,
+    });
+    await program.methods
+      .createProposal(5000, transfer.programId, transfer.keys, transfer.data)
+      .accounts({ poll: pollPda, yesCandidate: yesPda, creator: creator.publicKey })
+      .rpc();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(authorityPda, 2 * amount)
+    );
+
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .vote()
+      .accounts({ poll: pollPda, candidate: yesPda, voter: voter.publicKey, payer: voter.publicKey })
+      .signers([voter])
+      .rpc();
+
+    await sleep(5000);
+    await program.methods
+      .finalizePoll()
+      .accounts({ poll: pollPda })
+      .remainingAccounts([{ pubkey: yesPda, isSigner: false, isWritable: true }])
+      .rpc();
+
+    await program.methods
+      .executeProposal()
+      .accounts({ poll: pollPda, yesCandidate: yesPda, targetProgram: transfer.programId })
+      .remainingAccounts(transfer.keys.map((key) => ({ ...key, isSigner: false })))
+      .rpc();
+
+    const balance = await provider.connection.getBalance(recipient.publicKey);
+    assert.equal(balance, amount);
+  });
+
+  it("Mints one winner badge to the winner's payout wallet", async () => {
+    const badgePollId = new anchor.BN(32);
+    const [pollPda] = await getPollPda(badgePollId);
+    const [winnerPda] = await getCandidatePda(pollPda, 0);
+    const now = Math.floor(Date.now() / 1000);
+    const tokenMetadataProgram = new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+    const badgeAccounts = (badgeMint: anchor.web3.PublicKey) => ({
+      poll: pollPda,
+      winner: winnerPda,
+      payoutWallet: creator.publicKey,
+      badgeMint,
+      badgeTokenAccount: getAssociatedTokenAddressSync(badgeMint, creator.publicKey),
+      metadata: anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), tokenMetadataProgram.toBuffer(), badgeMint.toBuffer()],
+        tokenMetadataProgram
+      )[0],
+      masterEdition: anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), tokenMetadataProgram.toBuffer(), badgeMint.toBuffer(), Buffer.from("edition")],
+        tokenMetadataProgram
+      )[0],
+      payer: creator.publicKey,
+    });
+
+    await program.methods
+      .initializePoll(badgePollId, "Best validator?", "Winner gets a badge.", new anchor.BN(now - 10), new anchor.BN(now + 3), defaultPollOptions())
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .initializeCandidate("Kai", "", creator.publicKey, "")
+      .accounts({ poll: pollPda, candidate: winnerPda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .activatePoll()
+      .accounts({ poll: pollPda, creator: creator.publicKey })
+      .rpc();
+    await program.methods
+      .vote()
+      .accounts({ poll: pollPda, candidate: winnerPda, voter: voter.publicKey, payer: voter.publicKey })
+      .signers([voter])
+      .rpc();
+
+    await sleep(5000);
+    await program.methods
+      .finalizePoll()
+      .accounts({ poll: pollPda })
+      .remainingAccounts([{ pubkey: winnerPda, isSigner: false, isWritable: true }])
+      .rpc();
+
+    const badgeMint = anchor.web3.Keypair.generate();
+    await program.methods
+      .mintWinnerBadge("https://example.com/badge.json")
+      .accounts(badgeAccounts(badgeMint.publicKey))
+      .signers([badgeMint])
+      .rpc();
+
+    const badge = await getAccount(provider.connection, badgeAccounts(badgeMint.publicKey).badgeTokenAccount);
+    assert.equal(Number(badge.amount), 1);
+    assert.isNotNull(await provider.connection.getAccountInfo(badgeAccounts(badgeMint.publicKey).masterEdition));
+    assert.isTrue((await program.account.poll.fetch(pollPda)).winnerBadgeMinted);
+
+    try {
+      const secondMint = anchor.web3.Keypair.generate();
+      await program.methods
+        .mintWinnerBadge("https://example.com/badge.json")
+        .accounts(badgeAccounts(secondMint.publicKey))
+        .signers([secondMint])
+        .rpc();
+      assert.fail("A second badge should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "WinnerBadgeAlreadyMinted");
+    }
+  });
+
+  it("Requires council approval in council mode", async () => {
+    const councilPollId = new anchor.BN(7);
+    const question = "Adopt the charter?";
+    const now = Math.floor(Date.now() / 1000);
+    const [pollPda] = await getPollPda(councilPollId);
+    const [proposalPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("poll_proposal"), councilPollId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const initialize = () =>
+      program.methods
+        .initializePoll(councilPollId, question, "Council poll.", new anchor.BN(now), new anchor.BN(now + 3600), defaultPollOptions())
+        .accounts({ creator: creator.publicKey, pollProposal: proposalPda });
+
+    await program.methods
+      .setCouncil([voter.publicKey], 1)
+      .accounts({ admin: creator.publicKey })
+      .rpc();
+    await program.methods
+      .proposePoll(councilPollId, question)
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+
+    try {
+      await initialize().rpc();
+      assert.fail("The poll should not be created without approvals.");
+    } catch (error) {
+      assert.include(error.message, "CouncilApprovalMissing");
+    }
+
+    await program.methods
+      .approvePollCreation()
+      .accounts({ pollProposal: proposalPda, member: voter.publicKey })
+      .signers([voter])
+      .rpc();
+    await initialize().rpc();
+
+    const pollAccount = await program.account.poll.fetch(pollPda);
+    assert.equal(pollAccount.question, question);
+    assert.isNull(await provider.connection.getAccountInfo(proposalPda));
+
+    // Leave council mode off for any later tests
+    await program.methods
+      .setCouncil([], 0)
+      .accounts({ admin: creator.publicKey })
+      .rpc();
+  });
+
+  it("Lets the admin clear a poll proposal that squats a poll ID", async () => {
+    const squattedPollId = new anchor.BN(31);
+    const now = Math.floor(Date.now() / 1000);
+    const [proposalPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("poll_proposal"), squattedPollId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .proposePoll(squattedPollId, "Held for later?")
+      .accounts({ creator: voter.publicKey })
+      .signers([voter])
+      .rpc();
+
+    try {
+      await program.methods
+        .initializePoll(squattedPollId, "Held for later?", "Someone else's proposal.", new anchor.BN(now), new anchor.BN(now + 3600), defaultPollOptions())
+        .accounts({ creator: creator.publicKey, pollProposal: proposalPda })
+        .rpc();
+      assert.fail("Another wallet's proposal should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "Unauthorized");
+    }
+
+    const balanceBefore = await provider.connection.getBalance(voter.publicKey);
+    const rent = await provider.connection.getBalance(proposalPda);
+    await program.methods
+      .cancelPollProposal()
+      .accounts({ pollProposal: proposalPda, creator: voter.publicKey, authority: creator.publicKey })
+      .rpc();
+
+    assert.isNull(await provider.connection.getAccountInfo(proposalPda));
+    assert.equal(await provider.connection.getBalance(voter.publicKey), balanceBefore + rent);
+  });
+
+  it("Only lets creator pass holders create polls when the allowlist is on", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const [passPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("creator_pass"), creator.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .grantCreatorPass(creator.publicKey)
+      .accounts({ admin: creator.publicKey })
+      .rpc();
+    await program.methods
+      .setCreatorAllowlist(true)
+      .accounts({ admin: creator.publicKey })
+      .rpc();
+
+    try {
+      await program.methods
+        .initializePoll(new anchor.BN(8), "Unapproved", "No pass.", new anchor.BN(now), new anchor.BN(now + 3600), defaultPollOptions())
+        .accounts({ creator: voter.publicKey })
+        .signers([voter])
+        .rpc();
+      assert.fail("A wallet without a pass should not create polls.");
+    } catch (error) {
+      assert.include(error.message, "CreatorNotAllowed");
+    }
+
+    await program.methods
+      .initializePoll(new anchor.BN(9), "Approved", "Has a pass.", new anchor.BN(now), new anchor.BN(now + 3600), defaultPollOptions())
+      .accounts({ creator: creator.publicKey, creatorPass: passPda })
+      .rpc();
+
+    await program.methods
+      .setCreatorAllowlist(false)
+      .accounts({ admin: creator.publicKey })
+      .rpc();
+    await program.methods
+      .revokeCreatorPass()
+      .accounts({ creatorPass: passPda, admin: creator.publicKey })
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(passPda));
+  });
+
+  it("Lets anyone nominate themselves in an open-candidacy poll", async () => {
+    const openPollId = new anchor.BN(25);
+    const [pollPda] = await getPollPda(openPollId);
+    const [candidatePda] = await getCandidatePda(pollPda, 0);
+    const [nominationPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("nomination"), pollPda.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    const now = Math.floor(Date.now() / 1000);
+
+    await program.methods
+      .initializePoll(openPollId, "Open field", "Anyone can run.", new anchor.BN(now), new anchor.BN(now + 3600), {
+        ...defaultPollOptions(),
+        openCandidacy: true,
+      })
+      .accounts({ creator: creator.publicKey })
+      .rpc();
+
+    try {
+      await program.methods
+        .initializeCandidate("Proxy", "", creator.publicKey, "")
+        .accounts({ poll: pollPda, candidate: candidatePda, creator: voter.publicKey })
+        .signers([voter])
+        .rpc();
+      assert.fail("Nominating someone else should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "Unauthorized");
+    }
+
+    await program.methods
+      .initializeCandidate("Self", "", voter.publicKey, "")
+      .accounts({ poll: pollPda, candidate: candidatePda, creator: voter.publicKey, nomination: nominationPda })
+      .signers([voter])
+      .rpc();
+    const candidate = await program.account.candidate.fetch(candidatePda);
+    assert.equal(candidate.payoutWallet.toBase58(), voter.publicKey.toBase58());
+
+    // A wallet stands only once
+    const [secondPda] = await getCandidatePda(pollPda, 1);
+    try {
+      await program.methods
+        .initializeCandidate("Self again", "", voter.publicKey, "")
+        .accounts({ poll: pollPda, candidate: secondPda, creator: voter.publicKey, nomination: nominationPda })
+        .signers([voter])
+        .rpc();
+      assert.fail("A second self-nomination should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "InvalidNomination");
+    }
+  });
+
+  it("Caps how many polls a creator makes per window", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const spammer = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(spammer.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+
+    await program.methods
+      .setCreationLimit(1, new anchor.BN(3600))
+      .accounts({ admin: creator.publicKey })
+      .rpc();
+
+    await program.methods
+      .initializePoll(new anchor.BN(21), "First", "Within the limit.", new anchor.BN(now), new anchor.BN(now + 3600), defaultPollOptions())
+      .accounts({ creator: spammer.publicKey })
+      .signers([spammer])
+      .rpc();
+    try {
+      await program.methods
+        .initializePoll(new anchor.BN(22), "Second", "Over the limit.", new anchor.BN(now), new anchor.BN(now + 3600), defaultPollOptions())
+        .accounts({ creator: spammer.publicKey })
+        .signers([spammer])
+        .rpc();
+      assert.fail("A second poll within the window should be rejected.");
+    } catch (error) {
+      assert.include(error.message, "CreationLimitReached");
+    }
+
+    const [statsPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("creator_stats"), spammer.publicKey.toBuffer()],
+      program.programId
+    );
+    const stats = await program.account.creatorStats.fetch(statsPda);
+    assert.equal(stats.totalPolls.toNumber(), 1);
+
+    await program.methods
+      .setCreationLimit(0, new anchor.BN(0))
+      .accounts({ admin: creator.publicKey })
+      .rpc();
+  });
 });